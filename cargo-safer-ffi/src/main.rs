@@ -0,0 +1,212 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! `cargo safer-ffi generate`: builds a `::safer_ffi` library with its
+//! headers feature, and runs the generation binary which
+//! `::safer_ffi::headers_entry_point!()` defines along it (`generate-headers`,
+//! by default), with the `::safer_ffi::headers::Builder` options configured
+//! there.
+//!
+//! ```shell
+//! cargo safer-ffi generate \
+//!     --features generate-headers \
+//!     --language c \
+//!     --output include/mylib.h
+//! ```
+//!
+//! With `--check`, the existing output file is compared with the would-be
+//! generated one (rather than overwritten), the command failing if they
+//! differ, _e.g._, for CI to catch stale headers.
+
+use ::std::{
+    env,
+    ffi::OsString,
+    fs,
+    ops::Not as _,
+    path::PathBuf,
+    process::{self, Command},
+};
+
+const USAGE: &str = "\
+Generate the headers of a `::safer_ffi` library.
+
+Usage: cargo safer-ffi generate [OPTIONS] --output <PATH>
+
+Options:
+  -o, --output <PATH>          The file to generate
+  -l, --language <LANGUAGE>    c (default), csharp, python, msvc-def,
+                               ld-version-script, macos-exported-symbols,
+                               markdown, cython, c-smoke-test, or
+                               layout-snapshot
+      --check                  Fail if <PATH> is not up to date, rather than
+                               (re)generating it
+  -F, --features <FEATURES>    The feature(s) of the crate enabling the
+                               `headers` one of `safer-ffi` (default:
+                               `generate-headers`)
+      --bin <NAME>             The `headers_entry_point!()` binary of the
+                               package (default: `generate-headers`)
+  -p, --package <SPEC>         The package defining the headers
+      --manifest-path <PATH>   Path to the `Cargo.toml`
+      --locked                 Forwarded to `cargo`
+      --offline                Forwarded to `cargo`
+  -h, --help                   Print this help
+";
+
+#[derive(Debug)]
+struct Generate {
+    output: PathBuf,
+    language: Option<String>,
+    check: bool,
+    features: String,
+    bin: String,
+    package: Option<String>,
+    manifest_path: Option<PathBuf>,
+    /// `--locked` and `--offline`.
+    cargo_flags: Vec<String>,
+}
+
+fn main ()
+{
+    match parse_args(env::args_os().skip(1)).and_then(generate) {
+        | Ok(()) => {},
+        | Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        },
+    }
+}
+
+fn parse_args (args: impl Iterator<Item = OsString>)
+  -> Result<Generate, String>
+{
+    let mut args = args.map(|arg| {
+        arg.into_string().map_err(|arg| format!("invalid argument: {arg:?}"))
+    }).peekable();
+    // When invoked as `cargo safer-ffi`, `cargo` passes `safer-ffi` along.
+    if matches!(args.peek(), Some(Ok(arg)) if arg == "safer-ffi") {
+        args.next();
+    }
+    match args.next().transpose()? {
+        | Some(cmd) if cmd == "generate" => {},
+        | Some(cmd) if cmd == "-h" || cmd == "--help" => {
+            print!("{USAGE}");
+            process::exit(0);
+        },
+        | Some(cmd) => return Err(format!("unknown command `{cmd}`\n\n{USAGE}")),
+        | None => return Err(format!("missing command\n\n{USAGE}")),
+    }
+    let mut output = None;
+    let mut language = None;
+    let mut check = false;
+    let mut features = None;
+    let mut bin = None;
+    let mut package = None;
+    let mut manifest_path = None;
+    let mut cargo_flags = vec![];
+    while let Some(arg) = args.next().transpose()? {
+        let (flag, inline_value) = match arg.split_once('=') {
+            | Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            },
+            | _ => (arg, None),
+        };
+        let mut value = || match inline_value.clone() {
+            | Some(value) => Ok(value),
+            | None => {
+                args.next()
+                    .transpose()?
+                    .ok_or_else(|| format!("missing value for `{flag}`"))
+            },
+        };
+        match &*flag {
+            | "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            | "-l" | "--language" => language = Some(value()?),
+            | "--check" => check = true,
+            | "-F" | "--features" => features = Some(value()?),
+            | "--bin" => bin = Some(value()?),
+            | "-p" | "--package" => package = Some(value()?),
+            | "--manifest-path" => manifest_path = Some(PathBuf::from(value()?)),
+            | "--locked" | "--offline" => cargo_flags.push(flag.clone()),
+            | "-h" | "--help" => {
+                print!("{USAGE}");
+                process::exit(0);
+            },
+            | _ => return Err(format!("unknown option `{flag}`\n\n{USAGE}")),
+        }
+    }
+    Ok(Generate {
+        output: output.ok_or_else(|| format!("missing `--output`\n\n{USAGE}"))?,
+        language,
+        check,
+        features: features.unwrap_or_else(|| "generate-headers".into()),
+        bin: bin.unwrap_or_else(|| "generate-headers".into()),
+        package,
+        manifest_path,
+        cargo_flags,
+    })
+}
+
+fn generate (args: Generate)
+  -> Result<(), String>
+{
+    let Generate {
+        output, language, check, features, bin, package, manifest_path, cargo_flags,
+    } = args;
+    // The headers are always generated to a scratch file first, so as to
+    // detect the binary not having generated them, and, with `--check`, to
+    // leave `output` untouched.
+    let scratch = env::temp_dir().join(format!(
+        "cargo-safer-ffi-{}.{}",
+        process::id(),
+        output.extension().and_then(|it| it.to_str()).unwrap_or("h"),
+    ));
+    let _ = fs::remove_file(&scratch);
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut cmd = Command::new(cargo);
+    cmd.args(["run", "--bin", &bin, "--features", &features]);
+    cmd.args(&cargo_flags);
+    if let Some(package) = &package {
+        cmd.args(["--package", package]);
+    }
+    if let Some(manifest_path) = &manifest_path {
+        cmd.arg("--manifest-path").arg(manifest_path);
+    }
+    cmd.env("SAFER_FFI_HEADERS_OUTPUT", &scratch);
+    if let Some(language) = &language {
+        cmd.env("SAFER_FFI_HEADERS_LANGUAGE", language);
+    }
+    let status = cmd.status().map_err(|err| format!("failed to run `cargo`: {err}"))?;
+    if status.success().not() {
+        let _ = fs::remove_file(&scratch);
+        return Err(format!(
+            "`cargo run --bin {bin}` failed ({status}): is it a \
+            `::safer_ffi::headers_entry_point!()` binary, and does \
+            `--features {features}` enable the `headers` feature of `safer-ffi`?",
+        ));
+    }
+    let generated = fs::read(&scratch).map_err(|_| format!(
+        "no headers were generated: is `{bin}` a \
+        `::safer_ffi::headers_entry_point!()` binary?",
+    ))?;
+    let _ = fs::remove_file(&scratch);
+    if check {
+        match fs::read(&output) {
+            | Ok(existing) if existing == generated => {
+                eprintln!("`{}` is up to date.", output.display());
+                Ok(())
+            },
+            | Ok(_) => Err(format!(
+                "`{}` is not up to date: run `cargo safer-ffi generate` without `--check`",
+                output.display(),
+            )),
+            | Err(err) => Err(format!("cannot read `{}`: {err}", output.display())),
+        }
+    } else {
+        if let Some(parent) = output.parent().filter(|it| it.as_os_str().is_empty().not()) {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("cannot create `{}`: {err}", parent.display()))?;
+        }
+        fs::write(&output, generated)
+            .map_err(|err| format!("cannot write `{}`: {err}", output.display()))?;
+        eprintln!("Generated `{}`.", output.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+::safer_ffi::headers_entry_point!(fixture, |builder| {
+    builder.with_guard("FIXTURE_H")
+});
@@ -0,0 +1,11 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use ::safer_ffi::prelude::*;
+
+/// Adds two integers.
+#[ffi_export]
+fn fixture_add (x: i32, y: i32)
+  -> i32
+{
+    x.wrapping_add(y)
+}
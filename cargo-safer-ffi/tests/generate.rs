@@ -0,0 +1,79 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Runs `cargo safer-ffi generate` on the `tests/fixture` library, whose
+//! `generate-headers` binary configures the `FIXTURE_H` guard.
+
+use ::std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn cargo_safer_ffi (args: &[&str])
+  -> Output
+{
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixture");
+    Command::new(env!("CARGO_BIN_EXE_cargo-safer-ffi"))
+        .args(["safer-ffi", "generate"])
+        .arg("--manifest-path").arg(fixture.join("Cargo.toml"))
+        .args(args)
+        .env("CARGO_TARGET_DIR", Path::new(env!("CARGO_TARGET_TMPDIR")).join("fixture"))
+        .output()
+        .unwrap()
+}
+
+fn scratch_dir (name: &'_ str)
+  -> PathBuf
+{
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn generate_and_check ()
+{
+    let dir = scratch_dir("generate_and_check");
+    let header = dir.join("include/fixture.h");
+    let header_str = header.to_str().unwrap();
+    let out = cargo_safer_ffi(&["--output", header_str]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let contents = fs::read_to_string(&header).unwrap();
+    // The options of the `headers_entry_point!` binary are honored.
+    assert!(contents.contains("#ifndef FIXTURE_H\n#define FIXTURE_H\n"), "{contents}");
+    assert!(contents.contains("int32_t\nfixture_add (\n    int32_t x,\n    int32_t y);"), "{contents}");
+    // Up to date.
+    let out = cargo_safer_ffi(&["--output", header_str, "--check"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    // Stale, and left as is.
+    fs::write(&header, "stale").unwrap();
+    let out = cargo_safer_ffi(&["--output", header_str, "--check"]);
+    assert!(out.status.success() == false);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("is not up to date"));
+    assert_eq!(fs::read_to_string(&header).unwrap(), "stale");
+}
+
+#[test]
+fn generate_other_language ()
+{
+    let dir = scratch_dir("generate_other_language");
+    let output = dir.join("Fixture.cs");
+    let out = cargo_safer_ffi(&["--output", output.to_str().unwrap(), "--language", "csharp"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let contents = fs::read_to_string(&output).unwrap();
+    assert!(contents.contains("Int32 fixture_add (\n"), "{contents}");
+}
+
+#[test]
+fn bad_invocations ()
+{
+    let out = cargo_safer_ffi(&[]);
+    assert!(out.status.success() == false);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("missing `--output`"));
+    let dir = scratch_dir("bad_invocations");
+    let output = dir.join("fixture.h");
+    let out = cargo_safer_ffi(&["--output", output.to_str().unwrap(), "--bin", "missing"]);
+    assert!(out.status.success() == false);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("`cargo run --bin missing` failed"));
+    assert!(output.exists() == false);
+}
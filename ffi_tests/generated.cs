@@ -387,6 +387,10 @@ public unsafe /* static */ delegate
     VirtualPtr__Erased_ptr_DropGlueVTable_Erased_const_ptr_fptr (
         Erased_t /*const*/ * _0);
 
+/// <summary>
+/// <c>Send</c>: the object behind the vtable may be used, and released, from any thread.
+/// <c>Sync</c>: the <c>&self</c> methods of the object behind the vtable may be called concurrently, from several threads.
+/// </summary>
 [StructLayout(LayoutKind.Sequential, Size = 48)]
 public unsafe struct FfiFutureExecutorVTable_t {
     [MarshalAs(UnmanagedType.FunctionPtr)]
@@ -325,7 +325,10 @@ typedef struct VirtualPtr__Erased_ptr_DropGlueVTable {
     DropGlueVTable_t vtable;
 } VirtualPtr__Erased_ptr_DropGlueVTable_t;
 
-/** <No documentation available> */
+/** \brief
+ *  `Send`: the object behind the vtable may be used, and released, from any thread.
+ *  `Sync`: the `&self` methods of the object behind the vtable may be called concurrently, from several threads.
+ */
 typedef struct FfiFutureExecutorVTable {
     /** <No documentation available> */
     void (*release_vptr)(Erased_t *);
@@ -84,12 +84,714 @@ use ::paste;
 /// To have custom structs implement [`ReprC`], it suffices to annotate the
 /// `struct` definitions with the <code>#\[[derive_ReprC]\]</code>
 /// (on top of the obviously required `#[repr(C)]`).
+///
+/// # Arrays
+///
+/// Since C functions can neither take nor return arrays by value, a `[T; N]`
+/// parameter or return value (of any non-zero length `N`) is lowered to a
+/// struct wrapping it:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// fn mylib_hash (data: c_slice::Ref<'_, u8>)
+///   -> [u8; 32]
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// typedef struct {
+///     uint8_t idx[32];
+/// } uint8_32_array_t;
+///
+/// uint8_32_array_t mylib_hash (
+///     slice_ref_uint8_t data);
+/// ```
+///
+/// whereas a `&[T; N]` is a pointer to such a struct (_i.e._, to its first
+/// element).
+///
+/// # Tuples
+///
+/// Similarly, a tuple (of up to 6 elements) parameter or return value is
+/// lowered to the matching [`Tuple2`][`crate::Tuple2`] (_etc._) `#[repr(C)]`
+/// struct, whose fields are named `_0`, `_1`, …:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// fn mylib_min_max (xs: c_slice::Ref<'_, i32>)
+///   -> (i32, i32)
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// typedef struct Tuple2_int32_int32 {
+///     int32_t _0;
+///     int32_t _1;
+/// } Tuple2_int32_int32_t;
+///
+/// Tuple2_int32_int32_t mylib_min_max (
+///     slice_ref_int32_t xs);
+/// ```
+///
+/// A hand-written `#[derive_ReprC]` struct remains the way to pick the C name
+/// (or that of the fields).
+///
+/// # Optional values
+///
+/// An `Option<T>` is [`ReprC`] whenever `T` has a "niche" to represent `None`
+/// with, at no cost:
+///
+///   - a null pointer, for references,
+///     [`char_p::Box`][`crate::prelude::char_p::Box`],
+///     [`repr_c::Box`][`crate::prelude::repr_c::Box`] & co.;
+///
+///   - a zero integer, for `NonZeroU32` & co.;
+///
+///   - a null `.ptr` field, for the (by value) slices and
+///     [`repr_c::Vec`][`crate::prelude::repr_c::Vec`] or
+///     [`repr_c::String`][`crate::prelude::repr_c::String`].
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// fn mylib_read_file (path: char_p::Ref<'_>)
+///   -> Option<repr_c::Vec<u8>>
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// Vec_uint8_t mylib_read_file (
+///     char const * path);
+/// ```
+///
+/// with the null `.ptr` meaning `None` (as the headers can point out, see
+/// [`Builder::with_nullability_annotations()`][`crate::headers::Builder::with_nullability_annotations`]).
+///
+/// There being no second niche, a nested `Option<Option<T>>` is not
+/// [`ReprC`]: use an `enum`, or a `#[derive_ReprC]` struct with an extra
+/// `bool` field, instead.
+///
+/// # Returning lists of records
+///
+/// `#[ffi_export(vec)]` on such a `struct MyStruct` also exports a
+/// `vec_my_struct_get()` indexing helper and a `vec_my_struct_free()`
+/// function, which, together with the `Vec_MyStruct_t` type definition, make
+/// returning a [`repr_c::Vec`]`<MyStruct>` usable from C as is.
+///
+/// [`repr_c::Vec`]: `crate::prelude::repr_c::Vec`
+///
+/// # Returning `Result`s
+///
+/// A function returning a `Result<T, E>`, where `E` is an
+/// [`ErrorCode`][`crate::error_code::ErrorCode`] (_e.g._, a `#[repr(i32)]`
+/// error `enum`), is exported as returning `E`, with `T` written to a trailing
+/// `T * out` parameter:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// fn mylib_lookup (key: u32)
+///   -> Result<u64, MyError>
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// MyError_t mylib_lookup (
+///     uint32_t key,
+///     uint64_t * out);
+/// ```
+///
+/// See [the `error_code` module][`crate::error_code`] for more info.
+///
+/// `#[ffi_export(last_error)]` additionally records the [`Display`] of the
+/// errors, for the host to query through an exported
+/// `mylib_last_error_message()`: see [the `error` module][`crate::error`].
+///
+/// [`Display`]: `::core::fmt::Display`
+///
+/// # Validating strings
+///
+/// The `&str`, [`str::Ref`] and [`char_p::Ref`] parameters are trusted to be
+/// valid UTF-8 (and nul-terminated, for the latter).
+///
+/// `#[ffi_export(validate_utf8)]`, on a function returning a `Result<T, E>`
+/// where `E : From<`[`InvalidStr`]`>`, validates them instead: a `NULL` or
+/// invalid string is then recorded as the last error message (naming the
+/// argument), and returned as `Err(E::from(invalid_str))`, without the
+/// function being called.
+///
+/// ```rust,ignore
+/// #[ffi_export(validate_utf8)]
+/// fn mylib_set_name (name: char_p::Ref<'_>)
+///   -> Result<(), MyError> // : From<char_p::InvalidStr>
+/// ```
+///
+/// The checked constructors themselves,
+/// [`char_p::Ref::from_ptr_checked()`][`crate::prelude::char_p::Ref::from_ptr_checked`]
+/// and
+/// [`str::Ref::from_raw_parts_checked()`][`crate::prelude::str::Ref::from_raw_parts_checked`],
+/// are also available.
+///
+/// [`str::Ref`]: `crate::prelude::str::Ref`
+/// [`char_p::Ref`]: `crate::prelude::char_p::Ref`
+/// [`InvalidStr`]: `crate::char_p::InvalidStr`
+///
+/// # Methods
+///
+/// `#[ffi_export]` on an `impl` block exports each of its `pub fn`s as a free
+/// function prefixed with the (snake-cased) name of the type, the receiver
+/// becoming the first parameter:
+///
+/// ```rust,ignore
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// pub
+/// struct Counter {
+///     n: u32,
+/// }
+///
+/// #[ffi_export]
+/// impl Counter {
+///     pub
+///     fn new ()
+///       -> Self
+///     {
+///         Self { n: 0 }
+///     }
+///
+///     pub
+///     fn increment (&mut self)
+///     {
+///         self.n += 1;
+///     }
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// Counter_t *
+/// counter_new (void);
+///
+/// void
+/// counter_increment (
+///     Counter_t * counter);
+///
+/// void
+/// counter_free (
+///     Counter_t * counter);
+/// ```
+///
+/// That is, the functions returning `Self` return a [`repr_c::Box`] of it
+/// (which `counter_free()`, always exported, frees), and a `self` receiver
+/// takes it by [`repr_c::Box`]. `#[ffi_export(prefix = "…")]` overrides the
+/// prefix.
+///
+/// [`repr_c::Box`]: `crate::prelude::repr_c::Box`
+///
+/// # `static`s
+///
+/// `#[ffi_export]` on a `static` (of a [`ReprC`] type) exports it as a global
+/// variable:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// static MYLIB_MAX_CONNECTIONS: u32 = 64;
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// extern uint32_t const MYLIB_MAX_CONNECTIONS;
+/// ```
+///
+/// (a `static mut` not being `const`-qualified). The languages unable to
+/// import data symbols, such as C#, skip it; a `const`, which gets inlined
+/// into the headers, is then the more portable option.
+///
+/// # Generic types
+///
+/// `#[ffi_export]` on a generic type requires listing the instantiations to
+/// define in the headers, each of which gets its own (mangled) C name:
+///
+/// ```rust,ignore
+/// #[ffi_export(instantiate = (i32, f64))]
+/// #[derive_ReprC]
+/// #[repr(C)]
+/// pub
+/// struct Pair<T : ReprC> {
+///     a: T,
+///     b: T,
+/// }
+/// ```
+///
+/// defines `Pair_int32_t` and `Pair_double_t`. With several generic
+/// parameters, each instantiation is a tuple of types:
+/// `instantiate = ((i32, u8), (f64, u8))`.
+///
+/// # Opaque type hierarchies
+///
+/// `#[ffi_export(extends = Base)]` on a `struct Derived` with a `Base` field
+/// exports a `Derived_as_Base()` upcast and a `Base_try_as_Derived()`
+/// downcast, the latter being checked at runtime through `Base`'s
+/// [`Tagged`][`crate::layout::Tagged`] implementation.
+///
+/// # Overridable defaults
+///
+/// `#[ffi_export(weak_linkage)]` emits the function as a weak symbol, so that
+/// the application linking the library may override it (_e.g._, a default
+/// logger) with its own definition. The generated declaration documents it.
+///
+/// This requires a nightly compiler, with `#![feature(linkage)]` enabled in
+/// the crate, and a target whose object format supports weak symbols (ELF,
+/// Mach-O).
+///
+/// # C-variadic functions
+///
+/// An `unsafe extern "C" fn` whose last parameter is a named `args: ...`
+/// is declared, in the C headers, with a trailing `...`:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// unsafe extern "C"
+/// fn log_printf (fmt: char_p::Ref<'_>, mut args: ...)
+/// {
+///     // `args.arg::<c_int>()`, _etc._
+/// }
+/// ```
+///
+/// Languages unable to express such a signature (_e.g._, C#) skip the
+/// function instead. The Rust callers keep calling the function as is (the
+/// exported symbol, being unable to forward the `...`, gets its own copy of
+/// the body).
+///
+/// This requires a nightly compiler, with `#![feature(c_variadic)]` enabled in
+/// the crate.
+///
+/// # Versioned availability
+///
+/// `#[ffi_export(since = "1.2")]` records the version of the library in which
+/// the function first appeared, which the generated declaration documents.
+/// See [`Builder::with_version_macro()`] to also have C consumers targeting
+/// older versions not get to see it.
+///
+/// [`Builder::with_version_macro()`]: `crate::headers::Builder::with_version_macro`
+///
+/// # Target-specific functions
+///
+/// The C declaration of a function gated behind some `#[cfg(…)]` (written
+/// after the `#[ffi_export]`) is guarded by the equivalent preprocessor
+/// condition, whichever target the header is generated for:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// #[cfg(windows)]
+/// fn mylib_open_handle (handle: *mut c_void)
+///   -> i32
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// #if defined(_WIN32)
+/// int32_t
+/// mylib_open_handle (
+///     void * handle);
+/// #endif /* defined(_WIN32) */
+/// ```
+///
+/// The types it involves are still expected to exist on every target. The
+/// other languages merely skip the function when it does not exist on the
+/// target they are generated for.
+///
+/// The conditions of the predicates other than the common target ones (such
+/// as `feature = "gpu"`) are to be provided through
+/// [`Builder::with_cfg_conditions()`], or, for a given function, through
+/// `#[ffi_export(cfg_header = "defined(MYLIB_GPU)")]`, which overrides its
+/// condition. Otherwise, such a function is only (and unconditionally)
+/// declared when it exists on the target the header is generated for.
+///
+/// [`Builder::with_cfg_conditions()`]: `crate::headers::Builder::with_cfg_conditions`
+///
+/// # Deprecation
+///
+/// A `#[deprecated]` function (or `#[derive_ReprC]` type) is flagged as such
+/// in the generated headers, for downstream code still using it to get a
+/// compiler warning:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// #[deprecated(note = "use `mylib_open_v2()` instead")]
+/// fn mylib_open (path: char_p::Ref<'_>)
+///   -> i32
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// SAFER_FFI_DEPRECATED("use `mylib_open_v2()` instead")
+/// int32_t
+/// mylib_open (
+///     char const * path);
+/// ```
+///
+/// where `SAFER_FFI_DEPRECATED(note)` is `[[deprecated(note)]]` in C++14, and
+/// the equivalent attribute of the compiler otherwise. The C# bindings get an
+/// `[Obsolete("…")]` attribute.
+///
+/// Similarly, the C declaration of a `#[must_use]` function (_e.g._, one
+/// returning an owned [`char_p::Box`][`crate::char_p::Box`], which leaks if
+/// ignored) is annotated with `SAFER_FFI_MUST_USE`, _i.e._, `[[nodiscard]]` in
+/// C++17, and `__attribute__((warn_unused_result))` with GCC and Clang.
+///
+/// # Pure functions
+///
+/// `#[ffi_pure]` marks a function as having no side effects, its result only
+/// depending on its arguments and on the memory they point to; `#[ffi_const]`,
+/// stricter, as also not reading any memory (its result only depends on the
+/// values of its arguments). C compilers may then fold repeated calls to it:
+///
+/// ```rust,ignore
+/// #[ffi_export]
+/// #[ffi_const]
+/// fn mylib_clamp (x: i32, lo: i32, hi: i32)
+///   -> i32
+/// {
+///     x.clamp(lo, hi)
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  This function is `const`: it has no side effects, and its result only
+///  *  depends on the values of its arguments.
+///  */
+/// SAFER_FFI_CONST
+/// int32_t
+/// mylib_clamp (
+///     int32_t x,
+///     int32_t lo,
+///     int32_t hi);
+/// ```
+///
+/// where `SAFER_FFI_CONST` (resp. `SAFER_FFI_PURE`) is `__attribute__((const))`
+/// (resp. `__attribute__((pure))`) with GCC and Clang. The other languages only
+/// get the doc note. These are promises which are not checked: lying about
+/// them (_e.g._, on a function mutating some global state) is Undefined
+/// Behavior for the C callers.
+///
+/// # Grouping
+///
+/// `#[ffi_export(group = "Image")]` declares the function, in the generated C#
+/// bindings, within a `public static class Image` rather than within the
+/// catch-all `Ffi` one, and, in the C headers, within a `namespace image`
+/// (the `snake_case` of the group) when included from C++. The other
+/// languages ignore it.
+///
+/// # Naming
+///
+/// `#[ffi_export(rename = "mylib_foo")]` exports the function as `mylib_foo`,
+/// both as a symbol and in the headers.
+///
+/// `#[ffi_export(export_name = "mylib_foo_v2")]`, on the other hand, only
+/// changes the symbol: the headers keep declaring the function under its own
+/// name (or under the `rename`d one), as an alias for the symbol (through a
+/// `#define` in C, and an `EntryPoint` in C#). The symbol-listing formats
+/// (`.def` files, _etc._) list the symbol.
+///
+/// # Calling conventions
+///
+/// `#[ffi_export(convention = "stdcall")]` exports the function with the given
+/// ABI rather than `extern "C"`: one of `"C"`, `"system"`, `"stdcall"` or
+/// `"fastcall"` (or `"C-unwind"`, see below). The C headers annotate the declaration
+/// accordingly (_e.g._, `SAFER_FFI_STDCALL`, _i.e._, `__stdcall` on Windows),
+/// and so do the C# bindings (_e.g._, `CallingConvention.StdCall`).
+///
+/// Prefer `"system"` (`stdcall` on 32-bit Windows, `C` everywhere else) over
+/// `"stdcall"` when the library is not Windows-only.
+///
+/// With the `c-unwind` Cargo feature (which requires Rust 1.71.0 or later),
+/// `"C-unwind"` (which may also be spelled out as `extern "C-unwind" fn`)
+/// lets unwinding cross the boundary, for C++ callers and callees: a panic
+/// unwinds into the caller rather than aborting the process (unless
+/// `catch_panics` is used), and C++ exceptions thrown by the callbacks may
+/// unwind through the function. Such callbacks are to be taken as
+/// `extern "C-unwind" fn` pointers, which are [`ReprC`] too (with the same C
+/// type as their `extern "C"` counterparts), since the closure types of
+/// [`crate::closure`] use the `"C"` ABI.
+///
+/// # Panics
+///
+/// A panic unwinding out of an exported function aborts the process, since
+/// letting it cross the FFI boundary would be Undefined Behavior (unless the
+/// function uses the `"C-unwind"` ABI, see above).
+///
+/// `#[ffi_export(catch_panics = -1)]` rather catches it, logs its message, and
+/// returns the given sentinel value (of the function's return type) instead:
+///
+/// ```rust,ignore
+/// #[ffi_export(catch_panics = -1)]
+/// fn mylib_parse (s: char_p::Ref<'_>)
+///   -> i32
+/// {
+///     s.to_str().parse().unwrap()
+/// }
+/// ```
+///
+/// A bare `#[ffi_export(catch_panics)]` still aborts, but only after having
+/// logged the panic message. Either way, the generated declaration documents
+/// the behavior.
+///
+/// This requires the `std` Cargo feature.
+///
+/// The host may rather have these panics handed over to a crash-reporting
+/// callback, with their backtrace, or turned into aborts: see
+/// [`panic::set_ffi_panic_policy()`].
+///
+/// # Owned pointers in C#
+///
+/// `#[ffi_export(free_of = Foo)]` marks a function taking a single owned
+/// pointer (_e.g._, a `repr_c::Box<Foo>`) as the one freeing it. The C#
+/// bindings then feature a `FooHandle` `SafeHandle`, releasing it through that
+/// function, and the functions returning such a pointer return a `FooHandle`
+/// instead, so that it cannot be leaked.
+///
+/// # `async fn`s
+///
+/// `#[ffi_export(async_via = callback, executor = …)]` on an `async fn`
+/// exports a function which `spawn`s the future onto the given `executor`
+/// and, rather than returning, takes a trailing [`Completion`] callback to be
+/// called with the output. See [the `completion` module][`crate::completion`].
+///
+/// This requires the `async-fn` Cargo feature.
+///
+/// [`Completion`]: `crate::completion::Completion`
+///
+/// # Returning trait objects
+///
+/// A function returning `impl Trait`, for a `#[derive_ReprC(dyn)]` trait, is
+/// exported as returning a (boxed) [`VirtualPtr`]`<dyn Trait>`, which is also
+/// what it returns to Rust callers. The auto-traits and lifetimes of the
+/// `impl` (_e.g._, `impl Trait + Send + 'a`) carry over to the `dyn`.
+///
+/// This requires the `dyn-traits` Cargo feature.
+///
+/// [`VirtualPtr`]: `crate::prelude::VirtualPtr`
 pub use ::safer_ffi_proc_macros::ffi_export;
 
+/// Exports a getter and a setter for each field of a `struct`, so that C can
+/// read and mutate them even when the `struct` is `#[repr(opaque)]`.
+///
+/// ```rust,ignore
+/// #[ffi_export_accessors]
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// pub
+/// struct Config {
+///     retries: u32,
+///     #[ffi_export_accessors(readonly)]
+///     verbose: bool,
+///     #[ffi_export_accessors(skip)]
+///     cache: Vec<u8>,
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// uint32_t config_get_retries (
+///     Config_t const * config);
+///
+/// void config_set_retries (
+///     Config_t * config,
+///     uint32_t value);
+///
+/// bool config_get_verbose (
+///     Config_t const * config);
+/// ```
+///
+/// The getters return a `.clone()` of the field, whose type has thus to be
+/// both [`ReprC`] and `Clone`. `#[ffi_export_accessors(readonly)]` on the
+/// `struct` itself omits all the setters.
+pub use ::safer_ffi_proc_macros::ffi_export_accessors;
+
+/// Exports JSON (de)serialization functions for a `serde`-compatible type, as
+/// an escape hatch for the callers (_e.g._, scripting languages) needing to
+/// exchange values of it too complex to be laid out as a `#[repr(C)]` type.
+///
+/// ```rust,ignore
+/// #[ffi_export_json]
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// #[derive(::serde::Serialize, ::serde::Deserialize)]
+/// pub
+/// struct Config {
+///     servers: Vec<String>,
+///     limits: HashMap<String, u32>,
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// char * config_to_json (
+///     Config_t const * config);
+///
+/// Config_t * config_from_json (
+///     char const * json);
+///
+/// void config_json_free (
+///     char * json);
+/// ```
+///
+/// `config_from_json()` returns `NULL` when the JSON is invalid, and
+/// `config_to_json()` does so when the serialization fails (_e.g._, on a map
+/// with non-string keys). The type thus has to be both [`ReprC`] (usually
+/// `#[repr(opaque)]`) and `Serialize + DeserializeOwned`.
+///
+/// This requires the `serde-bridge` Cargo feature.
+#[cfg(feature = "serde-bridge")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "serde-bridge")),
+)]
+pub use ::safer_ffi_proc_macros::ffi_export_json;
+
 /// Identity macro when `feature = "headers"` is enabled, otherwise
 /// this macro outputs nothing.
 pub use ::safer_ffi_proc_macros::cfg_headers;
 
+/// Defines the header generation binary which the `cargo safer-ffi`
+/// subcommand (the `cargo-safer-ffi` crate) runs, so that
+///
+/// ```rust,ignore
+/// // src/bin/generate-headers.rs
+/// ::safer_ffi::headers_entry_point!(mylib, |builder| {
+///     builder
+///         .with_guard("MYLIB_H")
+///         .with_api_macro("MYLIB_API")
+/// });
+/// ```
+///
+/// along with
+///
+/// ```toml
+/// [lib]
+/// crate-type = ["cdylib", "lib"] # (so that the binary may link to it)
+///
+/// [[bin]]
+/// name = "generate-headers"
+/// required-features = ["generate-headers"]
+///
+/// [features]
+/// generate-headers = ["safer-ffi/headers"]
+/// ```
+///
+/// lets
+///
+/// ```shell
+/// cargo safer-ffi generate --features generate-headers --output mylib.h
+/// ```
+///
+/// build (and run) that binary with the `generate-headers` feature of the
+/// crate, and generate `mylib.h`, with the options of the given
+/// [`headers::Builder`] (see [`headers::generate_from_env()`]). The first
+/// parameter is the name of the library crate, and the second one may be
+/// omitted to use the default options.
+///
+/// `--check` compares the would-be output with the existing file instead
+/// (_e.g._, in CI), and `--language` picks another [`headers::Language`].
+///
+/// The [`headers::build_script`] helpers run that same binary from the
+/// `build.rs` script of the library, as part of a plain `cargo build`.
+///
+/// [`headers::Builder`]: `crate::headers::Builder`
+/// [`headers::generate_from_env()`]: `crate::headers::generate_from_env`
+/// [`headers::Language`]: `crate::headers::Language`
+/// [`headers::build_script`]: `crate::headers::build_script`
+#[macro_export]
+macro_rules! headers_entry_point {
+    (
+        $krate:ident $(,)?
+    ) => (
+        $crate::headers_entry_point!($krate, |builder| builder);
+    );
+
+    (
+        $krate:ident, $configure:expr $(,)?
+    ) => (
+        // Linked for its `#[ffi_export]`ed items.
+        extern crate $krate as _;
+
+        fn main ()
+          -> ::std::io::Result<()>
+        {
+            $crate::headers::generate_from_env($configure)
+        }
+    );
+}
+
+/// Defines a `#[test]` checking the size, the alignment, and the field
+/// offsets of every exported [`ReprC`] type against a snapshot committed
+/// along the crate, so that an accidental layout-breaking change fails
+/// `cargo test` before the headers are even regenerated.
+///
+/// ```rust,ignore
+/// ::safer_ffi::layout_tests!(); // `safer-ffi-layouts.snap`
+/// // or
+/// ::safer_ffi::layout_tests!("abi/layouts.snap");
+/// ```
+///
+/// The path of the snapshot is relative to the root of the crate (next to
+/// its `Cargo.toml`). It looks like:
+///
+/// ```text
+/// [Point_t]
+/// size = 16
+/// align = 8
+/// .x = 0
+/// .y = 8
+/// ```
+///
+/// The snapshot is written, rather than checked, when the
+/// `SAFER_FFI_UPDATE_LAYOUTS` environment variable is set (to create it, or
+/// for intended layout changes); the test fails if it is missing otherwise.
+/// It is the [`headers::Language::LayoutSnapshot`] output, so that
+/// `cargo safer-ffi generate --language layout-snapshot` may produce it too.
+///
+/// The field offsets are measured on the compiled types (hence only listed
+/// for the `#[derive_ReprC]` structs).
+///
+/// Like [`cfg_headers`], it expands to nothing unless the `headers` feature
+/// is enabled.
+///
+/// [`headers::Language::LayoutSnapshot`]: `crate::headers::Language::LayoutSnapshot`
+#[macro_export]
+macro_rules! layout_tests {
+    () => (
+        $crate::layout_tests!("safer-ffi-layouts.snap");
+    );
+
+    (
+        $snapshot:expr $(,)?
+    ) => (
+        $crate::__cfg_headers__! {
+            #[test]
+            fn __safer_ffi_layout_tests__ ()
+              -> ::std::io::Result<()>
+            {
+                $crate::headers::check_layout_snapshot(
+                    &::std::path::Path::new(::core::env!("CARGO_MANIFEST_DIR"))
+                        .join($snapshot)
+                )
+            }
+        }
+    );
+}
+
 /// Creates a compile-time checked [`char_p::Ref`]`<'static>` out of a
 /// string literal.
 ///
@@ -154,6 +856,36 @@ pub use ::safer_ffi_proc_macros::c_str as c;
 ///     } Instant_t;
 ///     ```
 ///
+/// ### `packed` or `align`ed `struct`
+///
+/// `#[repr(C, packed)]`, `#[repr(C, packed(N))]` and `#[repr(C, align(N))]`
+/// are supported too:
+///
+/// ```rust
+/// use ::safer_ffi::prelude::*;
+///
+/// #[derive_ReprC]
+/// #[repr(C, packed)]
+/// struct WireHeader {
+///     kind: u8,
+///     len: u32,
+/// }
+/// ```
+///
+///   - corresponding to the following C definition:
+///
+///     ```C
+///     #pragma pack(push, 1)
+///     typedef struct {
+///         uint8_t kind;
+///         uint32_t len;
+///     } WireHeader_t;
+///     #pragma pack(pop)
+///     ```
+///
+///   - whereas `align(N)` is expressed with a `SAFER_FFI_ALIGNAS(N)` (_i.e._,
+///     `_Alignas(N)` in C11) on the first field.
+///
 /// ### Field-less `enum`
 ///
 /// ```rust
@@ -182,6 +914,69 @@ pub use ::safer_ffi_proc_macros::c_str as c;
 ///     }
 ///     ```
 ///
+/// ### `enum` with payloads
+///
+/// ```rust
+/// use ::safer_ffi::prelude::*;
+///
+/// #[derive_ReprC]
+/// #[repr(C, u8)]
+/// enum Shape {
+///     Circle { radius: f32 },
+///     Rectangle(f32, f32),
+///     Empty,
+/// }
+/// ```
+///
+///   - corresponding to the following C definitions:
+///
+///     ```C
+///     typedef uint8_t Shape_Tag_t; enum {
+///         SHAPE_TAG_CIRCLE,
+///         SHAPE_TAG_RECTANGLE,
+///         SHAPE_TAG_EMPTY,
+///     }
+///
+///     typedef struct { float radius; } Shape_Circle_t;
+///
+///     typedef struct { float _0; float _1; } Shape_Rectangle_t;
+///
+///     typedef union {
+///         Shape_Circle_t Circle;
+///         Shape_Rectangle_t Rectangle;
+///     } Shape_Payload_t;
+///
+///     typedef struct {
+///         Shape_Tag_t tag;
+///         Shape_Payload_t payload;
+///     } Shape_t;
+///     ```
+///
+/// ### `union`
+///
+/// ```rust
+/// use ::safer_ffi::prelude::*;
+///
+/// #[derive_ReprC]
+/// #[repr(C)]
+/// union EventData {
+///     key_code: u32,
+///     position: [f32; 2],
+/// }
+/// ```
+///
+///   - corresponding to the following C definition:
+///
+///     ```C
+///     typedef union {
+///         uint32_t key_code;
+///         float position[2];
+///     } EventData_t;
+///     ```
+///
+///   - Since reading a field of a `union` is `unsafe` anyways, no validity
+///     checks are performed on the values coming from FFI.
+///
 /// ### Generic `struct`
 ///
 /// In that case, it is required that the struct's generic types carry a
@@ -219,6 +1014,141 @@ pub use ::safer_ffi_proc_macros::c_str as c;
 ///         double y;
 ///     } Point_double_t;
 ///     ```
+///
+/// ### Frozen `struct`
+///
+/// For `#[repr(C)]` structs which are part of a shipped (and thus stable)
+/// ABI, `#[derive_ReprC(frozen)]` guarantees that their layout never changes:
+///
+/// ```rust,ignore
+/// use ::safer_ffi::prelude::*;
+///
+/// #[derive_ReprC(frozen)]
+/// #[repr(C)]
+/// struct Header {
+///     version: u16,
+///     _padding: [u8; 2], // implicit padding is not allowed.
+///     len: u32,
+/// }
+/// ```
+///
+///   - the struct must not feature any implicit padding, so that its layout
+///     (size, alignment, and offsets of each field) is fully spelled out;
+///
+///   - [generating the headers](/safer_ffi/headers/) with
+///     [`Builder::with_update_frozen_layouts(true)`][`crate::headers::Builder::with_update_frozen_layouts`]
+///     records that layout, keyed by the path of the struct
+///     (`my_crate::module::Header`), in a `safer-ffi-abi.lock` file at the
+///     root of the crate (to be committed);
+///
+///   - from then on, any change to that layout is a compilation error.
+///
+/// ### Opaque types
+///
+/// `#[derive_ReprC(opaque)]` is akin to `#[repr(opaque)]`, but for it leaving
+/// the `repr` of the type untouched: the type, whatever its (Rust) layout,
+/// is only ever forward-declared to C, so that it can be handed over behind
+/// a pointer (_e.g._, a [`repr_c::Box`]), while its fields remain private
+/// (and free to change):
+///
+/// ```rust,ignore
+/// #[derive_ReprC(opaque)]
+/// pub
+/// struct Session {
+///     conn: Connection,
+///     cache: HashMap<String, Vec<u8>>,
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// typedef struct Session Session_t;
+/// ```
+///
+/// ### Owned opaque types
+///
+/// `#[derive_ReprC(auto_drop)]` on a `#[repr(opaque)]` (or
+/// `#[derive_ReprC(opaque)]`) type also exports a
+/// `<type>_free()` function, freeing the `repr_c::Box`es of it handed over to
+/// C, and `auto_clone` a `<type>_clone()` one (for `Clone` types):
+///
+/// ```rust,ignore
+/// #[derive_ReprC(auto_drop, auto_clone)]
+/// #[repr(opaque)]
+/// #[derive(Clone)]
+/// pub
+/// struct Config { /* … */ }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// void
+/// config_free (
+///     Config_t * config);
+///
+/// Config_t *
+/// config_clone (
+///     Config_t const * config);
+/// ```
+///
+/// (`#[ffi_export]` on an `impl` block of the type already exports the
+/// `<type>_free()` function.)
+///
+/// ### Invariants across fields
+///
+/// `#[derive_ReprC(is_valid = path::to::check)]`, on a `#[repr(C)]` struct,
+/// has the given `fn(&CLayoutOf<Self>) -> bool` validate its C layout (once
+/// each field has been validated), for the invariants tying several fields
+/// together (_e.g._, `len <= cap`).
+///
+/// ### `dyn` traits: supertraits and default methods
+///
+/// A `#[derive_ReprC(dyn)]` trait may have `Send` and `Sync` supertraits,
+/// which the generated vtable documents as thread-safety guarantees, and
+/// default methods, whose vtable entries are then pre-filled with the default
+/// implementation (unless overridden):
+///
+/// ```rust,ignore
+/// #[derive_ReprC(dyn)]
+/// pub
+/// trait Handler : Send + Sync {
+///     fn on_event (&self, ev: Event);
+///
+///     fn on_error (&self, code: i32)
+///     {
+///         eprintln!("error {code}");
+///     }
+/// }
+/// ```
+///
+/// ### `async` trait methods
+///
+/// The `-> Pin<Box<dyn '_ + Send + Future<Output = T>>>` methods of a
+/// `#[derive_ReprC(dyn)]` trait get, as their vtable entry, a function
+/// returning a [`repr_c::Box`]`<`[`repr_c::Future`]`<T>>` handle, to be driven
+/// by the caller:
+///
+/// ```rust,ignore
+/// #[derive_ReprC(dyn)]
+/// pub
+/// trait Plugin {
+///     fn fetch (&self, id: u32)
+///       -> Pin<Box<dyn '_ + Send + Future<Output = i32>>>
+///     ;
+/// }
+/// ```
+///
+/// The handle borrows the receiver (and arguments): these must outlive it.
+///
+/// On Rust 1.75.0 or later, `async fn`s (and `-> impl Future<Output = T> + Send`
+/// methods) are supported too. These are not available on `dyn Plugin` itself
+/// (they are opted out through a `where Self : Sized` clause), only on its
+/// [`VirtualPtr`][`crate::prelude::VirtualPtr`]s.
+///
+/// [`repr_c::Box`]: `crate::prelude::repr_c::Box`
+/// [`repr_c::Future`]: `crate::prelude::repr_c::Future`
 pub use ::safer_ffi_proc_macros::derive_ReprC;
 
 #[macro_use]
@@ -250,6 +1180,9 @@ __cfg_headers__! {
         pub
         name: &'static str,
 
+        pub
+        kind: headers::ItemKind,
+
         pub
         gen_def:
             fn(&mut dyn headers::Definer, headers::Language)
@@ -258,17 +1191,143 @@ __cfg_headers__! {
     }
 
     self::inventory::collect!(FfiExport);
+
+    // Layout of a `#[derive_ReprC(frozen)]` struct, to be recorded in its
+    // `lockfile`.
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[doc(hidden)] /** Not part of the public API */ pub
+    struct FrozenLayout {
+        pub
+        lockfile: &'static str,
+
+        pub
+        name: &'static str,
+
+        pub
+        size: usize,
+
+        pub
+        align: usize,
+
+        pub
+        fields: &'static [(&'static str, usize)],
+    }
+
+    self::inventory::collect!(FrozenLayout);
+
+    // A `#[ffi_export(free_of = …)]` function, for the C# backend to wrap the
+    // pointers it frees in a `SafeHandle`.
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[doc(hidden)] /** Not part of the public API */ pub
+    struct FreeFn {
+        pub
+        handle: &'static str,
+
+        /// The `#[ffi_export(group = …)]` of `free_fn` (else, it is declared
+        /// in the main C# class).
+        pub
+        group: Option<&'static str>,
+
+        pub
+        free_fn: &'static str,
+
+        /// The name of the (C layout of the) freed pointer type.
+        pub
+        ty: fn(&dyn headers::languages::HeaderLanguage) -> String,
+    }
+
+    self::inventory::collect!(FreeFn);
+
+    // A `#[derive_ReprC(dyn)]` trait, for the C# backend to emit a base class
+    // implementing it (see `Builder::with_csharp_dyn_trait_classes()`).
+    #[allow(missing_copy_implementations, missing_debug_implementations)]
+    #[doc(hidden)] /** Not part of the public API */ pub
+    struct DynTrait {
+        pub
+        name: &'static str,
+
+        /// The name of (the C layout of) the vtable `struct`.
+        pub
+        vtable: fn(&dyn headers::languages::HeaderLanguage) -> String,
+
+        /// The name of (the C layout of) the `VirtualPtr<dyn Trait>`.
+        pub
+        virtual_ptr: fn(&dyn headers::languages::HeaderLanguage) -> String,
+
+        /// The names of the owned (resp. shared) type-erased pointer, as
+        /// released (resp. retained) by the vtable.
+        pub
+        owned_ptr: fn(&dyn headers::languages::HeaderLanguage) -> String,
+
+        pub
+        ref_ptr: fn(&dyn headers::languages::HeaderLanguage) -> String,
+
+        pub
+        methods: fn(&dyn headers::languages::HeaderLanguage) -> Vec<DynTraitMethod>,
+    }
+
+    self::inventory::collect!(DynTrait);
+
+    #[allow(missing_debug_implementations)]
+    #[doc(hidden)] /** Not part of the public API */ pub
+    struct DynTraitMethod {
+        pub
+        name: &'static str,
+
+        /// The (type-erased) receiver type.
+        pub
+        self_ty: String,
+
+        pub
+        args: Vec<(&'static str, String)>,
+
+        pub
+        ret: String,
+    }
 }
 
 cfg_alloc! {
     extern crate alloc;
 }
 
+pub
+mod abi;
+
+#[cfg(feature = "c-allocator")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "c-allocator")),
+)]
+pub
+mod allocator;
+
+pub
+mod batch;
+
+cfg_std! {
+    pub
+    mod callback_scope;
+
+    pub
+    mod cancellation;
+}
+
 cfg_alloc! {
     pub
     mod boxed;
 }
 
+cfg_alloc! {
+    pub
+    mod cow;
+}
+
+#[cfg(feature = "buffer-utils")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "buffer-utils")),
+)]
+pub
+mod buffer_utils;
+
 #[doc(inline)]
 pub use self::c_char_module::c_char;
 #[path = "c_char.rs"]
@@ -277,9 +1336,71 @@ mod c_char_module;
 pub
 mod char_p;
 
+pub
+mod char16_p;
+
 pub
 mod closure;
 
+pub
+mod completion;
+
+pub
+mod debug_checks;
+
+cfg_std! {
+    pub
+    mod error;
+}
+
+pub
+mod error_code;
+
+#[cfg(feature = "debug-ffi-checks")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "debug-ffi-checks")),
+)]
+pub
+mod ffi_checks;
+
+pub
+mod ffi_iter;
+
+pub
+mod flags;
+
+cfg_std! {
+    pub
+    mod future;
+
+    pub
+    mod hash_map;
+}
+
+pub
+mod int128;
+
+#[cfg(feature = "leak-tracking")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "leak-tracking")),
+)]
+pub
+mod leak_tracking;
+
+#[cfg(feature = "log-callback")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "log-callback")),
+)]
+pub
+mod log;
+
+#[cfg(feature = "net")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "net")),
+)]
+pub
+mod net;
+
 #[cfg(feature = "dyn-traits")]
 #[cfg_attr(feature = "nightly",
     doc(cfg(feature = "dyn-traits")),
@@ -298,6 +1419,14 @@ pub use dyn_traits::futures;
 pub
 mod libc;
 
+cfg_std! {
+    pub
+    mod panic;
+}
+
+pub
+mod progress;
+
 pub
 mod ptr;
 
@@ -308,10 +1437,27 @@ mod slice;
 pub
 mod string;
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "alloc")),
+)]
+pub
+mod symbols;
+
 #[doc(no_inline)]
 pub
 use tuple::*;
 
+pub
+mod time;
+
+#[cfg(feature = "uuid")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "uuid")),
+)]
+pub
+mod uuid;
+
 pub
 mod tuple;
 
@@ -322,11 +1468,24 @@ cfg_alloc! {
     #[doc(inline)]
     pub use vec::Vec;
     pub mod vec;
+
+    #[doc(inline)]
+    pub use vec_deque::VecDeque;
+    pub mod vec_deque;
 }
 
 #[doc(inline)]
 pub use layout::impls::c_int;
 
+/// A write-only reference to a (possibly uninitialized) `T`, for the
+/// out-parameters of `#[ffi_export]` functions: `T *` in C.
+///
+/// Contrary to a `&mut T`, the function may not read the pointee, which the
+/// caller thus need not initialize. A `&mut MaybeUninit<T>` parameter is
+/// exported the same way.
+#[doc(no_inline)]
+pub use ::uninit::out_ref::Out;
+
 pub
 mod prelude {
     #[doc(no_inline)]
@@ -350,6 +1509,18 @@ mod prelude {
         }
     }
     pub
+    mod char16_p {
+        #[doc(no_inline)]
+        pub use crate::char16_p::char16_p_ref as Ref;
+        cfg_alloc! {
+            #[doc(no_inline)]
+            pub use crate::char16_p::{
+                char16_p_boxed as Box,
+                new,
+            };
+        }
+    }
+    pub
     mod c_slice {
         #[doc(no_inline)]
         pub use crate::slice::{
@@ -360,20 +1531,51 @@ mod prelude {
         cfg_alloc! {
             #[doc(no_inline)]
             pub use crate::slice::slice_boxed as Box;
+            #[doc(no_inline)]
+            pub use crate::cow::slice_cow as Cow;
         }
     }
     pub
     mod repr_c {
+        #[doc(no_inline)]
+        pub use crate::int128::{
+            I128,
+            U128,
+        };
+        #[doc(no_inline)]
+        pub use crate::time::Duration;
+        #[cfg(feature = "net")]
+        #[doc(no_inline)]
+        pub use crate::net::{
+            IpAddr,
+            Ipv4Addr,
+            Ipv6Addr,
+            SocketAddr,
+        };
         cfg_alloc! {
             #[doc(no_inline)]
             pub use crate::{
                 boxed::Box,
+                ffi_iter::Iter,
                 string::String,
+                string::String16,
                 vec::Vec,
+                vec_deque::VecDeque,
             };
 
             pub
             type Arc<T> = <T as crate::boxed::FitForCArc>::CArcWrapped;
+
+            pub
+            type Cow<'lt, T> = <T as crate::cow::FitForCCow<'lt>>::CCowWrapped;
+        }
+        cfg_std! {
+            #[doc(no_inline)]
+            pub use crate::{
+                cancellation::CancellationToken,
+                future::Future,
+                hash_map::HashMap,
+            };
         }
     }
     pub
@@ -386,6 +1588,8 @@ mod prelude {
         cfg_alloc! {
             #[doc(no_inline)]
             pub use crate::string::str_boxed as Box;
+            #[doc(no_inline)]
+            pub use crate::cow::str_cow as Cow;
         }
     }
 
@@ -424,10 +1628,6 @@ macro_rules! NULL {() => (
     $crate::ඞ::ptr::null_mut()
 )}
 
-#[cfg(feature = "log")]
-#[apply(hidden_export)]
-use ::log;
-
 #[cfg(feature = "js")]
 // #[apply(hidden_export)]
 #[path = "js/_mod.rs"]
@@ -482,6 +1682,7 @@ mod __ {
                 CLayoutOf,
                 ConcreteReprC,
                 CType,
+                Niche,
                 OpaqueKind,
                 ReprC,
                 __HasNiche__,
@@ -490,12 +1691,19 @@ mod __ {
         },
     };
 
+    #[cfg(feature = "log")]
+    pub use ::log;
+
+    #[cfg(feature = "serde-bridge")]
+    pub use ::serde_json;
+
     #[cfg(feature = "headers")]
     pub use {
         crate::{
             headers::{
                 Definer,
                 Language,
+                __with_doxygen_group__,
                 languages::{
                     self,
                     EnumVariant,
@@ -505,7 +1713,10 @@ mod __ {
                 },
             },
             inventory,
+            DynTrait,
+            DynTraitMethod,
             FfiExport,
+            FreeFn,
         },
     };
 
@@ -552,11 +1763,32 @@ mod __ {
         type ItSelf = Self;
     }
 
+    /// `a == b`, in `const` contexts (for a `#[derive_ReprC(frozen)]` struct
+    /// to pick its own entry in the lockfile).
+    pub
+    const
+    fn str_eq (a: &'_ str, b: &'_ str)
+      -> bool
+    {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
     cfg_match! {
         feature = "log" => {
             #[apply(hidden_export)]
             macro_rules! __error__ {( $($msg:tt)* ) => (
-                $crate::log::error! { $($msg)* }
+                $crate::ඞ::log::error! { $($msg)* }
             )}
         },
         feature = "std" => {
@@ -584,6 +1816,8 @@ mod __ {
         fn drop (self: &'_ mut Self)
         {
             let &mut Self(fname) = self;
+            #[cfg(feature = "std")]
+            crate::panic::report_uncaught();
             __abort_with_msg__!("\
                 Error, attempted to panic across the FFI \
                 boundary of `{fname}()`, \
@@ -593,6 +1827,27 @@ mod __ {
         }
     }
 
+    /// `#[ffi_export(catch_panics)]`: runs `f`, logging its panic, if any.
+    #[cfg(feature = "std")]
+    pub
+    fn catch_panic<R> (
+        fname: &'static str,
+        f: impl FnOnce() -> R,
+    ) -> Option<R>
+    {
+        let payload = match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+            | Ok(ret) => return Some(ret),
+            | Err(payload) => payload,
+        };
+        let msg =
+            payload.downcast_ref::<&'static str>().copied()
+                .or_else(|| payload.downcast_ref::<String>().map(|s| &**s))
+                .unwrap_or("Box<dyn Any>")
+        ;
+        crate::panic::report(fname, msg);
+        None
+    }
+
     #[cfg(feature = "alloc")]
     pub
     fn append_unqualified_name (
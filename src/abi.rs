@@ -0,0 +1,234 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A fingerprint of the exported ABI, for hosts to detect, at load time, a
+//! mismatch between the header they were compiled against and the library
+//! they load, rather than corrupting memory.
+//!
+//! The fingerprint hashes the names of the exported items along with their C
+//! declarations (the lowered signatures of the functions, the definitions of
+//! the types involved, and thus their layouts), comments and whitespace
+//! aside, so that it only changes along with the ABI.
+//!
+//! The registry the header generation uses being only available in the builds
+//! featuring the `headers` Cargo feature (and never on `wasm32`), the
+//! fingerprint is computed at header generation time, committed to a file
+//! (kept up to date by [`check_abi_fingerprint()`]), and then baked into the
+//! library by [`ffi_export_abi_fingerprint!`]:
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_abi_fingerprint!(mylib, "mylib.abi-fingerprint");
+//!
+//! #[::safer_ffi::cfg_headers]
+//! #[test]
+//! fn generate_headers ()
+//!   -> ::std::io::Result<()>
+//! {
+//!     ::safer_ffi::abi::check_abi_fingerprint("mylib.abi-fingerprint")?;
+//!     ::safer_ffi::headers::builder()
+//!         .with_abi_fingerprint_macro("MYLIB_ABI_FINGERPRINT")
+//!         .to_file("mylib.h")?
+//!         .generate()
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! #define MYLIB_ABI_FINGERPRINT 0x9c5e1d7f0a3b2468ULL
+//!
+//! /** \brief
+//!  *  The fingerprint of the exported ABI, to be compared with the one
+//!  *  defined by the header.
+//!  */
+//! uint64_t mylib_abi_fingerprint (void);
+//! ```
+//!
+//! The file being `include!`d, it has to exist before the first build: start
+//! from a placeholder, and let the test write the actual fingerprint.
+//!
+//! ```sh
+//! echo 0x0 > mylib.abi-fingerprint
+//! SAFER_FFI_UPDATE_ABI_FINGERPRINT=1 cargo test --features headers
+//! ```
+//!
+//! [`ffi_export_abi_fingerprint!`]: `crate::ffi_export_abi_fingerprint`
+
+use_prelude!();
+#[cfg(feature = "headers")]
+use crate::headers::{Language, Settings};
+
+/// Set to (re)write the fingerprint rather than checking it.
+#[cfg(feature = "headers")]
+const UPDATE: &str = "SAFER_FFI_UPDATE_ABI_FINGERPRINT";
+
+/// The fingerprint of the exported ABI (see the [module docs][self]).
+///
+/// It is computed out of the C declarations of the exported items, as
+/// generated with the default [`Settings`].
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn abi_fingerprint ()
+  -> u64
+{
+    let declarations =
+        crate::headers::declarations(Language::C, Settings::default())
+            .expect("writing to a `Vec` does not fail")
+    ;
+    let mut hasher = Fnv1a::default();
+    for (export, declaration) in declarations {
+        hasher.write(export.name.as_bytes());
+        hasher.write(b"\0");
+        hasher.write(strip_comments(&declaration).as_bytes());
+        hasher.write(b"\0");
+    }
+    hasher.0
+}
+
+/// Checks the [`abi_fingerprint()`] against the one committed to the file at
+/// `path` (relative to the current directory, _i.e._, to the root of the crate
+/// in a `cargo test`), which is written instead (see
+/// [`write_abi_fingerprint()`]) if the `SAFER_FFI_UPDATE_ABI_FINGERPRINT`
+/// environment variable is set.
+///
+/// A missing file is otherwise an error (lest a CI run without it silently
+/// pass).
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn check_abi_fingerprint (path: impl AsRef<::std::path::Path>)
+  -> ::std::io::Result<()>
+{
+    use ::std::io;
+
+    let path = path.as_ref();
+    if ::std::env::var_os(UPDATE).is_some() {
+        return write_abi_fingerprint(path);
+    }
+    let recorded = match ::std::fs::read_to_string(path) {
+        | Ok(it) => it,
+        | Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(err.kind(), format!(
+                "the ABI fingerprint file `{}` does not exist \
+                (set `{UPDATE}=1` to write it)",
+                path.display(),
+            )));
+        },
+        | Err(err) => return Err(err),
+    };
+    let fingerprint = abi_fingerprint();
+    if recorded.trim() == format!("{fingerprint:#018x}") {
+        return Ok(());
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+        "the ABI fingerprint is now {fingerprint:#018x}, not `{}` as recorded \
+        in `{}` (set `{UPDATE}=1` to update it, if intended)",
+        recorded.trim(),
+        path.display(),
+    )))
+}
+
+/// Writes the [`abi_fingerprint()`] to the file at `path` (relative to the
+/// current directory, _i.e._, to the root of the crate in a `cargo test`), for
+/// [`ffi_export_abi_fingerprint!`][`crate::ffi_export_abi_fingerprint`] to
+/// bake it into the library.
+///
+/// The file is left untouched when already up to date, not to trigger a
+/// rebuild.
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn write_abi_fingerprint (path: impl AsRef<::std::path::Path>)
+  -> ::std::io::Result<()>
+{
+    let path = path.as_ref();
+    let contents = format!("{:#018x}\n", abi_fingerprint());
+    match ::std::fs::read_to_string(path) {
+        | Ok(it) if it == contents => Ok(()),
+        | _ => ::std::fs::write(path, contents),
+    }
+}
+
+/// The C `declaration`, without its comments, and with its whitespace
+/// normalized.
+#[cfg(feature = "headers")]
+pub(in crate)
+fn strip_comments (declaration: &'_ str)
+  -> rust::String
+{
+    let mut code = rust::String::with_capacity(declaration.len());
+    let mut rest = declaration;
+    while let Some(start) = rest.find("/*") {
+        code.push_str(&rest[.. start]);
+        code.push(' ');
+        rest = rest[start + 2 ..].split_once("*/").map_or("", |(_, it)| it);
+    }
+    code.push_str(rest);
+    code.split_whitespace().collect::<rust::Vec<_>>().join(" ")
+}
+
+/// FNV-1a, which, unlike the `std` hashers, is stable across Rust versions.
+#[cfg(feature = "headers")]
+struct Fnv1a(u64);
+
+#[cfg(feature = "headers")]
+impl Default for Fnv1a {
+    fn default ()
+      -> Self
+    {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+#[cfg(feature = "headers")]
+impl Fnv1a {
+    fn write (self: &'_ mut Self, bytes: &'_ [u8])
+    {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Defines and exports `<prefix>_abi_fingerprint()` (see the
+/// [`abi` module][`crate::abi`]), returning the fingerprint written to the
+/// given file (relative to the root of the crate) by
+/// [`check_abi_fingerprint()`][`crate::abi::check_abi_fingerprint`].
+///
+/// The file has to exist before the first build (see the
+/// [`abi` module][`crate::abi`] for how to bootstrap it).
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_abi_fingerprint!(mylib, "mylib.abi-fingerprint");
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The fingerprint of the exported ABI, to be compared with the one
+///  *  defined by the header.
+///  */
+/// uint64_t mylib_abi_fingerprint (void);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_abi_fingerprint {(
+    $prefix:ident, $path:literal $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// The fingerprint of the exported ABI, to be compared with the one
+    /// defined by the header.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _abi_fingerprint>] ()
+      -> u64
+    {
+        include!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path))
+    }
+})}
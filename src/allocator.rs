@@ -0,0 +1,305 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! `malloc()`-based allocations (`c-allocator` Cargo feature), so that C code
+//! may `free()` the owned pointers handed to it.
+//!
+//! The final binary (_e.g._, the `cdylib` crate) is to install [`CAllocator`]
+//! as its `#[global_allocator]`, so that the heap allocations of the library,
+//! including those owned by the [`repr_c::Box`], [`repr_c::Vec`],
+//! [`repr_c::String`], or [`char_p::Box`] it returns, are performed with
+//! `malloc()`:
+//!
+//! ```rust,ignore
+//! #[global_allocator]
+//! static ALLOCATOR: ::safer_ffi::allocator::CAllocator =
+//!     ::safer_ffi::allocator::CAllocator::new()
+//! ;
+//! ```
+//!
+//! The C headers then state that `free()` is valid on:
+//!
+//!   - the returned boxes (of non-zero-sized types) and strings (`char *`),
+//!     even empty ones;
+//!
+//!   - the `.ptr` of the returned vectors and boxed slices, unless their
+//!     capacity (resp. length) is zero, in which case it is dangling.
+//!
+//! (Save for the over-aligned ones, _i.e._, aligned to more than `malloc()`
+//! is, on non-Unix platforms.)
+//!
+//! Alternatively, the host may register its own allocator, through the
+//! `mylib_set_allocator()` function which [`ffi_export_allocator!`] exports,
+//! provided it does so before the library allocates anything:
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_allocator!(mylib, ALLOCATOR);
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct AllocatorVTable {
+//!     void * (*alloc)(size_t, size_t);
+//!     void (*free)(void *);
+//! } AllocatorVTable_t;
+//!
+//! /** \brief
+//!  *  Makes the library allocate through the given `alloc()` and `free()`
+//!  *  functions, rather than `malloc()` and `free()`.
+//!  * ...
+//!  */
+//! bool mylib_set_allocator (
+//!     AllocatorVTable_t allocator);
+//! ```
+//!
+//! [`repr_c::Box`]: `crate::prelude::repr_c::Box`
+//! [`repr_c::Vec`]: `crate::prelude::repr_c::Vec`
+//! [`repr_c::String`]: `crate::prelude::repr_c::String`
+//! [`char_p::Box`]: `crate::prelude::char_p::Box`
+//! [`ffi_export_allocator!`]: `crate::ffi_export_allocator`
+
+use_prelude!();
+use ::core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::UnsafeCell,
+    sync::atomic::{self, AtomicU8},
+};
+
+/// A host-provided allocator.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub
+struct AllocatorVTable {
+    /// Allocates `size` bytes, aligned to `align` (a power of two), returning
+    /// `NULL` on failure.
+    pub
+    alloc: unsafe extern "C" fn (size: usize, align: usize) -> *mut c_void,
+
+    /// Frees a pointer returned by `alloc`.
+    pub
+    free: unsafe extern "C" fn (ptr: *mut c_void),
+}
+
+/// No allocation has happened yet.
+const UNSET: u8 = 0;
+/// `set_allocator()` is writing the custom allocator.
+const SETTING: u8 = 1;
+/// The custom allocator is the allocator.
+const SET: u8 = 2;
+/// `malloc()` is the allocator, for good.
+const MALLOC: u8 = 3;
+
+/// The alignment of the `malloc()`-returned pointers.
+const MIN_ALIGN: usize =
+    if cfg!(target_pointer_width = "64") { 16 } else { 8 }
+;
+
+/// The [`GlobalAlloc`] to be installed by the final binary (see the
+/// [module docs][self]): `malloc()`, `realloc()` and `free()` (or
+/// `posix_memalign()`, for over-aligned layouts), unless an allocator has been
+/// registered with [`CAllocator::set_allocator()`].
+#[derive(Debug)]
+pub
+struct CAllocator {
+    state: AtomicU8,
+    custom: UnsafeCell<Option<AllocatorVTable>>,
+}
+
+// Safety: `custom` is only written once, before being published through
+// `state`.
+unsafe impl Sync for CAllocator {}
+
+impl Default for CAllocator {
+    fn default ()
+      -> CAllocator
+    {
+        CAllocator::new()
+    }
+}
+
+impl CAllocator {
+    pub
+    const
+    fn new ()
+      -> CAllocator
+    {
+        CAllocator {
+            state: AtomicU8::new(UNSET),
+            custom: UnsafeCell::new(None),
+        }
+    }
+
+    /// Makes `self` allocate through `allocator` rather than `malloc()`.
+    ///
+    /// Returns `false`, doing nothing, if it has already allocated (or if an
+    /// allocator has already been set).
+    ///
+    /// # Safety
+    ///
+    /// `allocator.alloc` must return `NULL` or a pointer to `size` writable
+    /// bytes aligned to `align`, not aliased until handed back to
+    /// `allocator.free`; both functions have to be thread-safe, and must not
+    /// unwind.
+    pub
+    unsafe
+    fn set_allocator (self: &'_ CAllocator, allocator: AllocatorVTable)
+      -> bool
+    {
+        if self.state.compare_exchange(
+            UNSET,
+            SETTING,
+            atomic::Ordering::Acquire,
+            atomic::Ordering::Acquire,
+        ).is_err()
+        {
+            return false;
+        }
+        *self.custom.get() = Some(allocator);
+        self.state.store(SET, atomic::Ordering::Release);
+        true
+    }
+
+    /// The registered allocator, if any (locking the choice of `malloc()`
+    /// in, otherwise).
+    fn custom (self: &'_ CAllocator)
+      -> Option<AllocatorVTable>
+    {
+        loop {
+            match self.state.compare_exchange_weak(
+                UNSET,
+                MALLOC,
+                atomic::Ordering::Acquire,
+                atomic::Ordering::Acquire,
+            )
+            {
+                | Ok(_) | Err(MALLOC) => return None,
+                | Err(SET) => return unsafe { *self.custom.get() },
+                | Err(_) => ::core::hint::spin_loop(),
+            }
+        }
+    }
+}
+
+unsafe
+impl GlobalAlloc
+    for CAllocator
+{
+    unsafe
+    fn alloc (self: &'_ Self, layout: Layout)
+      -> *mut u8
+    {
+        if let Some(custom) = self.custom() {
+            return (custom.alloc)(layout.size(), layout.align()).cast();
+        }
+        if layout.align() <= MIN_ALIGN {
+            // (`malloc(n)` is only guaranteed to be suitably aligned for the
+            // objects of size `n`)
+            return ::libc::malloc(layout.size().max(layout.align())).cast();
+        }
+        #[cfg(unix)] {
+            let mut out = ptr::null_mut();
+            if ::libc::posix_memalign(&mut out, layout.align(), layout.size()) != 0 {
+                return ptr::null_mut();
+            }
+            out.cast()
+        }
+        #[cfg(not(unix))] {
+            // `_aligned_malloc()` pointers cannot be `free()`d (nor can these,
+            // but at least they are all released alike): over-allocate, and
+            // store the `malloc()`ed pointer right before the aligned one
+            // (`malloc()` aligning to `MIN_ALIGN`, there is room for it).
+            let size = match layout.size().checked_add(layout.align()) {
+                | Some(it) => it,
+                | None => return ptr::null_mut(),
+            };
+            let raw = ::libc::malloc(size).cast::<u8>();
+            if raw.is_null() {
+                return raw;
+            }
+            let aligned = raw.add(layout.align() - raw as usize % layout.align());
+            aligned.cast::<*mut u8>().sub(1).write_unaligned(raw);
+            aligned
+        }
+    }
+
+    unsafe
+    fn dealloc (self: &'_ Self, ptr: *mut u8, layout: Layout)
+    {
+        if let Some(custom) = self.custom() {
+            return (custom.free)(ptr.cast());
+        }
+        #[cfg(not(unix))]
+        if layout.align() > MIN_ALIGN {
+            return ::libc::free(ptr.cast::<*mut u8>().sub(1).read_unaligned().cast());
+        }
+        let _ = layout;
+        ::libc::free(ptr.cast())
+    }
+
+    unsafe
+    fn realloc (
+        self: &'_ Self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8
+    {
+        if self.custom().is_none()
+        && layout.align() <= MIN_ALIGN
+        && layout.align() <= new_size
+        {
+            return ::libc::realloc(ptr.cast(), new_size).cast();
+        }
+        // Fallback: `alloc()` + copy + `dealloc()`.
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = self.alloc(new_layout);
+        if new_ptr.is_null().not() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+/// Defines and exports `<prefix>_set_allocator()` (see the
+/// [`allocator` module][`crate::allocator`]), registering the allocator with
+/// the given [`CAllocator`][`crate::allocator::CAllocator`] `static`.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_allocator!(mylib, ALLOCATOR);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  Makes the library allocate through the given `alloc()` and `free()`
+///  *  functions, rather than `malloc()` and `free()`.
+///  * ...
+///  */
+/// bool mylib_set_allocator (
+///     AllocatorVTable_t allocator);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_allocator {(
+    $prefix:ident, $allocator:path $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Makes the library allocate through the given `alloc()` and `free()`
+    /// functions, rather than `malloc()` and `free()`.
+    ///
+    /// To be called before any other function of the library: returns
+    /// `false`, doing nothing, if it has already allocated.
+    ///
+    /// `alloc()` must return `NULL` or a pointer to `size` bytes aligned to
+    /// `align`; both functions have to be thread-safe.
+    #[allow(nonstandard_style)]
+    pub
+    unsafe
+    fn [<$prefix _set_allocator>] (
+        allocator: $crate::allocator::AllocatorVTable,
+    ) -> bool
+    {
+        $crate::allocator::CAllocator::set_allocator(&$allocator, allocator)
+    }
+})}
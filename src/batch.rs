@@ -0,0 +1,148 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Bulk-call batching: amortizing the FFI crossing cost over many calls.
+//!
+//! Chatty APIs (_e.g._, `set_pixel()`-style ones) pay the cost of the FFI
+//! boundary, and of the foreign runtime's marshaling machinery, _for each
+//! call_. The [`ffi_export_batch!`] macro lets such an API also be driven
+//! through a "command buffer": an array of tagged call descriptors, dispatched
+//! by a single exported function.
+
+/// Exports a batch dispatcher for a set of operations.
+///
+/// ```rust,ignore
+/// use ::safer_ffi::prelude::*;
+///
+/// #[derive_ReprC]
+/// #[repr(C)]
+/// pub
+/// struct SetPixelArgs {
+///     x: u32,
+///     y: u32,
+///     color: u32,
+/// }
+///
+/// ::safer_ffi::ffi_export_batch! {
+///     /// Executes the given calls, in order.
+///     ///
+///     /// Returns the number of calls that have been executed: if smaller
+///     /// than the length of `calls`, then `calls[returned_value]` is not a
+///     /// valid descriptor (unknown `op` or `NULL` `args`).
+///     pub
+///     fn mylib_execute_batch;
+///
+///     /// A call to be performed by `mylib_execute_batch()`.
+///     pub
+///     struct BatchCall;
+///
+///     /// The operations which can be batched.
+///     pub
+///     enum BatchOp {
+///         /// Sets the pixel at `(x, y)`. `args` points to a `SetPixelArgs`.
+///         SetPixel(args: SetPixelArgs) => set_pixel(args.x, args.y, args.color),
+///
+///         /// Clears the whole canvas. `args` is ignored (may be `NULL`).
+///         Clear => clear(),
+///     }
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// typedef enum BatchOp { BATCH_OP_SET_PIXEL, BATCH_OP_CLEAR } BatchOp_t;
+///
+/// typedef struct BatchCall {
+///     BatchOp_t op;
+///     void const * args;
+/// } BatchCall_t;
+///
+/// size_t mylib_execute_batch (slice_ref_BatchCall_t calls);
+/// ```
+///
+///   - The `args` of an operation are borrowed for the duration of the call
+///     (only); they are given to the expression on the right of the `=>` as
+///     a `&Args`.
+///
+///   - Execution stops at the first invalid descriptor (unknown `op`, or
+///     `NULL` or misaligned `args`), so that a caller can always tell which
+///     calls did happen.
+///
+/// # Safety
+///
+/// The generated function is `unsafe`: the non-`NULL` `args` of each call
+/// have to point to a valid instance of the `Args` of its `op`, for the
+/// duration of the call.
+#[macro_export]
+macro_rules! ffi_export_batch {(
+    $(#[$fn_attr:meta])*
+    $fn_pub:vis
+    fn $execute_batch:ident;
+
+    $(#[$call_attr:meta])*
+    $call_pub:vis
+    struct $Call:ident;
+
+    $(#[$op_attr:meta])*
+    $op_pub:vis
+    enum $Op:ident {
+        $(
+            $(#[$variant_attr:meta])*
+            $Variant:ident $(( $args:ident : $Args:ty ))? => $body:expr
+        ),* $(,)?
+    }
+) => (
+    #[$crate::derive_ReprC]
+    #[repr(u32)]
+    $(#[$op_attr])*
+    $op_pub
+    enum $Op {
+        $(
+            $(#[$variant_attr])*
+            $Variant,
+        )*
+    }
+
+    #[$crate::derive_ReprC]
+    #[repr(C)]
+    $(#[$call_attr])*
+    $call_pub
+    struct $Call {
+        /// The operation to perform.
+        pub
+        op: $crate::layout::CLayoutOf<$Op>,
+
+        /// The arguments of the operation (if any).
+        pub
+        args: *const $crate::ඞ::ffi::c_void,
+    }
+
+    #[$crate::ffi_export]
+    $(#[$fn_attr])*
+    $fn_pub
+    unsafe
+    fn $execute_batch (
+        calls: $crate::prelude::c_slice::Ref<'_, $Call>,
+    ) -> usize
+    {
+        for (i, call) in calls.as_slice().iter().enumerate() {
+            $(
+                if call.op.discriminant == $Op::$Variant as u32 {
+                    $(
+                        let at_args = call.args.cast::<$Args>();
+                        if at_args.is_null()
+                        || at_args.align_offset($crate::ඞ::mem::align_of::<$Args>()) != 0
+                        {
+                            return i;
+                        }
+                        // Safety: as per the contract of the function.
+                        let $args: &$Args = &*at_args;
+                    )?
+                    $body;
+                    continue;
+                }
+            )*
+            return i;
+        }
+        calls.len()
+    }
+)}
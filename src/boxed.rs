@@ -20,9 +20,13 @@ impl<T> From<rust::Box<T>>
     fn from (boxed: rust::Box<T>)
       -> Box_<T>
     {
+        let ptr = ptr::NonNull::from(rust::Box::leak(boxed));
+        #[cfg(feature = "leak-tracking")]
+        if mem::size_of::<T>() != 0 {
+            crate::leak_tracking::track::<Self>(ptr.as_ptr().cast());
+        }
         Self(
-            ptr::NonNull::from(rust::Box::leak(boxed))
-                .into()
+            ptr.into()
         )
     }
 }
@@ -43,6 +47,8 @@ impl<T> Box_<T> {
       -> rust::Box<T>
     {
         let mut this = mem::ManuallyDrop::new(self);
+        #[cfg(feature = "leak-tracking")]
+        crate::leak_tracking::untrack(this.0.as_mut_ptr().cast());
         unsafe {
             rust::Box::from_raw(this.0.as_mut_ptr())
         }
@@ -55,6 +61,8 @@ impl<T> Drop
     #[inline]
     fn drop (self: &'_ mut Box_<T>)
     {
+        #[cfg(feature = "leak-tracking")]
+        crate::leak_tracking::untrack(self.0.as_mut_ptr().cast());
         unsafe {
             drop::<rust::Box<T>>(
                 rust::Box::from_raw(self.0.as_mut_ptr())
@@ -150,3 +158,183 @@ pub
 trait FitForCArc {
     type CArcWrapped;
 }
+
+impl<T : Sized> FitForCArc for T {
+    type CArcWrapped = Arc_<T>;
+}
+
+ReprC! {
+    #[repr(transparent)]
+    /// Same as [`Arc<T>`][`::alloc::sync::Arc`], (_e.g._, same `#[repr(C)]`
+    /// layout as its [`into_raw()`][`::alloc::sync::Arc::into_raw`] pointer),
+    /// so that C may share ownership of the pointee (see
+    /// [`ffi_export_arc!`][`crate::ffi_export_arc`]).
+    pub
+    struct Arc_[T] (
+        ptr::NonNullRef<T>,
+    );
+}
+
+impl<T> From<::alloc::sync::Arc<T>>
+    for Arc_<T>
+{
+    #[inline]
+    fn from (arc: ::alloc::sync::Arc<T>)
+      -> Arc_<T>
+    {
+        let ptr = ::alloc::sync::Arc::into_raw(arc);
+        Self(
+            unsafe { ptr::NonNull::new_unchecked(ptr as *mut T) }
+                .into()
+        )
+    }
+}
+
+impl<T> Arc_<T> {
+    #[inline]
+    pub
+    fn new (value: T)
+      -> Self
+    {
+        ::alloc::sync::Arc::new(value)
+            .into()
+    }
+
+    #[inline]
+    pub
+    fn into (self: Arc_<T>)
+      -> ::alloc::sync::Arc<T>
+    {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe {
+            ::alloc::sync::Arc::from_raw(this.0.as_ptr())
+        }
+    }
+}
+
+impl<T> Drop
+    for Arc_<T>
+{
+    #[inline]
+    fn drop (self: &'_ mut Arc_<T>)
+    {
+        unsafe {
+            drop::<::alloc::sync::Arc<T>>(
+                ::alloc::sync::Arc::from_raw(self.0.as_ptr())
+            );
+        }
+    }
+}
+
+impl<T> Clone
+    for Arc_<T>
+{
+    #[inline]
+    fn clone (self: &'_ Self)
+      -> Self
+    {
+        unsafe {
+            ::alloc::sync::Arc::increment_strong_count(self.0.as_ptr());
+        }
+        Self(self.0)
+    }
+}
+
+impl<T> Deref
+    for Arc_<T>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref (self: &'_ Arc_<T>)
+      -> &'_ T
+    {
+        unsafe {
+            &*self.0.as_ptr()
+        }
+    }
+}
+
+unsafe impl<T> Send
+    for Arc_<T>
+where
+    ::alloc::sync::Arc<T> : Send,
+{}
+
+unsafe impl<T> Sync
+    for Arc_<T>
+where
+    ::alloc::sync::Arc<T> : Sync,
+{}
+
+impl<T : fmt::Debug> fmt::Debug
+    for Arc_<T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        T::fmt(self, fmt)
+    }
+}
+
+/// Defines and exports `<prefix>_retain()` and `<prefix>_release()`, for C to
+/// share the ownership of a [`repr_c::Arc<T>`][`Arc_`].
+///
+/// ```rust,ignore
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// pub
+/// struct Widget { /* … */ }
+///
+/// ::safer_ffi::ffi_export_arc!(mylib_widget: Widget);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  Returns a new owned reference to the pointee of `arc` (which remains
+///  *  owned by the caller).
+///  */
+/// Widget_t const * mylib_widget_retain (
+///     Widget_t const * arc);
+///
+/// /** \brief
+///  *  Releases an owned reference, dropping the pointee if it was the last
+///  *  one.
+///  */
+/// void mylib_widget_release (
+///     Widget_t const * arc);
+/// ```
+///
+/// Since the reference count is atomic, both functions may be called from any
+/// thread, provided `T` is `Send + Sync`.
+#[macro_export]
+macro_rules! ffi_export_arc {(
+    $prefix:ident : $T:ty $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Returns a new owned reference to the pointee of `arc` (which remains
+    /// owned by the caller).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _retain>] (
+        arc: $crate::prelude::repr_c::Arc<$T>,
+    ) -> $crate::prelude::repr_c::Arc<$T>
+    {
+        // `arc` is only borrowed.
+        $crate::ඞ::Clone::clone(&*$crate::ඞ::mem::ManuallyDrop::new(arc))
+    }
+
+    #[$crate::ffi_export]
+    /// Releases an owned reference, dropping the pointee if it was the last
+    /// one.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _release>] (
+        arc: $crate::prelude::repr_c::Arc<$T>,
+    )
+    {
+        $crate::ඞ::drop(arc)
+    }
+})}
@@ -0,0 +1,83 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Checksum and comparison exports over byte slices, for the integration tests
+//! of the consumer side to check data integrity across the FFI boundary
+//! without having to write (and trust) a C implementation of their own.
+
+use_prelude!();
+
+/// Defines and exports `<prefix>_buffer_crc32()` and
+/// `<prefix>_buffer_equal()`.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_buffer_utils!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The CRC-32 (ISO-HDLC, as used by zlib) of the given bytes.
+///  */
+/// uint32_t mylib_buffer_crc32 (
+///     slice_ref_uint8_t buf);
+///
+/// /** \brief
+///  *  Whether both buffers have the same length and contents.
+///  */
+/// bool mylib_buffer_equal (
+///     slice_ref_uint8_t a,
+///     slice_ref_uint8_t b);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_buffer_utils {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// The CRC-32 (ISO-HDLC, as used by zlib) of the given bytes.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _buffer_crc32>] (
+        buf: $crate::prelude::c_slice::Ref<'_, u8>,
+    ) -> u32
+    {
+        $crate::buffer_utils::crc32(buf.as_slice())
+    }
+
+    #[$crate::ffi_export]
+    /// Whether both buffers have the same length and contents.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _buffer_equal>] (
+        a: $crate::prelude::c_slice::Ref<'_, u8>,
+        b: $crate::prelude::c_slice::Ref<'_, u8>,
+    ) -> bool
+    {
+        a.as_slice() == b.as_slice()
+    }
+})}
+
+/// The CRC-32 (ISO-HDLC, as used by zlib, PNG, _etc._) of `bytes`.
+pub
+fn crc32 (bytes: &'_ [u8])
+  -> u32
+{
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC32_TABLE[usize::from((crc as u8) ^ byte)] ^ (crc >> 8)
+    })
+}
+
+static CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
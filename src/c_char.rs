@@ -66,6 +66,12 @@ impl LegacyCType
         )
     }
 
+    fn c_layout_on (_: &'_ crate::headers::Target)
+      -> Option<crate::headers::TypeLayout>
+    {
+        Some(crate::headers::TypeLayout { size: 1, align: 1 })
+    }
+
     fn c_define_self (
         _: &'_ mut dyn crate::headers::Definer,
     ) -> io::Result<()>
@@ -0,0 +1,184 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Tracking of the callbacks handed to background Rust threads, so that the
+//! host can wait for all of them to be released before freeing their
+//! `user_data`.
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! ::safer_ffi::ffi_export_callback_scope! {
+//!     static CALLBACKS;
+//!
+//!     /// Blocks until no callback is in use by the library anymore.
+//!     fn mylib_shutdown_callbacks;
+//! }
+//!
+//! #[ffi_export]
+//! fn mylib_on_event (cb: repr_c::Arc<dyn Send + Sync + Fn()>)
+//! {
+//!     // Rejected if `mylib_shutdown_callbacks()` has been called.
+//!     if let Some(cb) = CALLBACKS.track(cb) {
+//!         ::std::thread::spawn(move || cb.call());
+//!     }
+//! }
+//! ```
+
+use_prelude!();
+use ::std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Keeps count of the callbacks in flight (see the [module docs][self]).
+#[derive(Debug)]
+pub
+struct CallbackScope {
+    state: Mutex<State>,
+    idle: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    in_flight: usize,
+    shut_down: bool,
+}
+
+impl CallbackScope {
+    pub
+    const fn new ()
+      -> CallbackScope
+    {
+        Self {
+            state: Mutex::new(State { in_flight: 0, shut_down: false }),
+            idle: Condvar::new(),
+        }
+    }
+
+    fn state (self: &'_ Self)
+      -> MutexGuard<'_, State>
+    {
+        // The counter remains consistent even if a holder of the lock panicked.
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Starts tracking `callback`, until the returned [`Tracked`] is dropped.
+    ///
+    /// Returns `None` (dropping `callback`) if the scope has been
+    /// [shut down][`CallbackScope::shutdown`].
+    pub
+    fn track<F> (self: &'_ Self, callback: F)
+      -> Option<Tracked<'_, F>>
+    {
+        let mut state = self.state();
+        if state.shut_down {
+            return None;
+        }
+        state.in_flight += 1;
+        Some(Tracked { callback: mem::ManuallyDrop::new(callback), scope: self })
+    }
+
+    /// Number of callbacks currently in flight.
+    pub
+    fn in_flight (self: &'_ Self)
+      -> usize
+    {
+        self.state().in_flight
+    }
+
+    /// Stops accepting new callbacks, and blocks until the ones in flight
+    /// have all been released.
+    pub
+    fn shutdown (self: &'_ Self)
+    {
+        let mut state = self.state();
+        state.shut_down = true;
+        while state.in_flight > 0 {
+            state =
+                self.idle
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+            ;
+        }
+    }
+}
+
+impl Default for CallbackScope {
+    fn default ()
+      -> CallbackScope
+    {
+        Self::new()
+    }
+}
+
+/// A callback tracked by a [`CallbackScope`]: it derefs to the callback, and
+/// releases it on drop.
+#[derive(Debug)]
+pub
+struct Tracked<'scope, F> {
+    callback: mem::ManuallyDrop<F>,
+    scope: &'scope CallbackScope,
+}
+
+impl<F> Deref
+    for Tracked<'_, F>
+{
+    type Target = F;
+
+    fn deref (self: &'_ Self)
+      -> &'_ F
+    {
+        &self.callback
+    }
+}
+
+impl<F> DerefMut
+    for Tracked<'_, F>
+{
+    fn deref_mut (self: &'_ mut Self)
+      -> &'_ mut F
+    {
+        &mut self.callback
+    }
+}
+
+impl<F> Drop
+    for Tracked<'_, F>
+{
+    fn drop (self: &'_ mut Self)
+    {
+        // The callback (_e.g._, its `release` function) may still be using the
+        // `user_data`: only count it out once it has been dropped.
+        unsafe {
+            mem::ManuallyDrop::drop(&mut self.callback);
+        }
+        let mut state = self.scope.state();
+        state.in_flight -= 1;
+        if state.in_flight == 0 {
+            self.scope.idle.notify_all();
+        }
+    }
+}
+
+/// Defines a `static` [`CallbackScope`], and exports a function which
+/// [shuts it down][`CallbackScope::shutdown`].
+#[macro_export]
+macro_rules! ffi_export_callback_scope {(
+    $(#[$static_attr:meta])*
+    $static_pub:vis
+    static $SCOPE:ident;
+
+    $(#[$fn_attr:meta])*
+    $fn_pub:vis
+    fn $shutdown:ident;
+) => (
+    $(#[$static_attr])*
+    $static_pub
+    static $SCOPE: $crate::callback_scope::CallbackScope =
+        $crate::callback_scope::CallbackScope::new()
+    ;
+
+    #[$crate::ffi_export]
+    $(#[$fn_attr])*
+    $fn_pub
+    fn $shutdown ()
+    {
+        $SCOPE.shutdown()
+    }
+)}
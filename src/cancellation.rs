@@ -0,0 +1,266 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A standard cancellation token, for the long-running exports (and futures)
+//! to be cancelled cooperatively from C.
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! ::safer_ffi::ffi_export_cancellation_token!(mylib);
+//!
+//! #[ffi_export]
+//! /// Returns `false` if cancelled.
+//! fn mylib_process (
+//!     items: c_slice::Ref<'_, Item>,
+//!     token: &'_ repr_c::CancellationToken,
+//! ) -> bool
+//! {
+//!     items.iter().try_for_each(|item| {
+//!         token.check()?;
+//!         Ok(process(item))
+//!     }).is_ok()
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct CancellationToken CancellationToken_t;
+//!
+//! CancellationToken_t * mylib_cancellation_token_new (void);
+//!
+//! void mylib_cancellation_token_cancel (
+//!     CancellationToken_t const * token);
+//!
+//! bool mylib_cancellation_token_is_cancelled (
+//!     CancellationToken_t const * token);
+//!
+//! void mylib_cancellation_token_free (
+//!     CancellationToken_t * token);
+//!
+//! bool mylib_process (
+//!     slice_ref_Item_t items,
+//!     CancellationToken_t const * token);
+//! ```
+//!
+//! A token may be [`Clone`]d, _e.g._, into a spawned task: the clones share
+//! the same cancellation state. [`CancellationToken::cancelled()`] is the
+//! `Future` resolving once the token is cancelled.
+
+use_prelude!();
+use ::std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{self, AtomicBool, AtomicU64},
+        Arc,
+        Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+pub use crate::progress::Cancelled;
+
+#[derive_ReprC]
+#[repr(opaque)]
+/// A cooperative cancellation flag, shared between the host (which may
+/// cancel it) and the operations it is handed to (which are to check it).
+#[derive(Clone, Default)]
+pub
+struct CancellationToken {
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    cancelled: AtomicBool,
+    /// The wakers of the pending [`CancellationToken::cancelled()`] futures,
+    /// keyed by their `id` (one waker per future: the latest one).
+    wakers: Mutex<HashMap<u64, Waker>>,
+    next_id: AtomicU64,
+}
+
+impl CancellationToken {
+    pub
+    fn new ()
+      -> CancellationToken
+    {
+        Self::default()
+    }
+
+    /// Cancels the token, and all its clones, waking up the tasks awaiting
+    /// [`.cancelled()`][`Self::cancelled`].
+    pub
+    fn cancel (self: &'_ Self)
+    {
+        self.shared.cancelled.store(true, atomic::Ordering::Release);
+        let wakers = mem::take(
+            &mut *self.shared.wakers.lock().unwrap_or_else(|it| it.into_inner())
+        );
+        wakers.into_values().for_each(Waker::wake);
+    }
+
+    pub
+    fn is_cancelled (self: &'_ Self)
+      -> bool
+    {
+        self.shared.cancelled.load(atomic::Ordering::Acquire)
+    }
+
+    /// `Err(Cancelled)` if the token has been cancelled, for `?` to bail out.
+    pub
+    fn check (self: &'_ Self)
+      -> Result<(), Cancelled>
+    {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A `Future` resolving once the token is cancelled (_e.g._, to
+    /// `select!` an operation against).
+    pub
+    fn cancelled (self: &'_ Self)
+      -> WaitForCancellation
+    {
+        WaitForCancellation {
+            token: self.clone(),
+            id: self.shared.next_id.fetch_add(1, atomic::Ordering::Relaxed),
+        }
+    }
+}
+
+impl fmt::Debug
+    for CancellationToken
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// The `Future` returned by [`CancellationToken::cancelled()`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub
+struct WaitForCancellation {
+    token: CancellationToken,
+    id: u64,
+}
+
+impl Future
+    for WaitForCancellation
+{
+    type Output = ();
+
+    fn poll (self: Pin<&'_ mut Self>, cx: &'_ mut Context<'_>)
+      -> Poll<()>
+    {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut wakers =
+            self.token.shared.wakers.lock().unwrap_or_else(|it| it.into_inner())
+        ;
+        // (checked again with the lock held, lest a concurrent `cancel()`
+        // be missed)
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        // Only the waker of the latest poll is to be woken: replace it.
+        match wakers.get_mut(&self.id) {
+            | Some(waker) if waker.will_wake(cx.waker()) => {},
+            | Some(waker) => *waker = cx.waker().clone(),
+            | None => { wakers.insert(self.id, cx.waker().clone()); },
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop
+    for WaitForCancellation
+{
+    fn drop (self: &'_ mut Self)
+    {
+        self.token.shared.wakers
+            .lock()
+            .unwrap_or_else(|it| it.into_inner())
+            .remove(&self.id)
+        ;
+    }
+}
+
+/// Defines and exports `<prefix>_cancellation_token_new()`, `_cancel()`,
+/// `_is_cancelled()` and `_free()`, for C to manage
+/// [`repr_c::CancellationToken`][`CancellationToken`]s.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_cancellation_token!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// CancellationToken_t * mylib_cancellation_token_new (void);
+///
+/// void mylib_cancellation_token_cancel (
+///     CancellationToken_t const * token);
+///
+/// bool mylib_cancellation_token_is_cancelled (
+///     CancellationToken_t const * token);
+///
+/// void mylib_cancellation_token_free (
+///     CancellationToken_t * token);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_cancellation_token {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// A new (not cancelled) token, to be freed with
+    /// `..._cancellation_token_free()`.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _cancellation_token_new>] ()
+      -> $crate::prelude::repr_c::Box<$crate::prelude::repr_c::CancellationToken>
+    {
+        $crate::ඞ::Box::new($crate::prelude::repr_c::CancellationToken::new())
+            .into()
+    }
+
+    #[$crate::ffi_export]
+    /// Cancels the token, for the operations it has been handed to to stop.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _cancellation_token_cancel>] (
+        token: &'_ $crate::prelude::repr_c::CancellationToken,
+    )
+    {
+        token.cancel()
+    }
+
+    #[$crate::ffi_export]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _cancellation_token_is_cancelled>] (
+        token: &'_ $crate::prelude::repr_c::CancellationToken,
+    ) -> bool
+    {
+        token.is_cancelled()
+    }
+
+    #[$crate::ffi_export]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _cancellation_token_free>] (
+        token: $crate::prelude::repr_c::Box<$crate::prelude::repr_c::CancellationToken>,
+    )
+    {
+        $crate::ඞ::mem::drop(token)
+    }
+})}
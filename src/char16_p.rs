@@ -0,0 +1,590 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! `wchar_t *`-compatible UTF-16 strings (slim pointers), for Windows APIs
+//! (`LPCWSTR` / `LPWSTR`) and C# (`string`) interop.
+//!
+//! They thus do not support inner nulls. See [`repr_c::String16`] for a fat
+//! pointer (pointer + length) alternative.
+//!
+//! [`repr_c::String16`]: `crate::string::String16`
+
+use_prelude!();
+use ::core::slice;
+
+__cfg_headers__! {
+    use crate::headers::{
+        Definer,
+        languages::*,
+    };
+}
+
+/// A `ReprC` _standalone_ type with the same layout and ABI as `uint16_t`,
+/// for the UTF-16 code units of a [`char16_p_ref`] or [`char16_p_boxed`].
+///
+/// In the C headers, it is `c_char16_t`, a typedef for `wchar_t` on Windows
+/// (so that the pointers are `LPCWSTR` / `LPWSTR`), and for `uint16_t`
+/// elsewhere.
+#[repr(transparent)]
+#[derive(
+    Debug,
+    Clone, Copy,
+    Default,
+    PartialOrd, Ord,
+    PartialEq, Eq,
+    Hash,
+)]
+pub
+struct c_char16 /* = */ (
+    pub
+    u16,
+);
+
+unsafe
+impl CType
+    for c_char16
+{
+    type OPAQUE_KIND = OpaqueKind::Concrete;
+
+    __cfg_headers__! {
+        fn short_name ()
+          -> rust::String
+        {
+            "c_char16".into()
+        }
+
+        fn define_self (
+            language: &'_ dyn HeaderLanguage,
+            definer: &'_ mut dyn Definer,
+        ) -> io::Result<()>
+        {
+            match () {
+                | _case if language.is::<C>() => definer.define_once(
+                    "c_char16_t",
+                    &mut |definer| Self::define_self__impl(language, definer),
+                ),
+                | _ => <u16 as CType>::define_self(language, definer),
+            }
+        }
+
+        fn define_self__impl (
+            language: &'_ dyn HeaderLanguage,
+            definer: &'_ mut dyn Definer,
+        ) -> io::Result<()>
+        {
+            <u16 as CType>::define_self(language, definer)?;
+            write!(definer.out(),
+                concat!(
+                    "/** \\brief\n",
+                    " *  A UTF-16 code unit: `WCHAR` on Windows, so that",
+                    " `c_char16_t const *`\n",
+                    " *  and `c_char16_t *` are `LPCWSTR` and `LPWSTR`",
+                    " respectively.\n",
+                    " */\n",
+                    "#ifdef _WIN32\n",
+                    "typedef wchar_t c_char16_t;\n",
+                    "#else\n",
+                    "typedef uint16_t c_char16_t;\n",
+                    "#endif\n",
+                    "\n",
+                ),
+            )
+        }
+
+        fn name (
+            language: &'_ dyn HeaderLanguage,
+        ) -> rust::String
+        {
+            match () {
+                | _case if language.is::<C>() => {
+                    "c_char16_t".into()
+                },
+                // Other languages cannot use the preprocessor: they get the
+                // (portable) underlying integer type.
+                | _ => <u16 as CType>::name(language),
+            }
+        }
+
+        fn layout_on (
+            target: &'_ crate::headers::Target,
+        ) -> Option<crate::headers::TypeLayout>
+        {
+            <u16 as CType>::layout_on(target)
+        }
+    }
+}
+
+from_CType_impl_ReprC! {
+    c_char16
+}
+
+const NUL: u16 = 0;
+
+/// The `#[repr(C)]` layout of a [`char16_p_ref`] (see there).
+#[doc(hidden)] /** Not part of the public API */
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+pub
+struct char16_p_layout (
+    *const c_char16,
+);
+
+unsafe
+impl CType
+    for char16_p_layout
+{
+    type OPAQUE_KIND = OpaqueKind::Concrete;
+
+    __cfg_headers__! {
+        fn short_name ()
+          -> rust::String
+        {
+            "char16_p".into()
+        }
+
+        fn define_self (
+            language: &'_ dyn HeaderLanguage,
+            definer: &'_ mut dyn Definer,
+        ) -> io::Result<()>
+        {
+            Self::define_self__impl(language, definer)
+        }
+
+        fn define_self__impl (
+            language: &'_ dyn HeaderLanguage,
+            definer: &'_ mut dyn Definer,
+        ) -> io::Result<()>
+        {
+            <c_char16 as CType>::define_self(language, definer)
+        }
+
+        fn name (
+            language: &'_ dyn HeaderLanguage,
+        ) -> rust::String
+        {
+            Self::name_wrapping_var(language, "")
+        }
+
+        fn name_wrapping_var (
+            language: &'_ dyn HeaderLanguage,
+            var_name: &'_ str,
+        ) -> rust::String
+        {
+            let sep = if var_name.is_empty() { "" } else { " " };
+            match () {
+                | _case if language.is::<CSharp>() => {
+                    format!("string{sep}{var_name}")
+                },
+                | _ => format!(
+                    "{} const *{sep}{var_name}",
+                    <c_char16 as CType>::name(language),
+                ),
+            }
+        }
+
+        /// Let the C# runtime convert its (UTF-16) `string`s to and from the
+        /// (null-terminated) pointer.
+        fn csharp_marshaler ()
+          -> Option<rust::String>
+        {
+            Some("UnmanagedType.LPWStr".into())
+        }
+
+        fn layout_on (
+            target: &'_ crate::headers::Target,
+        ) -> Option<crate::headers::TypeLayout>
+        {
+            Some(target.pointer_layout())
+        }
+    }
+}
+
+/// A `#[repr(c)]` null-terminated UTF-16 encoded string, for compatibility
+/// with both the Windows `LPCWSTR` API and C#'s `string`.
+///
+/// This is a **borrowed** version, _i.e._, with the semantics of
+/// `&'lt [u16]`, but for it being a _slim_ pointer.
+///
+/// Since the C# side marshals it as a `string`
+/// (`[MarshalAs(UnmanagedType.LPWStr)]`), it is meant to be used for
+/// parameters: a returned `char16_p_ref` would be freed by the C# runtime.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub
+struct char16_p_ref<'lt> (
+    ptr::NonNull<c_char16>,
+    PhantomData<&'lt [c_char16]>,
+);
+
+unsafe
+impl ReprC
+    for char16_p_ref<'_>
+{
+    type CLayout = char16_p_layout;
+
+    #[inline]
+    fn is_valid (it: &'_ char16_p_layout)
+      -> bool
+    {
+        it.0.is_null().not()
+        &&
+        it.0.align_offset(mem::align_of::<c_char16>()) == 0
+    }
+}
+
+unsafe // Safety: proof delegated to `where` bound.
+impl Send for char16_p_ref<'_>
+where
+    for<'lt> &'lt [u16] : Send,
+{}
+
+unsafe // Safety: proof delegated to `where` bound.
+impl Sync for char16_p_ref<'_>
+where
+    for<'lt> &'lt [u16] : Sync,
+{}
+
+impl<'lt> char16_p_ref<'lt> {
+    /// # Safety
+    ///
+    ///   - For the duration of the `'lt` borrow, the pointer must point to the
+    ///     beginning of a valid and immutable null-terminated slice of `u16`s.
+    #[inline]
+    pub
+    const
+    unsafe
+    fn from_ptr_unchecked (ptr: ptr::NonNull<u16>)
+      -> char16_p_ref<'lt>
+    {
+        Self(ptr.cast(), PhantomData)
+    }
+
+    #[inline]
+    pub
+    fn as_ptr (self: char16_p_ref<'lt>)
+      -> *const u16
+    {
+        self.0.as_ptr().cast()
+    }
+
+    /// The UTF-16 code units, up to (and excluding) the `NUL` terminator.
+    #[inline]
+    pub
+    fn to_units (self: char16_p_ref<'lt>)
+      -> &'lt [u16]
+    {
+        let ptr = self.as_ptr();
+        unsafe {
+            let mut len = 0;
+            while ptr.add(len).read() != NUL {
+                len += 1;
+            }
+            slice::from_raw_parts(ptr, len)
+        }
+    }
+
+    /// The UTF-16 code units, including the `NUL` terminator.
+    #[inline]
+    pub
+    fn to_units_with_null (self: char16_p_ref<'lt>)
+      -> &'lt [u16]
+    {
+        unsafe {
+            slice::from_raw_parts(self.as_ptr(), self.to_units().len() + 1)
+        }
+    }
+
+    cfg_alloc! {
+        /// Decodes the string, replacing invalid UTF-16 (_e.g._, unpaired
+        /// surrogates) with `U+FFFD`.
+        #[inline]
+        pub
+        fn to_string_lossy (self: char16_p_ref<'lt>)
+          -> rust::String
+        {
+            rust::String::from_utf16_lossy(self.to_units())
+        }
+
+        #[inline]
+        pub
+        fn to_owned (self: char16_p_ref<'lt>)
+          -> char16_p_boxed
+        {
+            unsafe {
+                char16_p_boxed::from_units_with_null(
+                    self.to_units_with_null().into()
+                )
+            }
+        }
+    }
+}
+
+impl Eq for char16_p_ref<'_> {}
+impl PartialEq for char16_p_ref<'_> {
+    #[inline]
+    fn eq (self: &'_ Self, other: &'_ Self)
+      -> bool
+    {
+        self.to_units() == other.to_units()
+    }
+}
+
+impl fmt::Debug
+    for char16_p_ref<'_>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt.write_str("\"")?;
+        for c in ::core::char::decode_utf16(self.to_units().iter().copied()) {
+            match c {
+                | Ok(c) => fmt::Display::fmt(&c.escape_debug(), fmt)?,
+                | Err(err) => write!(fmt, "\\u{{{:x}}}", err.unpaired_surrogate())?,
+            }
+        }
+        fmt.write_str("\"")
+    }
+}
+
+#[derive(Debug)]
+pub
+struct InvalidNulTerminator<Payload> (
+    pub Payload,
+);
+
+impl<T> fmt::Display
+    for InvalidNulTerminator<T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt::Display::fmt(
+            "Null code unit not at the expected terminating position",
+            fmt,
+        )
+    }
+}
+
+cfg_std! {
+    impl<T : fmt::Debug> ::std::error::Error
+        for InvalidNulTerminator<T>
+    {}
+}
+
+impl<'lt> TryFrom<&'lt [u16]>
+    for char16_p_ref<'lt>
+{
+    type Error = InvalidNulTerminator<()>;
+
+    /// The slice must end with its one and only `NUL` code unit.
+    fn try_from (units: &'lt [u16])
+      -> Result<
+            char16_p_ref<'lt>,
+            InvalidNulTerminator<()>,
+        >
+    {
+        match units.iter().position(|&unit| unit == NUL) {
+            | Some(idx) if idx + 1 == units.len() => unsafe {
+                Ok(Self::from_ptr_unchecked(
+                    ptr::NonNull::new(units.as_ptr() as _).unwrap()
+                ))
+            },
+            | _ => Err(InvalidNulTerminator(())),
+        }
+    }
+}
+
+cfg_alloc! {
+    /// Constructs a new `char16_p::Box` off a string, by encoding it as
+    /// UTF-16 (_c.f._ [`str::encode_utf16()`]).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the string contains an interior (non-last) `NUL`.
+    #[inline]
+    pub
+    fn new (s: &'_ str)
+      -> char16_p_boxed
+    {
+        char16_p_boxed::try_from(s)
+            .unwrap_or_else(|_| panic!(concat!(
+                "Error, the string `{:?}` contains an inner nul byte",
+                " and can thus not be converted to a C string without ",
+                "truncating it.",
+            ), s))
+    }
+
+    ReprC! {
+        #[repr(transparent)]
+        /// A `#[repr(c)]` null-terminated UTF-16 encoded string, for
+        /// compatibility with both the Windows `LPWSTR` API and Rust's `str`.
+        ///
+        /// This is an **owned** / heap-allocated version, much like
+        /// `Box<[u16]>` but for it being a _slim_ pointer.
+        ///
+        /// Contrary to [`char16_p_ref`], the C# side sees it as a raw pointer
+        /// (to be freed by Rust), rather than as a `string`.
+        pub
+        struct char16_p_boxed (
+            ptr::NonNullOwned<c_char16>,
+        );
+    }
+
+    unsafe // Safety: inherited from `Box<[u16]>`.
+    impl Send
+        for char16_p_boxed
+    where
+        rust::Box<[u16]> : Send,
+    {}
+
+    unsafe // Safety: inherited from `Box<[u16]>`.
+    impl Sync
+        for char16_p_boxed
+    where
+        rust::Box<[u16]> : Sync,
+    {}
+
+    impl char16_p_boxed {
+        /// # Safety
+        ///
+        ///   - `units` must end with its one and only `NUL` code unit.
+        unsafe
+        fn from_units_with_null (units: rust::Box<[u16]>)
+          -> char16_p_boxed
+        {
+            let ptr = rust::Box::into_raw(units) as *mut c_char16;
+            Self(ptr::NonNullOwned(
+                ptr::NonNull::new_unchecked(ptr),
+                PhantomData,
+            ))
+        }
+
+        #[inline]
+        pub
+        fn as_ref (self: &'_ char16_p_boxed)
+          -> char16_p_ref<'_>
+        {
+            unsafe {
+                char16_p_ref::from_ptr_unchecked(self.0.0.cast())
+            }
+        }
+
+        #[inline]
+        pub
+        fn to_units (self: &'_ char16_p_boxed)
+          -> &'_ [u16]
+        {
+            self.as_ref().to_units()
+        }
+
+        #[inline]
+        pub
+        fn to_string_lossy (self: &'_ char16_p_boxed)
+          -> rust::String
+        {
+            self.as_ref().to_string_lossy()
+        }
+    }
+
+    impl<'lt> TryFrom<&'lt str>
+        for char16_p_boxed
+    {
+        type Error = InvalidNulTerminator<()>;
+
+        fn try_from (s: &'lt str)
+          -> Result<
+                char16_p_boxed,
+                InvalidNulTerminator<()>,
+            >
+        {
+            Self::try_from(s.encode_utf16().collect::<rust::Vec<u16>>())
+                .map_err(|_| InvalidNulTerminator(()))
+        }
+    }
+
+    impl TryFrom<rust::Vec<u16>>
+        for char16_p_boxed
+    {
+        type Error = InvalidNulTerminator<rust::Vec<u16>>;
+
+        /// Appends the `NUL` terminator if missing.
+        fn try_from (mut units: rust::Vec<u16>)
+          -> Result<
+                char16_p_boxed,
+                InvalidNulTerminator<rust::Vec<u16>>,
+            >
+        {
+            if units.last() != Some(&NUL) {
+                units.push(NUL);
+            }
+            if units[.. units.len() - 1].contains(&NUL) {
+                return Err(InvalidNulTerminator(units));
+            }
+            Ok(unsafe {
+                Self::from_units_with_null(units.into_boxed_slice())
+            })
+        }
+    }
+
+    cfg_std! {
+        impl<'lt> TryFrom<&'lt ::std::ffi::OsStr>
+            for char16_p_boxed
+        {
+            type Error = InvalidNulTerminator<()>;
+
+            /// On Windows, the (potentially ill-formed) UTF-16 is kept as is.
+            /// Elsewhere, the `OsStr` is lossily converted to a `str` first.
+            fn try_from (s: &'lt ::std::ffi::OsStr)
+              -> Result<
+                    char16_p_boxed,
+                    InvalidNulTerminator<()>,
+                >
+            {
+                #[cfg(windows)]
+                let units = ::std::os::windows::ffi::OsStrExt::encode_wide(s);
+                #[cfg(not(windows))]
+                let s = s.to_string_lossy();
+                #[cfg(not(windows))]
+                let units = s.encode_utf16();
+                Self::try_from(units.collect::<rust::Vec<u16>>())
+                    .map_err(|_| InvalidNulTerminator(()))
+            }
+        }
+    }
+
+    impl Drop
+        for char16_p_boxed
+    {
+        fn drop (self: &'_ mut char16_p_boxed)
+        {
+            unsafe {
+                let num_units = self.as_ref().to_units_with_null().len();
+                drop::<rust::Box<[c_char16]>>(
+                    rust::Box::from_raw(slice::from_raw_parts_mut(
+                        self.0.as_mut_ptr(),
+                        num_units,
+                    ))
+                );
+            }
+        }
+    }
+
+    impl Clone
+        for char16_p_boxed
+    {
+        #[inline]
+        fn clone (self: &'_ char16_p_boxed)
+          -> char16_p_boxed
+        {
+            self.as_ref().to_owned()
+        }
+    }
+
+    impl fmt::Debug
+        for char16_p_boxed
+    {
+        fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+          -> fmt::Result
+        {
+            fmt::Debug::fmt(&self.as_ref(), fmt)
+        }
+    }
+}
@@ -106,6 +106,96 @@ impl<T> fmt::Display
     }
 }
 
+/// Why a string handed over by C is not valid, as reported by the checked
+/// constructors ([`char_p_ref::from_ptr_checked()`],
+/// [`str::Ref::from_raw_parts_checked()`][from_raw_parts_checked], …).
+///
+/// [from_raw_parts_checked]: `crate::prelude::str::Ref::from_raw_parts_checked`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum InvalidStr {
+    /// A `NULL` pointer.
+    Null,
+
+    /// A length overflowing `isize::MAX`.
+    TooLong,
+
+    /// No `NUL` terminator.
+    MissingNul,
+
+    /// Invalid UTF-8, past the first `valid_up_to` bytes.
+    InvalidUtf8 {
+        valid_up_to: usize,
+    },
+}
+
+impl fmt::Display
+    for InvalidStr
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        match *self {
+            | Self::Null => fmt.write_str("null pointer"),
+            | Self::TooLong => fmt.write_str("length overflowing `isize::MAX`"),
+            | Self::MissingNul => fmt.write_str("missing nul terminator"),
+            | Self::InvalidUtf8 { valid_up_to } => write!(fmt,
+                "invalid UTF-8 (past the first {valid_up_to} bytes)",
+            ),
+        }
+    }
+}
+
+impl From<::core::str::Utf8Error>
+    for InvalidStr
+{
+    fn from (err: ::core::str::Utf8Error)
+      -> InvalidStr
+    {
+        Self::InvalidUtf8 { valid_up_to: err.valid_up_to() }
+    }
+}
+
+impl<'lt> char_p_ref<'lt> {
+    /// Checked counterpart of [`From<&CStr>`][`From`], for the strings
+    /// handed over by C, rather than trusting them to be valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    ///   - Unless `NULL`, `ptr` must point, for the duration of `'lt`, to the
+    ///     beginning of a valid and immutable null-terminated slice of
+    ///     `c_char`s.
+    pub
+    unsafe
+    fn from_ptr_checked (ptr: *const c_char)
+      -> Result<char_p_ref<'lt>, InvalidStr>
+    {
+        let ptr = ptr::NonNull::new(ptr as *mut u8).ok_or(InvalidStr::Null)?;
+        let it = unsafe { Self::from_ptr_unchecked(ptr) };
+        ::core::str::from_utf8(it.to_bytes())?;
+        Ok(it)
+    }
+
+    /// Checked constructor out of a buffer, which must end with the (only)
+    /// `NUL` terminator, and be valid UTF-8.
+    pub
+    fn from_bytes_with_nul (bytes: &'lt [u8])
+      -> Result<char_p_ref<'lt>, InvalidStr>
+    {
+        match bytes.iter().position(|&b| b == NUL) {
+            | Some(nul) if nul + 1 == bytes.len() => {
+                ::core::str::from_utf8(&bytes[.. nul])?;
+                Ok(unsafe {
+                    Self::from_ptr_unchecked(
+                        ptr::NonNull::new(bytes.as_ptr() as _).unwrap()
+                    )
+                })
+            },
+            | _ => Err(InvalidStr::MissingNul),
+        }
+    }
+}
+
 impl<'lt> TryFrom<&'lt str>
     for char_p_ref<'lt>
 {
@@ -137,6 +227,10 @@ cfg_std! {
         for InvalidNulTerminator<T>
     {}
 
+    impl ::std::error::Error
+        for InvalidStr
+    {}
+
     /// # Panic
     ///
     /// Panics if the `CStr` is not valid UTF-8.
@@ -476,11 +570,21 @@ cfg_alloc! {
                 InvalidNulTerminator<rust::String>,
             >
         {
-            Ok(if let ("" | "\0") = &*s {
-                unsafe {
-                    Self::from_ptr_unchecked(ptr::NonNull::new_unchecked(
-                        (&EMPTY_SENTINEL) as *const _ as *mut _
-                    ))
+            let it = if let ("" | "\0") = &*s {
+                if cfg!(feature = "c-allocator") {
+                    // An actual allocation, for C to be able to `free()` it.
+                    let boxed: rust::Box<[u8]> = rust::Box::new([NUL]);
+                    unsafe {
+                        Self::from_ptr_unchecked(ptr::NonNull::new_unchecked(
+                            rust::Box::into_raw(boxed).cast()
+                        ))
+                    }
+                } else {
+                    unsafe {
+                        Self::from_ptr_unchecked(ptr::NonNull::new_unchecked(
+                            (&EMPTY_SENTINEL) as *const _ as *mut _
+                        ))
+                    }
                 }
             } else {
                 let len_minus_one = s.len() - 1;
@@ -499,7 +603,21 @@ cfg_alloc! {
                             .unwrap()
                     )
                 }
-            })
+            };
+            #[cfg(feature = "leak-tracking")]
+            it.track();
+            Ok(it)
+        }
+    }
+
+    #[cfg(feature = "leak-tracking")]
+    impl char_p_boxed {
+        /// Registers the (actually boxed) string for leak tracking.
+        fn track (self: &'_ char_p_boxed)
+        {
+            if ptr::eq(self.0.as_ptr().cast(), &EMPTY_SENTINEL).not() {
+                crate::leak_tracking::track::<Self>(self.0.as_ptr() as *mut ());
+            }
         }
     }
 
@@ -510,6 +628,8 @@ cfg_alloc! {
                 if ptr::eq(self.0.as_mut_ptr().cast(), &EMPTY_SENTINEL) {
                     return;
                 }
+                #[cfg(feature = "leak-tracking")]
+                crate::leak_tracking::untrack(self.0.as_mut_ptr().cast());
                 let num_bytes = self.to_bytes_with_null().len();
                 drop::<rust::Box<[u8]>>(
                     rust::Box::from_raw(slice::from_raw_parts_mut(
@@ -579,6 +699,8 @@ cfg_alloc! {
             }
             let num_bytes = self.to_bytes_with_null().len();
             let ptr = mem::ManuallyDrop::new(self).0.as_mut_ptr();
+            #[cfg(feature = "leak-tracking")]
+            crate::leak_tracking::untrack(ptr.cast());
             let boxed_bytes = unsafe {
                 rust::Box::from_raw(slice::from_raw_parts_mut(
                     ptr.cast(),
@@ -652,12 +774,179 @@ cfg_std! {
                 s   .into_bytes_with_nul()
                     .into_boxed_slice()
             ;
-            unsafe {
+            let it = unsafe {
                 Self::from_ptr_unchecked(
                     ptr::NonNull::new(rust::Box::leak(s).as_mut_ptr())
                         .unwrap()
                 )
-            }
+            };
+            #[cfg(feature = "leak-tracking")]
+            it.track();
+            it
+        }
+    }
+}
+
+cfg_std! {
+    use ::std::{
+        ffi::{OsStr, OsString},
+        path::{Path, PathBuf},
+    };
+
+    /// The error of the (strict) conversions from [`Path`]s and [`OsStr`]s
+    /// to a [`char_p_boxed`].
+    ///
+    /// For lossy conversions, see [`char_p_boxed::from_path_lossy()`] and
+    /// [`char_p_boxed::from_os_str_lossy()`].
+    #[derive(Debug)]
+    pub
+    enum InvalidPath<Payload> {
+        /// The path is not valid UTF-8: arbitrary bytes on Unix, or
+        /// ill-formed UTF-16 (unpaired surrogates) on Windows.
+        NotUtf8(Payload),
+
+        /// The path contains an inner nul byte.
+        InnerNul(Payload),
+    }
+
+    impl<T> fmt::Display
+        for InvalidPath<T>
+    {
+        fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+          -> fmt::Result
+        {
+            fmt::Display::fmt(
+                match self {
+                    | Self::NotUtf8(_) => "Path is not valid UTF-8",
+                    | Self::InnerNul(_) => "Path contains an inner null byte",
+                },
+                fmt,
+            )
+        }
+    }
+
+    impl<T : fmt::Debug> ::std::error::Error
+        for InvalidPath<T>
+    {}
+
+    impl TryFrom<OsString>
+        for char_p_boxed
+    {
+        type Error = InvalidPath<OsString>;
+
+        fn try_from (s: OsString)
+          -> Result<
+                char_p_boxed,
+                InvalidPath<OsString>,
+            >
+        {
+            let s = s.into_string().map_err(InvalidPath::NotUtf8)?;
+            Self::try_from(s).map_err(|InvalidNulTerminator(s)| {
+                InvalidPath::InnerNul(s.into())
+            })
+        }
+    }
+
+    impl TryFrom<PathBuf>
+        for char_p_boxed
+    {
+        type Error = InvalidPath<PathBuf>;
+
+        #[inline]
+        fn try_from (path: PathBuf)
+          -> Result<
+                char_p_boxed,
+                InvalidPath<PathBuf>,
+            >
+        {
+            Self::try_from(path.into_os_string()).map_err(|err| match err {
+                | InvalidPath::NotUtf8(s) => InvalidPath::NotUtf8(s.into()),
+                | InvalidPath::InnerNul(s) => InvalidPath::InnerNul(s.into()),
+            })
+        }
+    }
+
+    impl<'lt> TryFrom<&'lt OsStr>
+        for char_p_boxed
+    {
+        type Error = InvalidPath<()>;
+
+        fn try_from (s: &'lt OsStr)
+          -> Result<
+                char_p_boxed,
+                InvalidPath<()>,
+            >
+        {
+            let s = s.to_str().ok_or(InvalidPath::NotUtf8(()))?;
+            Self::try_from(rust::String::from(s))
+                .map_err(|_| InvalidPath::InnerNul(()))
+        }
+    }
+
+    impl<'lt> TryFrom<&'lt Path>
+        for char_p_boxed
+    {
+        type Error = InvalidPath<()>;
+
+        #[inline]
+        fn try_from (path: &'lt Path)
+          -> Result<
+                char_p_boxed,
+                InvalidPath<()>,
+            >
+        {
+            Self::try_from(path.as_os_str())
+        }
+    }
+
+    impl char_p_boxed {
+        /// Same as the `TryFrom<&OsStr>` conversion, but for non-UTF-8
+        /// sequences being replaced with `U+FFFD` (_c.f._
+        /// [`OsStr::to_string_lossy()`]) rather than failing.
+        ///
+        /// On Windows, this only affects ill-formed UTF-16, so well-formed
+        /// paths never lose information.
+        #[inline]
+        pub
+        fn from_os_str_lossy (s: &'_ OsStr)
+          -> Result<
+                char_p_boxed,
+                InvalidNulTerminator<rust::String>,
+            >
+        {
+            Self::try_from(s.to_string_lossy().into_owned())
+        }
+
+        /// See [`char_p_boxed::from_os_str_lossy()`].
+        #[inline]
+        pub
+        fn from_path_lossy (path: &'_ Path)
+          -> Result<
+                char_p_boxed,
+                InvalidNulTerminator<rust::String>,
+            >
+        {
+            Self::from_os_str_lossy(path.as_os_str())
+        }
+
+        #[inline]
+        pub
+        fn to_path (self: &'_ char_p_boxed)
+          -> &'_ Path
+        {
+            self.as_ref().to_path()
+        }
+    }
+
+    impl<'lt> char_p_ref<'lt> {
+        /// Views the (UTF-8) string as a [`Path`], _e.g._, to open a file
+        /// whose name has been given by the FFI caller.
+        #[inline]
+        pub
+        fn to_path (self: char_p_ref<'lt>)
+          -> &'lt Path
+        {
+            Path::new(self.to_str())
         }
     }
 }
@@ -276,6 +276,9 @@ macro_rules! with_tuples {
 with_tuples! {
     ArcDynFn0,
 
+    (ArcDynFn12, A12),
+    (ArcDynFn11, A11),
+    (ArcDynFn10, A10),
     (ArcDynFn9, A9),
     (ArcDynFn8, A8),
     (ArcDynFn7, A7),
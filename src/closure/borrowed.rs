@@ -176,6 +176,9 @@ macro_rules! with_tuples {
 with_tuples! {
     RefDynFnMut0,
 
+    (RefDynFnMut12, A12),
+    (RefDynFnMut11, A11),
+    (RefDynFnMut10, A10),
     (RefDynFnMut9, A9),
     (RefDynFnMut8, A8),
     (RefDynFnMut7, A7),
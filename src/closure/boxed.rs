@@ -218,6 +218,9 @@ macro_rules! with_tuples {
 with_tuples! {
     BoxDynFnMut0,
 
+    (BoxDynFnMut12, A12),
+    (BoxDynFnMut11, A11),
+    (BoxDynFnMut10, A10),
     (BoxDynFnMut9, A9),
     (BoxDynFnMut8, A8),
     (BoxDynFnMut7, A7),
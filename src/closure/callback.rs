@@ -0,0 +1,240 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A host-provided callback along with its `user_data` (context), _i.e._,
+//! the `{ fn_ptr, ctx }` pair with which C expresses closures, rather than a
+//! bare function pointer (which forces the context through globals).
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::{closure::BorrowedCallback, prelude::*};
+//!
+//! #[ffi_export]
+//! fn mylib_for_each_item (
+//!     list: &'_ List,
+//!     mut cb: BorrowedCallback<'_, (u32, char_p::Ref<'_>), bool>,
+//! )
+//! {
+//!     for item in list.iter() {
+//!         if cb.call(item.id, item.name.as_ref()).not() {
+//!             break;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! where `cb` is, in C, a struct pairing the callback with its context:
+//!
+//! ```C
+//! typedef struct {
+//!     bool (*fn_ptr)(void * ctx, uint32_t, char const *);
+//!     void * ctx;
+//! } BorrowedCallback2_…_t;
+//! ```
+//!
+//! [`BorrowedCallback<'lt, Args, Ret>`][`BorrowedCallback`] names, for each
+//! arity of the `Args` tuple (up to 12), the matching `BorrowedCallbackN`
+//! type (_e.g._, [`BorrowedCallback1<'lt, Ret, A1>`][`BorrowedCallback1`]).
+
+use_prelude!();
+
+/// `BorrowedCallbackN<'lt, Ret, A1, …, AN>`, with `Args = (A1, …, AN)`.
+pub
+type BorrowedCallback<'lt, Args, Ret> =
+    <Args as CallbackArgs<'lt, Ret>>::BorrowedCallback
+;
+
+/// The tuples of (up to 12) `ReprC` types, as the arguments of a
+/// [`BorrowedCallback`].
+pub
+trait CallbackArgs<'lt, Ret : ReprC> {
+    type BorrowedCallback : ReprC;
+}
+
+macro_rules! with_tuple {(
+    $BorrowedCallback_N:ident => (
+        $( $A_N:ident, $($A_k:ident ,)* )?
+    )
+) => (
+    ReprC! {
+        @[doc = concat!(
+            "A host-provided `Ret (*)(void * ctx" $(,
+                ", ", stringify!($A_N) $(, ", ", stringify!($A_k))*
+            )?,
+            ")` callback, along with its `ctx`, valid (to call) for `'lt`.",
+        )]
+        #[repr(C)]
+        pub
+        struct $BorrowedCallback_N ['lt, Ret $(, $A_N $(, $A_k)*)?]
+        where {
+            Ret : ReprC, $(
+            $A_N : ReprC, $(
+            $A_k : ReprC, )*)?
+        }
+        {
+            fn_ptr:
+                unsafe extern "C"
+                fn (
+                    ctx: *mut c_void $(,
+                        $A_N $(,
+                        $A_k
+                    )*)?
+                ) -> Ret
+            ,
+            ctx: *mut c_void,
+            _lt: PhantomData<&'lt mut ()>,
+        }
+    }
+
+    impl<'lt, Ret $(, $A_N $(, $A_k)*)?>
+        CallbackArgs<'lt, Ret>
+    for
+        ($($A_N, $($A_k ,)*)?)
+    where
+        Ret : ReprC, $(
+        $A_N : ReprC, $(
+        $A_k : ReprC, )*)?
+    {
+        type BorrowedCallback = $BorrowedCallback_N<'lt, Ret $(, $A_N $(, $A_k)*)?>;
+    }
+
+    impl<'lt, Ret $(, $A_N $(, $A_k)*)?>
+        $BorrowedCallback_N <'lt, Ret $(, $A_N $(, $A_k)*)?>
+    where
+        Ret : ReprC, $(
+        $A_N : ReprC, $(
+        $A_k : ReprC, )*)?
+    {
+        /// Pairs a C callback with its `ctx`.
+        ///
+        /// # Safety
+        ///
+        /// For the duration of `'lt`, it must be sound to call `fn_ptr` with
+        /// `ctx`.
+        #[inline]
+        pub
+        unsafe
+        fn from_raw (
+            fn_ptr:
+                unsafe extern "C"
+                fn (
+                    ctx: *mut c_void $(,
+                        $A_N $(,
+                        $A_k
+                    )*)?
+                ) -> Ret
+            ,
+            ctx: *mut c_void,
+        ) -> Self
+        {
+            Self { fn_ptr, ctx, _lt: PhantomData }
+        }
+
+        /// A callback borrowing a Rust closure (_e.g._, to call a
+        /// callback-taking export from Rust).
+        #[inline]
+        pub
+        fn from_fn_mut<F> (f: &'lt mut F)
+          -> Self
+        where
+            F : FnMut( $($A_N $(, $A_k)*)? ) -> Ret,
+        {
+            unsafe extern "C"
+            fn fn_ptr<F, Ret $(, $A_N $(, $A_k)*)?> (
+                ctx: *mut c_void $(,
+                $A_N : $A_N $(,
+                $A_k : $A_k )*)?
+            ) -> Ret
+            where
+                F : FnMut($($A_N $(, $A_k)*)?) -> Ret,
+            {
+                let f = &mut *ctx.cast::<F>();
+                f( $($A_N $(, $A_k)*)? )
+            }
+            unsafe {
+                Self::from_raw(
+                    fn_ptr::<F, Ret $(, $A_N $(, $A_k)*)?>,
+                    <*mut F>::cast(f),
+                )
+            }
+        }
+
+        #[inline]
+        pub
+        fn call (
+            self: &'_ mut Self $(,
+            $A_N : $A_N $(,
+            $A_k : $A_k )*)?
+        ) -> Ret
+        {
+            unsafe {
+                (self.fn_ptr)(self.ctx, $($A_N $(, $A_k)*)?)
+            }
+        }
+    }
+
+    impl<Ret $(, $A_N $(, $A_k)*)?> fmt::Debug
+        for $BorrowedCallback_N <'_, Ret $(, $A_N $(, $A_k)*)?>
+    where
+        Ret : ReprC, $(
+        $A_N : ReprC, $(
+        $A_k : ReprC, )*)?
+    {
+        fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+          -> fmt::Result
+        {
+            fmt .debug_struct(stringify!($BorrowedCallback_N))
+                .field("fn_ptr", &(self.fn_ptr as *const ()))
+                .field("ctx", &self.ctx)
+                .finish()
+        }
+    }
+)}
+
+macro_rules! with_tuples {
+    (
+        $BorrowedCallback0:ident,
+    ) => (
+        with_tuple!($BorrowedCallback0 => ());
+    );
+
+    (
+        $BorrowedCallback0:ident,
+        ($BorrowedCallback_N:ident, $A_N:ident),
+        $(
+            ($BorrowedCallback_K:ident, $A_K:ident),
+        )*
+    ) => (
+        with_tuple!($BorrowedCallback_N => (
+            $A_N, $($A_K ,)*
+        ));
+        with_tuples!(
+            $BorrowedCallback0,
+            $(
+                ($BorrowedCallback_K, $A_K),
+            )*
+        );
+    );
+}
+
+#[cfg(not(docs))]
+with_tuples! {
+    BorrowedCallback0,
+
+    (BorrowedCallback12, A12),
+    (BorrowedCallback11, A11),
+    (BorrowedCallback10, A10),
+    (BorrowedCallback9, A9),
+    (BorrowedCallback8, A8),
+    (BorrowedCallback7, A7),
+    (BorrowedCallback6, A6),
+
+    (BorrowedCallback5, A5),
+    (BorrowedCallback4, A4),
+    (BorrowedCallback3, A3),
+    (BorrowedCallback2, A2),
+    (BorrowedCallback1, A1),
+}
+
+#[cfg(docs)]
+with_tuples! {
+    BorrowedCallback0,
+    (BorrowedCallback1, A1),
+}
@@ -1,9 +1,9 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 //! Closures with a `#[repr(C)]` layout (inlined vtable),
-//! up to 9 function arguments.
+//! up to 12 function arguments.
 //!
 //! Simplified for lighter documentation, but the actual `struct` definitions
-//! and impls range up to `...DynFn...9`.
+//! and impls range up to `...DynFn...12`.
 //!
 //! ## Examples
 //!
@@ -66,16 +66,35 @@ cfg_alloc! {
         arc::{
             ArcDynFn2, ArcDynFn3, ArcDynFn4, ArcDynFn5,
             ArcDynFn6, ArcDynFn7, ArcDynFn8, ArcDynFn9,
+            ArcDynFn10, ArcDynFn11, ArcDynFn12,
         },
         boxed::{
             BoxDynFnMut2, BoxDynFnMut3, BoxDynFnMut4, BoxDynFnMut5,
             BoxDynFnMut6, BoxDynFnMut7, BoxDynFnMut8, BoxDynFnMut9,
+            BoxDynFnMut10, BoxDynFnMut11, BoxDynFnMut12,
         },
     };
 }
 
 pub mod borrowed;
 
+pub mod callback;
+
+#[doc(no_inline)]
+pub use callback::{
+    BorrowedCallback,
+    BorrowedCallback0,
+    BorrowedCallback1,
+    CallbackArgs,
+};
+#[cfg(not(docs))]
+#[doc(no_inline)]
+pub use callback::{
+    BorrowedCallback2, BorrowedCallback3, BorrowedCallback4, BorrowedCallback5,
+    BorrowedCallback6, BorrowedCallback7, BorrowedCallback8, BorrowedCallback9,
+    BorrowedCallback10, BorrowedCallback11, BorrowedCallback12,
+};
+
 #[doc(no_inline)]
 pub use borrowed::{RefDynFnMut0, RefDynFnMut1};
 #[cfg(not(docs))]
@@ -83,4 +102,5 @@ pub use borrowed::{RefDynFnMut0, RefDynFnMut1};
 pub use borrowed::{
     RefDynFnMut2, RefDynFnMut3, RefDynFnMut4, RefDynFnMut5,
     RefDynFnMut6, RefDynFnMut7, RefDynFnMut8, RefDynFnMut9,
+    RefDynFnMut10, RefDynFnMut11, RefDynFnMut12,
 };
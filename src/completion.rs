@@ -0,0 +1,231 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Completion callbacks, for `#[ffi_export(async_via = callback)]`.
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export(async_via = callback, executor = |fut| RUNTIME.spawn(fut))]
+//! async fn mylib_fetch (id: u32)
+//!   -> i32
+//! {
+//!     ffi_await!(async move {
+//!         fetch(id).await
+//!     })
+//! }
+//! ```
+//!
+//! exports a (non-`async`) `mylib_fetch()` function taking, as an extra
+//! trailing parameter, the [`Completion`] to be called with the result once
+//! the future (spawned onto the given `executor`) resolves:
+//!
+//! ```C
+//! typedef struct Completion_int32 {
+//!     void * user_data;
+//!     void (*on_done)(void *, int32_t);
+//! } Completion_int32_t;
+//!
+//! void mylib_fetch (
+//!     uint32_t id,
+//!     Completion_int32_t completion);
+//! ```
+//!
+//! The functions returning `()` take a [`VoidCompletion`] instead.
+//!
+//! Beware that `on_done` is called from whichever thread the executor polls
+//! the future on, and never called (nor its `user_data` released) should the
+//! future be dropped before completion.
+
+use_prelude!();
+
+ReprC! {
+    #[repr(C)]
+    /// A host-provided callback, to be called exactly once with the result of
+    /// an operation.
+    ///
+    /// # C layout
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     void * user_data;
+    ///     void (*on_done)(void * user_data, T ret);
+    /// } Completion_T_t;
+    /// ```
+    pub
+    struct Completion[T]
+    where {
+        T : ReprC,
+    }
+    {
+        user_data: *mut c_void,
+        on_done: unsafe extern "C" fn (user_data: *mut c_void, ret: T),
+    }
+}
+
+ReprC! {
+    #[repr(C)]
+    /// A host-provided callback, to be called exactly once when an operation
+    /// returning `()` completes.
+    ///
+    /// # C layout
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     void * user_data;
+    ///     void (*on_done)(void * user_data);
+    /// } VoidCompletion_t;
+    /// ```
+    pub
+    struct VoidCompletion {
+        user_data: *mut c_void,
+        on_done: unsafe extern "C" fn (user_data: *mut c_void),
+    }
+}
+
+/// The host, by handing the callback over, vouches for it being callable from
+/// any thread.
+unsafe
+impl<T : ReprC> Send
+    for Completion<T>
+{}
+
+unsafe
+impl Send
+    for VoidCompletion
+{}
+
+impl<T : ReprC> Completion<T> {
+    /// Wraps a C callback and its `user_data` into a [`Completion`].
+    ///
+    /// # Safety
+    ///
+    /// It must be sound to call `on_done` with `user_data` (once), from any
+    /// thread.
+    pub
+    unsafe
+    fn from_raw (
+        user_data: *mut c_void,
+        on_done: unsafe extern "C" fn (user_data: *mut c_void, ret: T),
+    ) -> Completion<T>
+    {
+        Self { user_data, on_done }
+    }
+
+    /// Hands `ret` over to the host.
+    pub
+    fn complete (self: Completion<T>, ret: T)
+    {
+        unsafe { (self.on_done)(self.user_data, ret) }
+    }
+
+    cfg_alloc! {
+        /// A callback owning a Rust closure (_e.g._, to call an
+        /// `async_via = callback` export from Rust).
+        pub
+        fn new<F> (f: F)
+          -> Completion<T>
+        where
+            F : 'static + Send + FnOnce(T),
+        {
+            unsafe {
+                Self::from_raw(
+                    rust::Box::into_raw(rust::Box::new(f)).cast(),
+                    call_boxed::<T, F>,
+                )
+            }
+        }
+    }
+}
+
+impl VoidCompletion {
+    /// Wraps a C callback and its `user_data` into a [`VoidCompletion`].
+    ///
+    /// # Safety
+    ///
+    /// It must be sound to call `on_done` with `user_data` (once), from any
+    /// thread.
+    pub
+    unsafe
+    fn from_raw (
+        user_data: *mut c_void,
+        on_done: unsafe extern "C" fn (user_data: *mut c_void),
+    ) -> VoidCompletion
+    {
+        Self { user_data, on_done }
+    }
+
+    /// Notifies the host of the completion.
+    pub
+    fn complete (self: VoidCompletion)
+    {
+        unsafe { (self.on_done)(self.user_data) }
+    }
+
+    cfg_alloc! {
+        /// A callback owning a Rust closure (_e.g._, to call an
+        /// `async_via = callback` export from Rust).
+        pub
+        fn new<F> (f: F)
+          -> VoidCompletion
+        where
+            F : 'static + Send + FnOnce(),
+        {
+            // The `user_data` of a `Completion::new()` is the boxed closure,
+            // here called with a dummy `bool`.
+            fn discarding<G> (g: G)
+              -> VoidCompletion
+            where
+                G : 'static + Send + FnOnce(bool),
+            {
+                unsafe extern "C"
+                fn on_done<G> (user_data: *mut c_void)
+                where
+                    G : FnOnce(bool),
+                {
+                    call_boxed::<bool, G>(user_data, true)
+                }
+                let Completion { user_data, on_done: _ } = Completion::new(g);
+                unsafe {
+                    VoidCompletion::from_raw(user_data, on_done::<G>)
+                }
+            }
+            discarding(move |_| f())
+        }
+    }
+}
+
+/// The `on_done` of a [`Completion::new()`]: `user_data` is the boxed `F`.
+#[cfg(feature = "alloc")]
+unsafe extern "C"
+fn call_boxed<T, F> (user_data: *mut c_void, ret: T)
+where
+    F : FnOnce(T),
+{
+    let f = rust::Box::from_raw(user_data.cast::<F>());
+    f(ret)
+}
+
+impl<T : ReprC> fmt::Debug
+    for Completion<T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("Completion")
+            .field("user_data", &self.user_data)
+            .field("on_done", &self.on_done)
+            .finish()
+    }
+}
+
+impl fmt::Debug
+    for VoidCompletion
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("VoidCompletion")
+            .field("user_data", &self.user_data)
+            .field("on_done", &self.on_done)
+            .finish()
+    }
+}
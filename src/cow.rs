@@ -0,0 +1,284 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! `#[repr(C)]` [`Cow`][`::alloc::borrow::Cow`]s, for APIs which sometimes
+//! return borrowed data and sometimes owned data, without having to always
+//! allocate.
+//!
+//! ```rust,ignore
+//! use ::std::borrow::Cow;
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export]
+//! fn mylib_label<'obj> (obj: &'obj Object)
+//!   -> repr_c::Cow<'obj, str>
+//! {
+//!     match &obj.label {
+//!         | Some(label) => Cow::Borrowed(&label[..]),
+//!         | None => Cow::Owned(format!("object #{}", obj.id)),
+//!     }.into()
+//! }
+//! ```
+//!
+//! A `repr_c::Cow` is owned by whoever receives it: when `tag` is `OWNED`,
+//! the data is freed along with it.
+
+use_prelude!();
+use ::alloc::borrow::Cow;
+use ::core::slice;
+
+/// The phantoms from the crate are not `ReprC`.
+type PhantomCovariantLifetime<'lt> =
+    PhantomData<&'lt ()>
+;
+
+/// Whether a [`slice_cow`] (or a [`str_cow`]) borrows or owns its data.
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum CowTag {
+    Borrowed,
+    Owned,
+}
+
+ReprC! {
+    #[repr(C)]
+    /// [`Cow`][`::alloc::borrow::Cow`]`<'lt, [T]>`, but with a guaranteed
+    /// `#[repr(C)]` layout.
+    ///
+    /// # C layout (for some given type T)
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     CowTag_t tag;
+    ///     // Cannot be NULL
+    ///     T * ptr;
+    ///     size_t len;
+    /// } slice_cow_T;
+    /// ```
+    pub
+    struct slice_cow['lt, T]
+    where {
+        T : 'lt,
+    }
+    {
+        /// Whether the slice is to be freed along with the `slice_cow`.
+        pub(in crate)
+        tag: CowTag,
+
+        /// Pointer to the first element (if any).
+        pub(in crate)
+        ptr: ptr::NonNull<T>,
+
+        /// Element count
+        pub(in crate)
+        len: usize,
+
+        pub(in crate)
+        _lt: PhantomCovariantLifetime<'lt>,
+    }
+}
+
+impl<'lt, T : 'lt> slice_cow<'lt, T> {
+    #[inline]
+    pub
+    fn tag (self: &'_ Self)
+      -> CowTag
+    {
+        self.tag
+    }
+}
+
+impl<'lt, T : 'lt + Clone> From<Cow<'lt, [T]>>
+    for slice_cow<'lt, T>
+{
+    fn from (cow: Cow<'lt, [T]>)
+      -> slice_cow<'lt, T>
+    {
+        let (tag, slice): (_, *const [T]) = match cow {
+            | Cow::Borrowed(slice) => (CowTag::Borrowed, slice),
+            | Cow::Owned(vec) => (
+                CowTag::Owned,
+                rust::Box::into_raw(vec.into_boxed_slice()),
+            ),
+        };
+        unsafe {
+            slice_cow {
+                tag,
+                len: (&*slice).len(),
+                ptr: ptr::NonNull::new_unchecked(slice as *mut T),
+                _lt: PhantomData,
+            }
+        }
+    }
+}
+
+impl<'lt, T : 'lt + Clone> From<slice_cow<'lt, T>>
+    for Cow<'lt, [T]>
+{
+    fn from (value: slice_cow<'lt, T>)
+      -> Cow<'lt, [T]>
+    {
+        let this = mem::ManuallyDrop::new(value);
+        unsafe {
+            match this.tag {
+                | CowTag::Borrowed => Cow::Borrowed(
+                    slice::from_raw_parts(this.ptr.as_ptr(), this.len)
+                ),
+                | CowTag::Owned => Cow::Owned(
+                    rust::Box::from_raw(
+                        slice::from_raw_parts_mut(this.ptr.as_ptr(), this.len)
+                    )
+                    .into_vec()
+                ),
+            }
+        }
+    }
+}
+
+impl<'lt, T : 'lt> Drop
+    for slice_cow<'lt, T>
+{
+    #[inline]
+    fn drop (self: &'_ mut Self)
+    {
+        if let CowTag::Owned = self.tag {
+            unsafe {
+                drop::<rust::Box<[T]>>(
+                    rust::Box::from_raw(
+                        slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len)
+                    )
+                );
+            }
+        }
+    }
+}
+
+impl<'lt, T : 'lt> Deref
+    for slice_cow<'lt, T>
+{
+    type Target = [T];
+
+    #[inline]
+    fn deref (self: &'_ Self)
+      -> &'_ [T]
+    {
+        unsafe {
+            slice::from_raw_parts(self.ptr.as_ptr(), self.len)
+        }
+    }
+}
+
+impl<'lt, T : 'lt + fmt::Debug> fmt::Debug
+    for slice_cow<'lt, T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        <[T] as fmt::Debug>::fmt(self, fmt)
+    }
+}
+
+unsafe // Safety: same as `Cow<'lt, [T]>` (`&'lt [T]` or `Box<[T]>`).
+    impl<'lt, T : 'lt> Send
+        for slice_cow<'lt, T>
+    where
+        T : Send + Sync,
+    {}
+unsafe // Safety: same as `Cow<'lt, [T]>` (`&'lt [T]` or `Box<[T]>`).
+    impl<'lt, T : 'lt> Sync
+        for slice_cow<'lt, T>
+    where
+        T : Sync,
+    {}
+
+ReprC! {
+    #[repr(transparent)]
+    /// [`Cow`][`::alloc::borrow::Cow`]`<'lt, str>`, but with a guaranteed
+    /// `#[repr(C)]` layout.
+    pub
+    struct str_cow['lt,] (
+        slice_cow<'lt, u8>,
+    );
+}
+
+impl<'lt> str_cow<'lt> {
+    #[inline]
+    pub
+    fn tag (self: &'_ Self)
+      -> CowTag
+    {
+        self.0.tag
+    }
+}
+
+impl<'lt> From<Cow<'lt, str>>
+    for str_cow<'lt>
+{
+    #[inline]
+    fn from (cow: Cow<'lt, str>)
+      -> str_cow<'lt>
+    {
+        Self(match cow {
+            | Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+            | Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        }.into())
+    }
+}
+
+impl<'lt> From<str_cow<'lt>>
+    for Cow<'lt, str>
+{
+    #[inline]
+    fn from (value: str_cow<'lt>)
+      -> Cow<'lt, str>
+    {
+        unsafe {
+            match Cow::from(value.0) {
+                | Cow::Borrowed(bytes) => Cow::Borrowed(
+                    ::core::str::from_utf8_unchecked(bytes)
+                ),
+                | Cow::Owned(bytes) => Cow::Owned(
+                    rust::String::from_utf8_unchecked(bytes)
+                ),
+            }
+        }
+    }
+}
+
+impl<'lt> Deref
+    for str_cow<'lt>
+{
+    type Target = str;
+
+    #[inline]
+    fn deref (self: &'_ str_cow<'lt>)
+      -> &'_ str
+    {
+        unsafe {
+            ::core::str::from_utf8_unchecked(&self.0)
+        }
+    }
+}
+
+impl fmt::Debug
+    for str_cow<'_>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        <str as fmt::Debug>::fmt(self, fmt)
+    }
+}
+
+pub
+trait FitForCCow<'lt> {
+    type CCowWrapped;
+}
+
+impl<'lt, T : 'lt + Clone> FitForCCow<'lt> for [T] {
+    type CCowWrapped = slice_cow<'lt, T>;
+}
+
+impl<'lt> FitForCCow<'lt> for str {
+    type CCowWrapped = str_cow<'lt>;
+}
@@ -0,0 +1,157 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Runtime toggles for costly debug checking layers, so that hosts may only
+//! turn them on when reproducing a bug, through an exported
+//! `mylib_set_debug_checks()` (see [`ffi_export_debug_checks!`]).
+//!
+//! The library then gates each of its checks on [`enabled()`]:
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::debug_checks::{self, DebugChecks};
+//!
+//! #[ffi_export]
+//! fn mylib_widget_free (widget: repr_c::Box<Widget>)
+//! {
+//!     if debug_checks::enabled(DebugChecks::THREAD_AFFINITY) {
+//!         assert_eq!(widget.owner_thread, ::std::thread::current().id());
+//!     }
+//!     drop(widget);
+//! }
+//! ```
+//!
+//! [`ffi_export_debug_checks!`]: `crate::ffi_export_debug_checks`
+
+use_prelude!();
+use ::core::sync::atomic::{self, AtomicU32};
+
+/// A set of debug checking layers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub
+struct DebugChecks(pub u32);
+
+impl DebugChecks {
+    /// Tracking of which side owns each pointer handed over the FFI boundary
+    /// (_e.g._, to catch double frees).
+    pub
+    const OWNERSHIP_TRACKING: Self = Self(1 << 0);
+
+    /// Checking that thread-bound objects are only used from their thread.
+    pub
+    const THREAD_AFFINITY: Self = Self(1 << 1);
+
+    /// Reporting of the objects not freed by the host (see the
+    /// `leak-tracking` Cargo feature).
+    pub
+    const LEAK_CHECK: Self = Self(1 << 2);
+
+    /// All of the above.
+    pub
+    const ALL: Self = Self(
+        Self::OWNERSHIP_TRACKING.0
+        | Self::THREAD_AFFINITY.0
+        | Self::LEAK_CHECK.0
+    );
+
+    /// Whether all the layers in `other` are in `self`.
+    pub
+    fn contains (self: Self, other: Self)
+      -> bool
+    {
+        self.0 & other.0 == other.0
+    }
+}
+
+static ENABLED: AtomicU32 = AtomicU32::new(0);
+
+/// Whether all the given checking layers are currently enabled.
+///
+/// Always `false` in builds without `debug_assertions`, so that the checks
+/// get optimized out of release builds.
+pub
+fn enabled (checks: DebugChecks)
+  -> bool
+{
+    cfg!(debug_assertions)
+    &&
+    DebugChecks(ENABLED.load(atomic::Ordering::Relaxed)).contains(checks)
+}
+
+/// Enables exactly the given checking layers (disabling the other ones).
+///
+/// Returns `false`, and does nothing, in builds without `debug_assertions`,
+/// or if `checks` features unknown layers.
+pub
+fn set (checks: DebugChecks)
+  -> bool
+{
+    if cfg!(debug_assertions).not() || DebugChecks::ALL.contains(checks).not() {
+        return false;
+    }
+    ENABLED.store(checks.0, atomic::Ordering::Relaxed);
+    true
+}
+
+/// Defines and exports `<prefix>_set_debug_checks()`, along with the
+/// constants of the [`DebugChecks`][`crate::debug_checks::DebugChecks`]
+/// layers.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_debug_checks!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// #define MYLIB_DEBUG_CHECK_OWNERSHIP_TRACKING ((uint32_t) 1)
+///
+/// #define MYLIB_DEBUG_CHECK_THREAD_AFFINITY ((uint32_t) 2)
+///
+/// #define MYLIB_DEBUG_CHECK_LEAK_CHECK ((uint32_t) 4)
+///
+/// /** \brief
+///  *  Enables exactly the given `…_DEBUG_CHECK_…` layers (disabling the other
+///  *  ones).
+///  * ...
+///  */
+/// bool mylib_set_debug_checks (
+///     uint32_t flags);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_debug_checks {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Tracking of which side owns each pointer handed over the FFI boundary.
+    pub
+    const [<$prefix:upper _DEBUG_CHECK_OWNERSHIP_TRACKING>]: u32 =
+        $crate::debug_checks::DebugChecks::OWNERSHIP_TRACKING.0
+    ;
+
+    #[$crate::ffi_export]
+    /// Checking that thread-bound objects are only used from their thread.
+    pub
+    const [<$prefix:upper _DEBUG_CHECK_THREAD_AFFINITY>]: u32 =
+        $crate::debug_checks::DebugChecks::THREAD_AFFINITY.0
+    ;
+
+    #[$crate::ffi_export]
+    /// Reporting of the objects not freed by the host.
+    pub
+    const [<$prefix:upper _DEBUG_CHECK_LEAK_CHECK>]: u32 =
+        $crate::debug_checks::DebugChecks::LEAK_CHECK.0
+    ;
+
+    #[$crate::ffi_export]
+    /// Enables exactly the given `…_DEBUG_CHECK_…` layers (disabling the other
+    /// ones).
+    ///
+    /// Returns `false`, doing nothing, if the library has been built without
+    /// debug checking support, or if `flags` features unknown layers.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _set_debug_checks>] (
+        flags: u32,
+    ) -> bool
+    {
+        $crate::debug_checks::set($crate::debug_checks::DebugChecks(flags))
+    }
+})}
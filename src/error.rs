@@ -0,0 +1,134 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A thread-local "last error" message, for the host to query after a
+//! function reports a failure (_à la_ `errno` / `GetLastError()`).
+//!
+//! The library records it with [`set_last_error()`], and exports the accessor
+//! through [`ffi_export_last_error!`]:
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! ::safer_ffi::ffi_export_last_error!(mylib);
+//!
+//! #[ffi_export(last_error)]
+//! fn mylib_open (path: char_p::Ref<'_>)
+//!   -> Result<(), MyError>
+//! {
+//!     // …
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! MyError_t mylib_open (
+//!     char const * path);
+//!
+//! /** \brief
+//!  *  The message of the last error which occurred on the calling thread,
+//!  *  if any, to be freed with `mylib_last_error_free()`.
+//!  */
+//! char * mylib_last_error_message (void);
+//!
+//! /** \brief
+//!  *  Frees a string obtained from `mylib_last_error_message()`.
+//!  */
+//! void mylib_last_error_free (
+//!     char * message);
+//! ```
+//!
+//! With `#[ffi_export(last_error)]`, a function returning a `Result` (see
+//! [the `error_code` module][`crate::error_code`]) records the
+//! [`Display`][`fmt::Display`] of its `Err`ors with [`set_last_error()`].
+//!
+//! [`ffi_export_last_error!`]: `crate::ffi_export_last_error`
+
+use_prelude!();
+use ::std::cell::RefCell;
+
+::std::thread_local! {
+    static LAST_ERROR: RefCell<Option<rust::String>> = RefCell::new(None);
+}
+
+/// Records the message of an error, replacing the previous one (of the
+/// calling thread).
+pub
+fn set_last_error (err: impl fmt::Display)
+{
+    let message = err.to_string();
+    LAST_ERROR.with(|it| *it.borrow_mut() = Some(message));
+}
+
+/// Forgets about the last error (of the calling thread).
+pub
+fn clear_last_error ()
+{
+    LAST_ERROR.with(|it| *it.borrow_mut() = None);
+}
+
+/// The message of the last error recorded on the calling thread, if any.
+///
+/// Its interior nul bytes, if any, are replaced with `U+FFFD`.
+pub
+fn last_error_message ()
+  -> Option<char_p::Box>
+{
+    LAST_ERROR.with(|it| {
+        it.borrow().as_ref().map(|message| {
+            char_p::new(message.replace('\0', "\u{fffd}"))
+        })
+    })
+}
+
+/// Defines and exports `<prefix>_last_error_message()`, and the matching
+/// `<prefix>_last_error_free()`.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_last_error!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The message of the last error which occurred on the calling thread,
+///  *  if any, to be freed with `mylib_last_error_free()`.
+///  */
+/// char * mylib_last_error_message (void);
+///
+/// /** \brief
+///  *  Frees a string obtained from `mylib_last_error_message()`.
+///  */
+/// void mylib_last_error_free (
+///     char * message);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_last_error {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    #[doc = $crate::ඞ::concat!(
+        " The message of the last error which occurred on the calling thread,\n",
+        " if any, to be freed with `", $crate::ඞ::stringify!($prefix), "_last_error_free()`.",
+    )]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _last_error_message>] ()
+      -> $crate::ඞ::Option<$crate::prelude::char_p::Box>
+    {
+        $crate::error::last_error_message()
+    }
+
+    #[$crate::ffi_export]
+    #[doc = $crate::ඞ::concat!(
+        " Frees a string obtained from `", $crate::ඞ::stringify!($prefix), "_last_error_message()`.",
+    )]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _last_error_free>] (
+        message: $crate::ඞ::Option<$crate::prelude::char_p::Box>,
+    )
+    {
+        $crate::ඞ::mem::drop(message)
+    }
+})}
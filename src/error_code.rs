@@ -0,0 +1,65 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Status codes, for `#[ffi_export]`-ed functions returning a `Result`.
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[derive_ReprC]
+//! #[repr(i32)]
+//! pub
+//! enum MyError {
+//!     Ok = 0,
+//!     NotFound = 1,
+//! }
+//!
+//! impl ::safer_ffi::error_code::ErrorCode for MyError {
+//!     const SUCCESS: Self = MyError::Ok;
+//! }
+//!
+//! #[ffi_export]
+//! fn mylib_lookup (key: u32)
+//!   -> Result<u64, MyError>
+//! {
+//!     TABLE.get(&key).copied().ok_or(MyError::NotFound)
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! MyError_t mylib_lookup (
+//!     uint32_t key,
+//!     uint64_t * out);
+//! ```
+//!
+//! `Ok(value)` writes `value` to `out` (unless it is `NULL`, in which case the
+//! value is dropped) and returns [`ErrorCode::SUCCESS`], whereas `Err(err)`
+//! returns `err`, leaving `out` untouched. A `Result<(), E>` has no `out`
+//! parameter.
+
+use_prelude!();
+
+/// An error type, to be returned as is to C, with a dedicated value for
+/// success.
+pub
+trait ErrorCode : ReprC {
+    /// The value returned in the `Ok` case.
+    const SUCCESS: Self;
+}
+
+macro_rules! impl_ErrorCode {(
+    $($int:ty),* $(,)?
+) => (
+    $(
+        impl ErrorCode
+            for $int
+        {
+            const SUCCESS: Self = 0;
+        }
+    )*
+)}
+
+impl_ErrorCode! {
+    u8, u16, u32, u64, usize,
+    i8, i16, i32, i64, isize,
+}
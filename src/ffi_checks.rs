@@ -0,0 +1,218 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A "checked" build of the library (`debug-ffi-checks` Cargo feature), for
+//! the integration tests of its consumers.
+//!
+//! The `#[ffi_export]`ed functions then validate their arguments before using
+//! them:
+//!
+//!   - the bit-patterns which are not valid for the Rust type (_e.g._, a null
+//!     or misaligned pointer for a reference, or a `c_slice::Ref`);
+//!
+//!   - the slices (`c_slice::Ref`, `c_slice::Mut`, `str::Ref`) whose
+//!     `len * size_of::<T>()` overflows `isize::MAX`;
+//!
+//!   - the `str::Ref`s which are not valid UTF-8.
+//!
+//! Rather than the Undefined Behavior which such arguments would otherwise
+//! lead to, the [`Violation`] is handed over to the handler set with
+//! [`set_violation_handler()`] (by default, logging it), and the process is
+//! aborted. The handler may also panic: the panic is then that of the
+//! function (caught with `#[ffi_export(catch_panics = …)]`, or unwinding
+//! into the caller of an `extern "C-unwind"` function).
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::ffi_checks;
+//!
+//! #[test]
+//! fn integration ()
+//! {
+//!     ffi_checks::set_violation_handler(Some(|violation| panic!("{violation}")));
+//!     // …
+//! }
+//! ```
+
+use_prelude!();
+use ::std::sync::RwLock;
+use crate::{
+    slice::{slice_mut, slice_ref},
+    string::str_ref,
+};
+
+/// An invalid argument handed over to an `#[ffi_export]`ed function.
+#[derive(Debug, Clone, Copy)]
+pub
+struct Violation<'r> {
+    /// The name of the function.
+    pub
+    function: &'r str,
+
+    /// The name of the argument.
+    pub
+    argument: &'r str,
+
+    /// The Rust type of the argument.
+    pub
+    type_name: &'r str,
+
+    /// What is wrong with the argument.
+    pub
+    reason: &'r str,
+}
+
+impl fmt::Display
+    for Violation<'_>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        let Self { function, argument, type_name, reason } = *self;
+        write!(fmt,
+            "invalid argument `{argument}: {type_name}` of `{function}()`: \
+            {reason}",
+        )
+    }
+}
+
+/// What to do with a [`Violation`], before the process is aborted.
+pub
+type ViolationHandler = fn(&'_ Violation<'_>);
+
+static HANDLER: RwLock<Option<ViolationHandler>> = RwLock::new(None);
+
+/// Sets up the handler of the [`Violation`]s, from then on (`None` restoring
+/// the default one, which logs them).
+pub
+fn set_violation_handler (handler: Option<ViolationHandler>)
+{
+    *HANDLER.write().unwrap_or_else(|it| it.into_inner()) = handler;
+}
+
+#[doc(hidden)] /** Not part of the public API */
+#[cold]
+#[inline(never)]
+pub
+fn violation<T> (
+    function: &'_ str,
+    argument: &'_ str,
+    reason: &'_ str,
+) -> !
+{
+    let violation = Violation {
+        function,
+        argument,
+        type_name: ::core::any::type_name::<T>(),
+        reason,
+    };
+    let handler = *HANDLER.read().unwrap_or_else(|it| it.into_inner());
+    match handler {
+        | Some(handler) => handler(&violation),
+        | None => crate::ඞ::__error__!("Error, {violation}."),
+    }
+    ::std::process::abort()
+}
+
+/// `from_raw_unchecked()`, but for the invalid bit-patterns being reported
+/// as a [`Violation`].
+#[doc(hidden)] /** Not part of the public API */
+#[inline]
+pub
+unsafe
+fn from_raw<T : ReprC> (
+    c_layout: T::CLayout,
+    function: &'_ str,
+    argument: &'_ str,
+) -> T
+{
+    match unsafe { crate::layout::from_raw::<T>(c_layout) } {
+        | Some(it) => it,
+        | None => violation::<T>(
+            function,
+            argument,
+            "not a valid bit-pattern (e.g., a null or misaligned pointer)",
+        ),
+    }
+}
+
+/// Poor man's specialization of the checks of a (valid) argument, for the
+/// `#[ffi_export]` shims to call as `(&Arg(&arg)).check()`: [`Check`] for the
+/// types featuring extra checks, else the no-op [`CheckFallback`].
+#[doc(hidden)] /** Not part of the public API */
+#[allow(missing_debug_implementations)]
+pub
+struct Arg<'r, T> (
+    pub &'r T,
+);
+
+#[doc(hidden)] /** Not part of the public API */
+pub
+trait CheckFallback {
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    ;
+}
+
+impl<T> CheckFallback
+    for &'_ Arg<'_, T>
+{
+    #[inline]
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    {
+        Ok(())
+    }
+}
+
+#[doc(hidden)] /** Not part of the public API */
+pub
+trait Check {
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    ;
+}
+
+fn check_len<T> (len: usize)
+  -> Result<(), &'static str>
+{
+    match len.checked_mul(mem::size_of::<T>()) {
+        | Some(size) if size <= isize::MAX as usize => Ok(()),
+        | _ => Err("`len * size_of::<T>()` overflows `isize::MAX`"),
+    }
+}
+
+impl<T> Check
+    for Arg<'_, slice_ref<'_, T>>
+{
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    {
+        check_len::<T>(self.0.len)
+    }
+}
+
+impl<T> Check
+    for Arg<'_, slice_mut<'_, T>>
+{
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    {
+        check_len::<T>(self.0.len)
+    }
+}
+
+impl Check
+    for Arg<'_, str_ref<'_>>
+{
+    fn check (self: &'_ Self)
+      -> Result<(), &'static str>
+    {
+        let bytes = self.0.0;
+        check_len::<u8>(bytes.len)?;
+        let bytes = unsafe {
+            ::core::slice::from_raw_parts(bytes.ptr.as_ptr(), bytes.len)
+        };
+        match ::core::str::from_utf8(bytes) {
+            | Ok(_) => Ok(()),
+            | Err(_) => Err("not valid UTF-8"),
+        }
+    }
+}
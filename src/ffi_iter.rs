@@ -0,0 +1,231 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Lazily consuming host-provided sequences, through a C "next" callback
+//! (rather than requiring the host to materialize an array).
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::{prelude::*, ffi_iter::FfiIter};
+//!
+//! #[ffi_export]
+//! fn sum (numbers: FfiIter<'_, i32>)
+//!   -> i32
+//! {
+//!     numbers.sum()
+//! }
+//! ```
+//!
+//! Conversely, a [`repr_c::Iter`][`Iter`] hands a lazily-produced Rust sequence
+//! over to the host (rather than materializing a `repr_c::Vec`):
+//!
+//! ```rust,ignore
+//! #[ffi_export]
+//! fn mylib_primes ()
+//!   -> repr_c::Box<repr_c::Iter<u64>>
+//! {
+//!     repr_c::Iter::new((2..).filter(|&n| is_prime(n)))
+//! }
+//!
+//! ::safer_ffi::ffi_export_iter!(mylib_primes: Iter<u64>);
+//! ```
+
+use_prelude!();
+
+ReprC! {
+    #[repr(C)]
+    /// A host-provided sequence of `T`s, to be used as an [`Iterator`].
+    ///
+    /// # C layout (for some given type T)
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     void * user_data;
+    ///     // Writes the next element to `out` and returns `true`,
+    ///     // or returns `false` once the sequence is exhausted.
+    ///     bool (*next)(void * user_data, T * out);
+    /// } FfiIter_T;
+    /// ```
+    pub
+    struct FfiIter['lt, T]
+    where {
+        T : ReprC,
+    }
+    {
+        user_data: *mut c_void,
+        next: unsafe extern "C" fn (user_data: *mut c_void, out: *mut T) -> bool,
+        _lt: PhantomData<&'lt ()>,
+    }
+}
+
+/// Wraps a C "next" callback and its `user_data` into an [`FfiIter`].
+///
+/// # Safety
+///
+///   - For the duration of `'lt`, it must be sound to call `next` with
+///     `user_data`, as many times as needed until it returns `false`;
+///
+///   - when returning `true`, `next` must have written a valid `T` (in its C
+///     layout) to `out`.
+pub
+unsafe
+fn from_callback<'lt, T : ReprC> (
+    user_data: *mut c_void,
+    next: unsafe extern "C" fn (user_data: *mut c_void, out: *mut T) -> bool,
+) -> FfiIter<'lt, T>
+{
+    FfiIter {
+        user_data,
+        next,
+        _lt: PhantomData,
+    }
+}
+
+impl<T : ReprC> Iterator
+    for FfiIter<'_, T>
+{
+    type Item = T;
+
+    fn next (self: &'_ mut FfiIter<'_, T>)
+      -> Option<T>
+    {
+        let mut out = mem::MaybeUninit::<T::CLayout>::uninit();
+        unsafe {
+            if (self.next)(self.user_data, out.as_mut_ptr().cast()).not() {
+                return None;
+            }
+            let it = crate::layout::from_raw::<T>(out.assume_init());
+            if it.is_none() {
+                crate::ඞ::__error__!(
+                    "Got an invalid bit-pattern for `{}` from an `FfiIter`",
+                    ::core::any::type_name::<T>(),
+                );
+            }
+            it
+        }
+    }
+}
+
+impl<T : ReprC> fmt::Debug
+    for FfiIter<'_, T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("FfiIter")
+            .field("user_data", &self.user_data)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+cfg_alloc! {
+    #[derive_ReprC]
+    #[repr(opaque)]
+    #[cfg_attr(all(docs, feature = "nightly"), doc(cfg(feature = "alloc")))]
+    /// A (boxed) Rust [`Iterator`], for the host to consume through the
+    /// functions exported by [`ffi_export_iter!`][`crate::ffi_export_iter`],
+    /// behind a pointer (_e.g._, a [`repr_c::Box`][`crate::prelude::repr_c::Box`]).
+    pub
+    struct Iter<T> {
+        iter: rust::Box<dyn 'static + Send + Iterator<Item = T>>,
+    }
+
+    impl<T> Iter<T> {
+        pub
+        fn new<I> (iter: I)
+          -> repr_c::Box<Iter<T>>
+        where
+            I : IntoIterator<Item = T>,
+            I::IntoIter : 'static + Send,
+        {
+            rust::Box::new(Iter {
+                iter: rust::Box::new(iter.into_iter()),
+            })
+            .into()
+        }
+    }
+
+    impl<T> Iterator
+        for Iter<T>
+    {
+        type Item = T;
+
+        #[inline]
+        fn next (self: &'_ mut Iter<T>)
+          -> Option<T>
+        {
+            self.iter.next()
+        }
+
+        #[inline]
+        fn size_hint (self: &'_ Iter<T>)
+          -> (usize, Option<usize>)
+        {
+            self.iter.size_hint()
+        }
+    }
+
+    impl<T> fmt::Debug
+        for Iter<T>
+    {
+        fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+          -> fmt::Result
+        {
+            fmt .debug_struct("Iter")
+                .field("size_hint", &self.size_hint())
+                .finish()
+        }
+    }
+}
+
+/// Defines and exports `<prefix>_next()` and `<prefix>_free()`, for C to
+/// consume a [`repr_c::Iter<T>`][`Iter`].
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_iter!(mylib_primes: Iter<u64>);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// bool mylib_primes_next (
+///     Iter_uint64_t * it,
+///     uint64_t * out);
+///
+/// void mylib_primes_free (
+///     Iter_uint64_t * it);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_iter {(
+    $prefix:ident : Iter<$T:ty $(,)?> $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Moves the next element of the sequence into `out`.
+    ///
+    /// Returns `false`, leaving `out` untouched, once the sequence is
+    /// exhausted.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _next>] (
+        it: &'_ mut $crate::prelude::repr_c::Iter<$T>,
+        out: $crate::prelude::Out<'_, $T>,
+    ) -> bool
+    {
+        match $crate::ඞ::Iterator::next(it) {
+            | $crate::ඞ::Some(value) => {
+                out.write(value);
+                true
+            },
+            | $crate::ඞ::None => false,
+        }
+    }
+
+    #[$crate::ffi_export]
+    /// Frees the sequence, along with the elements not yet consumed.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _free>] (
+        it: $crate::prelude::repr_c::Box<$crate::prelude::repr_c::Iter<$T>>,
+    )
+    {
+        $crate::ඞ::drop(it)
+    }
+})}
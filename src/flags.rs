@@ -0,0 +1,253 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Option sets ("bitflags"), exported along with a parser of their textual
+//! form, so that configuration-heavy hosts can turn a `"a,b,c"` setting
+//! (_e.g._, from a config file) into a flags value using Rust's parsing.
+
+/// Defines and exports a set of flags, backed by an integer.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_flags! {
+///     /// The rendering options.
+///     pub
+///     struct RenderFlags: u32 {
+///         /// Smoothes the edges.
+///         const ANTIALIAS = 1 << 0;
+///
+///         const DITHER = 1 << 1;
+///     }
+/// }
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The rendering options.
+///  */
+/// typedef uint32_t RenderFlags_t;
+///
+/// /** \brief
+///  *  Smoothes the edges.
+///  */
+/// #define RENDER_FLAGS_ANTIALIAS ((uint32_t) 1)
+///
+/// /** <No documentation available> */
+/// #define RENDER_FLAGS_DITHER ((uint32_t) 2)
+///
+/// /** \brief
+///  *  Parses a comma-separated list of `RenderFlags` names,
+///  *  case-insensitively.
+///  * ...
+///  */
+/// bool RenderFlags_from_csv (
+///     char const * csv,
+///     RenderFlags_t * out);
+/// ```
+///
+/// and, in C#:
+///
+/// ```csharp
+/// /// <summary>
+/// /// The rendering options.
+/// /// </summary>
+/// [Flags]
+/// public enum RenderFlags_t : UInt32 {
+///     /// <summary>
+///     /// Smoothes the edges.
+///     /// </summary>
+///     ANTIALIAS = 1,
+///     DITHER = 2,
+/// }
+/// ```
+///
+///   - The type itself is a `#[repr(transparent)]` wrapper around the integer
+///     (and is thus `ReprC`), with the flags also available as associated
+///     constants (`RenderFlags::ANTIALIAS`) and as `RENDER_FLAGS_ANTIALIAS`
+///     constants, and the `|`, `&` and `!` operators.
+///
+///   - The `…_from_csv()` function (_e.g._, fed `"antialias, dither"`) returns
+///     `false`, leaving `out` untouched, if some name is not a known flag.
+///     Surrounding whitespace and empty items are ignored (so `""` yields no
+///     flags).
+#[macro_export]
+macro_rules! ffi_export_flags {(
+    $(#[$($attr:tt)*])*
+    $pub:vis
+    struct $Flags:ident : $Int:ty {
+        $(
+            $(#[$($flag_attr:tt)*])*
+            const $FLAG:ident = $value:expr;
+        )*
+    }
+) => ($crate::paste::item! {
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    $(#[$($attr)*])*
+    $pub
+    struct $Flags(pub $Int);
+
+    unsafe
+    impl $crate::layout::CType for $Flags {
+        type OPAQUE_KIND = $crate::layout::OpaqueKind::Concrete;
+
+        $crate::__cfg_headers__! {
+            fn short_name ()
+              -> $crate::ඞ::String
+            {
+                $crate::ඞ::stringify!($Flags).into()
+            }
+
+            #[allow(nonstandard_style)]
+            fn define_self__impl (
+                language: &'_ dyn $crate::ඞ::HeaderLanguage,
+                definer: &'_ mut dyn $crate::ඞ::Definer,
+            ) -> $crate::ඞ::io::Result<()>
+            {
+                <$Int as $crate::layout::CType>::define_self(language, definer)?;
+                language.emit_flags(
+                    definer,
+                    $crate::__flags_docs__!([] $(#[$($attr)*])*),
+                    &$crate::ඞ::PhantomData::<Self>,
+                    &$crate::ඞ::PhantomData::<$Int>,
+                    &[$(
+                        $crate::ඞ::EnumVariant {
+                            docs: $crate::__flags_docs__!([] $(#[$($flag_attr)*])*),
+                            name: $crate::ඞ::stringify!($FLAG),
+                            discriminant: $crate::ඞ::Some(&[<$Flags:snake:upper _ $FLAG>]),
+                        },
+                    )*],
+                )
+            }
+
+            fn layout_on (
+                target: &'_ $crate::headers::Target,
+            ) -> $crate::ඞ::Option<$crate::headers::TypeLayout>
+            {
+                <$Int as $crate::layout::CType>::layout_on(target)
+            }
+        }
+    }
+
+    $crate::from_CType_impl_ReprC! {
+        $Flags
+    }
+
+    #[allow(dead_code)]
+    impl $Flags {
+        $(
+            $(#[$($flag_attr)*])*
+            pub
+            const $FLAG: Self = Self([<$Flags:snake:upper _ $FLAG>]);
+        )*
+
+        /// Whether all the flags in `other` are set in `self`.
+        pub
+        fn contains (self: Self, other: Self)
+          -> bool
+        {
+            self.0 & other.0 == other.0
+        }
+
+        /// Parses a comma-separated list of flag names (case-insensitively).
+        pub
+        fn from_csv (csv: &'_ str)
+          -> Option<Self>
+        {
+            let mut ret = Self(0);
+            for name in csv.split(',').map(str::trim).filter(|it| !it.is_empty()) {
+                ret.0 |= match () {
+                $(
+                    | _case if name.eq_ignore_ascii_case($crate::ඞ::stringify!($FLAG)) => {
+                        Self::$FLAG.0
+                    },
+                )*
+                    | _ => return None,
+                };
+            }
+            Some(ret)
+        }
+    }
+
+    impl $crate::ඞ::core::ops::BitOr for $Flags {
+        type Output = Self;
+
+        fn bitor (self: Self, other: Self)
+          -> Self
+        {
+            Self(self.0 | other.0)
+        }
+    }
+
+    impl $crate::ඞ::core::ops::BitAnd for $Flags {
+        type Output = Self;
+
+        fn bitand (self: Self, other: Self)
+          -> Self
+        {
+            Self(self.0 & other.0)
+        }
+    }
+
+    impl $crate::ඞ::core::ops::Not for $Flags {
+        type Output = Self;
+
+        fn not (self: Self)
+          -> Self
+        {
+            Self(!self.0)
+        }
+    }
+
+    $(
+        $(#[$($flag_attr)*])*
+        #[allow(dead_code)]
+        $pub
+        const [<$Flags:snake:upper _ $FLAG>]: $Int = $value;
+    )*
+
+    #[$crate::ffi_export]
+    #[doc = " Parses a comma-separated list of `" $Flags "` names, case-insensitively."]
+    ///
+    /// Returns `false`, leaving `out` untouched, if some name is not a known
+    /// flag.
+    #[allow(nonstandard_style)]
+    $pub
+    fn [<$Flags _from_csv>] (
+        csv: $crate::prelude::char_p::Ref<'_>,
+        out: $crate::prelude::Out<'_, $Flags>,
+    ) -> bool
+    {
+        match $Flags::from_csv(csv.to_str()) {
+            | Some(flags) => {
+                out.write(flags);
+                true
+            },
+            | None => false,
+        }
+    }
+})}
+
+#[doc(hidden)] /** not part of the public API */ #[macro_export]
+macro_rules! __flags_docs__ {
+    (
+        [$($doc:expr,)*]
+    ) => (
+        &[$($doc),*]
+    );
+
+    (
+        [$($doc:expr,)*]
+        #[doc = $new_doc:expr]
+        $($rest:tt)*
+    ) => (
+        $crate::__flags_docs__!([$($doc,)* $new_doc,] $($rest)*)
+    );
+
+    (
+        [$($doc:expr,)*]
+        #[$($_other_attr:tt)*]
+        $($rest:tt)*
+    ) => (
+        $crate::__flags_docs__!([$($doc,)*] $($rest)*)
+    );
+}
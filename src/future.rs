@@ -0,0 +1,343 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! An FFI-safe handle to a Rust `Future`, for C hosts with their own event
+//! loops to drive (rather than being called back, _c.f._
+//! [`completion`][`crate::completion`]).
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export]
+//! fn mylib_fetch (id: u32)
+//!   -> repr_c::Box<repr_c::Future<i32>>
+//! {
+//!     repr_c::Future::new(async move {
+//!         fetch(id).await
+//!     })
+//! }
+//!
+//! ::safer_ffi::ffi_export_future!(mylib_fetch: Future<i32>);
+//! ```
+//!
+//! See [`ffi_export_future!`][`crate::ffi_export_future`] for the functions
+//! through which C drives the future.
+
+use_prelude!();
+use ::std::{
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+type BoxFuture<T> = Pin<rust::Box<dyn 'static + Send + ::core::future::Future<Output = T>>>;
+
+enum State<T> {
+    Pending(BoxFuture<T>),
+    /// Completed, or cancelled: either way, there is no output to be had.
+    Gone,
+}
+
+#[derive_ReprC]
+#[repr(opaque)]
+/// An `async` computation, driven by polling, to be used behind a pointer
+/// (_e.g._, a [`repr_c::Box`][`crate::prelude::repr_c::Box`]).
+pub
+struct Future<T> {
+    state: State<T>,
+}
+
+/// The outcome of polling a [`Future`] from C.
+#[derive_ReprC]
+#[repr(i8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum FuturePoll {
+    /// The output has been written to `out`.
+    Ready = 0,
+    /// `wake` will be called once it is worth polling again.
+    Pending = -1,
+    /// The future has been cancelled, or its output has already been taken.
+    Cancelled = 1,
+}
+
+impl<T> Future<T> {
+    pub
+    fn new (
+        fut: impl 'static + Send + ::core::future::Future<Output = T>,
+    ) -> repr_c::Box<Future<T>>
+    {
+        rust::Box::new(Future {
+            state: State::Pending(rust::Box::pin(fut)),
+        })
+        .into()
+    }
+
+    /// Like [`Future::new`], but for a non-`'static` computation, such as the
+    /// ones of the `async` methods of `#[derive_ReprC(dyn)]` traits.
+    ///
+    /// # Safety
+    ///
+    /// Whatever `fut` borrows must outlive the returned handle.
+    #[doc(hidden)] /** Not part of the public API! */ pub
+    unsafe
+    fn __new_unchecked<'fut> (
+        fut: impl 'fut + Send + ::core::future::Future<Output = T>,
+    ) -> repr_c::Box<Future<T>>
+    {
+        let fut: Pin<rust::Box<dyn 'fut + Send + ::core::future::Future<Output = T>>> =
+            rust::Box::pin(fut)
+        ;
+        rust::Box::new(Future {
+            state: State::Pending(::core::mem::transmute(fut)),
+        })
+        .into()
+    }
+
+    /// Awaits a handle from Rust: the converse of
+    /// [`Future::__new_unchecked`].
+    #[doc(hidden)] /** Not part of the public API! */ pub
+    fn __into_std (fut: repr_c::Box<Future<T>>)
+      -> impl Send + ::core::future::Future<Output = T>
+    {
+        IntoStd(fut)
+    }
+
+    /// `Ready(None)` once cancelled, or once the output has been taken.
+    pub
+    fn poll (self: &'_ mut Future<T>, cx: &'_ mut Context<'_>)
+      -> Poll<Option<T>>
+    {
+        let fut = match self.state {
+            | State::Pending(ref mut fut) => fut,
+            | State::Gone => return Poll::Ready(None),
+        };
+        let ret = ready!(fut.as_mut().poll(cx));
+        self.state = State::Gone;
+        Poll::Ready(Some(ret))
+    }
+
+    /// Drops the computation (right away).
+    pub
+    fn cancel (self: &'_ mut Future<T>)
+    {
+        self.state = State::Gone;
+    }
+
+    /// Blocks the current thread until completion.
+    pub
+    fn wait (self: &'_ mut Future<T>)
+      -> Option<T>
+    {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match self.poll(&mut cx) {
+                | Poll::Ready(ret) => return ret,
+                | Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    /// Polls the future on behalf of C, with a `wake` callback (if any).
+    ///
+    /// # Safety
+    ///
+    /// `wake`, if any, has to be sound to call with `user_data`, from any
+    /// thread, at any point (even after the future has been freed).
+    #[doc(hidden)] /** Not part of the public API! */ pub
+    unsafe
+    fn __poll_from_c (
+        self: &'_ mut Future<T>,
+        out: Out<'_, T>,
+        wake: Option<unsafe extern "C" fn (user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> FuturePoll
+    {
+        let waker = Waker::from(Arc::new(CWaker { wake, user_data }));
+        match self.poll(&mut Context::from_waker(&waker)) {
+            | Poll::Ready(Some(ret)) => {
+                out.write(ret);
+                FuturePoll::Ready
+            },
+            | Poll::Ready(None) => FuturePoll::Cancelled,
+            | Poll::Pending => FuturePoll::Pending,
+        }
+    }
+}
+
+impl<T> fmt::Debug
+    for Future<T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("Future")
+            .field("pending", &matches!(self.state, State::Pending(_)))
+            .finish()
+    }
+}
+
+struct IntoStd<T>(repr_c::Box<Future<T>>);
+
+impl<T> ::core::future::Future
+    for IntoStd<T>
+{
+    type Output = T;
+
+    fn poll (self: Pin<&'_ mut Self>, cx: &'_ mut Context<'_>)
+      -> Poll<T>
+    {
+        self.get_mut().0.poll(cx).map(|ret| {
+            ret.expect("`Future` polled after completion")
+        })
+    }
+}
+
+macro_rules! ready {( $e:expr $(,)? ) => (
+    match $e {
+        | Poll::Ready(it) => it,
+        | Poll::Pending => return Poll::Pending,
+    }
+)} use ready;
+
+struct Unpark(thread::Thread);
+
+impl Wake
+    for Unpark
+{
+    fn wake (self: Arc<Self>)
+    {
+        self.0.unpark()
+    }
+}
+
+struct CWaker {
+    wake: Option<unsafe extern "C" fn (user_data: *mut c_void)>,
+    user_data: *mut c_void,
+}
+
+/// The host, by handing `wake` over (which only the `unsafe fn`
+/// `__poll_from_c()` lets it do), vouches for it being callable from any
+/// thread.
+unsafe impl Send for CWaker {}
+unsafe impl Sync for CWaker {}
+
+impl Wake
+    for CWaker
+{
+    fn wake (self: Arc<Self>)
+    {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref (self: &'_ Arc<Self>)
+    {
+        if let Some(wake) = self.wake {
+            unsafe { wake(self.user_data) }
+        }
+    }
+}
+
+/// Defines and exports `<prefix>_poll()`, `<prefix>_wait()`,
+/// `<prefix>_cancel()` and `<prefix>_free()`, for C to drive a
+/// [`repr_c::Future<T>`][`Future`].
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_future!(mylib_fetch: Future<i32>);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// FuturePoll_t mylib_fetch_poll (
+///     Future_int32_t * fut,
+///     int32_t * out,
+///     void (*wake)(void *),
+///     void * user_data);
+///
+/// bool mylib_fetch_wait (
+///     Future_int32_t * fut,
+///     int32_t * out);
+///
+/// void mylib_fetch_cancel (
+///     Future_int32_t * fut);
+///
+/// void mylib_fetch_free (
+///     Future_int32_t * fut);
+/// ```
+///
+/// `wake` may be called from any thread, and after the future has been freed
+/// (in which case it ought to be a no-op): since Rust cannot check any of
+/// this, `…_poll()` is an `unsafe fn`.
+#[macro_export]
+macro_rules! ffi_export_future {(
+    $prefix:ident : Future<$T:ty $(,)?> $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Polls the future, writing its output to `out` once `Ready`.
+    ///
+    /// When `Pending`, `wake` (if not `NULL`) is to be called with `user_data`
+    /// once it is worth polling again.
+    ///
+    /// # Safety
+    ///
+    /// `wake`, if not `NULL`, has to be sound to call with `user_data`, from
+    /// any thread, at any point (even after the future has been freed).
+    #[allow(nonstandard_style)]
+    pub
+    unsafe
+    fn [<$prefix _poll>] (
+        fut: &'_ mut $crate::prelude::repr_c::Future<$T>,
+        out: $crate::prelude::Out<'_, $T>,
+        wake: $crate::ඞ::Option<
+            unsafe extern "C" fn (user_data: *mut $crate::ඞ::ffi::c_void)
+        >,
+        user_data: *mut $crate::ඞ::ffi::c_void,
+    ) -> $crate::future::FuturePoll
+    {
+        fut.__poll_from_c(out, wake, user_data)
+    }
+
+    #[$crate::ffi_export]
+    /// Blocks the calling thread until the future completes, writing its
+    /// output to `out`.
+    ///
+    /// Returns `false`, leaving `out` untouched, if the future has been
+    /// cancelled (or its output already taken).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _wait>] (
+        fut: &'_ mut $crate::prelude::repr_c::Future<$T>,
+        out: $crate::prelude::Out<'_, $T>,
+    ) -> bool
+    {
+        match fut.wait() {
+            | $crate::ඞ::Some(ret) => {
+                out.write(ret);
+                true
+            },
+            | $crate::ඞ::None => false,
+        }
+    }
+
+    #[$crate::ffi_export]
+    /// Drops the computation: subsequent polls return `FUTURE_POLL_CANCELLED`.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _cancel>] (
+        fut: &'_ mut $crate::prelude::repr_c::Future<$T>,
+    )
+    {
+        fut.cancel()
+    }
+
+    #[$crate::ffi_export]
+    /// Frees the future (cancelling it, if need be).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _free>] (
+        fut: $crate::prelude::repr_c::Box<$crate::prelude::repr_c::Future<$T>>,
+    )
+    {
+        $crate::ඞ::drop(fut)
+    }
+})}
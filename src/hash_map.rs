@@ -0,0 +1,252 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! An owned map, handed over to C as an opaque handle (rather than flattened
+//! into parallel `repr_c::Vec`s of keys and values).
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! ::safer_ffi::ffi_export_hash_map!(mylib_scores: HashMap<i32, f64>);
+//!
+//! #[ffi_export]
+//! fn mylib_scores_compute ()
+//!   -> repr_c::Box<repr_c::HashMap<i32, f64>>
+//! {
+//!     let mut scores = repr_c::HashMap::new();
+//!     scores.insert(42, 0.5);
+//!     Box::new(scores).into()
+//! }
+//! ```
+
+use_prelude!();
+use ::std::collections::HashMap as StdHashMap;
+
+/// An owned `HashMap<K, V>`, only to be used behind a pointer (_e.g._, a
+/// `repr_c::Box<repr_c::HashMap<K, V>>`).
+///
+/// Derefs to the [`std::collections::HashMap`] it wraps; see
+/// [`ffi_export_hash_map!`] for the functions through which C can use it.
+///
+/// [`ffi_export_hash_map!`]: `crate::ffi_export_hash_map`
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(Debug, Clone)]
+pub
+struct HashMap<K, V> {
+    map: StdHashMap<K, V>,
+}
+
+impl<K, V> HashMap<K, V> {
+    pub
+    fn new ()
+      -> HashMap<K, V>
+    {
+        Self { map: StdHashMap::new() }
+    }
+
+    pub
+    fn into_inner (self: Self)
+      -> StdHashMap<K, V>
+    {
+        self.map
+    }
+}
+
+impl<K, V> Default
+    for HashMap<K, V>
+{
+    fn default ()
+      -> HashMap<K, V>
+    {
+        Self::new()
+    }
+}
+
+impl<K, V> From<StdHashMap<K, V>>
+    for HashMap<K, V>
+{
+    #[inline]
+    fn from (map: StdHashMap<K, V>)
+      -> HashMap<K, V>
+    {
+        Self { map }
+    }
+}
+
+impl<K, V> Deref
+    for HashMap<K, V>
+{
+    type Target = StdHashMap<K, V>;
+
+    #[inline]
+    fn deref (self: &'_ Self)
+      -> &'_ StdHashMap<K, V>
+    {
+        &self.map
+    }
+}
+
+impl<K, V> DerefMut
+    for HashMap<K, V>
+{
+    #[inline]
+    fn deref_mut (self: &'_ mut Self)
+      -> &'_ mut StdHashMap<K, V>
+    {
+        &mut self.map
+    }
+}
+
+/// Defines and exports the functions through which C can create, query,
+/// mutate, iterate over, and free a [`repr_c::HashMap<K, V>`][`HashMap`].
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_hash_map!(mylib_scores: HashMap<i32, f64>);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// typedef struct HashMap_i32_f64 HashMap_i32_f64_t;
+///
+/// HashMap_i32_f64_t * mylib_scores_new (void);
+///
+/// void mylib_scores_free (
+///     HashMap_i32_f64_t * map);
+///
+/// size_t mylib_scores_len (
+///     HashMap_i32_f64_t const * map);
+///
+/// /** \brief
+///  *  Returns `NULL` if there is no value for `key`.
+///  * ...
+///  */
+/// double const * mylib_scores_get (
+///     HashMap_i32_f64_t const * map,
+///     int32_t const * key);
+///
+/// bool mylib_scores_insert (
+///     HashMap_i32_f64_t * map,
+///     int32_t key,
+///     double value);
+///
+/// bool mylib_scores_remove (
+///     HashMap_i32_f64_t * map,
+///     int32_t const * key);
+///
+/// void mylib_scores_for_each (
+///     HashMap_i32_f64_t const * map,
+///     void * user_data,
+///     void (*cb)(void *, int32_t const *, double const *));
+/// ```
+///
+///   - `…_insert()` and `…_remove()` return whether `key` was present, in
+///     which case its previous value has been dropped.
+///
+///   - The pointer returned by `…_get()`, and those given to the `…_for_each()`
+///     callback, are only valid until the map is next mutated (or freed).
+///
+///   - `…_for_each()` is an `unsafe fn`: the caller vouches for `cb` being
+///     sound to call with `user_data` (and the entries' pointers).
+#[macro_export]
+macro_rules! ffi_export_hash_map {(
+    $prefix:ident : HashMap<$K:ty, $V:ty $(,)?> $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    #[doc = " Creates a new empty map, to be freed with `" [<$prefix _free>] "()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _new>] ()
+      -> $crate::prelude::repr_c::Box<$crate::prelude::repr_c::HashMap<$K, $V>>
+    {
+        $crate::ඞ::Box::new($crate::prelude::repr_c::HashMap::new()).into()
+    }
+
+    #[$crate::ffi_export]
+    /// Frees the map, along with its keys and values.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _free>] (
+        map: $crate::prelude::repr_c::Box<$crate::prelude::repr_c::HashMap<$K, $V>>,
+    )
+    {
+        $crate::ඞ::drop(map)
+    }
+
+    #[$crate::ffi_export]
+    /// The number of entries in the map.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _len>] (
+        map: &'_ $crate::prelude::repr_c::HashMap<$K, $V>,
+    ) -> usize
+    {
+        map.len()
+    }
+
+    #[$crate::ffi_export]
+    /// Returns `NULL` if there is no value for `key`.
+    ///
+    /// The returned pointer is only valid until the map is next mutated.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _get>]<'map> (
+        map: &'map $crate::prelude::repr_c::HashMap<$K, $V>,
+        key: &'_ $K,
+    ) -> $crate::ඞ::Option<&'map $V>
+    {
+        map.get(key)
+    }
+
+    #[$crate::ffi_export]
+    /// Takes ownership of `key` and `value`.
+    ///
+    /// Returns whether `key` was present (its previous value being dropped).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _insert>] (
+        map: &'_ mut $crate::prelude::repr_c::HashMap<$K, $V>,
+        key: $K,
+        value: $V,
+    ) -> bool
+    {
+        map.insert(key, value).is_some()
+    }
+
+    #[$crate::ffi_export]
+    /// Returns whether `key` was present (its value being dropped).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _remove>] (
+        map: &'_ mut $crate::prelude::repr_c::HashMap<$K, $V>,
+        key: &'_ $K,
+    ) -> bool
+    {
+        map.remove(key).is_some()
+    }
+
+    #[$crate::ffi_export]
+    /// Calls `cb` with `user_data` and each of the entries, in an unspecified
+    /// order.
+    ///
+    /// # Safety
+    ///
+    /// `cb` has to be sound to call with `user_data`, and with pointers to a
+    /// key and its value, for as long as the call to `…_for_each()` lasts.
+    #[allow(nonstandard_style)]
+    pub
+    unsafe
+    fn [<$prefix _for_each>] (
+        map: &'_ $crate::prelude::repr_c::HashMap<$K, $V>,
+        user_data: *mut $crate::ඞ::ffi::c_void,
+        cb: unsafe extern "C" fn (
+            user_data: *mut $crate::ඞ::ffi::c_void,
+            key: *const $K,
+            value: *const $V,
+        ),
+    )
+    {
+        for (key, value) in map.iter() {
+            cb(user_data, key, value);
+        }
+    }
+})}
@@ -98,6 +98,19 @@
 //!
 //! <span style="color:#004a43; ">#</span><span style="color:#004a43; ">endif</span><span style="color:#004a43; "> </span><span style="color:#595979; ">/* __ASGARD__ */</span>
 //! </pre>
+//!
+//! Alternatively, a [`headers_entry_point!`][`crate::headers_entry_point`]
+//! binary lets the `cargo safer-ffi generate` subcommand
+//! (`cargo install cargo-safer-ffi`) take care of the feature, and of the
+//! output, without a test run:
+//!
+//! ```shell
+//! cargo safer-ffi generate --features generate-headers --output filename.h
+//! cargo safer-ffi generate --features generate-headers --output filename.h --check
+//! ```
+//!
+//! The `#[ffi_export]`ed items are collected through linker-section tricks,
+//! which some platforms do not support: see [`registry`] for the alternative.
 
 #![allow(missing_copy_implementations, missing_debug_implementations)]
 
@@ -105,7 +118,7 @@ use ::std::{
     collections::HashSet,
     fs,
     io,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use_prelude!();
@@ -114,9 +127,43 @@ use rust::{String};
 pub // (in crate)
 mod languages;
 
+#[cfg(feature = "abi-diff")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "abi-diff")),
+)]
+pub use abi_diff::{abi_manifest, diff, AbiChange, AbiDiff};
+#[cfg(feature = "abi-diff")]
+mod abi_diff;
+
+pub use analyze::Report;
+pub use crate::symbols::ItemKind;
+pub(in crate) use analyze::declarations;
+mod analyze;
+
+pub
+mod build_script;
+
 pub use definer::{Definer, HashSetDefiner};
 mod definer;
 
+mod frozen;
+
+#[doc(hidden)] /** Not part of the public API */
+pub use layout_snapshot::check_layout_snapshot;
+mod layout_snapshot;
+
+mod line_endings;
+
+pub
+mod registry;
+
+pub use settings::Settings;
+mod settings;
+
+pub use targets::{Target, TypeLayout};
+pub(in crate) use targets::check_layout;
+mod targets;
+
 
 
 match_! {(
@@ -129,6 +176,13 @@ match_! {(
     /// ```
     guard: &'__ str,
 
+    /// Sets up the style of the include guard of the C header file.
+    ///
+    /// It defaults to [`GuardStyle::IfnDef`], _i.e._, the
+    /// `#ifndef`/`#define`/`#endif` dance using the [`guard`][`Builder::with_guard`]
+    /// name.
+    guard_style: GuardStyle,
+
     /// Sets up the banner of the generated C header file.
     ///
     /// It defaults to:
@@ -162,6 +216,320 @@ match_! {(
     /// It defaults to [`Language::C`].
     language: Language,
 
+    /// Sets up a [`Profile`], _i.e._, a coherent bundle of defaults for the
+    /// other C-header-related options ([C standard][`Builder::with_c_standard`],
+    /// [integer types][`Builder::with_stdint_fallback`],
+    /// [doc comments][`Builder::with_doc_tags`],
+    /// [guard style][`Builder::with_guard_style`],
+    /// [attributes][`Builder::with_nullability_annotations`], …).
+    ///
+    /// Options explicitly set on the builder take precedence over the ones
+    /// of the profile.
+    profile: Profile,
+
+    /// Sets the revision of the C standard the generated C header has to
+    /// comply with, for the sake of (legacy) compilers lacking support for
+    /// the newer C features (`<stdbool.h>`, trailing commas in `enum`s,
+    /// `_Static_assert`, …).
+    ///
+    /// It defaults to [`CStandard::C99`].
+    c_standard: CStandard,
+
+    /// Whether to emit, after each C `struct` definition, compile-time
+    /// assertions (`_Static_assert` / `static_assert`) of its size and
+    /// alignment, as computed on the Rust side.
+    ///
+    /// This catches ABI drift between the header and the compiled library at
+    /// the consumer's compile time.
+    ///
+    /// It defaults to `false`.
+    static_asserts: bool,
+
+    /// Whether the C header is to `typedef` the fixed-width integer types
+    /// (`int32_t` & co.) by itself when `<stdint.h>` is not available
+    /// (pre-C99 compilers, and MSVC before Visual Studio 2010), rather than
+    /// `#include` it unconditionally.
+    ///
+    /// It defaults to `false`.
+    stdint_fallback: bool,
+
+    /// Sets up the name of a macro (_e.g._, `"MYLIB_API"`) with which to
+    /// decorate every C function declaration.
+    ///
+    /// The header then defines it (unless already defined) as:
+    ///
+    ///   - nothing when `MYLIB_STATIC` is defined;
+    ///
+    ///   - `__declspec(dllexport)` on Windows when `MYLIB_EXPORTS` is defined
+    ///     (_i.e._, when building the DLL), else `__declspec(dllimport)`;
+    ///
+    ///   - `__attribute__((visibility("default")))` on GCC-compatible
+    ///     compilers.
+    ///
+    /// (with the `MYLIB` prefix being the macro name stripped of its `_API`
+    /// suffix, if any).
+    ///
+    /// It defaults to no decoration.
+    api_macro: &'__ str,
+
+    /// Sets up the (Rust) target triples the generated header is meant to be
+    /// shipped with (_e.g._, `&["x86_64-unknown-linux-gnu", "armv7-linux-androideabi"]`).
+    ///
+    /// The generation then errors if any of the emitted types has a layout
+    /// (size or alignment) which differs across these targets, since a single
+    /// header could then not correctly describe all of them.
+    ///
+    /// It defaults to no targets, _i.e._, to no such verification.
+    layout_targets: &'__ [&'__ str],
+
+    /// Whether to group the C items (functions, types, constants and
+    /// `static`s) by Rust module in the generated Doxygen documentation
+    /// (through `\defgroup` and `\ingroup` commands), the brief of each group
+    /// being taken from the leading `//!` doc comment of the module's file.
+    ///
+    /// It defaults to `false`.
+    doxygen_groups: bool,
+
+    /// Whether to translate the `# Arguments`, `# Returns` and `# Safety`
+    /// sections of the Rust docs into the dedicated doc comment tags of the
+    /// target language: `\param`, `\return` and `\warning` for C (and
+    /// C++), `<param>`, `<returns>` and `<remarks>` for C#.
+    ///
+    /// The `# Arguments` section is expected to be a list of
+    /// `` * `name` - description`` items.
+    ///
+    /// It defaults to `false`, _i.e._, to copying the docs verbatim.
+    doc_tags: bool,
+
+    /// Whether to annotate the pointers stemming from `&mut` parameters, which
+    /// are guaranteed not to alias any other argument, with `restrict` in C
+    /// (through a `SAFER_FFI_RESTRICT` macro, which is also compatible with
+    /// C++ and pre-C99 compilers), and to document that contract in C#.
+    ///
+    /// It defaults to `false`.
+    restrict_pointers: bool,
+
+    /// Whether to reflect, in C, the "niche" of the function parameters
+    /// (see [`Niche`][`crate::layout::Niche`]):
+    ///
+    ///   - pointers which cannot be null (_e.g._, `&T`, `char_p::Ref`) are
+    ///     annotated `_Nonnull`, and those for which null means `None`
+    ///     (_e.g._, `Option<&T>`) `_Nullable`, through the
+    ///     `SAFER_FFI_NONNULL` and `SAFER_FFI_NULLABLE` macros (which only
+    ///     expand to these annotations on Clang);
+    ///
+    ///   - integers which cannot be zero (`NonZeroU32` & co.), and those for
+    ///     which zero means `None` (`Option<NonZeroU32>` & co.), get a
+    ///     comment saying so;
+    ///
+    ///   - so do the (by value) slices and vectors for which a null `.ptr`
+    ///     means `None` (_e.g._, `Option<repr_c::Vec<T>>`).
+    ///
+    /// It defaults to `false`.
+    nullability_annotations: bool,
+
+    /// Whether to declare each function, in C, within a
+    /// `#pragma clang assume_nonnull` region (through the
+    /// `SAFER_FFI_ASSUME_NONNULL_BEGIN` and `SAFER_FFI_ASSUME_NONNULL_END`
+    /// macros, which only expand to it on Clang), wherein the unannotated
+    /// pointers are deemed non-null, as Swift and the Clang static analyzer
+    /// then get to know.
+    ///
+    /// This implies [`Builder::with_nullability_annotations()`], with the
+    /// pointers which may be null, raw pointers included, all being annotated
+    /// `_Nullable` (return values included). Nested pointers (_e.g._, the
+    /// `T *` in `T * *`) are deemed non-null.
+    ///
+    /// It defaults to `false`.
+    assume_nonnull: bool,
+
+    /// Sets up the name of a macro (_e.g._, `"MYLIB_VERSION"`) with which C
+    /// consumers can select the version of the library they target, so that
+    /// the functions introduced after it (through
+    /// `#[ffi_export(since = "1.2")]`) are not declared, yielding compile
+    /// errors rather than link errors.
+    ///
+    /// Such functions are then declared within a
+    /// `#if !defined(MYLIB_VERSION) || MYLIB_VERSION >= 1002000` guard, the
+    /// version being encoded as `MAJOR * 1000000 + MINOR * 1000 + PATCH`.
+    ///
+    /// Only C headers are affected. It defaults to no guards, _i.e._, to
+    /// declaring every function.
+    version_macro: &'__ str,
+
+    /// Sets up the C preprocessor conditions of the `#[cfg(…)]` predicates
+    /// gating the exported functions, as `(predicate, condition)` pairs:
+    ///
+    /// ```rust,ignore
+    /// .with_cfg_conditions(&[
+    ///     ("feature = \"gpu\"", "defined(MYLIB_GPU)"),
+    ///     ("target_os = \"ios\"", "(defined(__APPLE__) && TARGET_OS_IPHONE)"),
+    /// ])
+    /// ```
+    ///
+    /// The declaration of a `#[cfg(windows)]` function, for instance, is
+    /// emitted within an `#if defined(_WIN32)` … `#endif` block: these take
+    /// precedence over the built-in conditions, which only cover the common
+    /// `windows` / `unix` / `target_family` / `target_os` / `target_arch` /
+    /// `target_pointer_width` predicates. For a one-off,
+    /// `#[ffi_export(cfg_header = "…")]` overrides the condition of a given
+    /// function. A function some predicate of which is mapped by neither is
+    /// left unguarded, and only declared when it exists on the target the
+    /// header is generated for.
+    ///
+    /// Only C headers are affected (the other languages merely skip the
+    /// functions not exported by the target they are generated for).
+    cfg_conditions: &'__ [(&'__ str, &'__ str)],
+
+    /// Sets up the name of a macro (_e.g._, `"MYLIB_ABI_FINGERPRINT"`) to be
+    /// defined, in the C header, as the [fingerprint][`crate::abi`] of the
+    /// exported ABI, for C consumers to compare, at load time, with the one
+    /// returned by the library (through the function which
+    /// [`ffi_export_abi_fingerprint!`][`crate::ffi_export_abi_fingerprint`]
+    /// exports).
+    ///
+    /// Only C headers are affected. It defaults to no such macro.
+    abi_fingerprint_macro: &'__ str,
+
+    /// Sets up the prefix (_e.g._, `"mylib"`) of a table of the exported
+    /// functions to be emitted at the end of the C header, along with a
+    /// loader filling it, for the hosts which `dlopen()` the library rather
+    /// than linking against it:
+    ///
+    /// ```C
+    /// struct mylib_api {
+    ///     int32_t (*mylib_add) (int32_t x, int32_t y);
+    /// };
+    ///
+    /// /* Returns the number of missing functions. */
+    /// static inline
+    /// int mylib_load_api (void * dlhandle, struct mylib_api * out);
+    /// ```
+    ///
+    /// The loader `dlsym()`s every function, and is thus only defined for
+    /// POSIX hosts (`#ifndef _WIN32`).
+    ///
+    /// Only C headers are affected. It defaults to no table.
+    dlopen_api: &'__ str,
+
+    /// Sets up the name of a C# class (_e.g._, `"MyLib"`) in which to also
+    /// emit, for each function, an idiomatic wrapper around its raw
+    /// `[DllImport]`:
+    ///
+    ///   - `char_p::Ref` parameters are taken as `string`s, and
+    ///     `c_slice::Ref<u8>` (resp. `c_slice::Mut<u8>`) ones as
+    ///     `ReadOnlySpan<byte>` (resp. `Span<byte>`);
+    ///
+    ///   - `char_p::Ref` returns are converted to `string`s, and so are
+    ///     `char_p::Box` ones when some `#[ffi_export(free_of = …)]` function
+    ///     frees them (which the wrapper then does);
+    ///
+    ///   - non-zero returns of the `#[ffi_export(error_code)]` functions
+    ///     throw an `FfiException`.
+    ///
+    /// Only C# output is affected. It defaults to no wrappers.
+    csharp_wrapper_class: &'__ str,
+
+    /// Whether to declare the C# functions as source-generated
+    /// `[LibraryImport]` `partial` methods (.NET 7+), as required by NativeAOT
+    /// and trimming, rather than as runtime-marshaled `[DllImport]` `extern`
+    /// ones.
+    ///
+    /// The source generator only marshals `bool`s (`UnmanagedType.U1`): the
+    /// functions taking or returning a marshaled `delegate` (see
+    /// [`Builder::with_csharp_function_pointers()`]), a `string`
+    /// (`UnmanagedType.LPWStr`), or any other such type, keep being declared
+    /// as `[DllImport]` `extern` ones.
+    ///
+    /// It defaults to `false`.
+    csharp_library_import: bool,
+
+    /// Whether to declare the C# callback types (`extern "C" fn`s, such as
+    /// the `call` of the closure types) as blittable structs wrapping a
+    /// `delegate* unmanaged[Cdecl]` function pointer (C# 9+), rather than as
+    /// marshaled `delegate`s.
+    ///
+    /// The former avoid marshaling and the lifetime pitfalls of delegates
+    /// (which must be kept alive by the caller for as long as Rust may call
+    /// them), but can only point to `[UnmanagedCallersOnly]` static methods,
+    /// whereas the latter can be created out of lambdas.
+    ///
+    /// It defaults to `false`.
+    csharp_function_pointers: bool,
+
+    /// Whether to also emit, for each `#[derive_ReprC(dyn)]` trait whose
+    /// vtable the C# code needs, an `abstract class` named after the trait,
+    /// whose `IntoVirtualPtr()` method hands an instance of a subclass over
+    /// to Rust, as a `VirtualPtr<dyn Trait>` (the vtable of which forwards to
+    /// the `abstract` methods).
+    ///
+    /// The vtable (and its delegates) being `static`, it is kept alive for
+    /// the whole program; each instance, for as long as Rust holds it.
+    ///
+    /// Generic traits, and the `csharp_function_pointers` mode, are not
+    /// supported. It defaults to `false`.
+    csharp_dyn_trait_classes: bool,
+
+    /// Sets up the `namespace` of the generated C# code.
+    ///
+    /// It defaults to the PascalCased crate name.
+    csharp_namespace: &'__ str,
+
+    /// Sets up the name of the C# class declaring the (non-`group`ed)
+    /// functions and constants.
+    ///
+    /// It defaults to `Ffi`.
+    csharp_class_name: &'__ str,
+
+    /// Makes the Python output an `ffi_build.py` script, using `cffi`'s API
+    /// mode (`set_source()` + `cdef()`) to compile a `_{lib}` extension
+    /// module against the given C header (as generated for [`Language::C`],
+    /// _e.g._, `"mylib.h"`), rather than a bare `cdef()` input.
+    ///
+    /// Such a compiled module is much faster than ABI-mode (or `ctypes`)
+    /// bindings for tight call loops.
+    ///
+    /// Only Python output is affected. It defaults to a bare `cdef()` input.
+    python_cffi_header: &'__ str,
+
+    /// Sets up the C header (as generated for [`Language::C`]) which the
+    /// Cython declarations are to be `cdef extern from`.
+    ///
+    /// Only Cython output is affected. It defaults to `"{library name}.h"`.
+    cython_header: &'__ str,
+
+    /// Sets up the C header (as generated for [`Language::C`]) which the
+    /// [`Language::CSmokeTest`] translation unit `#include`s.
+    ///
+    /// Only C smoke-test output is affected. It defaults to
+    /// `"{library name}.h"`.
+    smoke_test_header: &'__ str,
+
+    /// Sets up the name of the dynamic library to load, _e.g._, `mylib` for
+    /// `mylib.dll` / `libmylib.so` / `libmylib.dylib`, or a path to it.
+    ///
+    /// In C#, this is the `[DllImport]` library name, and the main class then
+    /// also gets a `SetDllImportResolver()` stub, to customize how the library
+    /// is loaded. In `.def` files, this is the `LIBRARY`.
+    ///
+    /// It defaults to the crate name.
+    library_name: &'__ str,
+
+    /// Sets up extra headers to `#include` in the generated C header, right
+    /// after the prelude (_e.g._, `&["<stdint.h>", "\"my_defs.h\""]`).
+    ///
+    /// Only C headers are affected. It defaults to no extra includes.
+    includes: &'__ [&'__ str],
+
+    /// Sets up a handwritten snippet (macros, platform guards, extra
+    /// `typedef`s, …) to be inserted verbatim between the prelude (and the
+    /// [`includes`][`Builder::with_includes`]) and the generated items.
+    header_snippet: &'__ str,
+
+    /// Sets up a handwritten snippet to be inserted verbatim between the
+    /// generated items and the epilogue.
+    footer_snippet: &'__ str,
+
     /// Sets prefix for generated functions, structs & enums
     naming_convention: NamingConvention,
 
@@ -170,6 +538,29 @@ match_! {(
     ///
     /// It defaults to `true`.
     stable_header: bool,
+
+    /// Whether to also record, in their crate's `safer-ffi-abi.lock`, the
+    /// layouts of the [`#[derive_ReprC(frozen)]`][`crate::derive_ReprC`]
+    /// structs not recorded yet (erroring if a recorded one has changed).
+    ///
+    /// It defaults to `false`, so that generating the headers (_e.g._, from a
+    /// test) does not write to the crate.
+    update_frozen_layouts: bool,
+
+    /// Sets up the line endings of the generated file (_e.g._,
+    /// [`LineEndings::CrLf`] for Windows-centric toolchains).
+    ///
+    /// Only the output of [`.generate()`][`Builder::generate`] is affected
+    /// (a custom [`Definer`] being in charge of its own output). It defaults to
+    /// [`LineEndings::Lf`].
+    line_endings: LineEndings,
+
+    /// Whether to start the generated (UTF-8) file with a byte order mark, as
+    /// some (older) editors and toolchains expect.
+    ///
+    /// Only the output of [`.generate()`][`Builder::generate`] is affected.
+    /// It defaults to `false`.
+    utf8_bom: bool,
 ) /* as */ {(
     $(
         $(#[$field_meta:meta])*
@@ -295,11 +686,22 @@ match_! {(
           -> io::Result<()>
         {
             let Self { mut target, $($field ,)* } = self;
+            if utf8_bom == Some(true) {
+                io::Write::write_all(&mut target, line_endings::UTF8_BOM)?;
+            }
+            let mut crlf;
+            let out: &mut dyn io::Write = match line_endings {
+                | None | Some(LineEndings::Lf) => &mut target,
+                | Some(LineEndings::CrLf) => {
+                    crlf = line_endings::CrLfWriter::new(&mut target);
+                    &mut crlf
+                },
+            };
             Builder {
                 target: WhereTo, $(
                 $field, )*
             }.generate_with_definer(&mut HashSetDefiner {
-                out: &mut target,
+                out,
                 defines_set: Default::default(),
             })
         }
@@ -329,17 +731,161 @@ impl Builder<'_, WhereTo> {
       -> io::Result<()>
     {
         let config = self;
+        let definer = &mut settings::WithSettings {
+            settings: config.settings()?,
+            definer,
+        };
         // Banner
         config.write_banner(definer)?;
         // Prelude
         config.write_prelude(definer)?;
+        // Handwritten additions
+        config.write_header_snippet(definer)?;
         /* User-provided defs! */
         config.write_body(definer)?;
+        if let Some(footer_snippet) = config.footer_snippet {
+            writeln!(definer.out(), "{}", footer_snippet)?;
+        }
         // Epilogue
         config.write_epilogue(definer)?;
+        // `#[derive_ReprC(frozen)]` layouts.
+        if config.update_frozen_layouts == Some(true) {
+            frozen::record_layouts()?;
+        }
         Ok(())
     }
 
+    /// Dry-runs the generation, to report on what the headers would contain
+    /// (without writing them anywhere): how many items of each kind are
+    /// exported, which definitions they pull in, which names collide, and
+    /// which items the language cannot express.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # fn main () -> ::std::io::Result<()> { Ok({
+    /// let report = ::safer_ffi::headers::builder().analyze()?;
+    /// assert!(report.name_collisions.is_empty());
+    /// assert!(report.functions <= 200, "API surface is getting too big!");
+    /// # })}
+    /// ```
+    pub
+    fn analyze (&'_ self)
+      -> io::Result<Report>
+    {
+        analyze::analyze(self.language.unwrap_or(Language::C), self.settings()?)
+    }
+
+    fn settings (&'_ self)
+      -> io::Result<Settings>
+    {
+        let Settings {
+            c_standard,
+            static_asserts,
+            stdint_fallback,
+            api_macro,
+            layout_targets,
+            doxygen_groups,
+            doc_tags,
+            restrict_pointers,
+            nullability_annotations,
+            assume_nonnull,
+            version_macro,
+            cfg_conditions,
+            csharp_wrapper_class,
+            csharp_library_import,
+            csharp_function_pointers,
+            csharp_dyn_trait_classes,
+            csharp_class_name,
+            library_name,
+            function_pointer_fields,
+            cfg_guards,
+        } = Settings::DEFAULT;
+        let profile = self.profile;
+        Ok(Settings {
+            c_standard:
+                self.c_standard
+                    .or(profile.map(Profile::c_standard))
+                    .unwrap_or(c_standard)
+            ,
+            static_asserts:
+                self.static_asserts
+                    .or(profile.map(Profile::static_asserts))
+                    .unwrap_or(static_asserts)
+            ,
+            stdint_fallback:
+                self.stdint_fallback
+                    .or(profile.map(Profile::stdint_fallback))
+                    .unwrap_or(stdint_fallback)
+            ,
+            api_macro:
+                self.api_macro
+                    .map(String::from)
+                    .or_else(|| {
+                        profile?.api_macro().then(|| format!(
+                            "{}_API",
+                            Self::lib_name().to_ascii_uppercase(),
+                        ))
+                    })
+                    .or(api_macro)
+            ,
+            layout_targets: match self.layout_targets {
+                | Some(triples) => triples.iter().map(|&triple| {
+                    Target::from_triple(triple).ok_or_else(|| io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("unsupported target triple: `{triple}`"),
+                    ))
+                }).collect::<io::Result<_>>()?,
+                | None => layout_targets,
+            },
+            doxygen_groups: self.doxygen_groups.unwrap_or(doxygen_groups),
+            doc_tags:
+                self.doc_tags
+                    .or(profile.map(Profile::doc_tags))
+                    .unwrap_or(doc_tags)
+            ,
+            restrict_pointers:
+                self.restrict_pointers
+                    .or(profile.map(Profile::restrict_pointers))
+                    .unwrap_or(restrict_pointers)
+            ,
+            nullability_annotations:
+                self.nullability_annotations
+                    .or(profile.map(Profile::nullability_annotations))
+                    .unwrap_or(nullability_annotations)
+            ,
+            assume_nonnull: self.assume_nonnull.unwrap_or(assume_nonnull),
+            version_macro: self.version_macro.map(String::from).or(version_macro),
+            cfg_conditions: match self.cfg_conditions {
+                | Some(conditions) => {
+                    conditions
+                        .iter()
+                        .map(|&(predicate, condition)| (predicate.into(), condition.into()))
+                        .collect()
+                },
+                | None => cfg_conditions,
+            },
+            csharp_wrapper_class:
+                self.csharp_wrapper_class
+                    .map(String::from)
+                    .or(csharp_wrapper_class)
+            ,
+            csharp_library_import: self.csharp_library_import.unwrap_or(csharp_library_import),
+            csharp_function_pointers:
+                self.csharp_function_pointers
+                    .unwrap_or(csharp_function_pointers)
+            ,
+            csharp_dyn_trait_classes:
+                self.csharp_dyn_trait_classes
+                    .unwrap_or(csharp_dyn_trait_classes)
+            ,
+            csharp_class_name: self.csharp_class_name.map(String::from).or(csharp_class_name),
+            library_name: self.library_name.map(String::from).or(library_name),
+            function_pointer_fields,
+            cfg_guards,
+        })
+    }
+
     fn write_banner (&'_ self, definer: &'_ mut dyn Definer)
       -> io::Result<()>
     {
@@ -353,7 +899,30 @@ impl Builder<'_, WhereTo> {
             " *                                         *\n",
             " *******************************************/\n",
         ));
-        writeln!(definer.out(), "{}", banner)
+        let line_comment = match self.language.unwrap_or(Language::C) {
+            // `.def` files only feature `;` line comments.
+            | Language::MsvcDef => "; ",
+            // Neither do exported symbols lists, with `#` ones.
+            | Language::MacosExportedSymbols => "# ",
+            // Nor do `ffi_build.py` scripts.
+            #[cfg(feature = "python-headers")]
+            | Language::Python if self.python_cffi_header.is_some() => "# ",
+            | Language::Cython => "# ",
+            | Language::LayoutSnapshot => match self.banner {
+                | Some(_) => "# ",
+                | None => return writeln!(definer.out(),
+                    "{}", languages::LAYOUT_SNAPSHOT_BANNER,
+                ),
+            },
+            | Language::Markdown => {
+                return writeln!(definer.out(), "<!--\n{}-->\n", banner);
+            },
+            | _ => return writeln!(definer.out(), "{}", banner),
+        };
+        for line in banner.lines() {
+            writeln!(definer.out(), "{}{}", line_comment, line.trim_end())?;
+        }
+        writeln!(definer.out())
     }
 
     fn write_prelude (&'_ self, definer: &'_ mut dyn Definer)
@@ -361,24 +930,112 @@ impl Builder<'_, WhereTo> {
     {
         let lang = self.language.unwrap_or(Language::C);
 
-        let guard = self.guard();
-
         match lang {
-            | Language::C => writeln!(definer.out(),
-                include_str!("templates/c/_prelude.h"),
-                guard = guard,
+            | Language::C => {
+                writeln!(definer.out(),
+                    include_str!("templates/c/_prelude.h"),
+                    guard_open = self.guard_open(),
+                )?;
+                if let Some(abi_fingerprint_macro) = self.abi_fingerprint_macro {
+                    writeln!(definer.out(),
+                        "#define {} {:#018x}ULL\n",
+                        abi_fingerprint_macro,
+                        crate::abi::abi_fingerprint(),
+                    )?;
+                }
+                if cfg!(feature = "c-allocator") {
+                    writeln!(definer.out(), "{}", concat!(
+                        "/* The owned pointers returned by this library (boxes and `char *`\n",
+                        " * strings, and the `.ptr` of the vectors and boxed slices of non-zero\n",
+                        " * capacity) have been allocated with `malloc()`, and may thus be\n",
+                        " * released with `free()` (unless another allocator has been\n",
+                        " * registered, in which case its own `free` is to be used). */\n",
+                    ))?;
+                }
+                Ok(())
+            },
+
+            | Language::CSharp => {
+                let class_name = self.csharp_class_name.unwrap_or("Ffi");
+                writeln!(definer.out(),
+                    include_str!("templates/csharp/_prelude.cs"),
+                    NameSpace = self.csharp_namespace(),
+                    ClassName = class_name,
+                    RustLib = self.library_name(),
+                    resolver = if self.library_name.is_some() {
+                        format!(
+                            include_str!("templates/csharp/resolver.cs"),
+                            ClassName = class_name,
+                        ).trim_end().to_owned()
+                    } else {
+                        "".into()
+                    },
+                )
+            },
+
+            #[cfg(feature = "python-headers")]
+            | Language::Python => match self.python_cffi_header {
+                | Some(header) => write!(definer.out(),
+                    include_str!("templates/python/ffi_build_prelude.py"),
+                    module = format!("_{}", self.library_name()),
+                    lib = self.library_name(),
+                    header = header,
+                ),
+                // CHECKME
+                | None => Ok(()),
+            },
+
+            | Language::MsvcDef => {
+                languages::SymbolList::MsvcDef.emit_prelude(definer, &self.library_name())
+            },
+
+            | Language::LdVersionScript => {
+                languages::SymbolList::LdVersionScript.emit_prelude(definer, &self.library_name())
+            },
+
+            | Language::MacosExportedSymbols => {
+                languages::SymbolList::MacosExportedSymbols.emit_prelude(definer, &self.library_name())
+            },
+
+            | Language::Markdown => writeln!(definer.out(),
+                "# `{}` API reference\n",
+                Self::lib_name(),
             ),
 
-            | Language::CSharp => writeln!(definer.out(),
-                include_str!("templates/csharp/_prelude.cs"),
-                NameSpace = Self::pascal_cased_lib_name(),
-                RustLib = Self::lib_name(),
+            | Language::Cython => writeln!(definer.out(),
+                include_str!("templates/cython/_prelude.pxd"),
+                header = self.cython_header.map_or_else(
+                    || format!("{}.h", self.library_name()),
+                    Into::into,
+                ),
             ),
 
-            #[cfg(feature = "python-headers")]
-            // CHECKME
-            | Language::Python => Ok(()),
+            | Language::CSmokeTest => writeln!(definer.out(),
+                include_str!("templates/c_smoke_test/_prelude.c"),
+                header = self.smoke_test_header.map_or_else(
+                    || format!("{}.h", self.library_name()),
+                    Into::into,
+                ),
+            ),
+
+            | Language::LayoutSnapshot => Ok(()),
+        }
+    }
+
+    fn write_header_snippet (&'_ self, definer: &'_ mut dyn Definer)
+      -> io::Result<()>
+    {
+        let includes = self.includes.unwrap_or(&[]);
+        if includes.is_empty().not() && self.language.unwrap_or(Language::C) == Language::C {
+            for include in includes {
+                writeln!(definer.out(), "#include {}", include)?;
+            }
+            writeln!(definer.out())?;
+        }
+        if let Some(header_snippet) = self.header_snippet {
+            writeln!(definer.out(), "{}", header_snippet)?;
         }
+        Ok(())
     }
 
     /// Heart of safer ffi: write the items in the header
@@ -395,9 +1052,9 @@ impl Builder<'_, WhereTo> {
         let (mut storage0, mut storage1) = (None, None);
         let gen_defs: &mut dyn Iterator<Item = _> = if stable_header {
             storage0.get_or_insert(
-                crate::inventory::iter
+                registry::ffi_exports()
                     .into_iter()
-                    .map(|crate::FfiExport { name, gen_def }| (name, gen_def))
+                    .map(|&crate::FfiExport { name, gen_def, .. }| (name, gen_def))
                     // Sort the definitions for a reliable header generation.
                     .collect::<::std::collections::BTreeMap<_, _>>()
                     .into_iter()
@@ -405,12 +1062,12 @@ impl Builder<'_, WhereTo> {
             )
         } else {
             storage1.get_or_insert(
-                crate::inventory::iter
+                registry::ffi_exports()
                     .into_iter()
                     // Iterate in reverse fashion to more closely match
                     // the Rust definition order.
-                    .collect::<rust::Vec<_>>().into_iter().rev()
-                    .map(|crate::FfiExport { gen_def, .. }| gen_def)
+                    .rev()
+                    .map(|&crate::FfiExport { gen_def, .. }| gen_def)
             )
         };
         (&mut { gen_defs }).try_for_each(|gen_def| gen_def(definer, lang))?;
@@ -422,22 +1079,146 @@ impl Builder<'_, WhereTo> {
     {
         let lang = self.language.unwrap_or(Language::C);
         match lang {
-            | Language::C => write!(definer.out(),
-                include_str!("templates/c/epilogue.h"),
-                guard = self.guard(),
-            ),
+            | Language::C => {
+                if let Some(prefix) = self.dlopen_api {
+                    self.write_dlopen_api(definer, prefix)?;
+                }
+                write!(definer.out(),
+                    include_str!("templates/c/epilogue.h"),
+                    guard_close = self.guard_close(),
+                )
+            },
 
             | Language::CSharp => {
-                let pkg_name = Self::pascal_cased_lib_name();
+                let pkg_name = self.csharp_namespace();
                     write!(definer.out(),
                 include_str!("templates/csharp/epilogue.cs"),
                 PkgName = pkg_name,
             )
             },
             #[cfg(feature = "python-headers")]
-            // CHECKME
-            | Language::Python => Ok(()),
+            | Language::Python => match self.python_cffi_header {
+                | Some(_) => write!(definer.out(),
+                    include_str!("templates/python/ffi_build_epilogue.py"),
+                ),
+                // CHECKME
+                | None => Ok(()),
+            },
+
+            | Language::MsvcDef => languages::SymbolList::MsvcDef.emit_epilogue(definer),
+
+            | Language::LdVersionScript => languages::SymbolList::LdVersionScript.emit_epilogue(definer),
+
+            | Language::MacosExportedSymbols => {
+                languages::SymbolList::MacosExportedSymbols.emit_epilogue(definer)
+            },
+
+            | Language::Markdown => Ok(()),
+
+            | Language::Cython => Ok(()),
+
+            | Language::CSmokeTest => write!(definer.out(),
+                include_str!("templates/c_smoke_test/epilogue.c"),
+            ),
+
+            | Language::LayoutSnapshot => Ok(()),
+        }
+    }
+
+    /// See [`Builder::with_dlopen_api()`].
+    fn write_dlopen_api (&'_ self, definer: &'_ mut dyn Definer, prefix: &'_ str)
+      -> io::Result<()>
+    {
+        let functions =
+            registry::ffi_exports()
+                .into_iter()
+                .filter(|export| export.kind == ItemKind::Function)
+                .map(|&crate::FfiExport { name, gen_def, .. }| (name, gen_def))
+                .collect::<::std::collections::BTreeMap<_, _>>()
+        ;
+        let mut fields = FnPointerFields {
+            settings: Settings {
+                function_pointer_fields: true,
+                version_macro: None,
+                doxygen_groups: false,
+                .. definer.settings().clone()
+            },
+            fname: "",
+            out: vec![],
+        };
+        // The functions making it to the table, along with their `#[cfg]`
+        // guard, if any.
+        let mut loaded = vec![];
+        for (&fname, gen_def) in &functions {
+            fields.fname = fname;
+            let start = fields.out.len();
+            gen_def(&mut fields, Language::C)?;
+            let field = ::core::str::from_utf8(&fields.out[start ..]).unwrap();
+            if field.is_empty() {
+                // (`#[cfg]`-gated away, and lacking a C condition)
+                continue;
+            }
+            let cfg_guard =
+                field
+                    .strip_prefix("#if ")
+                    .and_then(|it| it.lines().next())
+                    .map(str::to_owned)
+            ;
+            loaded.push((fname, cfg_guard));
+        }
+        let static_inline = if definer.settings().c_standard.has_inline() {
+            "static inline"
+        } else {
+            "static"
+        };
+        let symbol_name = format!("{}_API_SYMBOL_NAME", prefix.to_ascii_uppercase());
+        let out = definer.out();
+        write!(out,
+            concat!(
+                "/** \\brief\n",
+                " *  The exported functions, as loaded by `{prefix}_load_api()`.\n",
+                " */\n",
+                "struct {prefix}_api {{\n",
+                "{fields}",
+                "}};\n",
+                "\n",
+                "#ifndef _WIN32\n",
+                "#include <dlfcn.h>\n",
+                "\n",
+                "/* (expanding the `#define`s of the renamed functions) */\n",
+                "#define {symbol_name}_(name) #name\n",
+                "#define {symbol_name}(name) {symbol_name}_(name)\n",
+                "\n",
+                "/** \\brief\n",
+                " *  Looks up (`dlsym()`) every exported function within the library\n",
+                " *  `dlopen()`ed as `dlhandle`.\n",
+                " *\n",
+                " *  Returns the number of missing functions (whose fields are then\n",
+                " *  `NULL`).\n",
+                " */\n",
+                "{static_inline}\n",
+                "int {prefix}_load_api (void * dlhandle, struct {prefix}_api * out)\n",
+                "{{\n",
+                "    int missing = 0;\n",
+            ),
+            prefix = prefix,
+            fields = String::from_utf8(fields.out).unwrap(),
+            symbol_name = symbol_name,
+            static_inline = static_inline,
+        )?;
+        for (fname, cfg_guard) in loaded {
+            if let Some(cfg_guard) = &cfg_guard {
+                writeln!(out, "#if {cfg_guard}")?;
+            }
+            writeln!(out,
+                "    *(void **) &out->{fname} = dlsym(dlhandle, {symbol_name}({fname}));\n    \
+                missing += out->{fname} == NULL;",
+            )?;
+            if let Some(cfg_guard) = &cfg_guard {
+                writeln!(out, "#endif /* {cfg_guard} */")?;
+            }
         }
+        writeln!(out, "    return missing;\n}}\n#endif /* !_WIN32 */\n")
     }
 
     fn guard (&'_ self)
@@ -449,6 +1230,40 @@ impl Builder<'_, WhereTo> {
         )
     }
 
+    fn guard_style (&'_ self)
+      -> GuardStyle
+    {
+        self.guard_style
+            .or(self.profile.map(Profile::guard_style))
+            .unwrap_or(GuardStyle::IfnDef)
+    }
+
+    fn guard_open (&'_ self)
+      -> String
+    {
+        let guard = self.guard();
+        match self.guard_style() {
+            | GuardStyle::IfnDef => format!(
+                "#ifndef {guard}\n#define {guard}\n",
+            ),
+            | GuardStyle::PragmaOnce => "#pragma once\n".into(),
+            | GuardStyle::Both => format!(
+                "#pragma once\n#ifndef {guard}\n#define {guard}\n",
+            ),
+        }
+    }
+
+    fn guard_close (&'_ self)
+      -> String
+    {
+        match self.guard_style() {
+            | GuardStyle::PragmaOnce => "".into(),
+            | GuardStyle::IfnDef | GuardStyle::Both => format!(
+                "\n#endif /* {} */\n", self.guard(),
+            ),
+        }
+    }
+
     /// Return the library name
     fn lib_name ()
       -> String
@@ -461,6 +1276,20 @@ impl Builder<'_, WhereTo> {
             .expect("Missing `CARGO_{CRATE,PKG}_NAME` env vars")
     }
 
+    /// Return the name of the dynamic library to load.
+    fn library_name (&'_ self)
+      -> String
+    {
+        self.library_name.map_or_else(Self::lib_name, Into::into)
+    }
+
+    /// Return the C# `namespace`.
+    fn csharp_namespace (&'_ self)
+      -> String
+    {
+        self.csharp_namespace.map_or_else(Self::pascal_cased_lib_name, Into::into)
+    }
+
     /// Return a Pascal Cased (UpperCamelCase) version of the lib name.
     fn pascal_cased_lib_name() -> String {
         Self::lib_name()
@@ -505,6 +1334,306 @@ enum Language {
     /// Python (experimental).
     #[cfg(feature = "python-headers")]
     Python,
+
+    /// Windows module-definition file (`.def`), listing the exported
+    /// functions, for MSVC consumers.
+    MsvcDef,
+
+    /// GNU `ld` version script, exporting the `#[ffi_export]`ed functions
+    /// while hiding every other symbol of the shared library.
+    LdVersionScript,
+
+    /// macOS `-exported_symbols_list` file, listing the `#[ffi_export]`ed
+    /// functions (every other symbol of the dylib is then hidden).
+    MacosExportedSymbols,
+
+    /// Markdown API reference: the docs of every exported item, along with
+    /// its C definition (and, for structs, its size and fields).
+    Markdown,
+
+    /// Cython `.pxd` file, declaring the exported items within a
+    /// `cdef extern from` block for the C header (see
+    /// [`Builder::with_cython_header()`]).
+    Cython,
+
+    /// C smoke-test translation unit (_e.g._, `test_mylib.c`), `#include`-ing
+    /// the C header (see [`Builder::with_smoke_test_header()`]), for CI to
+    /// compile (as C11) and link against the library: it assigns every
+    /// exported function to a pointer of its expected type, instantiates every
+    /// emitted `struct`, and `_Static_assert`s their layouts (if
+    /// [`Builder::with_layout_targets()`] agree on them).
+    CSmokeTest,
+
+    /// Snapshot of the size, alignment, and field offsets of every exported
+    /// type (_e.g._, `safer-ffi-layouts.snap`), as checked by the
+    /// [`layout_tests!`][`crate::layout_tests`].
+    LayoutSnapshot,
+}
+
+/// The (kebab-cased) names of the [`Language`]s, as accepted by the
+/// `cargo safer-ffi generate --language` command line.
+impl ::core::str::FromStr
+    for Language
+{
+    type Err = String;
+
+    fn from_str (s: &'_ str)
+      -> Result<Language, String>
+    {
+        Ok(match s {
+            | "c" => Language::C,
+            | "csharp" => Language::CSharp,
+            #[cfg(feature = "python-headers")]
+            | "python" => Language::Python,
+            | "msvc-def" => Language::MsvcDef,
+            | "ld-version-script" => Language::LdVersionScript,
+            | "macos-exported-symbols" => Language::MacosExportedSymbols,
+            | "markdown" => Language::Markdown,
+            | "cython" => Language::Cython,
+            | "c-smoke-test" => Language::CSmokeTest,
+            | "layout-snapshot" => Language::LayoutSnapshot,
+            | _ => return Err(format!("unknown headers language: `{s}`")),
+        })
+    }
+}
+
+/// The `main()` of the binary which `cargo safer-ffi generate` runs, as
+/// defined by [`headers_entry_point!`][`crate::headers_entry_point`]:
+/// generates the headers, with the options of the `configure`d [`builder()`],
+/// to the `SAFER_FFI_HEADERS_OUTPUT` file, in the `SAFER_FFI_HEADERS_LANGUAGE`
+/// (if set, overriding the configured one).
+pub
+fn generate_from_env (
+    configure: impl FnOnce(Builder<'static, WhereTo>) -> Builder<'static, WhereTo>,
+) -> io::Result<()>
+{
+    let output = ::std::env::var_os("SAFER_FFI_HEADERS_OUTPUT").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "`$SAFER_FFI_HEADERS_OUTPUT` is not set: \
+            run this through `cargo safer-ffi generate`",
+        )
+    })?;
+    let mut builder = configure(builder());
+    if let Ok(language) = ::std::env::var("SAFER_FFI_HEADERS_LANGUAGE") {
+        builder = builder.with_language(language.parse().map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidInput, err)
+        })?);
+    }
+    builder
+        .to_file(output)?
+        .generate()
+}
+
+/// Presets for the C header generation options, so as to get a coherent
+/// output without having to tune each option individually.
+///
+/// See [`Builder::with_profile()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub
+enum Profile {
+    /// For legacy compilers: ANSI C ([`CStandard::C89`]), with
+    /// [`typedef`ed integer types][`Builder::with_stdint_fallback`] when
+    /// `<stdint.h>` is missing, verbatim doc comments, `#ifndef` include
+    /// guards, and no attributes.
+    StrictC89,
+
+    /// [`CStandard::C11`], Doxygen [doc tags][`Builder::with_doc_tags`],
+    /// both `#pragma once` and `#ifndef` include guards, static assertions of
+    /// the layout of the `struct`s, and [`restrict`][`Builder::with_restrict_pointers`]
+    /// and [nullability][`Builder::with_nullability_annotations`] annotations.
+    ModernC,
+
+    /// For freestanding (embedded) environments: [`CStandard::C99`] (whose
+    /// `<stddef.h>`, `<stdint.h>` and `<stdbool.h>` headers are available even
+    /// without a standard library), verbatim doc comments, `#ifndef` include
+    /// guards, static assertions of the layout of the `struct`s
+    /// (cross-compilation being the norm there), and
+    /// [`restrict`][`Builder::with_restrict_pointers`] annotations.
+    EmbeddedNoStdlib,
+
+    /// In the style of the Windows SDK headers: Doxygen
+    /// [doc tags][`Builder::with_doc_tags`], `#pragma once`, static
+    /// assertions of the layout of the `struct`s, and function declarations
+    /// decorated with a `<LIB>_API` [DLL export/import macro][`Builder::with_api_macro`]
+    /// (but no Clang-specific attributes).
+    WindowsSdkStyle,
+}
+
+impl Profile {
+    fn c_standard (self: Profile)
+      -> CStandard
+    {
+        match self {
+            | Profile::StrictC89 => CStandard::C89,
+            | Profile::ModernC => CStandard::C11,
+            | Profile::EmbeddedNoStdlib => CStandard::C99,
+            | Profile::WindowsSdkStyle => CStandard::C11,
+        }
+    }
+
+    fn guard_style (self: Profile)
+      -> GuardStyle
+    {
+        match self {
+            | Profile::StrictC89 => GuardStyle::IfnDef,
+            | Profile::ModernC => GuardStyle::Both,
+            | Profile::EmbeddedNoStdlib => GuardStyle::IfnDef,
+            | Profile::WindowsSdkStyle => GuardStyle::PragmaOnce,
+        }
+    }
+
+    fn static_asserts (self: Profile)
+      -> bool
+    {
+        match self {
+            | Profile::StrictC89 => false,
+            | Profile::ModernC => true,
+            | Profile::EmbeddedNoStdlib => true,
+            | Profile::WindowsSdkStyle => true,
+        }
+    }
+
+    fn stdint_fallback (self: Profile)
+      -> bool
+    {
+        matches!(self, Profile::StrictC89)
+    }
+
+    fn doc_tags (self: Profile)
+      -> bool
+    {
+        matches!(self, Profile::ModernC | Profile::WindowsSdkStyle)
+    }
+
+    fn restrict_pointers (self: Profile)
+      -> bool
+    {
+        matches!(self, Profile::ModernC | Profile::EmbeddedNoStdlib)
+    }
+
+    fn nullability_annotations (self: Profile)
+      -> bool
+    {
+        matches!(self, Profile::ModernC)
+    }
+
+    fn api_macro (self: Profile)
+      -> bool
+    {
+        matches!(self, Profile::WindowsSdkStyle)
+    }
+}
+
+/// Revision of the C standard which the generated C header complies with.
+///
+/// The variants are ordered chronologically, so that they can be compared
+/// (_e.g._, `c_standard >= CStandard::C99`).
+#[derive(
+    Debug,
+    Copy, Clone,
+    PartialEq, Eq,
+    PartialOrd, Ord,
+)]
+pub
+enum CStandard {
+    /// ANSI C (C89 / C90), for legacy (embedded) compilers.
+    C89,
+
+    /// C99.
+    C99,
+
+    /// C11.
+    C11,
+
+    /// C23.
+    C23,
+}
+
+impl CStandard {
+    /// Whether `bool` is a keyword of the language (C23).
+    pub
+    fn has_bool_keyword (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C23
+    }
+
+    /// Whether `<stdbool.h>` is available (C99).
+    pub
+    fn has_stdbool (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C99
+    }
+
+    /// Whether the last enumerator of an `enum` may be followed by a comma
+    /// (C99).
+    pub
+    fn has_trailing_enum_commas (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C99
+    }
+
+    /// Whether `inline` functions are available (C99).
+    pub
+    fn has_inline (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C99
+    }
+
+    /// Whether `_Static_assert` is available (C11).
+    pub
+    fn has_static_assert (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C11
+    }
+
+    /// Whether `static_assert` and `alignof` are keywords of the language
+    /// (C23), as in C++.
+    pub
+    fn has_static_assert_keyword (self: CStandard)
+      -> bool
+    {
+        self >= CStandard::C23
+    }
+}
+
+/// Style of the include guard of the generated C header.
+#[derive(
+    Debug,
+    Copy, Clone,
+    PartialEq, Eq,
+)]
+pub
+enum GuardStyle {
+    /// The classic `#ifndef GUARD` / `#define GUARD` / `#endif` macro guard.
+    IfnDef,
+
+    /// A single `#pragma once` line (non-standard, but supported by every
+    /// mainstream compiler).
+    PragmaOnce,
+
+    /// Both a `#pragma once` and the macro guard.
+    Both,
+}
+
+/// Line endings of the generated file.
+#[derive(
+    Debug,
+    Copy, Clone,
+    PartialEq, Eq,
+)]
+pub
+enum LineEndings {
+    /// `\n`
+    Lf,
+
+    /// `\r\n`
+    CrLf,
 }
 
 /// Allow user to specify
@@ -534,6 +1663,23 @@ hidden_export! {
             | Language::Python => {
                 <T::CLayout as CType>::define_self(&crate::headers::languages::Python, definer)
             },
+            | Language::Markdown => {
+                <T::CLayout as CType>::define_self(&crate::headers::languages::Markdown, definer)
+            },
+            | Language::Cython => {
+                <T::CLayout as CType>::define_self(&crate::headers::languages::Cython, definer)
+            },
+            | Language::CSmokeTest => {
+                <T::CLayout as CType>::define_self(&crate::headers::languages::CSmokeTest, definer)
+            },
+            | Language::LayoutSnapshot => {
+                <T::CLayout as CType>::define_self(&crate::headers::languages::LayoutSnapshot, definer)
+            },
+            // No type definitions in symbol lists.
+            | Language::MsvcDef
+            | Language::LdVersionScript
+            | Language::MacosExportedSymbols
+            => Ok(()),
         }
     }
 }
@@ -544,6 +1690,184 @@ use self::languages::{
     PhantomCType,
 };
 
+/// The `#[ffi_export]`-provided information about a function, besides its
+/// signature and docs.
+#[apply(hidden_export)]
+#[derive(Debug, Clone, Copy)]
+struct FnMeta<'lt> {
+    /// The `module_path!()` of the function.
+    pub
+    module_path: &'lt str,
+
+    /// The `file!()` of the function.
+    pub
+    file: &'lt str,
+
+    /// The `CARGO_MANIFEST_DIR` of the crate of the function, for `file` to
+    /// be resolved against (it is relative to the workspace root, if any).
+    pub
+    manifest_dir: &'lt str,
+
+    /// `#[ffi_export(since = …)]`
+    pub
+    since: Option<&'lt str>,
+
+    /// `#[ffi_export(group = …)]`
+    pub
+    group: Option<&'lt str>,
+
+    /// `#[ffi_export(error_code)]`
+    pub
+    error_code: bool,
+
+    /// Whether the function is C-variadic (`args: ...`).
+    pub
+    variadic: bool,
+
+    /// `#[ffi_export(export_name = …)]`: the symbol, when it differs from the
+    /// name of the function in the headers.
+    pub
+    export_name: Option<&'lt str>,
+
+    /// `#[ffi_export(convention = …)]`: the calling convention (ABI string),
+    /// when other than `"C"`.
+    pub
+    convention: Option<&'lt str>,
+
+    /// `#[deprecated]`: its `note` (`""` if none).
+    pub
+    deprecated: Option<&'lt str>,
+
+    /// `#[must_use]`
+    pub
+    must_use: bool,
+
+    /// `#[ffi_pure]` (`"pure"`) or `#[ffi_const]` (`"const"`).
+    pub
+    purity: Option<&'lt str>,
+
+    /// The niche of the return value.
+    pub
+    ret_niche: crate::layout::Niche,
+
+    /// The `#[cfg(…)]` predicate of the function (or its
+    /// `#[ffi_export(cfg_header = …)]` override), if any.
+    pub
+    cfg: Option<&'lt CfgPredicate<'lt>>,
+
+    /// Whether the `cfg` predicate holds for the target the headers are being
+    /// generated for.
+    pub
+    cfg_active: bool,
+}
+
+/// A `#[cfg(…)]` predicate, to be translated to a C preprocessor condition
+/// (see [`Builder::with_cfg_conditions()`]).
+#[apply(hidden_export)]
+#[derive(Debug, Clone, Copy)]
+enum CfgPredicate<'lt> {
+    /// `#[ffi_export(cfg_header = "…")]`: the condition itself.
+    Header(&'lt str),
+    /// `#[cfg(name)]`
+    Name(&'lt str),
+    /// `#[cfg(key = "value")]`
+    KeyValue(&'lt str, &'lt str),
+    /// `#[cfg(all(…))]`
+    All(&'lt [CfgPredicate<'lt>]),
+    /// `#[cfg(any(…))]`
+    Any(&'lt [CfgPredicate<'lt>]),
+    /// `#[cfg(not(…))]`
+    Not(&'lt CfgPredicate<'lt>),
+}
+
+impl FnMeta<'_> {
+    pub(in crate)
+    const DEFAULT: Self = Self {
+        module_path: "",
+        file: "",
+        manifest_dir: "",
+        since: None,
+        group: None,
+        error_code: false,
+        variadic: false,
+        export_name: None,
+        convention: None,
+        deprecated: None,
+        must_use: false,
+        purity: None,
+        ret_niche: crate::layout::Niche::Unused,
+        cfg: None,
+        cfg_active: true,
+    };
+}
+
+/// The C preprocessor condition equivalent to the `cfg` predicate, the
+/// `Builder::with_cfg_conditions()` mappings taking precedence over the
+/// built-in ones (`None` if some predicate is mapped by neither).
+fn cfg_condition (
+    cfg: &'_ CfgPredicate<'_>,
+    settings: &'_ Settings,
+) -> Option<String>
+{
+    let join = |predicates: &[CfgPredicate<'_>], sep: &str| {
+        predicates
+            .iter()
+            .map(|it| {
+                let condition = cfg_condition(it, settings)?;
+                Some(if condition.contains(' ') && predicates.len() > 1 {
+                    format!("({condition})")
+                } else {
+                    condition
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|conditions| conditions.join(sep))
+    };
+    let key = match *cfg {
+        | CfgPredicate::Header(condition) => return Some(condition.into()),
+        | CfgPredicate::All([]) => return Some("1".into()),
+        | CfgPredicate::Any([]) => return Some("0".into()),
+        | CfgPredicate::All(predicates) => return join(predicates, " && "),
+        | CfgPredicate::Any(predicates) => return join(predicates, " || "),
+        | CfgPredicate::Not(predicate) => {
+            return Some(format!("!({})", cfg_condition(predicate, settings)?));
+        },
+        | CfgPredicate::Name(name) => name.to_owned(),
+        | CfgPredicate::KeyValue(key, value) => format!("{key} = \"{value}\""),
+    };
+    if let Some((_, condition)) = settings.cfg_conditions.iter().find(|(it, _)| *it == key) {
+        return Some(condition.clone());
+    }
+    let condition = match *cfg {
+        | CfgPredicate::Name("windows")
+        | CfgPredicate::KeyValue("target_family" | "target_os", "windows")
+        => "defined(_WIN32)",
+        | CfgPredicate::Name("unix")
+        | CfgPredicate::KeyValue("target_family", "unix")
+        => "(defined(__unix__) || defined(__APPLE__))",
+        | CfgPredicate::KeyValue("target_family", "wasm") => "defined(__wasm__)",
+        | CfgPredicate::KeyValue("target_os", "linux") => "defined(__linux__)",
+        | CfgPredicate::KeyValue("target_os", "macos") => {
+            "(defined(__APPLE__) && defined(__MACH__))"
+        },
+        | CfgPredicate::KeyValue("target_os", "android") => "defined(__ANDROID__)",
+        | CfgPredicate::KeyValue("target_os", "freebsd") => "defined(__FreeBSD__)",
+        | CfgPredicate::KeyValue("target_arch", "x86_64") => {
+            "(defined(__x86_64__) || defined(_M_X64))"
+        },
+        | CfgPredicate::KeyValue("target_arch", "x86") => "(defined(__i386__) || defined(_M_IX86))",
+        | CfgPredicate::KeyValue("target_arch", "aarch64") => {
+            "(defined(__aarch64__) || defined(_M_ARM64))"
+        },
+        | CfgPredicate::KeyValue("target_arch", "arm") => "(defined(__arm__) || defined(_M_ARM))",
+        | CfgPredicate::KeyValue("target_arch", "wasm32") => "defined(__wasm32__)",
+        | CfgPredicate::KeyValue("target_pointer_width", "64") => "(UINTPTR_MAX == UINT64_MAX)",
+        | CfgPredicate::KeyValue("target_pointer_width", "32") => "(UINTPTR_MAX == UINT32_MAX)",
+        | _ => return None,
+    };
+    Some(condition.into())
+}
+
 #[apply(hidden_export)]
 fn __define_fn__ (
     definer: &'_ mut dyn Definer,
@@ -552,6 +1876,211 @@ fn __define_fn__ (
     fname: &'_ str,
     args: &'_ [FunctionArg<'_>],
     ret_ty: &'_ dyn PhantomCType,
+    meta: &'_ FnMeta<'_>,
+) -> io::Result<()>
+{
+    let &FnMeta {
+        module_path,
+        file,
+        manifest_dir,
+        since,
+        group,
+        error_code,
+        variadic,
+        export_name,
+        convention,
+        deprecated,
+        must_use: _,
+        purity: _,
+        ret_niche: _,
+        cfg,
+        cfg_active,
+    } = meta;
+    // Only the C headers can guard the declaration of a function gated
+    // behind some `#[cfg]`: the other languages (or lacking a condition for
+    // some predicate) only get to see it when generated for a target where it
+    // exists.
+    let cfg_guard = match (lang, cfg) {
+        | (Language::C, Some(cfg)) if definer.settings().cfg_guards => {
+            cfg_condition(cfg, definer.settings())
+        },
+        | _ => None,
+    };
+    if cfg_guard.is_none() && cfg_active.not() {
+        return Ok(());
+    }
+    if lang == Language::C && definer.settings().function_pointer_fields {
+        if let Some(cfg_guard) = &cfg_guard {
+            writeln!(definer.out(), "#if {cfg_guard}")?;
+        }
+        languages::C.emit_function_pointer_field(definer, fname, args, ret_ty, meta)?;
+        if let Some(cfg_guard) = &cfg_guard {
+            writeln!(definer.out(), "#endif /* {cfg_guard} */")?;
+        }
+        return Ok(());
+    }
+    if lang == Language::CSmokeTest {
+        // (for the pointer to have the calling convention of the function)
+        return languages::CSmokeTest.emit_function_(definer, fname, args, ret_ty, meta);
+    }
+    if let Some(cfg_guard) = &cfg_guard {
+        writeln!(definer.out(), "#if {cfg_guard}")?;
+    }
+    let ingroup;
+    let mut storage = None;
+    let docs = if lang == Language::C && definer.settings().doxygen_groups {
+        ingroup = doxygen_ingroup(definer, module_path, file, manifest_dir)?;
+        with_ingroup(storage.get_or_insert(docs.to_vec()), &ingroup)
+    } else {
+        docs
+    };
+    let dyn_lang: &dyn HeaderLanguage = match lang {
+        | Language::C => &languages::C,
+        | Language::CSharp => &languages::CSharp,
+        #[cfg(feature = "python-headers")]
+        | Language::Python => &languages::Python,
+        | Language::MsvcDef => &languages::SymbolList::MsvcDef,
+        | Language::LdVersionScript => &languages::SymbolList::LdVersionScript,
+        | Language::MacosExportedSymbols => &languages::SymbolList::MacosExportedSymbols,
+        | Language::Markdown => &languages::Markdown,
+        | Language::Cython => &languages::Cython,
+        | Language::CSmokeTest => &languages::CSmokeTest,
+        | Language::LayoutSnapshot => &languages::LayoutSnapshot,
+    };
+    let version_guard = match (lang, since, &definer.settings().version_macro) {
+        | (Language::C, Some(since), Some(version_macro)) => Some(format!(
+            "#if !defined({version_macro}) || {version_macro} >= {} /* {since} */",
+            encoded_version(since),
+        )),
+        | _ => None,
+    };
+    if let Some(version_guard) = &version_guard {
+        writeln!(definer.out(), "{version_guard}")?;
+    }
+    // Bar C#'s `EntryPoint`, the languages get to see the symbol itself (C
+    // code can still call the function by its name, through a `#define`).
+    let symbol = export_name.unwrap_or(fname);
+    match (lang, group) {
+        // C gets to see the whole `meta` (_e.g._, to annotate the declaration
+        // with its calling convention), and C++, the `group` (as a
+        // `namespace`).
+        | (Language::C, _) => {
+            let namespace = group.map(|group| {
+                crate::utils::screaming_case(group, "").to_string().to_ascii_lowercase()
+            });
+            if let Some(namespace) = &namespace {
+                writeln!(definer.out(), "#ifdef __cplusplus\nnamespace {namespace} {{\n#endif\n")?;
+            }
+            languages::C.emit_function_(
+                definer,
+                docs,
+                symbol,
+                args,
+                ret_ty,
+                meta,
+            )?;
+            if let Some(namespace) = &namespace {
+                writeln!(definer.out(), "#ifdef __cplusplus\n}} /* namespace {namespace} */\n#endif\n")?;
+            }
+        },
+        // Languages unable to express `...` skip the function altogether.
+        | _ if variadic => dyn_lang.emit_variadic_function(
+            definer,
+            docs,
+            symbol,
+            args,
+            ret_ty,
+        )?,
+        // C# namespaces through its static classes.
+        | (Language::CSharp, _) => languages::CSharp.emit_grouped_function(
+            definer,
+            group,
+            docs,
+            fname,
+            symbol,
+            convention,
+            deprecated,
+            args,
+            ret_ty,
+        )?,
+        | _ => dyn_lang.emit_function(
+            definer,
+            docs,
+            symbol,
+            args,
+            ret_ty,
+        )?,
+    }
+    if lang == Language::C && symbol != fname {
+        writeln!(definer.out(), "#define {fname} {symbol}\n")?;
+    }
+    if version_guard.is_some() {
+        writeln!(definer.out(), "#endif\n")?;
+    }
+    if let Some(cfg_guard) = &cfg_guard {
+        writeln!(definer.out(), "#endif /* {cfg_guard} */\n")?;
+    }
+    if let (Language::CSharp, Some(wrapper_class), false) =
+        (lang, definer.settings().csharp_wrapper_class.clone(), variadic)
+    {
+        let class_name = definer.settings().csharp_class_name().to_owned();
+        languages::CSharp.emit_wrapper(
+            definer,
+            &wrapper_class,
+            group.unwrap_or(&class_name),
+            docs,
+            fname,
+            args,
+            ret_ty,
+            error_code,
+            deprecated,
+        )?;
+    }
+    Ok(())
+}
+
+/// The [`Definer`] of the fields of the [`Builder::with_dlopen_api()`]
+/// table, one function (`fname`) at a time: the types involved have already
+/// been defined by then.
+struct FnPointerFields {
+    settings: Settings,
+    fname: &'static str,
+    out: Vec<u8>,
+}
+
+impl Definer
+    for FnPointerFields
+{
+    fn insert (self: &'_ mut Self, name: &'_ str)
+      -> bool
+    {
+        name == self.fname
+    }
+
+    fn out (self: &'_ mut Self)
+      -> &'_ mut dyn io::Write
+    {
+        &mut self.out
+    }
+
+    fn settings (self: &'_ Self)
+      -> &'_ Settings
+    {
+        &self.settings
+    }
+}
+
+#[apply(hidden_export)]
+fn __define_static__ (
+    definer: &'_ mut dyn Definer,
+    lang: Language,
+    docs: &'_ [&'_ str],
+    name: &'_ str,
+    ty: &'_ dyn PhantomCType,
+    mutable: bool,
+    module_path: &'_ str,
+    file: &'_ str,
+    manifest_dir: &'_ str,
 ) -> io::Result<()>
 {
     let dyn_lang: &dyn HeaderLanguage = match lang {
@@ -559,16 +2088,171 @@ fn __define_fn__ (
         | Language::CSharp => &languages::CSharp,
         #[cfg(feature = "python-headers")]
         | Language::Python => &languages::Python,
+        | Language::MsvcDef => &languages::SymbolList::MsvcDef,
+        | Language::LdVersionScript => &languages::SymbolList::LdVersionScript,
+        | Language::MacosExportedSymbols => &languages::SymbolList::MacosExportedSymbols,
+        | Language::Markdown => &languages::Markdown,
+        | Language::Cython => &languages::Cython,
+        | Language::CSmokeTest => &languages::CSmokeTest,
+        | Language::LayoutSnapshot => &languages::LayoutSnapshot,
     };
-    dyn_lang.emit_function(
+    __with_doxygen_group__(
         definer,
+        dyn_lang,
         docs,
-        fname,
-        args,
-        ret_ty,
+        module_path,
+        file,
+        manifest_dir,
+        &mut |definer, docs| dyn_lang.emit_static(definer, docs, name, ty, mutable),
     )
 }
 
+/// `"MAJOR.MINOR[.PATCH]"` (as validated by `#[ffi_export(since = …)]`) to
+/// `MAJOR * 1000000 + MINOR * 1000 + PATCH`.
+fn encoded_version (version: &'_ str)
+  -> u64
+{
+    version
+        .split('.')
+        .chain(["0"])
+        .take(3)
+        .fold(0, |acc, part| acc * 1000 + part.parse::<u64>().unwrap_or(0))
+}
+
+::std::thread_local! {
+    /// Docs to append to those of the next emitted type (see
+    /// [`__define_vec__()`]).
+    static EXTRA_DOCS: ::core::cell::Cell<&'static [&'static str]> = {
+        ::core::cell::Cell::new(&[])
+    };
+}
+
+/// `__define_self__::<repr_c::Vec<T>>()`, with `docs` appended to the docs of
+/// the `Vec_<T>_t` definition (unless some earlier item already defined it).
+///
+/// Used by `#[ffi_export(vec)]`, for the definition to mention the helpers.
+#[apply(hidden_export)]
+fn __define_vec__<T : ReprC> (
+    definer: &'_ mut dyn Definer,
+    lang: Language,
+    docs: &'static [&'static str],
+) -> io::Result<()>
+{
+    // The only type definition left to emit is then the `Vec` one.
+    __define_self__::<T>(definer, lang)?;
+    EXTRA_DOCS.with(|it| it.set(docs));
+    let ret = __define_self__::<crate::vec::Vec<T>>(definer, lang);
+    EXTRA_DOCS.with(|it| it.set(&[]));
+    ret
+}
+
+/// Emits, through `emit`, the `docs` of an item of the Rust module at
+/// `module_path` (defined in `file`), suffixed, for
+/// [`Builder::with_doxygen_groups()`] C headers, with the `\ingroup` of that
+/// module.
+#[apply(hidden_export)]
+fn __with_doxygen_group__ (
+    definer: &'_ mut dyn Definer,
+    language: &'_ dyn HeaderLanguage,
+    docs: &'_ [&'_ str],
+    module_path: &'_ str,
+    file: &'_ str,
+    manifest_dir: &'_ str,
+    emit: &'_ mut dyn FnMut(&'_ mut dyn Definer, &'_ [&'_ str]) -> io::Result<()>,
+) -> io::Result<()>
+{
+    let extra_docs = EXTRA_DOCS.with(|it| it.replace(&[]));
+    let ref mut docs = docs.to_vec();
+    if extra_docs.is_empty().not() {
+        if docs.is_empty().not() {
+            docs.push("");
+        }
+        docs.extend(extra_docs);
+    }
+    if language.is::<languages::C>().not() || definer.settings().doxygen_groups.not() {
+        return emit(definer, docs);
+    }
+    let ingroup = doxygen_ingroup(definer, module_path, file, manifest_dir)?;
+    emit(definer, with_ingroup(docs, &ingroup))
+}
+
+fn with_ingroup<'docs> (
+    docs: &'docs mut Vec<&'docs str>,
+    ingroup: &'docs str,
+) -> &'docs [&'docs str]
+{
+    if docs.is_empty().not() {
+        docs.push("");
+    }
+    docs.push(ingroup);
+    docs
+}
+
+/// Emits (once) the `\defgroup` of the Rust module at `module_path`, and
+/// returns the `\ingroup` line for its items.
+///
+/// The brief of the group is the leading `//!` doc comment of the module's
+/// `file`, when readable.
+fn doxygen_ingroup (
+    definer: &'_ mut dyn Definer,
+    module_path: &'_ str,
+    file: &'_ str,
+    manifest_dir: &'_ str,
+) -> io::Result<String>
+{
+    let group = module_path.replace("::", "_");
+    definer.define_once(&format!("{group}__defgroup"), &mut |definer| {
+        let source =
+            resolve_source_file(file, manifest_dir)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .unwrap_or_default()
+        ;
+        let out = definer.out();
+        writeln!(out, "/** \\defgroup {group} `{module_path}`")?;
+        for line in module_docs(&source) {
+            writeln!(out, " *{}{line}", if line.is_empty() { "" } else { " " })?;
+        }
+        writeln!(out, " */\n")
+    })?;
+    Ok(format!("\\ingroup {group}"))
+}
+
+/// `file!()` is relative to the root of the workspace the crate belongs to
+/// (when not absolute), which is some ancestor of its `manifest_dir`.
+fn resolve_source_file (
+    file: &'_ str,
+    manifest_dir: &'_ str,
+) -> Option<PathBuf>
+{
+    let file = Path::new(file);
+    if file.is_absolute() {
+        return Some(file.to_owned());
+    }
+    Path::new(manifest_dir)
+        .ancestors()
+        .map(|dir| dir.join(file))
+        .find(|path| path.is_file())
+}
+
+/// The lines of the leading `//!` doc comment of `source` (inner attributes,
+/// plain comments and blank lines preceding it being skipped).
+fn module_docs (source: &'_ str)
+  -> impl '_ + Iterator<Item = &'_ str>
+{
+    source
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| {
+            line.starts_with("//!").not() && (
+                line.is_empty()
+                || line.starts_with("#![")
+                || line.starts_with("//")
+            )
+        })
+        .map_while(|line| line.strip_prefix("//!"))
+        .map(str::trim_end)
+}
+
 hidden_export! {
     /// Helpers for the generation of FFI-imported function declarations.
     mod __define_fn__ {
@@ -586,7 +2270,7 @@ hidden_export! {
         )
         {
             match lang {
-                | Language::C => write!(out,
+                | Language::C | Language::Cython | Language::Markdown => write!(out,
                     "{} (", f_name.trim(),
                 ),
 
@@ -597,6 +2281,15 @@ hidden_export! {
                 | Language::Python => write!(out,
                     "{} (", f_name.trim(),
                 ),
+
+                | Language::MsvcDef
+                | Language::LdVersionScript
+                | Language::MacosExportedSymbols
+                | Language::CSmokeTest
+                | Language::LayoutSnapshot
+                => write!(out,
+                    "{}", f_name.trim(),
+                ),
             }
             .expect("`write!`-ing to a `String` cannot fail")
         }
@@ -612,11 +2305,16 @@ hidden_export! {
                 out.push_str(",");
             }
             match lang {
-                | Language::C => write!(out,
+                | Language::C | Language::Markdown => write!(out,
                     "\n    {}",
                     Arg::CLayout::name_wrapping_var(&crate::headers::languages::C, arg_name),
                 ),
 
+                | Language::Cython => write!(out,
+                    "\n        {}",
+                    Arg::CLayout::name_wrapping_var(&crate::headers::languages::Cython, arg_name),
+                ),
+
                 | Language::CSharp => write!(out,
                     "\n        {marshaler}{}",
                      Arg::CLayout::name_wrapping_var(&crate::headers::languages::CSharp, arg_name),
@@ -632,6 +2330,13 @@ hidden_export! {
                     "\n    {}",
                     Arg::CLayout::name_wrapping_var(&crate::headers::languages::Python, arg_name),
                 ),
+
+                | Language::MsvcDef
+                | Language::LdVersionScript
+                | Language::MacosExportedSymbols
+                | Language::CSmokeTest
+                | Language::LayoutSnapshot
+                => Ok(()),
             }
             .expect("`write!`-ing to a `String` cannot fail")
         }
@@ -643,6 +2348,7 @@ hidden_export! {
             mut fname_and_args: String,
         ) -> Result<()>
         {
+            let class_name = definer.settings().csharp_class_name().to_owned();
             let out = definer.out();
             match lang {
                 | Language::C => {
@@ -658,7 +2364,7 @@ hidden_export! {
                 | Language::CSharp => {
                     writeln!(out,
                         concat!(
-                            "public unsafe partial class Ffi {{\n    ",
+                            "public unsafe partial class {class_name} {{\n    ",
                             "{mb_marshaler}",
                             "[DllImport(RustLib, ExactSpelling = true)] public static unsafe extern\n",
                             "    {});\n",
@@ -671,6 +2377,7 @@ hidden_export! {
                                 .as_deref()
                                 .unwrap_or("")
                         ,
+                        class_name = class_name,
                     )
                 },
                 #[cfg(feature = "python-headers")]
@@ -683,6 +2390,44 @@ hidden_export! {
                         Ret::CLayout::name_wrapping_var(&crate::headers::languages::Python, &fname_and_args),
                     )
                 },
+
+                | Language::MsvcDef => {
+                    languages::SymbolList::MsvcDef.emit_entry(definer, &fname_and_args, false)
+                },
+
+                | Language::LdVersionScript => {
+                    languages::SymbolList::LdVersionScript.emit_entry(definer, &fname_and_args, false)
+                },
+
+                | Language::MacosExportedSymbols => {
+                    languages::SymbolList::MacosExportedSymbols.emit_entry(definer, &fname_and_args, false)
+                },
+
+                | Language::Markdown => {
+                    if fname_and_args.ends_with("(") {
+                        fname_and_args.push_str("void");
+                    }
+                    writeln!(out,
+                        "```c\n{});\n```\n",
+                        Ret::CLayout::name_wrapping_var(&crate::headers::languages::C, &fname_and_args),
+                    )
+                },
+
+                | Language::Cython => {
+                    writeln!(out,
+                        "    {})\n",
+                        Ret::CLayout::name_wrapping_var(&crate::headers::languages::Cython, &fname_and_args),
+                    )
+                },
+
+                | Language::CSmokeTest => {
+                    writeln!(out,
+                        "void (*safer_ffi_smoke_test_{0}) (void) = (void (*) (void)) &{0};\n",
+                        fname_and_args,
+                    )
+                },
+
+                | Language::LayoutSnapshot => Ok(()),
             }
         }
     }
@@ -0,0 +1,333 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! JSON ABI manifests of the exported items, and the classification of the
+//! changes between two of them, so that releases may be gated on a
+//! semver-correct evolution of the FFI.
+//!
+//! ```rust,ignore
+//! #[::safer_ffi::cfg_headers]
+//! #[test]
+//! fn abi_evolution ()
+//!   -> ::std::io::Result<()>
+//! {
+//!     // The manifest of the last release.
+//!     let old = ::std::fs::read_to_string("abi.json")?;
+//!     let new = ::safer_ffi::headers::abi_manifest()?;
+//!     let diff = ::safer_ffi::headers::diff(&old, &new)?;
+//!     assert!(diff.is_breaking().not(), "{diff}");
+//!     Ok(())
+//! }
+//! ```
+
+use super::*;
+use ::std::collections::BTreeMap;
+use ::serde_json::{self, json, Value};
+
+/// Bumped along with the format of the manifests.
+const VERSION: u64 = 1;
+
+/// The JSON ABI manifest of the exported items: the C declaration of each
+/// of them (comments and whitespace aside), keyed by name, along with the
+/// size, alignment, and field offsets of every type involved (see
+/// [`Language::LayoutSnapshot`]).
+///
+/// ```json
+/// {
+///   "safer_ffi_abi_manifest": 1,
+///   "items": {
+///     "point_norm": {
+///       "kind": "function",
+///       "declaration": "double point_norm ( Point_t const * p);"
+///     }
+///   },
+///   "layouts": {
+///     "Point_t": { "size": 16, "align": 8, "fields": [["x", 0], ["y", 8]] }
+///   }
+/// }
+/// ```
+pub
+fn abi_manifest ()
+  -> io::Result<String>
+{
+    let mut items = serde_json::Map::new();
+    for (export, declaration) in declarations(Language::C, Settings::default())? {
+        items.insert(export.name.into(), json!({
+            "kind": kind_name(export.kind),
+            "declaration": crate::abi::strip_comments(&declaration),
+        }));
+    }
+    let snapshot = layout_snapshot::generate()?;
+    let mut layouts = serde_json::Map::new();
+    for (name, lines) in layout_snapshot::entries(&snapshot) {
+        let mut layout = json!({ "fields": [] });
+        for line in lines {
+            let (key, value) = line.split_once(" = ").unwrap();
+            let value: usize = value.parse().unwrap();
+            match key.strip_prefix('.') {
+                | Some(field) => {
+                    layout["fields"].as_array_mut().unwrap().push(json!([field, value]));
+                },
+                | None => {
+                    layout[key] = value.into();
+                },
+            }
+        }
+        layouts.insert(name.into(), layout);
+    }
+    let manifest = json!({
+        "safer_ffi_abi_manifest": VERSION,
+        "items": items,
+        "layouts": layouts,
+    });
+    Ok(serde_json::to_string_pretty(&manifest).unwrap() + "\n")
+}
+
+/// The changes from the `old` ABI manifest to the `new` one (see
+/// [`abi_manifest()`]).
+pub
+fn diff (old: &'_ str, new: &'_ str)
+  -> io::Result<AbiDiff>
+{
+    let (old, new) = (Manifest::parse(old)?, Manifest::parse(new)?);
+    let mut changes = vec![];
+    for (name, &(kind, ref declaration)) in &old.items {
+        match new.items.get(name) {
+            | None => changes.push(AbiChange::Removed {
+                kind,
+                name: name.clone(),
+            }),
+            | Some((new_kind, new_declaration))
+                if new_kind != &kind || new_declaration != declaration
+            => changes.push(AbiChange::ChangedSignature {
+                kind: *new_kind,
+                name: name.clone(),
+                old: declaration.clone(),
+                new: new_declaration.clone(),
+            }),
+            | Some(_) => {},
+        }
+    }
+    for (name, &(kind, _)) in &new.items {
+        if old.items.contains_key(name).not() {
+            changes.push(AbiChange::Added { kind, name: name.clone() });
+        }
+    }
+    // The types no longer (or newly) involved come along with the items
+    // using them, which are reported instead.
+    for (name, layout) in &old.layouts {
+        match new.layouts.get(name) {
+            | Some(new_layout) if new_layout != layout => {
+                changes.push(AbiChange::ChangedLayout {
+                    name: name.clone(),
+                    old: layout.clone(),
+                    new: new_layout.clone(),
+                });
+            },
+            | _ => {},
+        }
+    }
+    Ok(AbiDiff { changes })
+}
+
+/// The changes between two ABI manifests, as reported by [`diff()`].
+///
+/// Its `Display` impl is a human-readable report of the changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub
+struct AbiDiff {
+    /// The changes, breaking or not.
+    pub
+    changes: Vec<AbiChange>,
+}
+
+/// A change between two ABI manifests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub
+enum AbiChange {
+    /// A new item is exported: additive.
+    Added {
+        kind: ItemKind,
+        name: String,
+    },
+
+    /// An item is no longer exported: breaking.
+    Removed {
+        kind: ItemKind,
+        name: String,
+    },
+
+    /// The C declaration of an exported item changed (_e.g._, the signature
+    /// of a function, or the definition of a type): breaking.
+    ChangedSignature {
+        kind: ItemKind,
+        name: String,
+        old: String,
+        new: String,
+    },
+
+    /// The size, alignment, or field offsets of a type changed: breaking.
+    ChangedLayout {
+        name: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl AbiChange {
+    /// Whether the change breaks the existing consumers (_i.e._, anything
+    /// but an addition).
+    pub
+    fn is_breaking (self: &'_ Self)
+      -> bool
+    {
+        matches!(self, AbiChange::Added { .. }).not()
+    }
+}
+
+impl AbiDiff {
+    /// Whether there are no changes at all.
+    pub
+    fn is_empty (self: &'_ Self)
+      -> bool
+    {
+        self.changes.is_empty()
+    }
+
+    /// Whether some change breaks the existing consumers, thus warranting a
+    /// semver-major release.
+    pub
+    fn is_breaking (self: &'_ Self)
+      -> bool
+    {
+        self.changes.iter().any(AbiChange::is_breaking)
+    }
+}
+
+impl ::core::fmt::Display for AbiDiff {
+    fn fmt (self: &'_ Self, fmt: &'_ mut ::core::fmt::Formatter<'_>)
+      -> ::core::fmt::Result
+    {
+        if self.is_empty() {
+            return writeln!(fmt, "No ABI changes.");
+        }
+        writeln!(fmt,
+            "{} ABI change(s), {}:",
+            self.changes.len(),
+            if self.is_breaking() { "BREAKING" } else { "additive only" },
+        )?;
+        for change in &self.changes {
+            match change {
+                | AbiChange::Added { kind, name } => writeln!(fmt,
+                    "  + [additive] added {} `{name}`", kind_name(*kind),
+                )?,
+                | AbiChange::Removed { kind, name } => writeln!(fmt,
+                    "  - [breaking] removed {} `{name}`", kind_name(*kind),
+                )?,
+                | AbiChange::ChangedSignature { kind, name, old, new } => writeln!(fmt,
+                    "  ~ [breaking] changed {} `{name}`:\n      was: {old}\n      now: {new}",
+                    kind_name(*kind),
+                )?,
+                | AbiChange::ChangedLayout { name, old, new } => writeln!(fmt,
+                    "  ~ [breaking] changed the layout of `{name}`:\n      was: {old}\n      now: {new}",
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A parsed ABI manifest.
+struct Manifest {
+    items: BTreeMap<String, (ItemKind, String)>,
+
+    /// (`size = …, align = …, .field = …`)
+    layouts: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    fn parse (manifest: &'_ str)
+      -> io::Result<Manifest>
+    {
+        let malformed = |what: &str| io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed ABI manifest: {what}"),
+        );
+        let manifest: Value = serde_json::from_str(manifest).map_err(|err| {
+            malformed(&err.to_string())
+        })?;
+        match manifest["safer_ffi_abi_manifest"].as_u64() {
+            | Some(VERSION) => {},
+            | Some(version) => return Err(malformed(&format!(
+                "unsupported version {version}",
+            ))),
+            | None => return Err(malformed("missing `safer_ffi_abi_manifest` version")),
+        }
+        let mut items = BTreeMap::new();
+        let all_items =
+            manifest["items"]
+                .as_object()
+                .ok_or_else(|| malformed("expected an `items` object"))?
+        ;
+        for (name, item) in all_items {
+            let kind =
+                item["kind"]
+                    .as_str()
+                    .and_then(kind_of_name)
+                    .ok_or_else(|| malformed(&format!("invalid `kind` for `{name}`")))?
+            ;
+            let declaration =
+                item["declaration"]
+                    .as_str()
+                    .ok_or_else(|| malformed(&format!("invalid `declaration` for `{name}`")))?
+            ;
+            items.insert(name.clone(), (kind, declaration.to_owned()));
+        }
+        let mut layouts = BTreeMap::new();
+        let all_layouts =
+            manifest["layouts"]
+                .as_object()
+                .ok_or_else(|| malformed("expected a `layouts` object"))?
+        ;
+        for (name, layout) in all_layouts {
+            let invalid = || malformed(&format!("invalid layout for `{name}`"));
+            let mut summary = format!(
+                "size = {}, align = {}",
+                layout["size"].as_u64().ok_or_else(invalid)?,
+                layout["align"].as_u64().ok_or_else(invalid)?,
+            );
+            for field in layout["fields"].as_array().ok_or_else(invalid)? {
+                summary += &format!(
+                    ", .{} = {}",
+                    field[0].as_str().ok_or_else(invalid)?,
+                    field[1].as_u64().ok_or_else(invalid)?,
+                );
+            }
+            layouts.insert(name.clone(), summary);
+        }
+        Ok(Manifest { items, layouts })
+    }
+}
+
+fn kind_name (kind: ItemKind)
+  -> &'static str
+{
+    match kind {
+        | ItemKind::Function => "function",
+        | ItemKind::Constant => "constant",
+        | ItemKind::Static => "static",
+        | ItemKind::Type => "type",
+    }
+}
+
+fn kind_of_name (name: &'_ str)
+  -> Option<ItemKind>
+{
+    Some(match name {
+        | "function" => ItemKind::Function,
+        | "constant" => ItemKind::Constant,
+        | "static" => ItemKind::Static,
+        | "type" => ItemKind::Type,
+        | _ => return None,
+    })
+}
@@ -0,0 +1,169 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Dry-run of the headers generation, for [`Builder::analyze()`].
+
+use super::*;
+use ::std::collections::BTreeSet;
+
+/// What the headers generated by a [`Builder`] would contain, as reported by
+/// [`Builder::analyze()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub
+struct Report {
+    /// The language of the (would-be) headers.
+    pub
+    language: Language,
+
+    /// The number of exported functions.
+    pub
+    functions: usize,
+
+    /// The number of exported constants.
+    pub
+    constants: usize,
+
+    /// The number of exported `static`s.
+    pub
+    statics: usize,
+
+    /// The number of exported types.
+    pub
+    types: usize,
+
+    /// The names of the definitions the exports pull in (mainly, those of the
+    /// types involved), sorted.
+    pub
+    defined: Vec<String>,
+
+    /// The names which are exported more than once, or which are both those
+    /// of an exported function, constant or `static` and of a definition.
+    pub
+    name_collisions: Vec<String>,
+
+    /// The exports which the language has no way to express (_e.g._, the
+    /// types, in a `.def` file), and which are thus left out of its headers.
+    pub
+    skipped: Vec<String>,
+}
+
+/// A [`Definer`] recording what gets defined, and discarding the output.
+struct DryRun {
+    settings: Settings,
+    defines_set: BTreeSet<String>,
+    out: Vec<u8>,
+}
+
+impl Definer
+    for DryRun
+{
+    fn insert (self: &'_ mut Self, name: &'_ str)
+      -> bool
+    {
+        self.defines_set
+            .insert(name.to_owned())
+    }
+
+    fn out (self: &'_ mut Self)
+      -> &'_ mut dyn io::Write
+    {
+        &mut self.out
+    }
+
+    fn settings (self: &'_ Self)
+      -> &'_ Settings
+    {
+        &self.settings
+    }
+}
+
+pub(in super)
+fn analyze (language: Language, settings: Settings)
+  -> io::Result<Report>
+{
+    let mut exports = super::registry::ffi_exports();
+    exports.sort_by_key(|export| export.name);
+    let mut report = Report {
+        language,
+        functions: 0,
+        constants: 0,
+        statics: 0,
+        types: 0,
+        defined: vec![],
+        name_collisions: vec![],
+        skipped: vec![],
+    };
+    let mut exported = BTreeSet::new();
+    let mut defined = BTreeSet::new();
+    let mut name_collisions = BTreeSet::new();
+    for &crate::FfiExport { name, kind, gen_def } in exports {
+        if exported.insert(name).not() {
+            name_collisions.insert(name.to_owned());
+            continue;
+        }
+        *match kind {
+            | ItemKind::Function => &mut report.functions,
+            | ItemKind::Constant => &mut report.constants,
+            | ItemKind::Static => &mut report.statics,
+            | ItemKind::Type => &mut report.types,
+        } += 1;
+        // A fresh `Definer` for each export, so that its output does not
+        // depend on what the previous ones have already defined.
+        let mut dry_run = DryRun {
+            settings: settings.clone(),
+            defines_set: Default::default(),
+            out: vec![],
+        };
+        gen_def(&mut dry_run, language)?;
+        if dry_run.out.is_empty() {
+            report.skipped.push(name.to_owned());
+        }
+        // (functions `insert` their own name.)
+        dry_run.defines_set.remove(name);
+        defined.extend(dry_run.defines_set);
+    }
+    for &crate::FfiExport { name, kind, .. } in super::registry::ffi_exports() {
+        if kind != ItemKind::Type && defined.contains(name) {
+            name_collisions.insert(name.to_owned());
+        }
+    }
+    report.defined = defined.into_iter().collect();
+    report.name_collisions = name_collisions.into_iter().collect();
+    Ok(report)
+}
+
+/// The `#[ffi_export]`ed items, sorted by name, along with their declaration
+/// in the given language (for the types, their definition, preceded by those
+/// of the types they depend on).
+///
+/// The functions `#[cfg]`-gated away from the current target are left out.
+pub(in crate)
+fn declarations (language: Language, settings: Settings)
+  -> io::Result<Vec<(&'static crate::FfiExport, String)>>
+{
+    let settings = Settings { cfg_guards: false, .. settings };
+    let mut exports = super::registry::ffi_exports();
+    exports.sort_by_key(|export| export.name);
+    let mut ret = Vec::with_capacity(exports.len());
+    for export in exports {
+        let mut dry_run = DryRun {
+            settings: settings.clone(),
+            defines_set: Default::default(),
+            out: vec![],
+        };
+        (export.gen_def)(&mut dry_run, language)?;
+        if export.kind != ItemKind::Type {
+            // Run it again, with everything it pulls in already defined, so
+            // as to only get its own declaration.
+            dry_run.defines_set.remove(export.name);
+            dry_run.out.clear();
+            (export.gen_def)(&mut dry_run, language)?;
+        }
+        let declaration = String::from_utf8_lossy(&dry_run.out).trim().to_owned();
+        // (unguarded, an inactive `#[cfg]` yields no declaration at all)
+        if export.kind == ItemKind::Function && declaration.is_empty() {
+            continue;
+        }
+        ret.push((export, declaration));
+    }
+    Ok(ret)
+}
@@ -0,0 +1,388 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Generating the headers from the `build.rs` script of the library, as part
+//! of a plain `cargo build`.
+//!
+//! A build script runs before the library is compiled, and thus cannot
+//! enumerate its `#[ffi_export]`ed items itself: [`generate()`] rather builds
+//! (for the host) the library with its headers feature, in a dedicated target
+//! directory, and runs the generation binary defined along it by
+//! [`headers_entry_point!`][`crate::headers_entry_point`]:
+//!
+//! ```toml
+//! [lib]
+//! crate-type = ["cdylib", "lib"]
+//!
+//! [[bin]]
+//! name = "generate-headers"
+//! required-features = ["generate-headers"]
+//!
+//! [features]
+//! generate-headers = ["safer-ffi/headers"]
+//!
+//! [build-dependencies]
+//! safer-ffi = { version = "...", features = ["headers"] }
+//! ```
+//!
+//! ```rust,ignore
+//! // src/bin/generate-headers.rs
+//! ::safer_ffi::headers_entry_point!(mylib);
+//! ```
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main ()
+//! {
+//!     ::safer_ffi::headers::build_script::builder()
+//!         .to_file("include/mylib.h")
+//!         .generate()
+//!         .expect("failed to generate the headers")
+//! }
+//! ```
+//!
+//! Note that:
+//!
+//!   - the `headers` feature of the `[build-dependencies]` is not unified with
+//!     that of the `[dependencies]` as long as the crate uses the (2021
+//!     edition default) `resolver = "2"`, so that the library itself is built
+//!     without it;
+//!
+//!   - the nested build does not run the build script again (which would
+//!     otherwise recurse), and is built for the host, regardless of the
+//!     `--target` of the outer one, unless [`Builder::with_outer_target()`]
+//!     is set (the generation binary being then run through the `runner` of
+//!     that target);
+//!
+//!   - the nested build is a `--release` one if the outer one is, and
+//!     inherits the `CARGO_*` environment variables (_e.g._,
+//!     `CARGO_NET_OFFLINE`), but the command line flags of the outer build,
+//!     such as `--locked` or `--offline`, are not visible to build scripts,
+//!     and thus have to be forwarded through [`Builder::with_cargo_args()`];
+//!
+//!   - the nested build enables the `generate-headers` feature (see
+//!     [`Builder::with_features()`]) on top of the default ones, but not the
+//!     other features of the outer build (the environment only mentioning them
+//!     in a lossy, uppercased, form), which may be forwarded through
+//!     [`Builder::with_features()`] as well.
+
+use ::std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use super::*;
+
+/// Set in the environment of the nested build, so that the build script it
+/// runs does not generate the headers in turn.
+const NESTED: &str = "SAFER_FFI_HEADERS_BUILD_SCRIPT";
+
+/// The options of [`generate()`], see [`builder()`].
+pub
+struct Builder {
+    features: String,
+    bin: String,
+    language: Option<Language>,
+    output: Option<PathBuf>,
+    cargo_args: Vec<String>,
+    outer_target: bool,
+}
+
+/// Generates the headers, with the default options (see [`builder()`]).
+pub
+fn generate ()
+  -> io::Result<()>
+{
+    builder().generate()
+}
+
+/// The (default) options of [`generate()`]: the `generate-headers` feature
+/// and binary, the language configured in the latter, to
+/// `$OUT_DIR/<package name>.<extension>`.
+pub
+fn builder ()
+  -> Builder
+{
+    Builder {
+        features: "generate-headers".into(),
+        bin: "generate-headers".into(),
+        language: None,
+        output: None,
+        cargo_args: vec![],
+        outer_target: false,
+    }
+}
+
+impl Builder {
+    /// Sets up the (space or comma separated) feature(s) of the library
+    /// enabling the `headers` feature of `::safer_ffi`.
+    ///
+    /// It defaults to `"generate-headers"`.
+    pub
+    fn with_features (self, features: &'_ str)
+      -> Self
+    {
+        Self { features: features.into(), .. self }
+    }
+
+    /// Sets up the [`headers_entry_point!`][`crate::headers_entry_point`]
+    /// binary of the library.
+    ///
+    /// It defaults to `"generate-headers"`.
+    pub
+    fn with_bin (self, bin: &'_ str)
+      -> Self
+    {
+        Self { bin: bin.into(), .. self }
+    }
+
+    /// Sets the [`Language`] of the generated headers, overriding the one
+    /// configured in the binary.
+    pub
+    fn with_language (self, language: Language)
+      -> Self
+    {
+        Self { language: Some(language), .. self }
+    }
+
+    /// Sets up the path of the file to be generated (relative to the root of
+    /// the package, since it is where build scripts run).
+    ///
+    /// **⚠️ If it already exists, its contents will be overwritten ⚠️**
+    ///
+    /// It defaults to `$OUT_DIR/<package name>.<extension>`.
+    pub
+    fn to_file (self, output: impl AsRef<Path>)
+      -> Self
+    {
+        Self { output: Some(output.as_ref().to_owned()), .. self }
+    }
+
+    /// Sets up extra arguments of the nested `cargo run` (_e.g._,
+    /// `["--locked", "--offline"]`).
+    pub
+    fn with_cargo_args (self, cargo_args: &'_ [&'_ str])
+      -> Self
+    {
+        Self {
+            cargo_args: cargo_args.iter().map(|&it| it.into()).collect(),
+            .. self
+        }
+    }
+
+    /// Whether to build the nested build for the `--target` (and with the
+    /// `RUSTFLAGS`) of the outer one, rather than for the host, so that the
+    /// layouts in the headers are the ones of that target.
+    ///
+    /// The generation binary is then run through the `runner` configured for
+    /// that target, if any (_e.g._, `qemu`).
+    ///
+    /// It defaults to `false`.
+    pub
+    fn with_outer_target (self, outer_target: bool)
+      -> Self
+    {
+        Self { outer_target, .. self }
+    }
+
+    /// Generates the headers, unless called from within the nested build
+    /// (a no-op then).
+    ///
+    /// Must be called from a build script.
+    pub
+    fn generate (self)
+      -> io::Result<()>
+    {
+        if env::var_os(NESTED).is_some() {
+            return Ok(());
+        }
+        let ref var = |name: &str| env::var_os(name);
+        let var_or_err = |name: &str| var(name).ok_or_else(|| io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("`${name}` is not set: is this called from a build script?"),
+        ));
+        let manifest_dir = PathBuf::from(var_or_err("CARGO_MANIFEST_DIR")?);
+        let out_dir = PathBuf::from(var_or_err("OUT_DIR")?);
+        let (name, extension) = match self.language {
+            | Some(language) => {
+                let (name, extension) = language_name_and_extension(language);
+                (Some(name), extension)
+            },
+            // (C, unless configured otherwise in the binary)
+            | None => (None, "h"),
+        };
+        let output = match &self.output {
+            | Some(output) => manifest_dir.join(output),
+            | None => out_dir.join(format!(
+                "{}.{extension}",
+                var_or_err("CARGO_PKG_NAME")?.to_string_lossy().replace('-', "_"),
+            )),
+        };
+        // Generated to a scratch file first, and only then copied over to
+        // `output` if it changed, lest the build script be re-run forever,
+        // because of the (rewritten) file in the package.
+        let scratch = out_dir.join(format!("safer-ffi-headers.{extension}"));
+        let _ = fs::remove_file(&scratch);
+        let mut cmd = self.nested_cargo(var, &manifest_dir, &out_dir)?;
+        cmd.env("SAFER_FFI_HEADERS_OUTPUT", &scratch);
+        if let Some(name) = name {
+            cmd.env("SAFER_FFI_HEADERS_LANGUAGE", name);
+        }
+        let Self { features, bin, .. } = self;
+        let status = cmd.status()?;
+        if status.success().not() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("the nested `cargo run --bin {bin}` generating the headers failed ({status})"),
+            ));
+        }
+        let generated = fs::read(&scratch).map_err(|_| io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "no headers were generated: is `{bin}` a \
+                `::safer_ffi::headers_entry_point!()` binary, enabled by the \
+                `{features}` feature?",
+            ),
+        ))?;
+        if fs::read(&output).ok().as_ref() != Some(&generated) {
+            if let Some(parent) = output.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&output, generated)?;
+        }
+        Ok(())
+    }
+
+    /// The nested `cargo run` of the generation binary, as per the
+    /// environment of the build script (`var`).
+    fn nested_cargo (
+        self: &'_ Self,
+        var: &'_ dyn Fn(&str) -> Option<OsString>,
+        manifest_dir: &'_ Path,
+        out_dir: &'_ Path,
+    ) -> io::Result<Command>
+    {
+        let Self { features, bin, cargo_args, outer_target, .. } = self;
+        let mut cmd = Command::new(var("CARGO").unwrap_or_else(|| "cargo".into()));
+        cmd .arg("run")
+            .arg("--manifest-path").arg(manifest_dir.join("Cargo.toml"))
+            .arg("--bin").arg(bin)
+            .arg("--features").arg(features)
+            // A target directory of its own, lest the nested `cargo` wait
+            // forever on the lock of the outer one.
+            .arg("--target-dir").arg(out_dir.join("safer-ffi-headers"))
+            .env(NESTED, "1")
+            .env_remove("CARGO_TARGET_DIR")
+        ;
+        if var("PROFILE").map_or(false, |it| it == "release") {
+            cmd.arg("--release");
+        }
+        if *outer_target {
+            let target = var("TARGET").ok_or_else(|| io::Error::new(
+                io::ErrorKind::NotFound,
+                "`$TARGET` is not set: is this called from a build script?",
+            ))?;
+            cmd.arg("--target").arg(target);
+            // (`CARGO_ENCODED_RUSTFLAGS` being inherited)
+        } else {
+            // The flags of the outer build are meant for its `--target`.
+            cmd .env_remove("CARGO_ENCODED_RUSTFLAGS")
+                .env_remove("CARGO_BUILD_TARGET")
+            ;
+        }
+        cmd.args(cargo_args);
+        Ok(cmd)
+    }
+}
+
+/// The `SAFER_FFI_HEADERS_LANGUAGE` name (see [`Language`'s `FromStr`
+/// impl][`Language`]), and the file extension, of the `language`.
+fn language_name_and_extension (language: Language)
+  -> (&'static str, &'static str)
+{
+    match language {
+        | Language::C => ("c", "h"),
+        | Language::CSharp => ("csharp", "cs"),
+        #[cfg(feature = "python-headers")]
+        | Language::Python => ("python", "py"),
+        | Language::MsvcDef => ("msvc-def", "def"),
+        | Language::LdVersionScript => ("ld-version-script", "map"),
+        | Language::MacosExportedSymbols => ("macos-exported-symbols", "exp"),
+        | Language::Markdown => ("markdown", "md"),
+        | Language::Cython => ("cython", "pxd"),
+        | Language::CSmokeTest => ("c-smoke-test", "c"),
+        | Language::LayoutSnapshot => ("layout-snapshot", "snap"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested_cargo (builder: Builder, vars: &'_ [(&'_ str, &'_ str)])
+      -> (Vec<String>, Vec<(String, Option<String>)>)
+    {
+        let var = |name: &str| {
+            vars.iter().find(|&&(it, _)| it == name).map(|&(_, value)| OsString::from(value))
+        };
+        let cmd = builder.nested_cargo(&var, "pkg".as_ref(), "out".as_ref()).unwrap();
+        assert_eq!(cmd.get_program(), &*var("CARGO").unwrap_or_else(|| "cargo".into()));
+        let to_string = |it: &::std::ffi::OsStr| it.to_str().unwrap().to_owned();
+        (
+            cmd.get_args().map(to_string).collect(),
+            cmd.get_envs().map(|(k, v)| (to_string(k), v.map(to_string))).collect(),
+        )
+    }
+
+    #[test]
+    fn defaults ()
+    {
+        let (args, envs) = nested_cargo(builder(), &[
+            ("PROFILE", "debug"),
+            ("TARGET", "aarch64-linux-android"),
+        ]);
+        assert_eq!(args, [
+            "run",
+            "--manifest-path", &*Path::new("pkg").join("Cargo.toml").to_string_lossy(),
+            "--bin", "generate-headers",
+            "--features", "generate-headers",
+            "--target-dir", &*Path::new("out").join("safer-ffi-headers").to_string_lossy(),
+        ]);
+        assert!(envs.contains(&(NESTED.into(), Some("1".into()))));
+        // Built for the host.
+        assert!(envs.contains(&("CARGO_ENCODED_RUSTFLAGS".into(), None)));
+        assert!(envs.contains(&("CARGO_BUILD_TARGET".into(), None)));
+    }
+
+    #[test]
+    fn forwarded ()
+    {
+        let forwarding =
+            builder()
+                .with_bin("gen")
+                .with_features("ffi")
+                .with_cargo_args(&["--locked", "--offline"])
+                .with_outer_target(true)
+        ;
+        let (args, envs) = nested_cargo(forwarding, &[
+            ("CARGO", "/path/to/cargo"),
+            ("PROFILE", "release"),
+            ("TARGET", "aarch64-linux-android"),
+        ]);
+        assert!(args.windows(2).any(|it| it == ["--bin", "gen"]));
+        assert!(args.windows(2).any(|it| it == ["--features", "ffi"]));
+        assert!(args.ends_with(&[
+            "--release".into(),
+            "--target".into(), "aarch64-linux-android".into(),
+            "--locked".into(), "--offline".into(),
+        ]));
+        assert!(envs.iter().all(|(k, _)| k != "CARGO_ENCODED_RUSTFLAGS"));
+        // Needs the `$TARGET` of the outer build.
+        let err =
+            builder()
+                .with_outer_target(true)
+                .nested_cargo(&|_| None, "pkg".as_ref(), "out".as_ref())
+                .unwrap_err()
+        ;
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
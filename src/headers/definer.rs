@@ -25,6 +25,17 @@ trait Definer : define_once_seal::__ {
       -> &'_ mut dyn io::Write
     ;
 
+    /// The [`Settings`] of the headers being generated.
+    ///
+    /// Implementors are expected to keep the default implementation, which
+    /// yields the default settings: the [`Builder`] takes care of overriding
+    /// it with the actually configured ones when driving the generation.
+    fn settings (self: &'_ Self)
+      -> &'_ Settings
+    {
+        &settings::DEFAULT_SETTINGS
+    }
+
     #[cfg(docs)]
     /// Convenience method to perform an [`.insert()`][`Definer::insert`] so
     /// that if it succeeds (thus guaranteeing the call happens for the first
@@ -0,0 +1,124 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Recording of the layouts of the `#[derive_ReprC(frozen)]` structs in their
+//! crate's `safer-ffi-abi.lock`.
+
+use super::*;
+use ::std::collections::BTreeMap;
+
+const LOCKFILE_BANNER: &str = concat!(
+    "# Layouts of the `#[derive_ReprC(frozen)]` structs.\n",
+    "#\n",
+    "# File auto-generated by `::safer_ffi`.\n",
+    "# Do not manually edit this file.\n",
+);
+
+/// Adds the layouts not recorded yet to their lockfiles, and errors if a
+/// recorded one has changed.
+pub(in crate)
+fn record_layouts ()
+  -> io::Result<()>
+{
+    let mut layouts_per_lockfile =
+        BTreeMap::<&'static str, BTreeMap<&'static str, String>>::new()
+    ;
+    for layout in crate::inventory::iter::<crate::FrozenLayout> {
+        layouts_per_lockfile
+            .entry(layout.lockfile)
+            .or_default()
+            .insert(layout.name, entry(layout))
+        ;
+    }
+    for (lockfile, layouts) in layouts_per_lockfile {
+        let contents = match fs::read_to_string(lockfile) {
+            | Ok(it) => it,
+            | Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                LOCKFILE_BANNER.into()
+            },
+            | Err(err) => return Err(err),
+        };
+        let new_contents = updated_lockfile(&contents, lockfile, &layouts)?;
+        if new_contents != contents {
+            fs::write(lockfile, new_contents)?;
+        }
+    }
+    Ok(())
+}
+
+/// The `contents` of the `lockfile`, with the entries of the `layouts` (keyed
+/// by the path of their struct) not recorded yet added to it.
+fn updated_lockfile (
+    contents: &'_ str,
+    lockfile: &'_ str,
+    layouts: &'_ BTreeMap<&'_ str, String>,
+) -> io::Result<String>
+{
+    let mut new_contents = contents.to_owned();
+    for (name, entry) in layouts {
+        let header = format!("[{name}]\n");
+        match contents.find(&header) {
+            | Some(start) => {
+                let recorded =
+                    contents[start ..]
+                        .split("\n\n")
+                        .next()
+                        .unwrap()
+                        .trim_end()
+                ;
+                if recorded != entry.trim_end() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "the layout of the `frozen` `{name}` differs \
+                            from the one recorded in `{lockfile}`",
+                        ),
+                    ));
+                }
+            },
+            | None => {
+                new_contents.push('\n');
+                new_contents.push_str(entry);
+            },
+        }
+    }
+    Ok(new_contents)
+}
+
+fn entry (
+    &crate::FrozenLayout { name, size, align, fields, .. }: &'_ crate::FrozenLayout,
+) -> String
+{
+    let mut ret = format!("[{name}]\nsize = {size}\nalign = {align}\n");
+    for &(field, offset) in fields {
+        ret.push_str(&format!(".{field} = {offset}\n"));
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_name_in_different_modules ()
+    {
+        let layouts = BTreeMap::from([
+            ("krate::a::Header", "[krate::a::Header]\nsize = 4\nalign = 4\n.len = 0\n".to_owned()),
+            ("krate::b::Header", "[krate::b::Header]\nsize = 2\nalign = 2\n.tag = 0\n".to_owned()),
+        ]);
+        let contents = updated_lockfile(LOCKFILE_BANNER, "lock", &layouts).unwrap();
+        assert_eq!(contents, format!(
+            "{LOCKFILE_BANNER}\n{}\n{}",
+            layouts["krate::a::Header"],
+            layouts["krate::b::Header"],
+        ));
+        // Recording is idempotent.
+        assert_eq!(updated_lockfile(&contents, "lock", &layouts).unwrap(), contents);
+
+        // Each entry is checked against its own struct only.
+        let changed = BTreeMap::from([
+            ("krate::b::Header", "[krate::b::Header]\nsize = 4\nalign = 2\n.tag = 0\n".to_owned()),
+        ]);
+        let err = updated_lockfile(&contents, "lock", &changed).unwrap_err();
+        assert!(err.to_string().contains("`krate::b::Header`"));
+    }
+}
@@ -1,10 +1,307 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 
 use super::*;
+use crate::headers::FnMeta;
 
 pub
 struct C;
 
+impl C {
+    /// A `struct` or a `union`, depending on `keyword`.
+    fn emit_aggregate (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        keyword: &'_ str,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        let short_name = self_ty.short_name();
+        let full_ty_name = self_ty.name(self);
+
+        if self_ty.size() == 0 {
+            panic!("C does not support zero-sized {keyword}s!")
+        }
+        let deprecated = deprecated_attribute(ctx, self_ty.deprecated())?;
+
+        let packing = Packing::of(self_ty, fields);
+        if let Packing::Aligned(_) = packing {
+            ctx.define_once("SAFER_FFI_ALIGNAS", &mut |ctx| {
+                mk_out!(indent, ctx.out());
+                out!((
+                    "#ifndef SAFER_FFI_ALIGNAS"
+                    "#if defined(__cplusplus)"
+                    "#define SAFER_FFI_ALIGNAS(n) alignas(n)"
+                    "#elif defined(__STDC_VERSION__) && __STDC_VERSION__ >= 201112L"
+                    "#define SAFER_FFI_ALIGNAS(n) _Alignas(n)"
+                    "#elif defined(_MSC_VER)"
+                    "#define SAFER_FFI_ALIGNAS(n) __declspec(align(n))"
+                    "#else"
+                    "#define SAFER_FFI_ALIGNAS(n) __attribute__((aligned(n)))"
+                    "#endif"
+                    "#endif"
+                ));
+                out!("\n");
+                Ok(())
+            })?;
+        }
+        if let Packing::Packed(n) = packing {
+            out!(("#pragma pack(push, {n})"));
+        }
+        self.emit_docs(ctx, docs, indent)?;
+        if let Some(deprecated) = deprecated {
+            out!(("{deprecated}"));
+        }
+        out!(("typedef {keyword} {short_name} {{"));
+        if let _ = indent.scope() {
+            let ref mut first = true;
+            for &StructField { docs, name, ty } in fields {
+                // Skip ZSTs
+                if ty.size() == 0 {
+                    if ty.align() > 1 {
+                        panic!("Zero-sized fields must have an alignment of `1`");
+                    } else {
+                        continue;
+                    }
+                }
+                // Over-aligning the first field over-aligns the whole type.
+                let alignas = match packing {
+                    | Packing::Aligned(n) if *first => format!("SAFER_FFI_ALIGNAS({n}) "),
+                    | _ => String::new(),
+                };
+                if mem::take(first).not() {
+                    out!("\n");
+                }
+                self.emit_docs(ctx, docs, indent)?;
+                out!(
+                    ("{alignas}{};"),
+                    ty.name_wrapping_var(self, name)
+                );
+            }
+        }
+        out!(("}} {full_ty_name};"));
+        if let Packing::Packed(_) = packing {
+            out!(("#pragma pack(pop)"));
+        }
+
+        if ctx.settings().static_asserts {
+            out!("\n");
+            emit_layout_assertions(ctx, &full_ty_name, self_ty)?;
+        }
+
+        out!("\n");
+        Ok(())
+    }
+
+    /// A function declaration, followed by `...` when `variadic`, and
+    /// annotated according to the rest of its `meta`: its calling
+    /// `convention` (the Rust ABI string), its `#[deprecated]`,
+    /// `#[must_use]` and `#[ffi_pure]`/`#[ffi_const]` attributes, and the
+    /// niche of its return value.
+    pub(in crate::headers)
+    fn emit_function_ (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+        &FnMeta { variadic, convention, deprecated, must_use, purity, ret_niche, .. }: &'_ FnMeta<'_>,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+
+        let api = match ctx.settings().api_macro.clone() {
+            | Some(api_macro) => {
+                emit_api_macro(ctx, &api_macro)?;
+                format!("{api_macro} ")
+            },
+            | None => String::new(),
+        };
+        let restrict_pointers = ctx.settings().restrict_pointers;
+        if restrict_pointers && args.iter().any(|arg| arg.restrict) {
+            emit_restrict_macro(ctx)?;
+        }
+        let assume_nonnull = ctx.settings().assume_nonnull;
+        // Within an `assume_nonnull` region, the pointers which may be null
+        // (raw ones included) have to be annotated as such.
+        let nullable = |niche, ty: &dyn PhantomCType| match niche {
+            | Niche::NullMeansNone => true,
+            | Niche::Unused => assume_nonnull && ty.name(self).ends_with('*'),
+            | _ => false,
+        };
+        let nullability_annotations =
+            ctx.settings().nullability_annotations || assume_nonnull
+        ;
+        if assume_nonnull {
+            emit_assume_nonnull_macros(ctx)?;
+        }
+        if nullability_annotations && (
+            assume_nonnull
+            || args.iter().any(|arg| matches!(
+                arg.niche,
+                Niche::NonNull | Niche::NullMeansNone,
+            ))
+        )
+        {
+            emit_nullability_macros(ctx)?;
+        }
+        let deprecated = deprecated_attribute(ctx, deprecated)?;
+        if must_use {
+            emit_must_use_macro(ctx)?;
+        }
+        let purity = match purity {
+            | Some(purity) => Some(emit_purity_macro(ctx, purity)?),
+            | None => None,
+        };
+        let convention = match convention.and_then(calling_convention_macro) {
+            | Some(macro_) => {
+                emit_calling_convention_macro(ctx, macro_)?;
+                format!("{} ", macro_.name)
+            },
+            | None => String::new(),
+        };
+
+        if assume_nonnull {
+            writeln!(ctx.out(), "{ASSUME_NONNULL_MACRO}_BEGIN")?;
+        }
+        self.emit_docs(ctx, docs, indent)?;
+        if let Some(deprecated) = deprecated {
+            writeln!(ctx.out(), "{deprecated}")?;
+        }
+        if must_use {
+            writeln!(ctx.out(), "{MUST_USE_MACRO}")?;
+        }
+        if let Some(purity) = purity {
+            writeln!(ctx.out(), "{purity}")?;
+        }
+
+        let ref fn_sig_but_for_ret_type: String = {
+            let mut buf = Vec::<u8>::new();
+            mk_out!(indent, buf);
+
+            // (the return value is only annotated within `assume_nonnull`
+            // regions, wherein it would otherwise be deemed non-null)
+            if assume_nonnull && nullable(ret_niche, ret_ty) {
+                out!("{NULLABLE_MACRO}");
+            }
+            out!(
+                "\n{indent}{fn}{convention}{fname} (",
+                fn = if cfg!(feature = "c-headers-with-fn-style") {
+                    "/* fn */ "
+                } else {
+                    ""
+                },
+            );
+            let mut first = true;
+            if let _ = indent.scope() {
+                for arg in args {
+                    if mem::take(&mut first).not() {
+                        out!(",");
+                    }
+                    let mut var_name = String::from(arg.name);
+                    if nullability_annotations {
+                        match arg.niche {
+                            | Niche::NonNull => {
+                                var_name = format!("{NONNULL_MACRO} {var_name}");
+                            },
+                            | niche if nullable(niche, arg.ty) => {
+                                var_name = format!("{NULLABLE_MACRO} {var_name}");
+                            },
+                            | Niche::NonZero => {
+                                var_name += " /* cannot be 0 */";
+                            },
+                            | Niche::ZeroMeansNone => {
+                                var_name += " /* 0 means None */";
+                            },
+                            | Niche::NullPtrMeansNone => {
+                                var_name += " /* NULL .ptr means None */";
+                            },
+                            | _ => {},
+                        }
+                    }
+                    if restrict_pointers && arg.restrict {
+                        var_name = format!("{RESTRICT_MACRO} {var_name}");
+                    }
+                    out!("\n{indent}{}", arg.ty.name_wrapping_var(self, &var_name))
+                }
+                if variadic {
+                    out!(",\n{indent}...");
+                } else if first {
+                    out!("void");
+                }
+            }
+            out!(")");
+            String::from_utf8(buf).unwrap()
+        };
+
+        mk_out!(indent, ctx.out());
+        out!(
+            ("{api}{};"), ret_ty.name_wrapping_var(self, fn_sig_but_for_ret_type)
+        );
+        if assume_nonnull {
+            out!(("{ASSUME_NONNULL_MACRO}_END"));
+        }
+
+        out!("\n");
+        Ok(())
+    }
+
+    /// The `ret (*fname) (args);` field of the function pointer table of
+    /// [`Builder::with_dlopen_api()`][`crate::headers::Builder::with_dlopen_api`].
+    pub(in crate)
+    fn emit_function_pointer_field (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+        meta: &'_ FnMeta<'_>,
+    ) -> io::Result<()>
+    {
+        writeln!(ctx.out(),
+            "    {};",
+            self.function_pointer_var(fname, args, ret_ty, meta),
+        )
+    }
+
+    /// `ret (*var_name) (args)`: the declaration of a `var_name` pointer to a
+    /// function of the exact type of the one declared by
+    /// [`Self::emit_function_()`].
+    pub(in crate)
+    fn function_pointer_var (
+        self: &'_ Self,
+        var_name: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+        &FnMeta { variadic, convention, .. }: &'_ FnMeta<'_>,
+    ) -> String
+    {
+        // (the macro has been defined along with the declaration)
+        let convention = match convention.and_then(calling_convention_macro) {
+            | Some(macro_) => format!("{} ", macro_.name),
+            | None => String::new(),
+        };
+        let mut each_arg =
+            args.iter()
+                .map(|arg| arg.ty.name_wrapping_var(self, arg.name))
+                .collect::<Vec<_>>()
+        ;
+        if variadic {
+            each_arg.push("...".into());
+        } else if each_arg.is_empty() {
+            each_arg.push("void".into());
+        }
+        ret_ty.name_wrapping_var(self, &format!(
+            "({convention}*{var_name}) ({})",
+            each_arg.join(", "),
+        ))
+    }
+}
+
 impl HeaderLanguage for C {
     fn emit_docs (
         self: &'_ Self,
@@ -20,11 +317,34 @@ impl HeaderLanguage for C {
             return Ok(());
         }
 
+        let mut storage = None;
+        let sections = ctx.settings().doc_tags.then(|| DocSections::parse(docs));
+        let docs = sections.as_ref().map_or(docs, |it| &it.body[..]);
+        if let Some(DocSections { params, returns, safety, .. }) = &sections {
+            let tags = storage.get_or_insert_with(rust::Vec::new);
+            for (name, description) in params {
+                tags.push(format!("\\param {name} {description}"));
+            }
+            if let Some(returns) = returns {
+                tags.push(format!("\\return {returns}"));
+            }
+            if let Some(safety) = safety {
+                tags.push(format!("\\warning <b>Safety:</b> {safety}"));
+            }
+        }
+        let tags = storage.as_deref().unwrap_or_default();
+
         out!(("/** \\brief"));
         for line in docs.iter().copied().map(str::trim) {
             let sep = if line.is_empty() { "" } else { "  " };
             out!((" *{sep}{line}"));
         }
+        if tags.is_empty().not() {
+            out!((" *"));
+            for tag in tags {
+                out!((" *  {tag}"));
+            }
+        }
         out!((" */"));
 
         Ok(())
@@ -45,6 +365,15 @@ impl HeaderLanguage for C {
         let ref intn_t =
             backing_integer.map(|it| it.name(self))
         ;
+        let trailing_commas =
+            ctx.settings().c_standard.has_trailing_enum_commas()
+        ;
+
+        // (inlined, since it goes with the `typedef`)
+        let deprecated =
+            deprecated_attribute(ctx, self_ty.deprecated())?
+                .map_or_else(String::new, |it| it + " ")
+        ;
 
         self.emit_docs(ctx, docs, indent)?;
 
@@ -60,21 +389,29 @@ impl HeaderLanguage for C {
                 "enum {short_name} {{"
             ));
         } else {
-            out!(("typedef enum {short_name} {{"));
+            out!(("{deprecated}typedef enum {short_name} {{"));
         }
 
         if let _ = indent.scope() {
-            for v in variants {
+            for (i, v) in variants.iter().enumerate() {
                 self.emit_docs(ctx, v.docs, indent)?;
                 let variant_name = crate::utils::screaming_case(short_name, v.name) /* ctx.adjust_variant_name(
                     Language::C,
                     enum_name,
                     v.name,
                 ) */;
+                // C89 does not allow a trailing comma after the last variant.
+                let comma =
+                    if i + 1 == variants.len() && trailing_commas.not() {
+                        ""
+                    } else {
+                        ","
+                    }
+                ;
                 if let Some(value) = v.discriminant {
-                    out!(("{variant_name} = {value:?},"));
+                    out!(("{variant_name} = {value:?}{comma}"));
                 } else {
-                    out!(("{variant_name},"));
+                    out!(("{variant_name}{comma}"));
                 }
             }
         }
@@ -83,7 +420,7 @@ impl HeaderLanguage for C {
             out!((
                 "}}"
                 "#ifndef DOXYGEN"
-                "; typedef {intn_t}"
+                "; {deprecated}typedef {intn_t}"
                 "#endif"
                 "{full_ty_name};"
             ));
@@ -95,52 +432,66 @@ impl HeaderLanguage for C {
         Ok(())
     }
 
-    fn emit_struct (
+    /// Emitted as `#define`s rather than as an `enum`, since C requires the
+    /// latter's constants to fit in an `int`.
+    fn emit_flags (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
         docs: Docs<'_>,
         self_ty: &'_ dyn PhantomCType,
-        fields: &'_ [StructField<'_>]
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
     ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
-        let short_name = self_ty.short_name();
-        let full_ty_name = self_ty.name(self);
 
-        if self_ty.size() == 0 {
-            panic!("C does not support zero-sized structs!")
-        }
+        let deprecated = deprecated_attribute(ctx, self_ty.deprecated())?;
 
         self.emit_docs(ctx, docs, indent)?;
-        out!(("typedef struct {short_name} {{"));
-        if let _ = indent.scope() {
-            let ref mut first = true;
-            for &StructField { docs, name, ty } in fields {
-                // Skip ZSTs
-                if ty.size() == 0 {
-                    if ty.align() > 1 {
-                        panic!("Zero-sized fields must have an alignment of `1`");
-                    } else {
-                        continue;
-                    }
-                }
-                if mem::take(first).not() {
-                    out!("\n");
-                }
-                self.emit_docs(ctx, docs, indent)?;
-                out!(
-                    ("{};"),
-                    ty.name_wrapping_var(self, name)
-                );
-            }
-        }
-        out!(("}} {full_ty_name};"));
 
+        let ref full_ty_name = self_ty.name(self);
+        let ref intn_t = backing_integer.name(self);
+        if let Some(deprecated) = deprecated {
+            out!(("{deprecated}"));
+        }
+        out!(("typedef {intn_t} {full_ty_name};"));
         out!("\n");
+
+        for flag in flags {
+            self.emit_constant(
+                ctx,
+                flag.docs,
+                &flag.flag_constant_name(self_ty),
+                backing_integer,
+                flag.discriminant.expect("flags have explicit values"),
+            )?;
+        }
         Ok(())
     }
 
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, docs, self_ty, "struct", fields)
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, docs, self_ty, "union", fields)
+    }
+
     fn emit_opaque_type (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
@@ -152,8 +503,12 @@ impl HeaderLanguage for C {
         mk_out!(indent, ctx.out());
         let short_name = self_ty.short_name();
         let full_ty_name = self_ty.name(self);
+        let deprecated = deprecated_attribute(ctx, self_ty.deprecated())?;
 
         self.emit_docs(ctx, docs, indent)?;
+        if let Some(deprecated) = deprecated {
+            out!(("{deprecated}"));
+        }
         out!(("typedef struct {short_name} {full_ty_name};"));
 
         out!("\n");
@@ -169,66 +524,424 @@ impl HeaderLanguage for C {
         ret_ty: &'_ dyn PhantomCType,
     ) -> io::Result<()>
     {
-        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
-
-        self.emit_docs(ctx, docs, indent)?;
-
-        let ref fn_sig_but_for_ret_type: String = {
-            let mut buf = Vec::<u8>::new();
-            mk_out!(indent, buf);
+        self.emit_function_(ctx, docs, fname, args, ret_ty, &FnMeta::DEFAULT)
+    }
 
-            out!(
-                "\n{indent}{fn}{fname} (",
-                fn = if cfg!(feature = "c-headers-with-fn-style") {
-                    "/* fn */ "
-                } else {
-                    ""
-                },
-            );
-            let mut first = true;
-            if let _ = indent.scope() {
-                for arg in args {
-                    if mem::take(&mut first).not() {
-                        out!(",");
-                    }
-                    out!("\n{indent}{}", arg.ty.name_wrapping_var(self, arg.name))
-                }
-                if first {
-                    out!("void");
-                }
-            }
-            out!(")");
-            String::from_utf8(buf).unwrap()
-        };
+    fn emit_variadic_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_function_(ctx, docs, fname, args, ret_ty, &FnMeta {
+            variadic: true,
+            ..FnMeta::DEFAULT
+        })
+    }
 
+    fn emit_constant (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        name: &'_ str,
+        ty: &'_ dyn PhantomCType,
+        value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
-        out!(
-            ("{};"), ret_ty.name_wrapping_var(self, fn_sig_but_for_ret_type)
-        );
+
+        self.emit_docs(ctx, docs, indent)?;
+        let ty = ty.name(self);
+        out!((
+            "#define {name} (({ty}) {value:?})"
+        ));
 
         out!("\n");
         Ok(())
     }
 
-    fn emit_constant (
+    fn emit_static (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
         docs: Docs<'_>,
         name: &'_ str,
         ty: &'_ dyn PhantomCType,
-        value: &'_ dyn ::core::fmt::Debug,
+        mutable: bool,
     ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+
+        let api = match ctx.settings().api_macro.clone() {
+            | Some(api_macro) => {
+                emit_api_macro(ctx, &api_macro)?;
+                format!("{api_macro} ")
+            },
+            | None => String::new(),
+        };
         mk_out!(indent, ctx.out());
 
         self.emit_docs(ctx, docs, indent)?;
-        let ty = ty.name(self);
+        let var = if mutable { name.to_owned() } else { format!("const {name}") };
+        out!(
+            ("{api}extern {};"), ty.name_wrapping_var(self, &var)
+        );
+
+        out!("\n");
+        Ok(())
+    }
+}
+
+/// Defines the `api_macro` decorating the function declarations, so that the
+/// same header can be used to both build (`dllexport`) and consume
+/// (`dllimport`) a DLL.
+fn emit_api_macro (
+    ctx: &'_ mut dyn Definer,
+    api_macro: &'_ str,
+) -> io::Result<()>
+{
+    let prefix = api_macro.strip_suffix("_API").unwrap_or(api_macro);
+    ctx.define_once(api_macro, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
         out!((
-            "#define {name} (({ty}) {value:?})"
+            "#ifndef {api_macro}"
+            "#if defined({prefix}_STATIC)"
+            "#define {api_macro}"
+            "#elif defined(_WIN32) || defined(__CYGWIN__)"
+            "#ifdef {prefix}_EXPORTS"
+            "#define {api_macro} __declspec(dllexport)"
+            "#else"
+            "#define {api_macro} __declspec(dllimport)"
+            "#endif"
+            "#elif defined(__GNUC__)"
+            "#define {api_macro} __attribute__((visibility(\"default\")))"
+            "#else"
+            "#define {api_macro}"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+const RESTRICT_MACRO: &str = "SAFER_FFI_RESTRICT";
+
+/// Defines the macro annotating the pointers which cannot alias: `restrict` in
+/// C99 and above, or the `__restrict` extension in C++.
+fn emit_restrict_macro (
+    ctx: &'_ mut dyn Definer,
+) -> io::Result<()>
+{
+    ctx.define_once(RESTRICT_MACRO, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {RESTRICT_MACRO}"
+            "#if defined(__cplusplus)"
+            "#define {RESTRICT_MACRO} __restrict"
+            "#elif defined(__STDC_VERSION__) && __STDC_VERSION__ >= 199901L"
+            "#define {RESTRICT_MACRO} restrict"
+            "#else"
+            "#define {RESTRICT_MACRO}"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+struct CallingConventionMacro {
+    name: &'static str,
+    /// The keyword, on Windows (MSVC and MinGW alike).
+    windows: &'static str,
+    /// The attribute, for the other x86 (32-bit) compilers, if any (else, the
+    /// convention is the C one).
+    x86: Option<&'static str>,
+}
+
+/// The macro annotating functions with the given calling convention, unless
+/// it is the C one.
+fn calling_convention_macro (convention: &'_ str)
+  -> Option<&'static CallingConventionMacro>
+{
+    Some(match convention {
+        | "stdcall" => &CallingConventionMacro {
+            name: "SAFER_FFI_STDCALL",
+            windows: "__stdcall",
+            x86: Some("__attribute__((stdcall))"),
+        },
+        | "fastcall" => &CallingConventionMacro {
+            name: "SAFER_FFI_FASTCALL",
+            windows: "__fastcall",
+            x86: Some("__attribute__((fastcall))"),
+        },
+        | "system" => &CallingConventionMacro {
+            name: "SAFER_FFI_SYSTEM",
+            windows: "__stdcall",
+            x86: None,
+        },
+        | _ => return None,
+    })
+}
+
+/// Defines the macro annotating the functions with a non-C calling convention
+/// (which compilers ignore on the platforms where it is the C one anyway).
+fn emit_calling_convention_macro (
+    ctx: &'_ mut dyn Definer,
+    &CallingConventionMacro { name, windows, x86 }: &'_ CallingConventionMacro,
+) -> io::Result<()>
+{
+    ctx.define_once(name, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {name}"
+            "#if defined(_WIN32)"
+            "#define {name} {windows}"
+        ));
+        if let Some(x86) = x86 {
+            out!((
+                "#elif defined(__i386__)"
+                "#define {name} {x86}"
+            ));
+        }
+        out!((
+            "#else"
+            "#define {name}"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+const DEPRECATED_MACRO: &str = "SAFER_FFI_DEPRECATED";
+
+/// Defines the macro flagging the `#[deprecated]` items (for their uses to
+/// trigger warnings): `[[deprecated(…)]]` in C++14, or the equivalent
+/// compiler-specific attribute.
+fn emit_deprecated_macro (
+    ctx: &'_ mut dyn Definer,
+) -> io::Result<()>
+{
+    ctx.define_once(DEPRECATED_MACRO, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {DEPRECATED_MACRO}"
+            "#if defined(__cplusplus) && __cplusplus >= 201402L"
+            "#define {DEPRECATED_MACRO}(note) [[deprecated(note)]]"
+            "#elif defined(__GNUC__) || defined(__clang__)"
+            "#define {DEPRECATED_MACRO}(note) __attribute__((deprecated(note)))"
+            "#elif defined(_MSC_VER)"
+            "#define {DEPRECATED_MACRO}(note) __declspec(deprecated(note))"
+            "#else"
+            "#define {DEPRECATED_MACRO}(note)"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+/// `SAFER_FFI_DEPRECATED("…")`, for the `#[deprecated]` items (defining
+/// the macro beforehand, so this is to be called before emitting the item).
+fn deprecated_attribute (
+    ctx: &'_ mut dyn Definer,
+    deprecated: Option<&'_ str>,
+) -> io::Result<Option<String>>
+{
+    Ok(match deprecated {
+        | Some(note) => {
+            emit_deprecated_macro(ctx)?;
+            Some(format!("{DEPRECATED_MACRO}({})", string_literal(note)))
+        },
+        | None => None,
+    })
+}
+
+/// `s` as a C string literal (Rust's `{:?}` escapes, such as `\u{…}`, are
+/// not valid C).
+///
+/// Control characters get (fixed-length) octal escapes, and the non-ASCII ones
+/// are kept as is, _i.e._, UTF-8-encoded.
+fn string_literal (s: &'_ str)
+  -> String
+{
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        match c {
+            | '"' => ret.push_str("\\\""),
+            | '\\' => ret.push_str("\\\\"),
+            | '\n' => ret.push_str("\\n"),
+            | '\r' => ret.push_str("\\r"),
+            | '\t' => ret.push_str("\\t"),
+            // (`?` too, lest `??x` be read as a trigraph.)
+            | '?' => ret.push_str("\\?"),
+            | _ if c.is_ascii_control() => {
+                ret.push_str(&format!("\\{:03o}", c as u32));
+            },
+            | _ => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+const MUST_USE_MACRO: &str = "SAFER_FFI_MUST_USE";
+
+/// Defines the macro annotating the `#[must_use]` functions, for C(++) code
+/// ignoring their return value to trigger a warning.
+fn emit_must_use_macro (
+    ctx: &'_ mut dyn Definer,
+) -> io::Result<()>
+{
+    ctx.define_once(MUST_USE_MACRO, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {MUST_USE_MACRO}"
+            "#if defined(__cplusplus) && __cplusplus >= 201703L"
+            "#define {MUST_USE_MACRO} [[nodiscard]]"
+            "#elif defined(__GNUC__) || defined(__clang__)"
+            "#define {MUST_USE_MACRO} __attribute__((warn_unused_result))"
+            "#else"
+            "#define {MUST_USE_MACRO}"
+            "#endif"
+            "#endif"
         ));
+        out!("\n");
+        Ok(())
+    })
+}
 
+/// Defines the `SAFER_FFI_PURE` (resp. `SAFER_FFI_CONST`) macro annotating
+/// the `#[ffi_pure]` (resp. `#[ffi_const]`) functions, so that GCC and Clang
+/// may fold repeated calls to them; returns the name of the macro.
+fn emit_purity_macro (
+    ctx: &'_ mut dyn Definer,
+    purity: &'_ str,
+) -> io::Result<String>
+{
+    let name = format!("SAFER_FFI_{}", purity.to_ascii_uppercase());
+    ctx.define_once(&name, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {name}"
+            "#if defined(__GNUC__) || defined(__clang__)"
+            "#define {name} __attribute__(({purity}))"
+            "#else"
+            "#define {name}"
+            "#endif"
+            "#endif"
+        ));
         out!("\n");
         Ok(())
+    })?;
+    Ok(name)
+}
+
+const ASSUME_NONNULL_MACRO: &str = "SAFER_FFI_ASSUME_NONNULL";
+
+/// Defines the macros opening and closing a region wherein the unannotated
+/// pointers are deemed non-null: Clang's `#pragma clang assume_nonnull`, or
+/// nothing for the other compilers.
+fn emit_assume_nonnull_macros (
+    ctx: &'_ mut dyn Definer,
+) -> io::Result<()>
+{
+    ctx.define_once(ASSUME_NONNULL_MACRO, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {ASSUME_NONNULL_MACRO}_BEGIN"
+            "#if defined(__clang__)"
+            "#define {ASSUME_NONNULL_MACRO}_BEGIN _Pragma(\"clang assume_nonnull begin\")"
+            "#define {ASSUME_NONNULL_MACRO}_END _Pragma(\"clang assume_nonnull end\")"
+            "#else"
+            "#define {ASSUME_NONNULL_MACRO}_BEGIN"
+            "#define {ASSUME_NONNULL_MACRO}_END"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+const NONNULL_MACRO: &str = "SAFER_FFI_NONNULL";
+const NULLABLE_MACRO: &str = "SAFER_FFI_NULLABLE";
+
+/// Defines the macros annotating the nullability of pointers: Clang's
+/// `_Nonnull` and `_Nullable`, or nothing for the other compilers.
+fn emit_nullability_macros (
+    ctx: &'_ mut dyn Definer,
+) -> io::Result<()>
+{
+    ctx.define_once(NONNULL_MACRO, &mut |ctx| {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {NONNULL_MACRO}"
+            "#if defined(__clang__)"
+            "#define {NONNULL_MACRO} _Nonnull"
+            "#define {NULLABLE_MACRO} _Nullable"
+            "#else"
+            "#define {NONNULL_MACRO}"
+            "#define {NULLABLE_MACRO}"
+            "#endif"
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    })
+}
+
+/// Emits compile-time checks of the size and alignment of `ty` against the
+/// ones of the Rust side, so as to catch ABI drift between the header and the
+/// compiled library.
+fn emit_layout_assertions (
+    ctx: &'_ mut dyn Definer,
+    ty_name: &'_ str,
+    ty: &'_ dyn PhantomCType,
+) -> io::Result<()>
+{
+    let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+    let c_standard = ctx.settings().c_standard;
+    mk_out!(indent, ctx.out());
+    let size = ty.size();
+    let align = ty.align();
+    if c_standard.has_static_assert_keyword() {
+        out!((
+            "static_assert(sizeof({ty_name}) == {size}, \"ABI mismatch: size of `{ty_name}`\");"
+            "static_assert(alignof({ty_name}) == {align}, \"ABI mismatch: alignment of `{ty_name}`\");"
+        ));
+    } else if c_standard.has_static_assert() {
+        out!((
+            "#ifdef __cplusplus"
+            "static_assert(sizeof({ty_name}) == {size}, \"ABI mismatch: size of `{ty_name}`\");"
+            "static_assert(alignof({ty_name}) == {align}, \"ABI mismatch: alignment of `{ty_name}`\");"
+            "#else"
+            "_Static_assert(sizeof({ty_name}) == {size}, \"ABI mismatch: size of `{ty_name}`\");"
+            "_Static_assert(_Alignof({ty_name}) == {align}, \"ABI mismatch: alignment of `{ty_name}`\");"
+            "#endif"
+        ));
+    } else {
+        // No `_Static_assert` (nor `_Alignof`) before C11: resort to the
+        // negative-array-size trick, for the size only.
+        out!((
+            "typedef char {ty_name}__size_check[(sizeof({ty_name}) == {size}) ? 1 : -1];"
+        ));
     }
+    Ok(())
 }
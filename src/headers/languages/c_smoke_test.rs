@@ -0,0 +1,187 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+use crate::headers::{FnMeta, TypeLayout};
+
+/// C (11) smoke-test translation unit, `#include`-ing the C header: compiling
+/// it checks that the header is valid C (and that each function is declared
+/// with the expected signature), whereas linking it against the library checks
+/// that every function declared by the header is indeed exported.
+///
+/// The layouts are only `_Static_assert`ed when the
+/// [`Builder::with_layout_targets()`][`crate::headers::Builder::with_layout_targets`]
+/// agree on them: the Rust ones are those of the host the translation unit
+/// is generated on, which need not be the target it is compiled for.
+///
+/// Every item is thus "used" at file scope, the epilogue providing an empty
+/// `main()`.
+pub
+struct CSmokeTest;
+
+impl CSmokeTest {
+    /// Instantiates the (sized) type, and `_Static_assert`s its layout, if
+    /// the layout targets agree on it.
+    fn emit_instance (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let layouts =
+            ctx .settings()
+                .layout_targets
+                .iter()
+                .map(|target| self_ty.layout_on(target))
+                .collect::<Option<Vec<_>>>()
+        ;
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        // (named as in the C header)
+        let ty_name = self_ty.name(&C);
+        out!(("{ty_name} safer_ffi_smoke_test_{ty_name};"));
+        match layouts.as_deref() {
+            | Some([first, rest @ ..]) if rest.iter().all(|it| it == first) => {
+                let &TypeLayout { size, align } = first;
+                out!((
+                    "_Static_assert(sizeof({ty_name}) == {size}, \"ABI mismatch: size of `{ty_name}`\");"
+                    "_Static_assert(_Alignof({ty_name}) == {align}, \"ABI mismatch: alignment of `{ty_name}`\");"
+                ));
+            },
+            // No targets, or an unknown layout: nothing to assert.
+            | _ => {},
+        }
+        out!("\n");
+        Ok(())
+    }
+
+    /// Assigns the function to a pointer of the exact type it is expected to
+    /// have (rather than casting it away), for a mismatched declaration to
+    /// be caught.
+    pub(in crate::headers)
+    fn emit_function_ (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+        meta: &'_ FnMeta<'_>,
+    ) -> io::Result<()>
+    {
+        let pointer = C.function_pointer_var(
+            &format!("safer_ffi_smoke_test_{fname}"),
+            args,
+            ret_ty,
+            meta,
+        );
+        writeln!(ctx.out(), "{pointer} = &{fname};\n")
+    }
+}
+
+impl HeaderLanguage for CSmokeTest {
+    fn emit_simple_enum (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        _backing_integer: Option<&dyn PhantomCType>,
+        _variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_instance(ctx, self_ty)
+    }
+
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        _fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_instance(ctx, self_ty)
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        _fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_instance(ctx, self_ty)
+    }
+
+    /// Opaque types can only be pointed to.
+    fn emit_opaque_type (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let ty_name = self_ty.name(&C);
+        writeln!(ctx.out(), "{ty_name} * safer_ffi_smoke_test_{ty_name};\n")
+    }
+
+    fn emit_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_function_(ctx, fname, args, ret_ty, &FnMeta::DEFAULT)
+    }
+
+    fn emit_variadic_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_function_(ctx, fname, args, ret_ty, &FnMeta {
+            variadic: true,
+            ..FnMeta::DEFAULT
+        })
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        out!((
+            "#ifndef {name}"
+            "#error \"missing constant `{name}`\""
+            "#endif"
+        ));
+        out!("\n");
+        Ok(())
+    }
+
+    fn emit_static (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _mutable: bool,
+    ) -> io::Result<()>
+    {
+        writeln!(ctx.out(),
+            "void const * safer_ffi_smoke_test_{name} = &{name};\n",
+        )
+    }
+}
@@ -20,6 +20,9 @@ impl HeaderLanguage for CSharp {
             return Ok(());
         }
 
+        let sections = ctx.settings().doc_tags.then(|| DocSections::parse(docs));
+        let docs = sections.as_ref().map_or(docs, |it| &it.body[..]);
+
         out!(("/// <summary>"));
         for mut line in docs.iter().copied().map(str::trim) {
             let mut storage = None;
@@ -46,6 +49,17 @@ impl HeaderLanguage for CSharp {
             out!(("///{sep}{line}"));
         }
         out!(("/// </summary>"));
+        if let Some(DocSections { params, returns, safety, .. }) = &sections {
+            for (name, description) in params {
+                out!(("/// <param name=\"{name}\">{description}</param>"));
+            }
+            if let Some(returns) = returns {
+                out!(("/// <returns>{returns}</returns>"));
+            }
+            if let Some(safety) = safety {
+                out!(("/// <remarks><b>Safety:</b> {safety}</remarks>"));
+            }
+        }
 
         Ok(())
     }
@@ -58,50 +72,91 @@ impl HeaderLanguage for CSharp {
         backing_integer: Option<&dyn PhantomCType>,
         variants: &'_ [EnumVariant<'_>],
     ) -> io::Result<()>
+    {
+        self.emit_enum(ctx, docs, self_ty, backing_integer, None, variants)
+    }
+
+    fn emit_flags (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_enum(ctx, docs, self_ty, Some(backing_integer), Some("[Flags]"), flags)
+    }
+
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
 
-        let ref IntN =
-            backing_integer.map(|it| it.name(self))
-        ;
+        let size = self_ty.size();
+        if size == 0 {
+            panic!("C# does not support zero-sized structs!")
+        }
 
-        let ref full_ty_name = self_ty.name(self);
+        let ref name = self_ty.name(self);
+        let pack = match Packing::of(self_ty, fields) {
+            | Packing::Packed(n) => format!(", Pack = {n}"),
+            | _ => String::new(),
+        };
 
         self.emit_docs(ctx, docs, indent)?;
-
-        out!(
-            ("public enum {full_ty_name} {super} {{"),
-            super = if let Some(IntN) = IntN {
-                format!(": {IntN}")
-            } else {
-                "".into()
-            },
-        );
-
+        emit_obsolete_attribute(ctx, self_ty.deprecated(), indent)?;
+        out!((
+            "[StructLayout(LayoutKind.Sequential, Size = {size}{pack})]"
+            "public unsafe struct {name} {{"
+        ));
         if let _ = indent.scope() {
-            for v in variants {
-                self.emit_docs(ctx, v.docs, indent)?;
-                let variant_name = v.name /* ctx.adjust_variant_name(
-                    Language::CSharp,
-                    enum_name,
-                    v.name,
-                ) */;
-                if let Some(value) = v.discriminant {
-                    out!(("{variant_name} = {value:?},"));
-                } else {
-                    out!(("{variant_name},"));
+            let ref mut first = true;
+            for &StructField { docs, name, ty } in fields {
+                // Skip ZSTs
+                if ty.size() == 0 {
+                    if ty.align() > 1 {
+                        panic!("Zero-sized fields must have an alignment of `1`");
+                    } else {
+                        continue;
+                    }
+                }
+                if mem::take(first).not() {
+                    out!("\n");
+                }
+                self.emit_docs(ctx, docs, indent)?;
+                if let Some(csharp_marshaler) = marshaler_of(ctx, ty) {
+                    out!((
+                        "[MarshalAs({csharp_marshaler})]"
+                    ));
                 }
+                out!(
+                    ("public {} {name};"),
+                    ty.name(self), // _wrapping_var(self, name)
+                );
             }
         }
-
         out!(("}}"));
 
         out!("\n");
+
+        if ctx.settings().csharp_dyn_trait_classes && ctx.settings().csharp_function_pointers.not() {
+            for dyn_trait in crate::inventory::iter::<crate::DynTrait> {
+                if (dyn_trait.vtable)(self) == *name {
+                    self.emit_dyn_trait_class(ctx, dyn_trait, fields)?;
+                }
+            }
+        }
         Ok(())
     }
 
-    fn emit_struct (
+    fn emit_union (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
         docs: Docs<'_>,
@@ -114,14 +169,15 @@ impl HeaderLanguage for CSharp {
 
         let size = self_ty.size();
         if size == 0 {
-            panic!("C# does not support zero-sized structs!")
+            panic!("C# does not support zero-sized unions!")
         }
 
         let ref name = self_ty.name(self);
 
         self.emit_docs(ctx, docs, indent)?;
+        emit_obsolete_attribute(ctx, self_ty.deprecated(), indent)?;
         out!((
-            "[StructLayout(LayoutKind.Sequential, Size = {size})]"
+            "[StructLayout(LayoutKind.Explicit, Size = {size})]"
             "public unsafe struct {name} {{"
         ));
         if let _ = indent.scope() {
@@ -139,14 +195,15 @@ impl HeaderLanguage for CSharp {
                     out!("\n");
                 }
                 self.emit_docs(ctx, docs, indent)?;
-                if let Some(csharp_marshaler) = ty.csharp_marshaler() {
+                out!(("[FieldOffset(0)]"));
+                if let Some(csharp_marshaler) = marshaler_of(ctx, ty) {
                     out!((
                         "[MarshalAs({csharp_marshaler})]"
                     ));
                 }
                 out!(
                     ("public {} {name};"),
-                    ty.name(self), // _wrapping_var(self, name)
+                    ty.name(self),
                 );
             }
         }
@@ -169,6 +226,7 @@ impl HeaderLanguage for CSharp {
         let full_ty_name = self_ty.name(self);
 
         self.emit_docs(ctx, docs, indent)?;
+        emit_obsolete_attribute(ctx, self_ty.deprecated(), indent)?;
         out!(("public struct {full_ty_name} {{"));
         if let _ = indent.scope() {
             out!((
@@ -191,37 +249,242 @@ impl HeaderLanguage for CSharp {
         args: &'_ [FunctionArg<'_>],
         ret_ty: &'_ dyn PhantomCType,
     ) -> io::Result<()>
+    {
+        self.emit_grouped_function(ctx, None, docs, fname, fname, None, None, args, ret_ty)
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        name: &'_ str,
+        ty: &'_ dyn PhantomCType,
+        value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        let class_name = ctx.settings().csharp_class_name().to_owned();
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+
+        out!(("public unsafe partial class {class_name} {{"));
+        if let _ = indent.scope() {
+            self.emit_docs(ctx, docs, indent)?;
+            let ty = ty.name(self);
+            out!((
+                "public const {ty} {name} = {value:?};"
+            ));
+        }
+        out!(("}}"));
+
+        out!("\n");
+        Ok(())
+    }
+}
+
+impl CSharp {
+    /// `attribute`, if any, decorates the `enum` (_e.g._, `[Flags]`).
+    fn emit_enum (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: Option<&dyn PhantomCType>,
+        attribute: Option<&'_ str>,
+        variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
 
+        let ref IntN =
+            backing_integer.map(|it| it.name(self))
+        ;
+
+        let ref full_ty_name = self_ty.name(self);
+
+        self.emit_docs(ctx, docs, indent)?;
+        emit_obsolete_attribute(ctx, self_ty.deprecated(), indent)?;
+
+        if let Some(attribute) = attribute {
+            out!(("{attribute}"));
+        }
+        out!(
+            ("public enum {full_ty_name} {super} {{"),
+            super = if let Some(IntN) = IntN {
+                format!(": {IntN}")
+            } else {
+                "".into()
+            },
+        );
+
+        if let _ = indent.scope() {
+            for v in variants {
+                self.emit_docs(ctx, v.docs, indent)?;
+                let variant_name = v.name /* ctx.adjust_variant_name(
+                    Language::CSharp,
+                    enum_name,
+                    v.name,
+                ) */;
+                if let Some(value) = v.discriminant {
+                    out!(("{variant_name} = {value:?},"));
+                } else {
+                    out!(("{variant_name},"));
+                }
+            }
+        }
+
+        out!(("}}"));
+
+        out!("\n");
+        Ok(())
+    }
+
+    /// Emits the function within a `public static class {group}` (rather
+    /// than within the main `Ffi` one), for `#[ffi_export(group = "{group}")]`.
+    ///
+    /// The `entry_point` is the symbol, for `#[ffi_export(export_name = …)]`,
+    /// `convention` the (Rust) ABI, for `#[ffi_export(convention = …)]`, and
+    /// `deprecated` the note of its `#[deprecated]` attribute, if any.
+    pub(in crate::headers)
+    fn emit_grouped_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        group: Option<&'_ str>,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        entry_point: &'_ str,
+        convention: Option<&'_ str>,
+        deprecated: Option<&'_ str>,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let group = match group {
+            | Some(group) => group,
+            | None => {
+                let class_decl = format!("unsafe partial class {}", ctx.settings().csharp_class_name());
+                return self.emit_function_in_class(
+                    ctx,
+                    &class_decl,
+                    docs,
+                    fname,
+                    entry_point,
+                    convention,
+                    deprecated,
+                    args,
+                    ret_ty,
+                );
+            },
+        };
+        // The `RustLib` of the main class is private.
+        let rust_lib = ctx.settings().library_name.clone().unwrap_or_else(
+            crate::headers::Builder::<'_, crate::headers::WhereTo>::lib_name,
+        );
+        ctx.define_once(&format!("{group}__csharp_class"), &mut |ctx| writeln!(ctx.out(),
+            "public static unsafe partial class {group} {{\n    private const string RustLib = \"{rust_lib}\";\n}}\n",
+        ))?;
+        self.emit_function_in_class(
+            ctx,
+            &format!("static unsafe partial class {group}"),
+            docs,
+            fname,
+            entry_point,
+            convention,
+            deprecated,
+            args,
+            ret_ty,
+        )
+    }
+
+    fn emit_function_in_class (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        class: &'_ str,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        entry_point: &'_ str,
+        convention: Option<&'_ str>,
+        deprecated: Option<&'_ str>,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+
+        let entry_point = if entry_point != fname {
+            format!(", EntryPoint = \"{entry_point}\"")
+        } else {
+            String::new()
+        };
+        // Rust's `system` is C#'s `Winapi` (what `LibraryImport` defaults to).
+        let (calling_convention, unmanaged_call_conv) = match convention {
+            | Some("stdcall") => (", CallingConvention = CallingConvention.StdCall", Some("CallConvStdcall")),
+            | Some("fastcall") => (", CallingConvention = CallingConvention.FastCall", Some("CallConvFastcall")),
+            | Some("system") => (", CallingConvention = CallingConvention.Winapi", None),
+            | _ => ("", None),
+        };
+
+        // Owned pointers are returned as `SafeHandle`s (when they can be freed).
+        let safe_handle = ret_safe_handle(ret_ty);
+        for free_fn in crate::inventory::iter::<crate::FreeFn> {
+            if free_fn.free_fn == fname || safe_handle.map_or(false, |it| it.handle == free_fn.handle) {
+                self.emit_safe_handle(ctx, free_fn)?;
+            }
+        }
+
         out!((
-            "public unsafe partial class Ffi {{"
+            "public {class} {{"
         ));
 
         if let _ = indent.scope() {
             self.emit_docs(ctx, docs, indent)?;
+            if ctx.settings().restrict_pointers {
+                for FunctionArg { name, .. } in args.iter().filter(|arg| arg.restrict) {
+                    out!((
+                        "/// <remarks> The memory pointed to by <c>{name}</c> must not be accessed through any other argument during the call. </remarks>"
+                    ));
+                }
+            }
 
-            if let Some(marshaler) = ret_ty.csharp_marshaler() {
+            emit_obsolete_attribute(ctx, deprecated, indent)?;
+
+            if let Some(marshaler) = marshaler_of(ctx, ret_ty) {
                 out!((
                     "[return: MarshalAs({marshaler})]"
                 ));
             }
 
-            out!((
-                "[DllImport(RustLib, ExactSpelling = true)] public static unsafe extern"
-            ));
+            if ctx.settings().csharp_library_import
+            && library_import_marshals(ctx, args, ret_ty)
+            {
+                if let Some(call_conv) = unmanaged_call_conv {
+                    out!((
+                        "[UnmanagedCallConv(CallConvs = new[] {{ typeof(System.Runtime.CompilerServices.{call_conv}) }})]"
+                    ));
+                }
+                out!((
+                    "[LibraryImport(RustLib{entry_point})] public static unsafe partial"
+                ));
+            } else {
+                out!((
+                    "[DllImport(RustLib, ExactSpelling = true{entry_point}{calling_convention})] public static unsafe extern"
+                ));
+            }
 
-            let ret_ty = ret_ty.name(self);
+            let ret_ty = match safe_handle {
+                | Some(free_fn) => free_fn.handle.to_owned(),
+                | None => ret_ty.name(self),
+            };
             out!("{}{ret_ty} {fname} (", indent);
             let mut first = true;
             if let _ = indent.scope() {
-                for FunctionArg { name: arg_name, ty } in args {
+                for FunctionArg { name: arg_name, ty, .. } in args {
                     if mem::take(&mut first).not() {
                         out!(",");
                     }
                     out!("\n");
-                    if let Some(marshaler) = ty.csharp_marshaler() {
+                    if let Some(marshaler) = marshaler_of(ctx, ty) {
                         out!((
                             "[MarshalAs({marshaler})]"
                         ));
@@ -238,29 +501,367 @@ impl HeaderLanguage for CSharp {
         Ok(())
     }
 
-    fn emit_constant (
+    /// Defines (once) the `SafeHandle` subclass releasing its pointer through
+    /// the given `#[ffi_export(free_of = …)]` function.
+    fn emit_safe_handle (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
-        docs: Docs<'_>,
-        name: &'_ str,
-        ty: &'_ dyn PhantomCType,
-        value: &'_ dyn ::core::fmt::Debug,
+        &crate::FreeFn { handle, group, free_fn, ty }: &'_ crate::FreeFn,
+    ) -> io::Result<()>
+    {
+        let ref ptr_ty = ty(self);
+        let class = group.unwrap_or(ctx.settings().csharp_class_name()).to_owned();
+        ctx.define_once(handle, &mut |ctx| {
+            let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+            mk_out!(indent, ctx.out());
+
+            out!((
+                "/// <summary> Owned pointer, released through <c>{class}.{free_fn}()</c>. </summary>"
+                "public sealed class {handle} : SafeHandle {{"
+                "    public {handle} () : base(IntPtr.Zero, true) {{}}"
+                ""
+                "    public override bool IsInvalid => handle == IntPtr.Zero;"
+                ""
+                "    protected override unsafe bool ReleaseHandle ()"
+                "    {{"
+                "        {class}.{free_fn}(({ptr_ty}) handle.ToPointer());"
+                "        return true;"
+                "    }}"
+                "}}"
+            ));
+
+            out!("\n");
+            Ok(())
+        })
+    }
+
+    /// Emits, right after the vtable of a `#[derive_ReprC(dyn)]` trait, the
+    /// `abstract class` through which C# may implement it (see
+    /// [`Builder::with_csharp_dyn_trait_classes()`]).
+    ///
+    /// [`Builder::with_csharp_dyn_trait_classes()`]: `crate::headers::Builder::with_csharp_dyn_trait_classes`
+    fn emit_dyn_trait_class (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        &crate::DynTrait { name, vtable, virtual_ptr, owned_ptr, ref_ptr, methods }: &'_ crate::DynTrait,
+        vtable_fields: &'_ [StructField<'_>],
     ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
 
-        out!(("public unsafe partial class Ffi {{"));
+        let ref vtable = vtable(self);
+        let ref virtual_ptr = virtual_ptr(self);
+        let ref owned_ptr = owned_ptr(self);
+        let ref ref_ptr = ref_ptr(self);
+        let methods = methods(self);
+        let retain = vtable_fields.iter().any(|field| field.name == "retain_vptr");
+
+        out!((
+            "/// <summary>"
+            "/// Base class through which to implement the <c>{name}</c> (Rust) trait"
+            "/// in C#: see <c>IntoVirtualPtr()</c>."
+            "/// </summary>"
+            "public abstract unsafe class {name} {{"
+        ));
         if let _ = indent.scope() {
-            self.emit_docs(ctx, docs, indent)?;
-            let ty = ty.name(self);
+            for crate::DynTraitMethod { name, args, ret, .. } in &methods {
+                let params = args.iter().map(|(arg, ty)| format!("{ty} {arg}")).collect::<Vec<_>>();
+                out!((
+                    "public abstract {ret} {} ({});"
+                ), pascal_cased(name), params.join(", "));
+            }
+            out!("\n");
             out!((
-                "public const {ty} {name} = {value:?};"
+                "/// <summary>"
+                "/// Hands this object over to Rust, which keeps it alive until it"
+                "/// drops the returned pointer."
+                "/// </summary>"
+                "public {virtual_ptr} IntoVirtualPtr ()"
+                "{{"
+                "    return new {virtual_ptr} {{"
+                "        ptr = ({owned_ptr}) GCHandle.ToIntPtr(GCHandle.Alloc(this)),"
+                "        vtable = VTable,"
+                "    }};"
+                "}}"
+                ""
+                "private static {name} FromHandle (void * handle)"
+                "{{"
+                "    return ({name}) GCHandle.FromIntPtr((IntPtr) handle).Target;"
+                "}}"
+                ""
+                "// Being `static`, the vtable keeps its delegates alive."
+                "private static readonly {vtable} VTable = new {vtable} {{"
+                "    release_vptr = ReleaseVPtr,"
             ));
+            if retain {
+                out!(("    retain_vptr = RetainVPtr,"));
+            }
+            for crate::DynTraitMethod { name, .. } in &methods {
+                out!(("    {name} = {}Trampoline,"), pascal_cased(name));
+            }
+            out!((
+                "}};"
+                ""
+                "private static void ReleaseVPtr ({owned_ptr} handle)"
+                "{{"
+                "    GCHandle.FromIntPtr((IntPtr) handle).Free();"
+                "}}"
+            ));
+            if retain {
+                out!("\n");
+                out!((
+                    "private static {owned_ptr} RetainVPtr ({ref_ptr} handle)"
+                    "{{"
+                    "    return ({owned_ptr}) GCHandle.ToIntPtr(GCHandle.Alloc(FromHandle(handle)));"
+                    "}}"
+                ));
+            }
+            for crate::DynTraitMethod { name, self_ty, args, ret } in &methods {
+                let Name = pascal_cased(name);
+                let params = Iterator::chain(
+                    ::core::iter::once(format!("{self_ty} handle")),
+                    args.iter().map(|(arg, ty)| format!("{ty} {arg}")),
+                ).collect::<Vec<_>>();
+                let call_args = args.iter().map(|&(arg, _)| arg).collect::<Vec<_>>();
+                let return_ = if ret == "void" { "" } else { "return " };
+                out!("\n");
+                out!((
+                    "private static {ret} {Name}Trampoline ({})"
+                    "{{"
+                    "    {return_}FromHandle(handle).{Name}({});"
+                    "}}"
+                ), params.join(", "), call_args.join(", "));
+            }
         }
         out!(("}}"));
 
         out!("\n");
         Ok(())
     }
+
+    /// Emits, within the `wrapper_class`, the idiomatic wrapper around the
+    /// `raw_class.fname` `[DllImport]` (see
+    /// [`Builder::with_csharp_wrapper_class()`]).
+    ///
+    /// [`Builder::with_csharp_wrapper_class()`]: `crate::headers::Builder::with_csharp_wrapper_class`
+    pub(in crate::headers)
+    fn emit_wrapper (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        wrapper_class: &'_ str,
+        raw_class: &'_ str,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+        error_code: bool,
+        deprecated: Option<&'_ str>,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+
+        if error_code {
+            ctx.define_once("FfiException", &mut |ctx| writeln!(ctx.out(), concat!(
+                "public class FfiException : Exception {{\n",
+                "    public long Code {{ get; }}\n",
+                "\n",
+                "    public FfiException (string function, long code)\n",
+                "        : base(function + \"() failed with error code \" + code)\n",
+                "    {{\n",
+                "        Code = code;\n",
+                "    }}\n",
+                "}}\n",
+            )))?;
+        }
+
+        let mut params = vec![];
+        let mut pinned = vec![];
+        let mut call_args = vec![];
+        for &FunctionArg { name, ty, .. } in args {
+            match &ty.name(&C)[..] {
+                | "char const *" => {
+                    params.push(format!("string {name}"));
+                    pinned.push(format!(
+                        "byte[] {name}_utf8 = {name} is null ? null : System.Text.Encoding.UTF8.GetBytes({name} + \"\\0\");"
+                    ));
+                    pinned.push(format!("fixed (byte * {name}_ptr = {name}_utf8)"));
+                    call_args.push(format!("{name}_ptr"));
+                },
+                | c_ty @ ("slice_ref_uint8_t" | "slice_mut_uint8_t") => {
+                    let span = if c_ty == "slice_ref_uint8_t" { "ReadOnlySpan" } else { "Span" };
+                    params.push(format!("{span}<byte> {name}"));
+                    pinned.push(format!("fixed (byte * {name}_ptr = {name})"));
+                    call_args.push(format!(
+                        "new {} {{ ptr = {name}_ptr, len = (UIntPtr) {name}.Length }}",
+                        ty.name(self),
+                    ));
+                },
+                | _ => {
+                    params.push(format!("{} {name}", ty.name(self)));
+                    call_args.push(name.to_owned());
+                },
+            }
+        }
+        let call = format!("{raw_class}.{fname}({})", call_args.join(", "));
+
+        let safe_handle = ret_safe_handle(ret_ty);
+        let raw_ret_ty = match safe_handle {
+            | Some(free_fn) => free_fn.handle.to_owned(),
+            | None => ret_ty.name(self),
+        };
+        let (wrapper_ret_ty, body) = match &ret_ty.name(&C)[..] {
+            | _ if error_code => ("void".to_owned(), vec![
+                format!("var __ret = {call};"),
+                format!("if ((long) __ret != 0) throw new FfiException(\"{fname}\", (long) __ret);"),
+            ]),
+            | "char const *" => ("string".to_owned(), vec![
+                format!("return Marshal.PtrToStringUTF8((IntPtr) {call});"),
+            ]),
+            // Copied, and then freed.
+            | "char *" if safe_handle.is_some() => ("string".to_owned(), vec![
+                format!("using (var __ret = {call})"),
+                "    return Marshal.PtrToStringUTF8(__ret.DangerousGetHandle());".to_owned(),
+            ]),
+            | _ if raw_ret_ty == "void" => (raw_ret_ty, vec![
+                format!("{call};"),
+            ]),
+            | _ => (raw_ret_ty, vec![
+                format!("return {call};"),
+            ]),
+        };
+
+        out!(("public static unsafe partial class {wrapper_class} {{"));
+        if let _ = indent.scope() {
+            self.emit_docs(ctx, docs, indent)?;
+            emit_obsolete_attribute(ctx, deprecated, indent)?;
+            out!("{indent}public static {wrapper_ret_ty} {} (", pascal_cased(fname));
+            if let _ = indent.scope() {
+                for (i, param) in params.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { "," };
+                    out!("{sep}\n{indent}{param}");
+                }
+            }
+            out!(")\n");
+            out!(("{{"));
+            if let _ = indent.scope() {
+                for line in &pinned {
+                    out!(("{line}"));
+                }
+                out!(("{{"));
+                if let _ = indent.scope() {
+                    for line in body {
+                        out!(("{line}"));
+                    }
+                }
+                out!(("}}"));
+            }
+            out!(("}}"));
+        }
+        out!(("}}"));
+
+        out!("\n");
+        Ok(())
+    }
+}
+
+/// `[Obsolete("…")]`, for the `#[deprecated]` items.
+fn emit_obsolete_attribute (
+    ctx: &'_ mut dyn Definer,
+    deprecated: Option<&'_ str>,
+    indent: &'_ Indentation,
+) -> io::Result<()>
+{
+    mk_out!(indent, ctx.out());
+    match deprecated {
+        | Some("") => out!(("[Obsolete]")),
+        | Some(note) => {
+            let note = string_literal(note);
+            out!(("[Obsolete({note})]"));
+        },
+        | None => {},
+    }
+    Ok(())
+}
+
+/// `s` as a C# string literal (Rust's `{:?}` escapes, such as `\u{…}`, are
+/// not valid C#).
+fn string_literal (s: &'_ str)
+  -> String
+{
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        match c {
+            | '"' => ret.push_str("\\\""),
+            | '\\' => ret.push_str("\\\\"),
+            | '\n' => ret.push_str("\\n"),
+            | '\r' => ret.push_str("\\r"),
+            | '\t' => ret.push_str("\\t"),
+            | _ if c.is_control() => {
+                ret.push_str(&format!("\\u{:04x}", c as u32));
+            },
+            | _ => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/// The `[MarshalAs(…)]` of `ty`, if any.
+///
+/// With [`Builder::with_csharp_function_pointers()`], callback types are
+/// blittable structs, which are thus not to be marshaled.
+///
+/// [`Builder::with_csharp_function_pointers()`]: `crate::headers::Builder::with_csharp_function_pointers`
+fn marshaler_of (ctx: &'_ dyn Definer, ty: &'_ dyn PhantomCType)
+  -> Option<rust::String>
+{
+    ty.csharp_marshaler().filter(|marshaler| {
+        ctx.settings().csharp_function_pointers.not()
+        || marshaler != "UnmanagedType.FunctionPtr"
+    })
+}
+
+/// Whether the `[LibraryImport]` source generator can marshal the given
+/// signature: it rejects the `[MarshalAs(…)]` of `delegate`s and `string`s
+/// (which require custom marshallers), keeping only the `bool` one.
+fn library_import_marshals (
+    ctx: &'_ dyn Definer,
+    args: &'_ [FunctionArg<'_>],
+    ret_ty: &'_ dyn PhantomCType,
+) -> bool
+{
+    args.iter()
+        .map(|arg| arg.ty)
+        .chain([ret_ty])
+        .filter_map(|ty| marshaler_of(ctx, ty))
+        .all(|marshaler| marshaler == "UnmanagedType.U1")
+}
+
+/// The `SafeHandle` to be returned instead of `ret_ty` (if owned, with a
+/// `#[ffi_export(free_of = …)]` function to free it).
+fn ret_safe_handle (ret_ty: &'_ dyn PhantomCType)
+  -> Option<&'static crate::FreeFn>
+{
+    if ret_ty.csharp_marshaler().is_some() {
+        return None;
+    }
+    let ref c_ret_ty = ret_ty.name(&C);
+    crate::inventory::iter::<crate::FreeFn>
+        .into_iter()
+        .find(|it| (it.ty)(&C) == *c_ret_ty)
+}
+
+/// `snake_case` to `PascalCase`.
+fn pascal_cased (name: &'_ str)
+  -> rust::String
+{
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars)
+        })
+        .collect()
 }
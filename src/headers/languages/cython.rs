@@ -0,0 +1,286 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+/// Cython `.pxd` declarations: everything is declared within the
+/// `cdef extern from "mylib.h":` block of the prelude, so that Cython code
+/// may `cimport` the library without transcribing its C header.
+pub
+struct Cython;
+
+impl Cython {
+    /// A `struct` or a `union`, depending on `keyword`.
+    fn emit_aggregate (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        keyword: &'_ str,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        let _extern_block = indent.scope();
+        mk_out!(indent, ctx.out());
+        let full_ty_name = self_ty.name(self);
+
+        if self_ty.size() == 0 {
+            panic!("C does not support zero-sized {keyword}s!")
+        }
+
+        // Cython only knows of `#pragma pack(1)`-packed structs; since the
+        // types are `cdef extern`, the C compiler lays them out as per the
+        // header (`#pragma pack` / `SAFER_FFI_ALIGNAS`) either way, but the
+        // other modifiers are at least spelled out.
+        let packing = Packing::of(self_ty, fields);
+        let packed = match packing {
+            | Packing::Packed(1) if keyword == "struct" => "packed ",
+            | _ => "",
+        };
+
+        self.emit_docs(ctx, docs, indent)?;
+        if let (Some(repr), "") = (packing.to_repr(), packed) {
+            out!(("# `#[repr({repr})]`: laid out as per the C header."));
+        }
+        out!(("ctypedef {packed}{keyword} {full_ty_name}:"));
+        if let _ = indent.scope() {
+            for &StructField { docs, name, ty } in fields {
+                // Skip ZSTs
+                if ty.size() == 0 {
+                    if ty.align() > 1 {
+                        panic!("Zero-sized fields must have an alignment of `1`");
+                    } else {
+                        continue;
+                    }
+                }
+                self.emit_docs(ctx, docs, indent)?;
+                out!(
+                    ("{}"),
+                    const_first(&ty.name_wrapping_var(self, name)),
+                );
+            }
+        }
+
+        out!("\n");
+        Ok(())
+    }
+}
+
+impl HeaderLanguage for Cython {
+    fn emit_docs (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        indent: &'_ Indentation,
+    ) -> io::Result<()>
+    {
+        mk_out!(indent, ctx.out());
+
+        for line in docs.iter().copied().map(str::trim) {
+            let sep = if line.is_empty() { "" } else { " " };
+            out!(("#{sep}{line}"));
+        }
+
+        Ok(())
+    }
+
+    fn emit_simple_enum (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: Option<&dyn PhantomCType>,
+        variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        let _extern_block = indent.scope();
+        mk_out!(indent, ctx.out());
+
+        self.emit_docs(ctx, docs, indent)?;
+
+        let ref short_name = self_ty.short_name();
+        let ref full_ty_name = self_ty.name(self);
+
+        if let Some(intn_t) = backing_integer {
+            let intn_t = intn_t.name(self);
+            out!((
+                "ctypedef {intn_t} {full_ty_name}"
+                "enum:"
+            ));
+        } else {
+            out!(("ctypedef enum {full_ty_name}:"));
+        }
+
+        if let _ = indent.scope() {
+            for v in variants {
+                self.emit_docs(ctx, v.docs, indent)?;
+                let variant_name = crate::utils::screaming_case(short_name, v.name);
+                out!(("{variant_name}"));
+            }
+        }
+
+        out!("\n");
+        Ok(())
+    }
+
+    fn emit_flags (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        if let _extern_block = indent.scope() {
+            mk_out!(indent, ctx.out());
+
+            self.emit_docs(ctx, docs, indent)?;
+
+            let ref full_ty_name = self_ty.name(self);
+            let ref intn_t = backing_integer.name(self);
+            out!(("ctypedef {intn_t} {full_ty_name}"));
+            out!("\n");
+        }
+
+        for flag in flags {
+            self.emit_constant(
+                ctx,
+                flag.docs,
+                &flag.flag_constant_name(self_ty),
+                backing_integer,
+                flag.discriminant.expect("flags have explicit values"),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, docs, self_ty, "struct", fields)
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, docs, self_ty, "union", fields)
+    }
+
+    fn emit_opaque_type (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        let _extern_block = indent.scope();
+        mk_out!(indent, ctx.out());
+        let full_ty_name = self_ty.name(self);
+
+        self.emit_docs(ctx, docs, indent)?;
+        out!((
+            "ctypedef struct {full_ty_name}:"
+            "    pass"
+        ));
+
+        out!("\n");
+        Ok(())
+    }
+
+    fn emit_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        let _extern_block = indent.scope();
+        mk_out!(indent, ctx.out());
+
+        self.emit_docs(ctx, docs, indent)?;
+        let args =
+            args.iter()
+                .map(|arg| arg.ty.name_wrapping_var(self, arg.name))
+                .collect::<rust::Vec<_>>()
+                .join(", ")
+        ;
+        out!(
+            ("{}"),
+            const_first(&ret_ty.name_wrapping_var(self, &format!("{fname} ({args})"))),
+        );
+
+        out!("\n");
+        Ok(())
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        name: &'_ str,
+        ty: &'_ dyn PhantomCType,
+        _value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        let _extern_block = indent.scope();
+        mk_out!(indent, ctx.out());
+
+        self.emit_docs(ctx, docs, indent)?;
+        // Cython only needs the name (and type) of the `#define`.
+        out!(
+            ("const {}"),
+            const_first(&ty.name_wrapping_var(self, name)),
+        );
+
+        out!("\n");
+        Ok(())
+    }
+}
+
+/// Cython only understands `const` in leading position: `char const *` is to
+/// be spelled `const char *` (and the `* const` of const pointers dropped).
+fn const_first (c_decl: &'_ str)
+  -> rust::String
+{
+    let mut ret = rust::String::with_capacity(c_decl.len());
+    let mut rest = c_decl;
+    while let Some(idx) = rest.find(" const") {
+        let (before, after) = (&rest[.. idx], &rest[idx + " const".len() ..]);
+        if after.starts_with(|c: char| c == '_' || c.is_ascii_alphanumeric()) {
+            // Not the `const` keyword, but, _e.g._, a `constant` identifier.
+            ret.push_str(&rest[.. idx + " const".len()]);
+        } else {
+            let word_start =
+                before
+                    .rfind(|c: char| (c == '_' || c.is_ascii_alphanumeric()).not())
+                    .map_or(0, |i| i + 1)
+            ;
+            ret.push_str(&before[.. word_start]);
+            if word_start < before.len() {
+                ret.push_str("const ");
+                ret.push_str(&before[word_start ..]);
+            }
+        }
+        rest = after;
+    }
+    ret.push_str(rest);
+    ret
+}
@@ -0,0 +1,136 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+/// The default banner of the snapshots (the C-styled one not being valid
+/// within them).
+pub(in crate)
+const BANNER: &str = concat!(
+    "# Layouts of the types exported by the crate.\n",
+    "#\n",
+    "# File auto-generated by `::safer_ffi`.\n",
+    "# Do not manually edit this file.\n",
+);
+
+/// Snapshot of the Rust layouts of the exported types, checked by the
+/// [`layout_tests!`][`crate::layout_tests`]: a `[Name]` section per (sized)
+/// type, with its `size`, its `align`ment, and the offset of each of its
+/// `.field`s (as laid out by the compiler).
+///
+/// Functions, constants, statics, and opaque types are not part of it.
+pub
+struct LayoutSnapshot;
+
+impl LayoutSnapshot {
+    fn emit_entry (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [(&'_ str, usize)],
+    ) -> io::Result<()>
+    {
+        let out = ctx.out();
+        writeln!(out,
+            "[{}]\nsize = {}\nalign = {}",
+            // (named as in the C header)
+            self_ty.name(&C),
+            self_ty.size(),
+            self_ty.align(),
+        )?;
+        for &(name, offset) in fields {
+            writeln!(out, ".{name} = {offset}")?;
+        }
+        writeln!(out)
+    }
+}
+
+impl HeaderLanguage for LayoutSnapshot {
+    fn emit_simple_enum (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        _backing_integer: Option<&dyn PhantomCType>,
+        _variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_entry(ctx, self_ty, &[])
+    }
+
+    /// The offsets are the ones measured on the compiled type, hence omitted
+    /// for the types not exposing them (see [`CType::field_offsets()`]).
+    ///
+    /// [`CType::field_offsets()`]: `crate::layout::CType::field_offsets`
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        let offsets =
+            self_ty
+                .field_offsets()
+                .map_or_else(rust::Vec::new, |offsets| {
+                    fields
+                        .iter()
+                        .map(|&StructField { name, .. }| name)
+                        .zip(offsets)
+                        .collect()
+                })
+        ;
+        self.emit_entry(ctx, self_ty, &offsets)
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        let offsets =
+            fields
+                .iter()
+                .map(|&StructField { name, .. }| (name, 0))
+                .collect::<rust::Vec<_>>()
+        ;
+        self.emit_entry(ctx, self_ty, &offsets)
+    }
+
+    fn emit_opaque_type (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_function (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _fname: &'_ str,
+        _args: &'_ [FunctionArg<'_>],
+        _ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+}
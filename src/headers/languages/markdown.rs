@@ -0,0 +1,202 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+/// Markdown API reference: each item gets its own section, with its docs
+/// (already Markdown) followed by its definition in C syntax.
+pub
+struct Markdown;
+
+impl Markdown {
+    fn emit_heading (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        title: &'_ str,
+    ) -> io::Result<()>
+    {
+        let out = ctx.out();
+        writeln!(out, "## `{title}`\n")?;
+        let mut in_code_block = false;
+        for line in docs {
+            let line = line.strip_prefix(' ').unwrap_or(line);
+            if line.trim_start().starts_with("```") {
+                in_code_block = in_code_block.not();
+            }
+            // Nest the `# Safety`-like sections under the item's own heading.
+            if in_code_block.not() && line.starts_with('#') {
+                writeln!(out, "##{line}")?;
+            } else {
+                writeln!(out, "{line}")?;
+            }
+        }
+        if docs.is_empty().not() {
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Have the C backend emit the definition, within a code block.
+    fn emit_c_definition (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        emit: impl FnOnce(&'_ mut dyn Definer) -> io::Result<()>,
+    ) -> io::Result<()>
+    {
+        writeln!(ctx.out(), "```c")?;
+        emit(ctx)?;
+        writeln!(ctx.out(), "```\n")
+    }
+}
+
+impl HeaderLanguage for Markdown {
+    fn emit_simple_enum (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: Option<&dyn PhantomCType>,
+        variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &self_ty.name(&C))?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_simple_enum(ctx, &[], self_ty, backing_integer, variants)
+        })
+    }
+
+    fn emit_flags (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &self_ty.name(&C))?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_flags(ctx, &[], self_ty, backing_integer, &[])
+        })?;
+        for flag in flags {
+            self.emit_constant(
+                ctx,
+                flag.docs,
+                &flag.flag_constant_name(self_ty),
+                backing_integer,
+                flag.discriminant.expect("flags have explicit values"),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &self_ty.name(&C))?;
+        writeln!(ctx.out(),
+            "Size: {} bytes, alignment: {} bytes.\n",
+            self_ty.size(),
+            self_ty.align(),
+        )?;
+        writeln!(ctx.out(), "| Field | C type | Size | Description |")?;
+        writeln!(ctx.out(), "|-------|--------|------|-------------|")?;
+        for &StructField { docs, name, ty } in fields {
+            if ty.size() == 0 {
+                continue;
+            }
+            writeln!(ctx.out(),
+                "| `{name}` | `{}` | {} | {} |",
+                ty.name(&C),
+                ty.size(),
+                docs.iter().map(|line| line.trim()).collect::<rust::Vec<_>>().join(" "),
+            )?;
+        }
+        writeln!(ctx.out())?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_struct(ctx, &[], self_ty, fields)
+        })
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &self_ty.name(&C))?;
+        writeln!(ctx.out(),
+            "Size: {} bytes, alignment: {} bytes (all the members overlap).\n",
+            self_ty.size(),
+            self_ty.align(),
+        )?;
+        writeln!(ctx.out(), "| Member | C type | Size | Description |")?;
+        writeln!(ctx.out(), "|--------|--------|------|-------------|")?;
+        for &StructField { docs, name, ty } in fields {
+            if ty.size() == 0 {
+                continue;
+            }
+            writeln!(ctx.out(),
+                "| `{name}` | `{}` | {} | {} |",
+                ty.name(&C),
+                ty.size(),
+                docs.iter().map(|line| line.trim()).collect::<rust::Vec<_>>().join(" "),
+            )?;
+        }
+        writeln!(ctx.out())?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_union(ctx, &[], self_ty, fields)
+        })
+    }
+
+    fn emit_opaque_type (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &self_ty.name(&C))?;
+        writeln!(ctx.out(), "Opaque type: only to be used behind a pointer.\n")?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_opaque_type(ctx, &[], self_ty)
+        })
+    }
+
+    fn emit_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        fname: &'_ str,
+        args: &'_ [FunctionArg<'_>],
+        ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, &format!("{fname}()"))?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_function(ctx, &[], fname, args, ret_ty)
+        })
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        name: &'_ str,
+        ty: &'_ dyn PhantomCType,
+        value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        self.emit_heading(ctx, docs, name)?;
+        self.emit_c_definition(ctx, |ctx| {
+            C.emit_constant(ctx, &[], name, ty, value)
+        })
+    }
+}
@@ -15,6 +15,22 @@ use {
 pub use c::C;
 mod c;
 
+pub use c_smoke_test::CSmokeTest;
+mod c_smoke_test;
+
+pub use cython::Cython;
+mod cython;
+
+pub use layout_snapshot::LayoutSnapshot;
+pub(in crate) use layout_snapshot::BANNER as LAYOUT_SNAPSHOT_BANNER;
+mod layout_snapshot;
+
+pub use markdown::Markdown;
+mod markdown;
+
+pub use symbol_list::SymbolList;
+mod symbol_list;
+
 __cfg_csharp__! {
     pub use csharp::CSharp;
     mod csharp;
@@ -78,6 +94,24 @@ trait HeaderLanguage : UpcastAny {
     ) -> io::Result<()>
     ;
 
+    /// A set of bit `flags` (_cf._ [`ffi_export_flags!`]), meant to be
+    /// combined with `|`.
+    ///
+    /// Defaults to emitting them as a simple enum.
+    ///
+    /// [`ffi_export_flags!`]: `crate::ffi_export_flags`
+    fn emit_flags (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        self.emit_simple_enum(ctx, docs, self_ty, Some(backing_integer), flags)
+    }
+
     fn emit_struct (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
@@ -87,6 +121,28 @@ trait HeaderLanguage : UpcastAny {
     ) -> io::Result<()>
     ;
 
+    /// All the `fields` share the same (zero) offset.
+    ///
+    /// By default, the language is deemed unable to express it, and the
+    /// generation errors.
+    fn emit_union (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        _fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "`{}` cannot declare the union `{}`",
+                self.language_name(),
+                self_ty.short_name(),
+            ),
+        ))
+    }
+
     fn emit_opaque_type (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
@@ -105,6 +161,22 @@ trait HeaderLanguage : UpcastAny {
     ) -> io::Result<()>
     ;
 
+    /// A C-variadic function: `args`, then `...`.
+    ///
+    /// By default, the language is deemed unable to express it, and the
+    /// function is skipped (nothing is emitted).
+    fn emit_variadic_function (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _fname: &'_ str,
+        _args: &'_ [FunctionArg<'_>],
+        _ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
     fn emit_constant (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
@@ -115,6 +187,22 @@ trait HeaderLanguage : UpcastAny {
     ) -> io::Result<()>
     ;
 
+    /// A global variable (which C code may write to, if `mutable`).
+    ///
+    /// By default, the language is deemed unable to express it, and the
+    /// `static` is skipped (nothing is emitted).
+    fn emit_static (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _mutable: bool,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
     fn emit_docs (
         self: &'_ Self,
         _ctx: &'_ mut dyn Definer,
@@ -128,6 +216,95 @@ trait HeaderLanguage : UpcastAny {
     }
 }
 
+/// Rust docs split into their "body" and the `# Arguments`, `# Returns` and
+/// `# Safety` sections, for [`Builder::with_doc_tags()`] to map these to the
+/// dedicated tags of the target language (_e.g._, `\param` in Doxygen).
+///
+/// [`Builder::with_doc_tags()`]: `super::Builder::with_doc_tags`
+#[derive(Debug, Default)]
+pub
+struct DocSections<'lt> {
+    /// The lines not belonging to any of the following sections.
+    pub
+    body: Vec<&'lt str>,
+
+    /// The `` * `name` - description`` items of the `# Arguments` section.
+    pub
+    params: Vec<(&'lt str, rust::String)>,
+
+    pub
+    returns: Option<rust::String>,
+
+    pub
+    safety: Option<rust::String>,
+}
+
+impl<'lt> DocSections<'lt> {
+    pub
+    fn parse (docs: Docs<'lt>)
+      -> DocSections<'lt>
+    {
+        enum Section { Body, Arguments, Returns, Safety }
+
+        fn append (paragraph: &'_ mut rust::String, line: &'_ str)
+        {
+            if line.is_empty() {
+                return;
+            }
+            if paragraph.is_empty().not() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line);
+        }
+
+        let mut ret = DocSections::default();
+        let mut section = Section::Body;
+        let mut in_code_block = false;
+        for &line in docs {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                in_code_block = in_code_block.not();
+            }
+            if let (false, Some(title)) = (in_code_block, trimmed.strip_prefix('#')) {
+                section = match &title.trim_start_matches('#').trim().to_ascii_lowercase()[..] {
+                    | "arguments" | "parameters" => Section::Arguments,
+                    | "returns" | "return value" => Section::Returns,
+                    | "safety" => Section::Safety,
+                    | _ => Section::Body,
+                };
+                if let Section::Body = section {
+                    ret.body.push(line);
+                }
+                continue;
+            }
+            match section {
+                | Section::Body => ret.body.push(line),
+                | Section::Arguments => {
+                    if let Some(item) = trimmed.strip_prefix(&['*', '-'][..]) {
+                        let item = item.trim();
+                        let (name, description) = match item.strip_prefix('`') {
+                            | Some(item) => item.split_once('`').unwrap_or((item, "")),
+                            | None => item.split_once(' ').unwrap_or((item, "")),
+                        };
+                        let description =
+                            description.trim_start_matches(&[' ', '-', ':'][..])
+                        ;
+                        ret.params.push((name.trim_end_matches(':'), description.into()));
+                    } else if let Some((_, description)) = ret.params.last_mut() {
+                        append(description, trimmed);
+                    }
+                },
+                | Section::Returns => append(ret.returns.get_or_insert_with(Default::default), trimmed),
+                | Section::Safety => append(ret.safety.get_or_insert_with(Default::default), trimmed),
+            }
+        }
+        while matches!(ret.body.last(), Some(line) if line.trim().is_empty()) {
+            ret.body.pop();
+        }
+        ret
+    }
+}
+
 pub
 struct EnumVariant<'lt> {
     pub
@@ -140,6 +317,24 @@ struct EnumVariant<'lt> {
     discriminant: Option<&'lt dyn ::core::fmt::Debug>,
 }
 
+impl EnumVariant<'_> {
+    /// The name of the constant for this variant when it is one of the
+    /// [`HeaderLanguage::emit_flags()`] ones: `FOO` of `MyFlags` yields
+    /// `MY_FLAGS_FOO`.
+    pub
+    fn flag_constant_name (
+        self: &'_ Self,
+        flags_ty: &'_ dyn PhantomCType,
+    ) -> rust::String
+    {
+        format!(
+            "{}_{}",
+            crate::utils::screaming_case(&flags_ty.short_name(), ""),
+            self.name,
+        )
+    }
+}
+
 pub
 struct StructField<'lt> {
     pub
@@ -152,6 +347,58 @@ struct StructField<'lt> {
     ty: &'lt dyn PhantomCType,
 }
 
+/// How the layout of a `struct` (or `union`) departs from the natural one
+/// given its fields, _i.e._, the effect of a `#[repr(packed(N))]` or of a
+/// `#[repr(align(N))]` on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum Packing {
+    Natural,
+
+    /// The alignment of the fields is capped to `N`.
+    Packed(usize),
+
+    /// The alignment of the type is raised to `N`.
+    Aligned(usize),
+}
+
+impl Packing {
+    /// The Rust `#[repr(…)]` modifier, for diagnostics.
+    pub
+    fn to_repr (self: Self)
+      -> Option<String>
+    {
+        match self {
+            | Packing::Natural => None,
+            | Packing::Packed(n) => Some(format!("packed({n})")),
+            | Packing::Aligned(n) => Some(format!("align({n})")),
+        }
+    }
+
+    pub
+    fn of (
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>],
+    ) -> Packing
+    {
+        let natural_align =
+            fields
+                .iter()
+                .map(|f| f.ty.align())
+                .max()
+                .unwrap_or(1)
+        ;
+        match self_ty.align().cmp(&natural_align) {
+            | ::core::cmp::Ordering::Less => Packing::Packed(self_ty.align()),
+            | ::core::cmp::Ordering::Equal => Packing::Natural,
+            | ::core::cmp::Ordering::Greater => Packing::Aligned(self_ty.align()),
+        }
+    }
+}
+
+/// Construct it through [`FunctionArg::new()`] (and its `with_…` setters):
+/// more fields may be added in the future.
+#[non_exhaustive]
 pub
 struct FunctionArg<'lt> {
     // pub
@@ -162,6 +409,52 @@ struct FunctionArg<'lt> {
 
     pub
     ty: &'lt dyn PhantomCType,
+
+    /// Whether the argument is a `&mut` borrow, and thus cannot alias any
+    /// other argument (see [`Builder::with_restrict_pointers()`]).
+    ///
+    /// [`Builder::with_restrict_pointers()`]: `super::Builder::with_restrict_pointers`
+    pub
+    restrict: bool,
+
+    /// How the argument uses the niche of its C layout (see
+    /// [`Builder::with_nullability_annotations()`]).
+    ///
+    /// [`Builder::with_nullability_annotations()`]: `super::Builder::with_nullability_annotations`
+    pub
+    niche: crate::layout::Niche,
+}
+
+impl<'lt> FunctionArg<'lt> {
+    /// A `name: ty` argument, neither [`restrict`][`Self::restrict`] nor
+    /// using any [`niche`][`Self::niche`].
+    pub
+    fn new (
+        name: &'lt str,
+        ty: &'lt dyn PhantomCType,
+    ) -> FunctionArg<'lt>
+    {
+        Self {
+            name,
+            ty,
+            restrict: false,
+            niche: crate::layout::Niche::Unused,
+        }
+    }
+
+    pub
+    fn with_restrict (self: Self, restrict: bool)
+      -> FunctionArg<'lt>
+    {
+        Self { restrict, ..self }
+    }
+
+    pub
+    fn with_niche (self: Self, niche: crate::layout::Niche)
+      -> FunctionArg<'lt>
+    {
+        Self { niche, ..self }
+    }
 }
 
 /// `T::assoc_func()` -> `PhantomData::<T>.method()` conversion
@@ -207,6 +500,24 @@ trait PhantomCType {
         self: &'_ Self,
     ) -> usize
     ;
+
+    fn layout_on (
+        self: &'_ Self,
+        target: &'_ super::Target,
+    ) -> Option<super::TypeLayout>
+    ;
+
+    fn deprecated (
+        self: &'_ Self,
+    ) -> Option<&'static str>
+    ;
+
+    fn field_offsets (
+        self: &'_ Self,
+    ) -> Option<Vec<usize>>
+    {
+        None
+    }
 }
 
 impl<T : ?Sized>
@@ -260,6 +571,28 @@ where
     {
         ::core::mem::align_of::<T>()
     }
+
+    fn layout_on (
+        self: &'_ Self,
+        target: &'_ super::Target,
+    ) -> Option<super::TypeLayout>
+    {
+        T::layout_on(target)
+    }
+
+    fn deprecated (
+        self: &'_ Self,
+    ) -> Option<&'static str>
+    {
+        T::deprecated()
+    }
+
+    fn field_offsets (
+        self: &'_ Self,
+    ) -> Option<Vec<usize>>
+    {
+        T::field_offsets()
+    }
 }
 
 /// Generates an `out!` macro.
@@ -6,6 +6,66 @@ use super::*;
 pub struct Python;
 
 
+impl Python {
+    /// A `struct` or a `union`, depending on `keyword`.
+    fn emit_aggregate (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        self_ty: &'_ dyn PhantomCType,
+        keyword: &'_ str,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        let ref indent = Indentation::new(4 /* ctx.indent_width() */);
+        mk_out!(indent, ctx.out());
+        let short_name = self_ty.short_name();
+        let full_ty_name = self_ty.name(self);
+
+        if self_ty.size() == 0 {
+            panic!("C does not support zero-sized {keyword}s!")
+        }
+
+        // `cffi` has no per-type `#pragma pack` nor `alignas`: rather than a
+        // `cdef` with a different layout than the Rust one, bail out.
+        if let Some(repr) = Packing::of(self_ty, fields).to_repr() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "`cffi` cannot declare the `#[repr({repr})]` {keyword} \
+                    `{short_name}`: the Python headers do not support it",
+                ),
+            ));
+        }
+
+        out!(("typedef {keyword} {short_name} {{"));
+        if let _ = indent.scope() {
+            let ref mut first = true;
+            for &StructField { docs, name, ty } in fields {
+                // Skip ZSTs
+                if ty.size() == 0 {
+                    if ty.align() > 1 {
+                        panic!("Zero-sized fields must have an alignment of `1`");
+                    } else {
+                        continue;
+                    }
+                }
+                if mem::take(first).not() {
+                    out!("\n");
+                }
+                self.emit_docs(ctx, docs, indent)?;
+                out!(
+                    ("{};"),
+                    ty.name_wrapping_var(self, name)
+                );
+            }
+        }
+        out!(("}} {full_ty_name};"));
+
+        out!("\n");
+        Ok(())
+    }
+}
+
 /// Create a file whose content will be set in `ffi.cdef` of the Python module `cffi`
 impl HeaderLanguage for Python {
     fn emit_docs (
@@ -53,51 +113,57 @@ impl HeaderLanguage for Python {
         Ok(())
     }
 
-    fn emit_struct (
+    fn emit_flags (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
         _docs: Docs<'_>,
         self_ty: &'_ dyn PhantomCType,
-        fields: &'_ [StructField<'_>]
+        backing_integer: &'_ dyn PhantomCType,
+        flags: &'_ [EnumVariant<'_>],
     ) -> io::Result<()>
     {
         let ref indent = Indentation::new(4 /* ctx.indent_width() */);
         mk_out!(indent, ctx.out());
-        let short_name = self_ty.short_name();
-        let full_ty_name = self_ty.name(self);
 
-        if self_ty.size() == 0 {
-            panic!("C does not support zero-sized structs!")
-        }
+        let ref full_ty_name = self_ty.name(self);
+        let ref intn_t = backing_integer.name(self);
+        out!(("typedef {intn_t} {full_ty_name};"));
+        out!("\n");
 
-        out!(("typedef struct {short_name} {{"));
-        if let _ = indent.scope() {
-            let ref mut first = true;
-            for &StructField { docs, name, ty } in fields {
-                // Skip ZSTs
-                if ty.size() == 0 {
-                    if ty.align() > 1 {
-                        panic!("Zero-sized fields must have an alignment of `1`");
-                    } else {
-                        continue;
-                    }
-                }
-                if mem::take(first).not() {
-                    out!("\n");
-                }
-                self.emit_docs(ctx, docs, indent)?;
-                out!(
-                    ("{};"),
-                    ty.name_wrapping_var(self, name)
-                );
-            }
+        for flag in flags {
+            self.emit_constant(
+                ctx,
+                flag.docs,
+                &flag.flag_constant_name(self_ty),
+                backing_integer,
+                flag.discriminant.expect("flags have explicit values"),
+            )?;
         }
-        out!(("}} {full_ty_name};"));
-
-        out!("\n");
         Ok(())
     }
 
+    fn emit_struct (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, self_ty, "struct", fields)
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        self_ty: &'_ dyn PhantomCType,
+        fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        self.emit_aggregate(ctx, self_ty, "union", fields)
+    }
+
     fn emit_opaque_type (
         self: &'_ Self,
         ctx: &'_ mut dyn Definer,
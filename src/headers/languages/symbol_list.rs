@@ -0,0 +1,162 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+/// A list of the exported symbols, for the linker to export these (and only
+/// these): the types and constants are not emitted, and only the symbols of
+/// the functions (C-variadic ones included) and of the `static`s matter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum SymbolList {
+    /// Windows module-definition (`.def`) file: `EXPORTS` entries.
+    MsvcDef,
+
+    /// GNU `ld` version script (`-Wl,--version-script=…`): the exported
+    /// symbols are the `global:` ones, and everything else is made `local:`.
+    LdVersionScript,
+
+    /// macOS `ld64` exported symbols list (`-Wl,-exported_symbols_list,…`):
+    /// one (underscore-prefixed) symbol per line.
+    MacosExportedSymbols,
+}
+
+impl SymbolList {
+    /// The lines preceding the entries.
+    pub
+    fn emit_prelude (
+        self: Self,
+        ctx: &'_ mut dyn Definer,
+        library_name: &'_ str,
+    ) -> io::Result<()>
+    {
+        match self {
+            | SymbolList::MsvcDef => writeln!(ctx.out(), "LIBRARY {library_name}\nEXPORTS"),
+            | SymbolList::LdVersionScript => writeln!(ctx.out(), "{{\n  global:"),
+            | SymbolList::MacosExportedSymbols => Ok(()),
+        }
+    }
+
+    /// The lines following the entries.
+    pub
+    fn emit_epilogue (
+        self: Self,
+        ctx: &'_ mut dyn Definer,
+    ) -> io::Result<()>
+    {
+        match self {
+            | SymbolList::LdVersionScript => writeln!(ctx.out(), "  local:\n    *;\n}};"),
+            | SymbolList::MsvcDef | SymbolList::MacosExportedSymbols => Ok(()),
+        }
+    }
+
+    /// The entry exporting `symbol` (a `static` when `data`).
+    pub
+    fn emit_entry (
+        self: Self,
+        ctx: &'_ mut dyn Definer,
+        symbol: &'_ str,
+        data: bool,
+    ) -> io::Result<()>
+    {
+        match (self, data) {
+            | (SymbolList::MsvcDef, false) => writeln!(ctx.out(), "    {symbol}"),
+            | (SymbolList::MsvcDef, true) => writeln!(ctx.out(), "    {symbol} DATA"),
+            | (SymbolList::LdVersionScript, _) => writeln!(ctx.out(), "    {symbol};"),
+            | (SymbolList::MacosExportedSymbols, _) => writeln!(ctx.out(), "_{symbol}"),
+        }
+    }
+}
+
+impl HeaderLanguage for SymbolList {
+    fn emit_simple_enum (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _self_ty: &'_ dyn PhantomCType,
+        _backing_integer: Option<&dyn PhantomCType>,
+        _variants: &'_ [EnumVariant<'_>],
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_struct (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _self_ty: &'_ dyn PhantomCType,
+        _fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_union (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _self_ty: &'_ dyn PhantomCType,
+        _fields: &'_ [StructField<'_>]
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_opaque_type (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _self_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        fname: &'_ str,
+        _args: &'_ [FunctionArg<'_>],
+        _ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_entry(ctx, fname, false)
+    }
+
+    fn emit_variadic_function (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        fname: &'_ str,
+        _args: &'_ [FunctionArg<'_>],
+        _ret_ty: &'_ dyn PhantomCType,
+    ) -> io::Result<()>
+    {
+        self.emit_entry(ctx, fname, false)
+    }
+
+    fn emit_constant (
+        self: &'_ Self,
+        _ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        _name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _value: &'_ dyn ::core::fmt::Debug,
+    ) -> io::Result<()>
+    {
+        Ok(())
+    }
+
+    fn emit_static (
+        self: &'_ Self,
+        ctx: &'_ mut dyn Definer,
+        _docs: Docs<'_>,
+        name: &'_ str,
+        _ty: &'_ dyn PhantomCType,
+        _mutable: bool,
+    ) -> io::Result<()>
+    {
+        self.emit_entry(ctx, name, true)
+    }
+}
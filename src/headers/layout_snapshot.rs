@@ -0,0 +1,114 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! The test defined by [`layout_tests!`][`crate::layout_tests`]: checking the
+//! layouts of the exported types against their committed snapshot.
+
+use super::*;
+use ::std::{
+    collections::BTreeMap,
+    env,
+    path::Path,
+};
+
+/// Set to (re)write the snapshot rather than checking it.
+const UPDATE: &str = "SAFER_FFI_UPDATE_LAYOUTS";
+
+/// Checks the [`Language::LayoutSnapshot`] of the exported types against the
+/// `snapshot` file, which is written instead if the `SAFER_FFI_UPDATE_LAYOUTS`
+/// environment variable is set.
+///
+/// A missing `snapshot` is otherwise an error (lest a CI run without it
+/// silently pass).
+pub
+fn check_layout_snapshot (snapshot: &'_ Path)
+  -> io::Result<()>
+{
+    let generated = generate()?;
+    if env::var_os(UPDATE).is_some() {
+        return fs::write(snapshot, generated);
+    }
+    let recorded = match fs::read_to_string(snapshot) {
+        | Ok(it) => it,
+        | Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(err.kind(), format!(
+                "the layout snapshot `{}` does not exist \
+                (set `{UPDATE}=1` to write it)",
+                snapshot.display(),
+            )));
+        },
+        | Err(err) => return Err(err),
+    };
+    let (recorded, generated) = (entries(&recorded), entries(&generated));
+    if recorded == generated {
+        return Ok(());
+    }
+    let mut msg = format!(
+        "the layouts of the exported types no longer match `{}`:",
+        snapshot.display(),
+    );
+    for (name, entry) in &recorded {
+        match generated.get(name) {
+            | None => {
+                msg += &format!("\n  - `{name}` is no longer exported");
+            },
+            | Some(new_entry) if new_entry != entry => {
+                msg += &format!("\n  - `{name}` was:");
+                for line in entry {
+                    msg += &format!("\n      {line}");
+                }
+                msg += "\n    and now is:";
+                for line in new_entry {
+                    msg += &format!("\n      {line}");
+                }
+            },
+            | Some(_) => {},
+        }
+    }
+    for &name in generated.keys() {
+        if recorded.contains_key(name).not() {
+            msg += &format!("\n  - `{name}` is not in the snapshot");
+        }
+    }
+    msg += &format!("\n(set `{UPDATE}=1` to update the snapshot, if intended)");
+    Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+/// The [`Language::LayoutSnapshot`] of the exported types.
+pub(in super)
+fn generate ()
+  -> io::Result<String>
+{
+    let mut generated = vec![];
+    builder()
+        .with_language(Language::LayoutSnapshot)
+        .to_writer(&mut generated)
+        .generate()?
+    ;
+    String::from_utf8(generated).map_err(|err| {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    })
+}
+
+/// The `[Name]` sections of a snapshot, keyed by `Name`, along with their
+/// `key = value` lines (comments and line endings aside).
+pub(in super)
+fn entries (snapshot: &'_ str)
+  -> BTreeMap<&'_ str, Vec<&'_ str>>
+{
+    let mut ret = BTreeMap::new();
+    let mut current = None;
+    for line in snapshot.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+            | Some(name) => {
+                ret.entry(name).or_insert_with(Vec::new);
+                current = Some(name);
+            },
+            | None => if let Some(name) = current {
+                ret.get_mut(name).unwrap().push(line);
+            },
+        }
+    }
+    ret
+}
@@ -0,0 +1,52 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Post-processing of the generated output for [`Builder::with_line_endings()`]
+//! and [`Builder::with_utf8_bom()`].
+
+use super::*;
+
+pub(in super)
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Translates the `\n`s written to it into `\r\n`s (leaving the already
+/// present `\r\n`s untouched).
+pub(in super)
+struct CrLfWriter<W> {
+    inner: W,
+    last_was_cr: bool,
+}
+
+impl<W : io::Write> CrLfWriter<W> {
+    pub(in super)
+    fn new (inner: W)
+      -> CrLfWriter<W>
+    {
+        Self { inner, last_was_cr: false }
+    }
+}
+
+impl<W : io::Write> io::Write
+    for CrLfWriter<W>
+{
+    fn write (self: &'_ mut Self, buf: &'_ [u8])
+      -> io::Result<usize>
+    {
+        let written = match buf.iter().position(|&b| b == b'\n') {
+            | Some(0) => {
+                self.inner.write_all(if self.last_was_cr { b"\n" } else { b"\r\n" })?;
+                1
+            },
+            | Some(n) => self.inner.write(&buf[.. n])?,
+            | None => self.inner.write(buf)?,
+        };
+        if written > 0 {
+            self.last_was_cr = buf[written - 1] == b'\r';
+        }
+        Ok(written)
+    }
+
+    fn flush (self: &'_ mut Self)
+      -> io::Result<()>
+    {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,124 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Where the header generation gets the `#[ffi_export]`ed items from.
+//!
+//! By default, every `#[ffi_export]` annotation registers its item through
+//! [`::inventory`], _i.e._, through linker-section tricks, which some
+//! platforms (_e.g._, embedded targets, or `wasm`) do not support.
+//!
+//! The `explicit-registration` Cargo feature rather lists the items to be
+//! generated by hand, with [`register_headers!`], before generating the
+//! headers:
+//!
+//! ```rust,ignore
+//! #[::safer_ffi::cfg_headers]
+//! #[test]
+//! fn generate_headers ()
+//!   -> ::std::io::Result<()>
+//! {
+//!     ::safer_ffi::register_headers!(mylib_concat, mylib_free, Point);
+//!     ::safer_ffi::headers::builder()
+//!         .to_file("mylib.h")?
+//!         .generate()
+//! }
+//! ```
+//!
+//! Each item is to be named by a path to it (_e.g._, `crate::geometry::Point`),
+//! and is registered at most once, however many times it is listed. The items
+//! not registered are missing from the headers (or, in the case of the types
+//! which exported functions depend on, only defined on demand, as usual).
+//!
+//! With this feature, the `#[ffi_export]` and `#[derive_ReprC]` annotations no
+//! longer rely on [`::inventory`] whatsoever, so that the extra registries
+//! which it powers are empty: the C# `SafeHandle`s of the
+//! `#[ffi_export(free_of = …)]` functions, the C# base classes of the
+//! `#[derive_ReprC(dyn)]` traits, and the `#[derive_ReprC(frozen)]`
+//! lockfiles.
+//!
+//! [`register_headers!`]: `crate::register_headers`
+
+use super::*;
+
+cfg_match! {
+    feature = "explicit-registration" => {
+        /// The `#[ffi_export]`ed items to be generated, in reverse
+        /// registration order (as with [`::inventory`]).
+        pub(in crate)
+        fn ffi_exports ()
+          -> rust::Vec<&'static crate::FfiExport>
+        {
+            REGISTERED
+                .lock()
+                .unwrap_or_else(|it| it.into_inner())
+                .iter()
+                .rev()
+                .copied()
+                .collect()
+        }
+    },
+    _ => {
+        /// The `#[ffi_export]`ed items to be generated.
+        pub(in crate)
+        fn ffi_exports ()
+          -> rust::Vec<&'static crate::FfiExport>
+        {
+            crate::inventory::iter::<crate::FfiExport>
+                .into_iter()
+                .collect()
+        }
+    },
+}
+
+#[cfg(feature = "explicit-registration")]
+static REGISTERED: ::std::sync::Mutex<rust::Vec<&'static crate::FfiExport>> =
+    ::std::sync::Mutex::new(rust::Vec::new())
+;
+
+/// `register_headers!`: registers the item(s) of an `#[ffi_export]`
+/// annotation, unless they already are.
+#[cfg(feature = "explicit-registration")]
+pub
+fn register (exports: &'static [crate::FfiExport])
+{
+    let mut registered = REGISTERED.lock().unwrap_or_else(|it| it.into_inner());
+    for export in exports {
+        if registered.iter().any(|&it| ptr::eq(it, export)).not() {
+            registered.push(export);
+        }
+    }
+}
+
+/// Registers the given `#[ffi_export]`ed items for the header generation
+/// (`explicit-registration` Cargo feature; see
+/// [`headers::registry`][`crate::headers::registry`]).
+///
+/// ```rust,ignore
+/// ::safer_ffi::register_headers!(mylib_concat, mylib_free, geometry::Point);
+/// ```
+#[cfg(feature = "explicit-registration")]
+#[macro_export]
+macro_rules! register_headers {(
+    $( $($item:ident)::+ ),* $(,)?
+) => ({
+    $(
+        $crate::__register_headers__!([] $($item)::+);
+    )*
+})}
+
+#[cfg(feature = "explicit-registration")]
+#[doc(hidden)] /** Not part of the public API */
+#[macro_export]
+macro_rules! __register_headers__ {
+    (
+        [$($prefix:tt)*] $head:ident :: $($rest:tt)+
+    ) => (
+        $crate::__register_headers__!([$($prefix)* $head ::] $($rest)+)
+    );
+
+    (
+        [$($prefix:tt)*] $last:ident
+    ) => ($crate::paste::item! {
+        $crate::headers::registry::register(
+            $($prefix)* [<__ffi_export__ $last>]
+        )
+    });
+}
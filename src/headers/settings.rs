@@ -0,0 +1,175 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+use super::*;
+
+/// The knobs of the [`Builder`] which the [language backends][`HeaderLanguage`]
+/// need to be aware of when emitting their definitions.
+///
+/// They are made available to them through [`Definer::settings()`].
+///
+/// [`HeaderLanguage`]: `languages::HeaderLanguage`
+#[derive(Debug, Clone)]
+pub
+struct Settings {
+    /// See [`Builder::with_c_standard()`].
+    pub
+    c_standard: CStandard,
+
+    /// See [`Builder::with_static_asserts()`].
+    pub
+    static_asserts: bool,
+
+    /// See [`Builder::with_stdint_fallback()`].
+    pub
+    stdint_fallback: bool,
+
+    /// See [`Builder::with_api_macro()`].
+    pub
+    api_macro: Option<String>,
+
+    /// See [`Builder::with_layout_targets()`].
+    pub
+    layout_targets: Vec<Target>,
+
+    /// See [`Builder::with_doxygen_groups()`].
+    pub
+    doxygen_groups: bool,
+
+    /// See [`Builder::with_doc_tags()`].
+    pub
+    doc_tags: bool,
+
+    /// See [`Builder::with_restrict_pointers()`].
+    pub
+    restrict_pointers: bool,
+
+    /// See [`Builder::with_nullability_annotations()`].
+    pub
+    nullability_annotations: bool,
+
+    /// See [`Builder::with_assume_nonnull()`].
+    pub
+    assume_nonnull: bool,
+
+    /// See [`Builder::with_version_macro()`].
+    pub
+    version_macro: Option<String>,
+
+    /// See [`Builder::with_cfg_conditions()`].
+    pub
+    cfg_conditions: Vec<(String, String)>,
+
+    /// See [`Builder::with_csharp_wrapper_class()`].
+    pub
+    csharp_wrapper_class: Option<String>,
+
+    /// See [`Builder::with_csharp_library_import()`].
+    pub
+    csharp_library_import: bool,
+
+    /// See [`Builder::with_csharp_function_pointers()`].
+    pub
+    csharp_function_pointers: bool,
+
+    /// See [`Builder::with_csharp_dyn_trait_classes()`].
+    pub
+    csharp_dyn_trait_classes: bool,
+
+    /// See [`Builder::with_csharp_class_name()`].
+    pub
+    csharp_class_name: Option<String>,
+
+    /// See [`Builder::with_library_name()`].
+    pub
+    library_name: Option<String>,
+
+    /// Whether the C functions are emitted as the `ret (*fname) (args);`
+    /// fields of a function pointer table, rather than declared (see
+    /// [`Builder::with_dlopen_api()`]).
+    pub
+    function_pointer_fields: bool,
+
+    /// Whether the C declarations of the `#[cfg]`-gated functions are guarded
+    /// by the equivalent preprocessor condition (see
+    /// [`Builder::with_cfg_conditions()`]), rather than only featured when
+    /// the `#[cfg]` holds for the current target.
+    pub
+    cfg_guards: bool,
+}
+
+impl Settings {
+    pub(in crate)
+    const DEFAULT: Self = Self {
+        c_standard: CStandard::C99,
+        static_asserts: false,
+        stdint_fallback: false,
+        api_macro: None,
+        layout_targets: Vec::new(),
+        doxygen_groups: false,
+        doc_tags: false,
+        restrict_pointers: false,
+        nullability_annotations: false,
+        assume_nonnull: false,
+        version_macro: None,
+        cfg_conditions: Vec::new(),
+        csharp_wrapper_class: None,
+        csharp_library_import: false,
+        csharp_function_pointers: false,
+        csharp_dyn_trait_classes: false,
+        csharp_class_name: None,
+        library_name: None,
+        function_pointer_fields: false,
+        cfg_guards: true,
+    };
+
+    /// The C# class declaring the non-`group`ed functions.
+    pub
+    fn csharp_class_name (self: &'_ Self)
+      -> &'_ str
+    {
+        self.csharp_class_name.as_deref().unwrap_or("Ffi")
+    }
+}
+
+impl Default for Settings {
+    fn default ()
+      -> Settings
+    {
+        Self::DEFAULT
+    }
+}
+
+pub(in super)
+static DEFAULT_SETTINGS: Settings = Settings::DEFAULT;
+
+/// Wraps a (user-provided) [`Definer`] so as to override its
+/// [`.settings()`][`Definer::settings`] with those of the [`Builder`].
+pub(in super)
+struct WithSettings<'r> {
+    pub(in super)
+    definer: &'r mut dyn Definer,
+
+    pub(in super)
+    settings: Settings,
+}
+
+impl Definer
+    for WithSettings<'_>
+{
+    fn insert (self: &'_ mut Self, name: &'_ str)
+      -> bool
+    {
+        self.definer.insert(name)
+    }
+
+    fn out (self: &'_ mut Self)
+      -> &'_ mut dyn io::Write
+    {
+        self.definer.out()
+    }
+
+    fn settings (self: &'_ Self)
+      -> &'_ Settings
+    {
+        &self.settings
+    }
+}
@@ -0,0 +1,331 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Cross-compilation-aware layout verification.
+//!
+//! A single generated header is oftentimes shipped alongside libraries
+//! compiled for several targets. This is only sound if the C types it defines
+//! have the same layout on all of these targets, which is what
+//! [`Builder::with_layout_targets()`] checks, at header generation time.
+
+use super::*;
+
+/// The data model of a compilation target, in so far as the layout of the
+/// `#[repr(C)]` types is concerned.
+///
+/// All the sizes and alignments are expressed in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub
+struct Target {
+    pub
+    triple: String,
+
+    /// Size of pointers (and thus of `size_t`).
+    pub
+    pointer_width: usize,
+
+    /// Size of `int`.
+    pub
+    c_int_width: usize,
+
+    /// Alignment of `int64_t` / `uint64_t` (_e.g._, `4` on 32-bit x86 Linux).
+    pub
+    int64_align: usize,
+
+    /// Alignment of `double` (_e.g._, `4` on 32-bit x86 Linux).
+    pub
+    f64_align: usize,
+
+    /// Cap on the alignment of the scalar types, pointers and `int` included
+    /// (_e.g._, `1` on AVR, where everything is byte-aligned).
+    pub
+    max_align: usize,
+}
+
+impl Target {
+    /// Computes the data model of a target from its (Rust) triple.
+    ///
+    /// Returns `None` for architectures this crate does not know about.
+    pub
+    fn from_triple (triple: &'_ str)
+      -> Option<Target>
+    {
+        let arch = triple.split('-').next().unwrap_or(triple);
+        let is_windows = triple.contains("-windows");
+        // ILP32 ABIs of 64-bit architectures.
+        let is_ilp32 =
+            triple.ends_with("-gnux32")
+            || triple.ends_with("_ilp32")
+            || arch == "arm64_32"
+        ;
+        let mut max_align = 16;
+        let (pointer_width, c_int_width, int64_align) = match arch {
+            | "x86_64"
+            | "aarch64" | "aarch64_be"
+            | "loongarch64"
+            | "mips64" | "mips64el" | "mipsisa64r6" | "mipsisa64r6el"
+            | "powerpc64" | "powerpc64le"
+            | "riscv64gc" | "riscv64imac"
+            | "s390x"
+            | "sparc64" | "sparcv9"
+            | "wasm64"
+            => (if is_ilp32 { 4 } else { 8 }, 4, 8),
+
+            // (Before the 32-bit `arm*` ones.)
+            | _case if arch.starts_with("arm64")
+            => (if is_ilp32 { 4 } else { 8 }, 4, 8),
+
+            | "i386" | "i586" | "i686"
+            => (4, 4, if is_windows { 8 } else { 4 }),
+
+            | _case if arch.starts_with("arm")
+                    || arch.starts_with("thumb")
+                    || arch.starts_with("riscv32")
+            => (4, 4, 8),
+
+            | "hexagon"
+            | "mips" | "mipsel" | "mipsisa32r6" | "mipsisa32r6el"
+            | "powerpc"
+            | "sparc"
+            | "wasm32"
+            => (4, 4, 8),
+
+            | "msp430" => (2, 2, 2),
+
+            | "avr" => {
+                max_align = 1;
+                (2, 2, 1)
+            },
+
+            | _ => return None,
+        };
+        Some(Target {
+            triple: triple.into(),
+            pointer_width,
+            c_int_width,
+            int64_align,
+            f64_align: int64_align,
+            max_align,
+        })
+    }
+
+    /// Layout of a pointer (or of a `size_t`).
+    pub
+    fn pointer_layout (self: &'_ Self)
+      -> TypeLayout
+    {
+        TypeLayout {
+            size: self.pointer_width,
+            align: self.pointer_width.min(self.max_align),
+        }
+    }
+
+    /// Layout of an `int`.
+    pub
+    fn c_int_layout (self: &'_ Self)
+      -> TypeLayout
+    {
+        TypeLayout {
+            size: self.c_int_width,
+            align: self.c_int_width.min(self.max_align),
+        }
+    }
+
+    /// Layout of a fixed-size integer of `size` bytes.
+    pub
+    fn integer_layout (self: &'_ Self, size: usize)
+      -> TypeLayout
+    {
+        TypeLayout {
+            size,
+            align: if size == 8 { self.int64_align } else { size.min(self.max_align) },
+        }
+    }
+
+    /// Layout of a floating-point number of `size` bytes.
+    pub
+    fn float_layout (self: &'_ Self, size: usize)
+      -> TypeLayout
+    {
+        TypeLayout {
+            size,
+            align: if size == 8 { self.f64_align } else { size.min(self.max_align) },
+        }
+    }
+}
+
+/// Size and alignment of a type on a given [`Target`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+struct TypeLayout {
+    pub
+    size: usize,
+
+    pub
+    align: usize,
+}
+
+impl TypeLayout {
+    /// Layout of a `#[repr(C)]` struct with fields of the given layouts.
+    ///
+    /// Returns `None` if any of the fields' layouts is unknown.
+    pub
+    fn of_struct (
+        fields: impl IntoIterator<Item = Option<TypeLayout>>,
+    ) -> Option<TypeLayout>
+    {
+        let mut size = 0;
+        let mut align = 1;
+        for field in fields {
+            let field = field?;
+            size = round_up(size, field.align) + field.size;
+            align = align.max(field.align);
+        }
+        Some(TypeLayout {
+            size: round_up(size, align),
+            align,
+        })
+    }
+
+    /// Layout of a `#[repr(C, packed(pack))]` struct with fields of the given
+    /// layouts.
+    ///
+    /// Returns `None` if any of the fields' layouts is unknown.
+    pub
+    fn of_packed_struct (
+        fields: impl IntoIterator<Item = Option<TypeLayout>>,
+        pack: usize,
+    ) -> Option<TypeLayout>
+    {
+        Self::of_struct(fields.into_iter().map(|field| field.map(|field| {
+            TypeLayout {
+                align: field.align.min(pack),
+                ..field
+            }
+        })))
+    }
+
+    /// This layout, with its alignment raised to (at least) `align`, as with
+    /// `#[repr(align(N))]`.
+    pub
+    fn aligned_to (self: TypeLayout, align: usize)
+      -> TypeLayout
+    {
+        let align = self.align.max(align);
+        TypeLayout {
+            size: round_up(self.size, align),
+            align,
+        }
+    }
+
+    /// Layout of a `#[repr(C)]` union with fields of the given layouts.
+    ///
+    /// Returns `None` if any of the fields' layouts is unknown.
+    pub
+    fn of_union (
+        fields: impl IntoIterator<Item = Option<TypeLayout>>,
+    ) -> Option<TypeLayout>
+    {
+        let mut size = 0;
+        let mut align = 1;
+        for field in fields {
+            let field = field?;
+            size = size.max(field.size);
+            align = align.max(field.align);
+        }
+        Some(TypeLayout {
+            size: round_up(size, align),
+            align,
+        })
+    }
+
+    /// Layout of an inline array of `len` elements of the given layout.
+    pub
+    fn of_array (item: TypeLayout, len: usize)
+      -> TypeLayout
+    {
+        TypeLayout {
+            size: item.size * len,
+            align: item.align,
+        }
+    }
+}
+
+fn round_up (offset: usize, align: usize)
+  -> usize
+{
+    (offset + align - 1) / align * align
+}
+
+/// Errors if the layout of `ty` differs across the targets set up with
+/// [`Builder::with_layout_targets()`].
+pub(in crate)
+fn check_layout (
+    definer: &'_ dyn Definer,
+    ty: &'_ dyn languages::PhantomCType,
+) -> io::Result<()>
+{
+    let targets = &definer.settings().layout_targets;
+    let mut layouts = Vec::with_capacity(targets.len());
+    for target in targets {
+        match ty.layout_on(target) {
+            | Some(layout) => layouts.push((target, layout)),
+            // Unknown layout (_e.g._, a manual `CType` impl): skip it.
+            | None => return Ok(()),
+        }
+    }
+    if let Some(&(_, first)) = layouts.first() {
+        if layouts.iter().any(|&(_, layout)| layout != first) {
+            let mut msg = format!(
+                "the layout of `{}` differs across the targets:",
+                ty.short_name(),
+            );
+            for (target, TypeLayout { size, align }) in layouts {
+                msg += &format!(
+                    "\n  - `{}`: size = {size}, align = {align}",
+                    target.triple,
+                );
+            }
+            return Err(io::Error::new(io::ErrorKind::Other, msg));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layouts (triple: &'_ str)
+      -> (TypeLayout, TypeLayout, TypeLayout)
+    {
+        let target = Target::from_triple(triple).unwrap();
+        (target.pointer_layout(), target.c_int_layout(), target.integer_layout(8))
+    }
+
+    #[test]
+    fn from_triple ()
+    {
+        let layout = |size, align| TypeLayout { size, align };
+        for triple in [
+            "x86_64-unknown-linux-gnu",
+            "aarch64-apple-darwin",
+            "arm64e-apple-ios",
+            "arm64ec-pc-windows-msvc",
+        ] {
+            assert_eq!(layouts(triple), (layout(8, 8), layout(4, 4), layout(8, 8)), "{triple}");
+        }
+        for triple in [
+            "x86_64-unknown-linux-gnux32",
+            "aarch64-unknown-linux-gnu_ilp32",
+            "arm64_32-apple-watchos",
+            "armv7-unknown-linux-gnueabihf",
+        ] {
+            assert_eq!(layouts(triple), (layout(4, 4), layout(4, 4), layout(8, 8)), "{triple}");
+        }
+        assert_eq!(layouts("i686-unknown-linux-gnu"), (layout(4, 4), layout(4, 4), layout(8, 4)));
+        assert_eq!(layouts("avr-unknown-gnu-atmega328"), (layout(2, 1), layout(2, 1), layout(8, 1)));
+        let avr = Target::from_triple("avr-unknown-gnu-atmega328").unwrap();
+        assert_eq!(avr.integer_layout(4), layout(4, 1));
+        assert_eq!(avr.float_layout(4), layout(4, 1));
+        assert!(Target::from_triple("unknown-unknown-none").is_none());
+    }
+}
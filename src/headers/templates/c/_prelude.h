@@ -1,5 +1,3 @@
-#ifndef {guard}
-#define {guard}
-#ifdef __cplusplus
+{guard_open}#ifdef __cplusplus
 extern "C" {{
 #endif
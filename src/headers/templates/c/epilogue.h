@@ -2,5 +2,4 @@
 #ifdef __cplusplus
 }} /* extern \"C\" */
 #endif
-
-#endif /* {guard} */
+{guard_close}
\ No newline at end of file
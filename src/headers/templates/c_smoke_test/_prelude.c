@@ -0,0 +1,2 @@
+/* Compile (as C11) and link against the library, to check the header. */
+#include "{header}"
@@ -10,6 +10,6 @@ namespace {NameSpace} {{
 using System;
 using System.Runtime.InteropServices;
 
-public unsafe partial class Ffi {{
-    private const string RustLib = "{RustLib}";
+public unsafe partial class {ClassName} {{
+    private const string RustLib = "{RustLib}";{resolver}
 }}
@@ -0,0 +1,13 @@
+
+
+    /// <summary>
+    /// Registers (once per assembly) how to load the library, <em>e.g.</em>,
+    /// from a custom path: <c>load</c> is given the <c>[DllImport]</c> name.
+    /// </summary>
+    public static void SetDllImportResolver (Func<string, IntPtr> load)
+    {{
+        NativeLibrary.SetDllImportResolver(
+            typeof({ClassName}).Assembly,
+            (name, assembly, searchPath) => name == RustLib ? load(name) : IntPtr.Zero
+        );
+    }}
@@ -0,0 +1,6 @@
+from libc.stdint cimport *
+
+cdef extern from "<stdbool.h>":
+    ctypedef bint bool
+
+cdef extern from "{header}":
@@ -0,0 +1,17 @@
+# Compiles the `{module}` extension module (cffi's API mode):
+#
+#     python ffi_build.py
+#
+# (the `{lib}` library, and its `{header}` C header, are to be found by the
+# C compiler and linker, _e.g._, through `CFLAGS` and `LDFLAGS`).
+import cffi
+
+ffibuilder = cffi.FFI()
+
+ffibuilder.set_source(
+    "{module}",
+    '#include "{header}"',
+    libraries=["{lib}"],
+)
+
+ffibuilder.cdef("""
@@ -0,0 +1,171 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! 128-bit integers, for FFI.
+//!
+//! `u128` and `i128` are not `ReprC`: C has no standard 128-bit integer type,
+//! and their alignment is not even consistent across Rust versions and
+//! platforms. Instead, opt into the [`U128`] and [`I128`] "two-limb" structs,
+//! which have a portable layout (and matching C and C# definitions):
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export]
+//! fn mylib_next_id (id: repr_c::U128)
+//!   -> repr_c::U128
+//! {
+//!     (u128::from(id) + 1).into()
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct U128 {
+//!     uint64_t lo;
+//!     uint64_t hi;
+//! } U128_t;
+//!
+//! U128_t mylib_next_id (
+//!     U128_t id);
+//! ```
+
+use_prelude!();
+use ::core::cmp::Ordering;
+
+/// An `u128` with a portable `#[repr(C)]` layout: its low 64 bits, then its
+/// high 64 bits (regardless of the endianness).
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub
+struct U128 {
+    /// The low 64 bits.
+    pub
+    lo: u64,
+
+    /// The high 64 bits.
+    pub
+    hi: u64,
+}
+
+/// An `i128` with a portable `#[repr(C)]` layout: its low 64 bits, then its
+/// high 64 bits (regardless of the endianness), the latter carrying the sign
+/// (two's complement).
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub
+struct I128 {
+    /// The low 64 bits.
+    pub
+    lo: u64,
+
+    /// The high 64 bits (and sign).
+    pub
+    hi: i64,
+}
+
+impl From<u128>
+    for U128
+{
+    #[inline]
+    fn from (it: u128)
+      -> U128
+    {
+        Self {
+            lo: it as u64,
+            hi: (it >> 64) as u64,
+        }
+    }
+}
+
+impl From<U128>
+    for u128
+{
+    #[inline]
+    fn from (U128 { lo, hi }: U128)
+      -> u128
+    {
+        ((hi as u128) << 64) | (lo as u128)
+    }
+}
+
+impl From<i128>
+    for I128
+{
+    #[inline]
+    fn from (it: i128)
+      -> I128
+    {
+        Self {
+            lo: it as u64,
+            hi: (it >> 64) as i64,
+        }
+    }
+}
+
+impl From<I128>
+    for i128
+{
+    #[inline]
+    fn from (I128 { lo, hi }: I128)
+      -> i128
+    {
+        ((hi as i128) << 64) | (lo as i128)
+    }
+}
+
+macro_rules! impl_fmt_and_ord {(
+    $($T:ident => $int:ident),* $(,)?
+) => (
+    $(
+        impl fmt::Debug
+            for $T
+        {
+            fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+              -> fmt::Result
+            {
+                fmt::Debug::fmt(&$int::from(*self), fmt)
+            }
+        }
+
+        impl fmt::Display
+            for $T
+        {
+            fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+              -> fmt::Result
+            {
+                fmt::Display::fmt(&$int::from(*self), fmt)
+            }
+        }
+
+        /// Compares the 128-bit values (rather than the limbs, field by
+        /// field).
+        impl Ord
+            for $T
+        {
+            #[inline]
+            fn cmp (self: &'_ Self, other: &'_ Self)
+              -> Ordering
+            {
+                $int::from(*self).cmp(&$int::from(*other))
+            }
+        }
+
+        impl PartialOrd
+            for $T
+        {
+            #[inline]
+            fn partial_cmp (self: &'_ Self, other: &'_ Self)
+              -> Option<Ordering>
+            {
+                Some(self.cmp(other))
+            }
+        }
+    )*
+)}
+
+impl_fmt_and_ord! {
+    U128 => u128,
+    I128 => i128,
+}
@@ -64,7 +64,10 @@ trait CType
         {
             definer.define_once(
                 &Self::name(language),
-                &mut |definer| Self::define_self__impl(language, definer),
+                &mut |definer| {
+                    crate::headers::check_layout(definer, &PhantomData::<Self>)?;
+                    Self::define_self__impl(language, definer)
+                },
             )
         }
 
@@ -91,6 +94,40 @@ trait CType
         {
             None
         }
+
+        /// The layout of the type when compiled for the given `target`,
+        /// used by [`Builder::with_layout_targets()`].
+        ///
+        /// `None` means the layout is unknown, in which case the type is not
+        /// checked.
+        ///
+        /// [`Builder::with_layout_targets()`]: `crate::headers::Builder::with_layout_targets`
+        fn layout_on (
+            _target: &'_ crate::headers::Target,
+        ) -> Option<crate::headers::TypeLayout>
+        {
+            None
+        }
+
+        /// The offsets of the fields of the type, as laid out by the compiler
+        /// (rather than computed), for the [`Language::LayoutSnapshot`].
+        ///
+        /// `None` for the types without fields, or not exposing them.
+        ///
+        /// [`Language::LayoutSnapshot`]: `crate::headers::Language::LayoutSnapshot`
+        fn field_offsets ()
+          -> Option<Vec<usize>>
+        {
+            None
+        }
+
+        /// The `note` of the `#[deprecated]` attribute of the type (`""` if
+        /// none), for the headers to flag its uses.
+        fn deprecated ()
+          -> Option<&'static str>
+        {
+            None
+        }
     }
 }
 
@@ -131,6 +168,16 @@ impl<T : LegacyCType> CType for T {
                 | _case if language.is::<Python>() => {
                     <Self as LegacyCType>::c_define_self(definer)
                 },
+                | _case if language.is::<Cython>() => {
+                    <Self as LegacyCType>::cython_define_self(definer)
+                },
+                | _case if language.is::<SymbolList>() => Ok(()),
+                // Nothing worth instantiating either.
+                | _case if language.is::<CSmokeTest>() => Ok(()),
+                // Nor worth snapshotting (their layout is that of Rust).
+                | _case if language.is::<LayoutSnapshot>() => Ok(()),
+                // Primitive or built-in type: nothing worth documenting.
+                | _case if language.is::<Markdown>() => Ok(()),
                 | _ => unimplemented!(),
             }
         }
@@ -150,7 +197,11 @@ impl<T : LegacyCType> CType for T {
         ) -> String
         {
             match () {
-                | _case if language.is::<C>() => {
+                | _case
+                    if language.is::<C>()
+                    || language.is::<Cython>()
+                    || language.is::<Markdown>()
+                => {
                     <Self as LegacyCType>::c_var(var_name).to_string()
                 },
                 | _case if language.is::<CSharp>() => {
@@ -171,6 +222,14 @@ impl<T : LegacyCType> CType for T {
         {
             <T as LegacyCType>::legacy_csharp_marshaler()
         }
+
+        #[inline]
+        fn layout_on (
+            target: &'_ crate::headers::Target,
+        ) -> Option<crate::headers::TypeLayout>
+        {
+            <T as LegacyCType>::c_layout_on(target)
+        }
     }
 }
 
@@ -293,6 +352,18 @@ unsafe trait LegacyCType
         //     }
         // }
 
+        /// Cython counterpart of [`LegacyCType::c_define_self`]`()`: since
+        /// Cython spells C types much like C does, this only needs to define
+        /// the types this one refers to (_e.g._, the pointee of a pointer),
+        /// and those requiring a typedef (_e.g._, arrays).
+        ///
+        /// It defaults to defining nothing (_e.g._, primitive types).
+        fn cython_define_self (_definer: &'_ mut dyn Definer)
+          -> io::Result<()>
+        {
+            Ok(())
+        }
+
         /// Convenience function for _callers_ / users of types implementing
         /// [`CType`][`trait@CType`].
         ///
@@ -514,6 +585,17 @@ unsafe trait LegacyCType
         ) -> fmt::Result
         ;
 
+        /// The layout of the type when compiled for the given `target`.
+        ///
+        /// This provides the implementation used by [`CType::layout_on`]`()`,
+        /// and defaults to `None` (unknown layout).
+        fn c_layout_on (
+            _target: &'_ crate::headers::Target,
+        ) -> Option<crate::headers::TypeLayout>
+        {
+            None
+        }
+
         /// Convenience function for _callers_ / users of types implementing
         /// [`CType`][`trait@CType`].
         ///
@@ -818,6 +900,18 @@ trait ReprC : Sized {
     fn is_valid (it: &'_ Self::CLayout)
       -> bool
     ;
+
+    __cfg_headers__! {
+        /// How the niche of the C layout (a null pointer, a zero integer) is
+        /// used, for the headers to reflect it.
+        ///
+        /// It defaults to [`Niche::Unused`].
+        fn niche ()
+          -> Niche
+        {
+            Niche::Unused
+        }
+    }
 }
 
 #[doc(hidden)] /** For clarity;
@@ -899,12 +993,132 @@ fn into_raw<T : ReprC> (it: T)
     }
 }
 
+/// Runtime type information of a "base" type, as required by the checked
+/// downcasts generated by `#[ffi_export(extends = Base)]`.
+///
+/// ```rust,ignore
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// pub
+/// struct Base {
+///     concrete: ::core::any::TypeId,
+///     /* … */
+/// }
+///
+/// unsafe
+/// impl Tagged for Base {
+///     fn concrete_type_id (self: &'_ Base)
+///       -> ::core::any::TypeId
+///     {
+///         self.concrete
+///     }
+/// }
+///
+/// #[ffi_export(extends = Base)] // exports `Derived_as_Base()` and `Base_try_as_Derived()`
+/// #[derive_ReprC]
+/// #[repr(opaque)]
+/// pub
+/// struct Derived {
+///     base: Base, // `Base { concrete: TypeId::of::<Derived>(), … }`
+///     /* … */
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `concrete_type_id()` must only return the `TypeId` of a type `Derived`
+/// when `self` is the (only) `Base` field of such a `Derived` instance.
+pub
+unsafe
+trait Tagged {
+    /// The `TypeId` of the "most derived" type this instance is a field of,
+    /// if any (else, `TypeId::of::<Self>()`).
+    fn concrete_type_id (self: &'_ Self)
+      -> ::core::any::TypeId
+    ;
+}
+
+#[apply(hidden_export)]
+/// Checked downcast, used by `#[ffi_export(extends = Base)]`.
+///
+/// # Safety
+///
+///   - `base` must point to a live `Base`, with a provenance covering the
+///     whole `Derived` it may be the field of (hence a raw pointer: a `&Base`
+///     would only grant access to the `Base` bytes);
+///
+///   - `base_offset` must be the offset of the `Base` field within `Derived`.
+unsafe
+fn __downcast_ref__<Base, Derived> (
+    base: *const Base,
+    base_offset: usize,
+) -> Option<*const Derived>
+where
+    Base : Tagged,
+    Derived : 'static,
+{
+    if (*base).concrete_type_id() != ::core::any::TypeId::of::<Derived>() {
+        return None;
+    }
+    Some(
+        base.cast::<u8>()
+            .sub(base_offset)
+            .cast::<Derived>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::core::{any::TypeId, ptr};
+
+    struct Base {
+        concrete: TypeId,
+    }
+
+    unsafe
+    impl Tagged for Base {
+        fn concrete_type_id (self: &'_ Base)
+          -> TypeId
+        {
+            self.concrete
+        }
+    }
+
+    struct Derived {
+        x: u32,
+        base: Base,
+    }
+
+    /// (`cargo +nightly miri test --lib downcast` checks it against the
+    /// aliasing model.)
+    #[test]
+    fn downcast ()
+    {
+        let derived = Derived {
+            x: 42,
+            base: Base { concrete: TypeId::of::<Derived>() },
+        };
+        let at_derived = ptr::addr_of!(derived);
+        unsafe {
+            let base = ptr::addr_of!((*at_derived).base);
+            let base_offset = base as usize - at_derived as usize;
+            let downcast = __downcast_ref__::<Base, Derived>(base, base_offset).unwrap();
+            assert_eq!(downcast, at_derived);
+            assert_eq!((*downcast).x, 42);
+            assert!(__downcast_ref__::<Base, u32>(base, base_offset).is_none());
+        }
+    }
+}
+
 pub use impls::Opaque;
 pub(in crate)
 mod impls;
 
 mod niche;
 
+pub use niche::Niche;
+
 #[apply(hidden_export)]
 use niche::HasNiche as __HasNiche__;
 
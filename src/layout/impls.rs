@@ -8,6 +8,13 @@ const_assert! {
     ::core::mem::size_of::<crate::libc::size_t>()
 }
 
+/// Post-monomorphization check that an array is not empty.
+struct NonEmpty<const N: usize>;
+
+impl<const N: usize> NonEmpty<N> {
+    const CHECK: () = assert!(N != 0, "zero-length arrays are not `ReprC`");
+}
+
 const _: () = { macro_rules! impl_CTypes {
     () => (
         impl_CTypes! { @pointers }
@@ -79,37 +86,27 @@ const _: () = { macro_rules! impl_CTypes {
         }
         #[cfg(docs)] impl_CTypes! { @fns (A1, A2) } #[cfg(not(docs))]
         impl_CTypes! { @fns
-            (A9, A8, A7, A6, A5, A4, A3, A2, A1)
-        }
-        #[cfg(docs)] impl_CTypes! { @arrays 1 2 } #[cfg(not(docs))]
-        impl_CTypes! { @arrays
-            // 0
-            1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25
-            26 27 28 29 30 31 32 40 48 50 60 64 70 75 80 90 96 100 125 128 192
-            200 250 256 300 400 500 512 600 700 750 800 900 1000 1024
+            (A12, A11, A10, A9, A8, A7, A6, A5, A4, A3, A2, A1)
         }
+        impl_CTypes! { @arrays }
     );
 
     (
         @arrays
-        $($N:tt)*
-    ) => ($(
+    ) => (
         // LegacyCType
-        /// Simplified for lighter documentation, but the actual impls
-        /// range **from `1` up to `32`, plus a bunch of significant
-        /// lengths up to `1024`**.
+        /// For any non-zero length `N` (`[Item; 0]` fails to compile once
+        /// used, C having no zero-length arrays).
         unsafe // Safety: Rust arrays _are_ `#[repr(C)]`
-        impl<Item : CType> LegacyCType
-            for [Item; $N]
+        impl<Item : CType, const N: usize> LegacyCType
+            for [Item; N]
         { __cfg_headers__! {
             fn c_short_name_fmt (fmt: &'_ mut fmt::Formatter<'_>)
               -> fmt::Result
             {
                 // item_N_array
-                write!(fmt,
-                    concat!("{}_", stringify!($N), "_array"),
-                    Item::short_name(),
-                )
+                let () = NonEmpty::<N>::CHECK;
+                write!(fmt, "{}_{N}_array", Item::short_name())
             }
 
             fn c_define_self (definer: &'_ mut dyn Definer)
@@ -126,8 +123,8 @@ const _: () = { macro_rules! impl_CTypes {
                                 "    {inline_array};\n",
                                 "}} {me};\n",
                             ),
-                            inline_array = Item::name_wrapping_var(&crate::headers::languages::C, concat!(
-                                "idx[", stringify!($N), "]",
+                            inline_array = Item::name_wrapping_var(&crate::headers::languages::C, &format!(
+                                "idx[{N}]",
                             )),
                             me = me,
                         )
@@ -135,6 +132,36 @@ const _: () = { macro_rules! impl_CTypes {
                 )
             }
 
+            fn cython_define_self (definer: &'_ mut dyn Definer)
+              -> io::Result<()>
+            {
+                let ref me = Self::c_var("").to_string();
+                definer.define_once(
+                    me,
+                    &mut |definer| {
+                        Item::define_self(&crate::headers::languages::Cython, definer)?;
+                        writeln!(definer.out(),
+                            concat!(
+                                "    ctypedef struct {me}:\n",
+                                "        {inline_array}\n",
+                            ),
+                            inline_array = Item::name_wrapping_var(&crate::headers::languages::Cython, &format!(
+                                "idx[{N}]",
+                            )),
+                            me = me,
+                        )
+                    }
+                )
+            }
+
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Item::layout_on(target).map(|item| {
+                    crate::headers::TypeLayout::of_array(item, N)
+                })
+            }
+
             fn c_var_fmt (
                 fmt: &'_ mut fmt::Formatter<'_>,
                 var_name: &'_ str,
@@ -168,13 +195,13 @@ const _: () = { macro_rules! impl_CTypes {
                                 format!(
                                     "    public fixed {ItemTy} arr[{N}];\n",
                                     ItemTy = Item::name(&crate::headers::languages::CSharp),
-                                    N = $N,
+                                    N = N,
                                     // no need for a marshaler here
                                 )
                             } else {
                                 // Sadly for the general case fixed arrays are
                                 // not supported.
-                                (0 .. $N)
+                                (0 .. N)
                                     .map(|i| format!(
                                         "    \
                                         {marshaler}\
@@ -208,23 +235,23 @@ const _: () = { macro_rules! impl_CTypes {
         } type OPAQUE_KIND = OpaqueKind::Concrete; }
 
         // ReprC
-        /// Simplified for lighter documentation, but the actual impls
-        /// range **from `1` up to `32`, plus a bunch of significant
-        /// lengths up to `1024`**.
+        /// For any non-zero length `N` (`[Item; 0]` fails to compile once
+        /// used, C having no zero-length arrays).
         unsafe
-        impl<Item : ReprC> ReprC
-            for [Item; $N]
+        impl<Item : ReprC, const N: usize> ReprC
+            for [Item; N]
         {
-            type CLayout = [Item::CLayout; $N];
+            type CLayout = [Item::CLayout; N];
 
             #[inline]
             fn is_valid (it: &'_ Self::CLayout)
               -> bool
             {
+                let () = NonEmpty::<N>::CHECK;
                 it.iter().all(Item::is_valid)
             }
         }
-    )*);
+    );
 
     (@fns
         (
@@ -244,7 +271,7 @@ const _: () = { macro_rules! impl_CTypes {
 
         // LegacyCType
         /// Simplified for lighter documentation, but the actual impls include
-        /// **up to 9 function parameters**.
+        /// **up to 12 function parameters**.
         unsafe // Safety: this is the "blessed" type recommended across Rust
                // literature. Still the alignment of function pointers is not
                // as well-defined, as one would wish.
@@ -274,6 +301,15 @@ const _: () = { macro_rules! impl_CTypes {
                 Ok(())
             }
 
+            fn cython_define_self (definer: &'_ mut dyn Definer)
+              -> io::Result<()>
+            {
+                Ret::define_self(&crate::headers::languages::Cython, definer)?; $(
+                $An::define_self(&crate::headers::languages::Cython, definer)?; $(
+                $Ai::define_self(&crate::headers::languages::Cython, definer)?; )*)?
+                Ok(())
+            }
+
             fn c_var_fmt (
                 fmt: &'_ mut fmt::Formatter<'_>,
                 var_name: &'_ str,
@@ -291,6 +327,12 @@ const _: () = { macro_rules! impl_CTypes {
                 fmt.write_str(")")
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(target.pointer_layout())
+            }
+
             __cfg_csharp__! {
                 fn csharp_define_self (definer: &'_ mut dyn Definer)
                   -> io::Result<()>
@@ -303,6 +345,32 @@ const _: () = { macro_rules! impl_CTypes {
                         let mut iter = (0 ..).map(|c| format!("_{}", c));
                         move || iter.next().unwrap()
                     };
+                    if definer.settings().csharp_function_pointers {
+                        // No marshaling happens through function pointers,
+                        // so `bool`s are to be passed as the bytes they are.
+                        fn blittable<T : CType> ()
+                          -> rust::String
+                        {
+                            match T::csharp_marshaler().as_deref() {
+                                | Some("UnmanagedType.U1") => "byte".into(),
+                                | _ => T::name(&crate::headers::languages::CSharp),
+                            }
+                        }
+                        return definer.define_once(me, &mut |definer| writeln!(definer.out(),
+                            concat!(
+                                "public unsafe struct {me} {{\n",
+                                "    public delegate* unmanaged[Cdecl]<", $(
+                                "{", stringify!($An), "}, ", $(
+                                "{", stringify!($Ai), "}, ", )*)?
+                                "{Ret}> Ptr;\n",
+                                "}}\n",
+                            ),
+                            me = me,
+                            Ret = blittable::<Ret>(), $(
+                            $An = blittable::<$An>(), $(
+                            $Ai = blittable::<$Ai>(), )*)?
+                        ));
+                    }
                     definer.define_once(me, &mut |definer| writeln!(definer.out(),
                         concat!(
                             // IIUC,
@@ -355,13 +423,15 @@ const _: () = { macro_rules! impl_CTypes {
                 {
                     // This assumes the calling convention from the above
                     // `UnmanagedFunctionPointer` attribute.
+                    // (Dropped by the C# backend for the function pointer
+                    // structs of `.with_csharp_function_pointers(true)`.)
                     Some("UnmanagedType.FunctionPtr".into())
                 }
             }
         } type OPAQUE_KIND = OpaqueKind::Concrete; }
 
         /// Simplified for lighter documentation, but the actual impls include
-        /// **up to 9 function parameters**.
+        /// **up to 12 function parameters**.
         unsafe // Safety: byte-wise the layout is the same, but the safety
                // invariants will still have to be checked at each site.
         impl<
@@ -385,7 +455,7 @@ const _: () = { macro_rules! impl_CTypes {
         }
 
         /// Simplified for lighter documentation, but the actual impls include
-        /// **up to 9 function parameters**.
+        /// **up to 12 function parameters**.
         unsafe // Safety: byte-wise the layout is the same, but the safety
                // invariants will still have to be checked at each site.
         impl<
@@ -411,7 +481,7 @@ const _: () = { macro_rules! impl_CTypes {
         /* == ReprC for Option-less == */
 
         /// Simplified for lighter documentation, but the actual impls include
-        /// **up to 9 function parameters**.
+        /// **up to 12 function parameters**.
         unsafe // Safety: byte-wise the layout is the same, but the safety
                // invariants will still have to be checked at each site.
         impl<
@@ -435,7 +505,7 @@ const _: () = { macro_rules! impl_CTypes {
         }
 
         /// Simplified for lighter documentation, but the actual impls include
-        /// **up to 9 function parameters**.
+        /// **up to 12 function parameters**.
         unsafe // Safety: byte-wise the layout is the same, but the safety
                // invariants will still have to be checked at each site.
         impl<
@@ -458,6 +528,116 @@ const _: () = { macro_rules! impl_CTypes {
             }
         }
 
+        /* == ReprC for `extern "C-unwind"` == */
+
+        #[cfg(feature = "c-unwind")]
+        #[cfg_attr(feature = "nightly", doc(cfg(feature = "c-unwind")))]
+        /// Simplified for lighter documentation, but the actual impls include
+        /// **up to 12 function parameters**.
+        unsafe // Safety: byte-wise the layout is the same (C has no notion of
+               // unwinding), and the pointer is only called (unwinding-ly) from
+               // Rust, once back to `Self`.
+        impl<
+            Ret : ReprC, $(
+            $An : ReprC, $(
+            $Ai : ReprC,
+        )*)?> ReprC
+            for Option<unsafe extern "C-unwind" fn ($($An, $($Ai ,)*)?) -> Ret>
+        {
+            type CLayout = Option<
+                unsafe extern "C"
+                fn ($($An::CLayout, $($Ai::CLayout ,)*)?) -> Ret::CLayout
+            >;
+
+            #[inline]
+            fn is_valid (_: &'_ Self::CLayout)
+              -> bool
+            {
+                true
+            }
+        }
+
+        #[cfg(feature = "c-unwind")]
+        #[cfg_attr(feature = "nightly", doc(cfg(feature = "c-unwind")))]
+        /// Simplified for lighter documentation, but the actual impls include
+        /// **up to 12 function parameters**.
+        unsafe // Safety: byte-wise the layout is the same (C has no notion of
+               // unwinding), and the pointer is only called (unwinding-ly) from
+               // Rust, once back to `Self`.
+        impl<
+            Ret : ReprC, $(
+            $An : ReprC, $(
+            $Ai : ReprC,
+        )*)?> ReprC
+            for Option</*unsafe*/ extern "C-unwind" fn ($($An, $($Ai ,)*)?) -> Ret>
+        {
+            type CLayout = Option<
+                unsafe extern "C"
+                fn ($($An::CLayout, $($Ai::CLayout ,)*)?) -> Ret::CLayout
+            >;
+
+            #[inline]
+            fn is_valid (_: &'_ Self::CLayout)
+              -> bool
+            {
+                true
+            }
+        }
+
+        #[cfg(feature = "c-unwind")]
+        #[cfg_attr(feature = "nightly", doc(cfg(feature = "c-unwind")))]
+        /// Simplified for lighter documentation, but the actual impls include
+        /// **up to 12 function parameters**.
+        unsafe // Safety: byte-wise the layout is the same (C has no notion of
+               // unwinding), and the pointer is only called (unwinding-ly) from
+               // Rust, once back to `Self`.
+        impl<
+            Ret : ReprC, $(
+            $An : ReprC, $(
+            $Ai : ReprC,
+        )*)?> ReprC
+            for unsafe extern "C-unwind" fn ($($An, $($Ai ,)*)?) -> Ret
+        {
+            type CLayout = Option<
+                unsafe extern "C"
+                fn ($($An::CLayout, $($Ai::CLayout ,)*)?) -> Ret::CLayout
+            >;
+
+            #[inline]
+            fn is_valid (c_layout: &'_ Self::CLayout)
+              -> bool
+            {
+                c_layout.is_some()
+            }
+        }
+
+        #[cfg(feature = "c-unwind")]
+        #[cfg_attr(feature = "nightly", doc(cfg(feature = "c-unwind")))]
+        /// Simplified for lighter documentation, but the actual impls include
+        /// **up to 12 function parameters**.
+        unsafe // Safety: byte-wise the layout is the same (C has no notion of
+               // unwinding), and the pointer is only called (unwinding-ly) from
+               // Rust, once back to `Self`.
+        impl<
+            Ret : ReprC, $(
+            $An : ReprC, $(
+            $Ai : ReprC,
+        )*)?> ReprC
+            for /*unsafe*/ extern "C-unwind" fn ($($An, $($Ai ,)*)?) -> Ret
+        {
+            type CLayout = Option<
+                unsafe extern "C"
+                fn ($($An::CLayout, $($Ai::CLayout ,)*)?) -> Ret::CLayout
+            >;
+
+            #[inline]
+            fn is_valid (c_layout: &'_ Self::CLayout)
+              -> bool
+            {
+                c_layout.is_some()
+            }
+        }
+
         // Improve the error message when encountering a non-`extern "C"` fn
         // wrapped in an `Option` (otherwise `rustc` tunnelvisions _w.r.t_
         // the lack of Niche).
@@ -518,14 +698,53 @@ const _: () = { macro_rules! impl_CTypes {
             {
                 definer.define_once(
                     "__int_headers__",
-                    &mut |definer| write!(definer.out(),
-                        concat!(
-                            "\n",
-                            "#include <stddef.h>\n",
-                            "#include <stdint.h>\n",
-                            "\n",
-                        ),
-                    ),
+                    &mut |definer| if definer.settings().stdint_fallback {
+                        write!(definer.out(),
+                            concat!(
+                                "\n",
+                                "#include <stddef.h>\n",
+                                "#include <limits.h>\n",
+                                "\n",
+                                "#if (defined(__STDC_VERSION__) && __STDC_VERSION__ >= 199901L) \\\n",
+                                "    || defined(__cplusplus) \\\n",
+                                "    || (defined(_MSC_VER) && _MSC_VER >= 1600)\n",
+                                "#include <stdint.h>\n",
+                                "#else\n",
+                                "typedef signed char int8_t;\n",
+                                "typedef unsigned char uint8_t;\n",
+                                "typedef short int16_t;\n",
+                                "typedef unsigned short uint16_t;\n",
+                                "#if INT_MAX == 0x7fffffff\n",
+                                "typedef int int32_t;\n",
+                                "typedef unsigned int uint32_t;\n",
+                                "#else\n",
+                                "typedef long int32_t;\n",
+                                "typedef unsigned long uint32_t;\n",
+                                "#endif\n",
+                                "#if LONG_MAX > 0x7fffffff\n",
+                                "typedef long int64_t;\n",
+                                "typedef unsigned long uint64_t;\n",
+                                "#elif defined(_MSC_VER)\n",
+                                "typedef __int64 int64_t;\n",
+                                "typedef unsigned __int64 uint64_t;\n",
+                                "#else\n",
+                                "typedef long long int64_t;\n",
+                                "typedef unsigned long long uint64_t;\n",
+                                "#endif\n",
+                                "#endif\n",
+                                "\n",
+                            ),
+                        )
+                    } else {
+                        write!(definer.out(),
+                            concat!(
+                                "\n",
+                                "#include <stddef.h>\n",
+                                "#include <stdint.h>\n",
+                                "\n",
+                            ),
+                        )
+                    },
                 )
             }
 
@@ -541,6 +760,15 @@ const _: () = { macro_rules! impl_CTypes {
                 )
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(match stringify!($RustInt) {
+                    | "usize" | "isize" => target.pointer_layout(),
+                    | _ => target.integer_layout(::core::mem::size_of::<$RustInt>()),
+                })
+            }
+
             __cfg_csharp__! {
                 fn csharp_define_self (
                     _: &'_ mut dyn crate::headers::Definer,
@@ -587,6 +815,12 @@ const _: () = { macro_rules! impl_CTypes {
                 )
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(target.float_layout(::core::mem::size_of::<$fN>()))
+            }
+
             fn c_define_self (
                 _: &'_ mut dyn crate::headers::Definer,
             ) -> io::Result<()>
@@ -631,6 +865,12 @@ const _: () = { macro_rules! impl_CTypes {
                 T::define_self(&crate::headers::languages::C, definer)
             }
 
+            fn cython_define_self (definer: &'_ mut dyn Definer)
+              -> io::Result<()>
+            {
+                T::define_self(&crate::headers::languages::Cython, definer)
+            }
+
             fn c_var_fmt (
                 fmt: &'_ mut fmt::Formatter<'_>,
                 var_name: &'_ str,
@@ -644,6 +884,12 @@ const _: () = { macro_rules! impl_CTypes {
                 )
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(target.pointer_layout())
+            }
+
             __cfg_csharp__! {
                 fn csharp_define_self (definer: &'_ mut dyn $crate::headers::Definer)
                   -> $crate::ඞ::io::Result<()>
@@ -698,6 +944,12 @@ const _: () = { macro_rules! impl_CTypes {
                 T::define_self(&crate::headers::languages::C, definer)
             }
 
+            fn cython_define_self (definer: &'_ mut dyn Definer)
+              -> io::Result<()>
+            {
+                T::define_self(&crate::headers::languages::Cython, definer)
+            }
+
             fn c_var_fmt (
                 fmt: &'_ mut fmt::Formatter<'_>,
                 var_name: &'_ str,
@@ -711,6 +963,12 @@ const _: () = { macro_rules! impl_CTypes {
                 )
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(target.pointer_layout())
+            }
+
             __cfg_csharp__! {
                 fn csharp_define_self (definer: &'_ mut dyn $crate::headers::Definer)
                   -> $crate::ඞ::io::Result<()>
@@ -778,6 +1036,7 @@ const _: () = { macro_rules! impl_CTypes {
 macro_rules! impl_ReprC_for {(
     $unsafe:tt {
         $(
+            $(#[niche = $niche:ident])?
             $(@for [$($generics:tt)+])? $T:ty
                 => |ref $it:tt : $Layout:ty| $expr:expr
         ),* $(,)?
@@ -812,6 +1071,15 @@ macro_rules! impl_ReprC_for {(
                     false
                 }
             }
+        $(
+            __cfg_headers__! {
+                fn niche ()
+                  -> Niche
+                {
+                    Niche::$niche
+                }
+            }
+        )?
         }
     )*
 )}
@@ -868,9 +1136,25 @@ unsafe
                 definer.define_once(
                     "bool",
                     &mut |definer| {
-                        definer.out().write_all(
-                            b"\n#include <stdbool.h>\n\n"
-                        )
+                        let c_standard = definer.settings().c_standard;
+                        if c_standard.has_bool_keyword() {
+                            Ok(())
+                        } else if c_standard.has_stdbool() {
+                            definer.out().write_all(
+                                b"\n#include <stdbool.h>\n\n"
+                            )
+                        } else {
+                            // Same ABI as Rust's `bool`: a one-byte integer.
+                            definer.out().write_all(concat!(
+                                "\n",
+                                "#ifndef __cplusplus\n",
+                                "typedef unsigned char bool;\n",
+                                "#define true 1\n",
+                                "#define false 0\n",
+                                "#endif\n",
+                                "\n",
+                            ).as_bytes())
+                        }
                     },
                 )
             }
@@ -887,6 +1171,12 @@ unsafe
                 )
             }
 
+            fn c_layout_on (_: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(crate::headers::TypeLayout { size: 1, align: 1 })
+            }
+
             __cfg_csharp__! {
                 fn csharp_define_self (
                     _: &'_ mut dyn crate::headers::Definer,
@@ -957,6 +1247,12 @@ unsafe
                 )
             }
 
+            fn c_layout_on (target: &'_ crate::headers::Target)
+              -> Option<crate::headers::TypeLayout>
+            {
+                Some(target.c_int_layout())
+            }
+
             fn c_define_self (
                 _: &'_ mut dyn crate::headers::Definer,
             ) -> io::Result<()>
@@ -994,6 +1290,7 @@ impl_ReprC_for! { unsafe {
         => |ref byte: Bool| (byte.0 & !0b1) == 0
     ,
 
+    #[niche = NonNull]
     @for[T : ReprC]
     ptr::NonNull<T>
         => |ref it: *mut T::CLayout| {
@@ -1001,6 +1298,7 @@ impl_ReprC_for! { unsafe {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         }
     ,
+    #[niche = NonNull]
     @for[T : ReprC]
     ptr::NonNullRef<T>
         => |ref it: *const T::CLayout| {
@@ -1008,6 +1306,7 @@ impl_ReprC_for! { unsafe {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         }
     ,
+    #[niche = NonNull]
     @for[T : ReprC]
     ptr::NonNullMut<T>
         => |ref it: *mut T::CLayout| {
@@ -1015,6 +1314,7 @@ impl_ReprC_for! { unsafe {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         }
     ,
+    #[niche = NonNull]
     @for[T : ReprC]
     ptr::NonNullOwned<T>
         => |ref it: *mut T::CLayout| {
@@ -1022,6 +1322,7 @@ impl_ReprC_for! { unsafe {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         }
     ,
+    #[niche = NonNull]
     @for['a, T : 'a + ReprC]
     &'a T
         => |ref it: *const T::CLayout| {
@@ -1029,6 +1330,7 @@ impl_ReprC_for! { unsafe {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         }
     ,
+    #[niche = NonNull]
     @for['a, T : 'a + ReprC]
     &'a mut T
         => |ref it: *mut T::CLayout| {
@@ -1041,11 +1343,20 @@ impl_ReprC_for! { unsafe {
 /* `HasNiche` from `niche.rs` impls `ReprC` for `Option<ptr>` types. */
 
 impl_ReprC_for! { unsafe {
+    #[niche = NonNull]
     @for['out, T : 'out + Sized + ReprC]
     Out<'out, T>
         => |ref it: *mut T::CLayout| {
             (*it as usize) % ::core::mem::align_of::<T>() == 0
         },
+
+    #[niche = NonNull]
+    @for['a, T : 'a + Sized + ReprC]
+    &'a mut ::core::mem::MaybeUninit<T>
+        => |ref it: *mut T::CLayout| {
+            it.is_null().not() &&
+            (*it as usize) % ::core::mem::align_of::<T>() == 0
+        },
 }}
 
 pub
@@ -1,6 +1,40 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 use_prelude!();
 use crate::prelude::c_slice;
+use ::core::num;
+
+/// How a [`ReprC`] type uses the "niche" of its C layout (the null pointer,
+/// or the zero integer), for the headers to reflect it (see
+/// [`Builder::with_nullability_annotations()`]).
+///
+/// More kinds of niches may be added in the future.
+///
+/// [`Builder::with_nullability_annotations()`]: `crate::headers::Builder::with_nullability_annotations`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub
+enum Niche {
+    /// Nothing worth mentioning (_e.g._, a non-pointer type, or a raw
+    /// pointer).
+    Unused,
+
+    /// A pointer which cannot be null (_e.g._, `&T`, or `char_p::Ref`).
+    NonNull,
+
+    /// A pointer for which null means `None` (_e.g._, `Option<&T>`).
+    NullMeansNone,
+
+    /// An integer which cannot be zero (_e.g._, `NonZeroU32`).
+    NonZero,
+
+    /// An integer for which zero means `None` (_e.g._,
+    /// `Option<NonZeroU32>`).
+    ZeroMeansNone,
+
+    /// A (by value) struct for which a null `.ptr` field means `None` (_e.g._,
+    /// `Option<repr_c::Vec<T>>`).
+    NullPtrMeansNone,
+}
 
 pub
 unsafe
@@ -8,6 +42,19 @@ trait HasNiche : ReprC {
     fn is_niche (it: &'_ <Self as ReprC>::CLayout)
       -> bool
     ;
+
+    __cfg_headers__! {
+        /// The [`Niche`] of an `Option<Self>`.
+        fn niche_of_none ()
+          -> Niche
+        {
+            match Self::niche() {
+                | Niche::NonNull => Niche::NullMeansNone,
+                | Niche::NonZero => Niche::ZeroMeansNone,
+                | _ => Niche::Unused,
+            }
+        }
+    }
 }
 
 unsafe
@@ -22,12 +69,74 @@ impl<T : ReprC + HasNiche> ReprC
     {
         T::is_niche(it) || <T as ReprC>::is_valid(it)
     }
+
+    __cfg_headers__! {
+        fn niche ()
+          -> Niche
+        {
+            T::niche_of_none()
+        }
+    }
+}
+
+macro_rules! impl_non_zero {(
+    $($NonZero:ident => $int:ty),* $(,)?
+) => (
+    $(
+        unsafe
+        impl ReprC
+            for num::$NonZero
+        {
+            type CLayout = $int;
+
+            #[inline]
+            fn is_valid (it: &'_ $int)
+              -> bool
+            {
+                *it != 0
+            }
+
+            __cfg_headers__! {
+                fn niche ()
+                  -> Niche
+                {
+                    Niche::NonZero
+                }
+            }
+        }
+
+        unsafe
+        impl HasNiche
+            for num::$NonZero
+        {
+            #[inline]
+            fn is_niche (it: &'_ $int)
+              -> bool
+            {
+                *it == 0
+            }
+        }
+    )*
+)}
+
+impl_non_zero! {
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroIsize => isize,
 }
 
 macro_rules! unsafe_impls {(
     $(
         $(@for[$($generics:tt)*])?
         $T:ty => |$it:pat_param| $expr:expr
+        $(; none: $none:ident)?
     ),* $(,)?
 ) => (
     $(
@@ -41,6 +150,15 @@ macro_rules! unsafe_impls {(
             {
                 $expr
             }
+        $(
+            __cfg_headers__! {
+                fn niche_of_none ()
+                  -> Niche
+                {
+                    Niche::$none
+                }
+            }
+        )?
         }
     )*
 )}
@@ -61,11 +179,11 @@ unsafe_impls! {
     ptr::NonNullOwned<T> => |it| it.is_null(),
 
     @for['__, T : '__ + ReprC]
-    c_slice::Mut<'__, T> => |it| it.ptr.is_null(),
+    c_slice::Mut<'__, T> => |it| it.ptr.is_null(); none: NullPtrMeansNone,
     @for['__, T : '__ + ReprC]
-    c_slice::Ref<'__, T> => |it| it.ptr.is_null(),
+    c_slice::Ref<'__, T> => |it| it.ptr.is_null(); none: NullPtrMeansNone,
     @for[T : ReprC]
-    c_slice::Raw<T> => |it| it.ptr.is_null(),
+    c_slice::Raw<T> => |it| it.ptr.is_null(); none: NullPtrMeansNone,
 
     // crate::str::Raw => |it| it.ptr.is_null(),
     // str::Ref<'_> => |it| it.ptr.is_null(),
@@ -83,14 +201,13 @@ cfg_alloc! {
         // @for[T : ReprC]
         // repr_c::Box<T> => |it| it.is_null(),
         @for[T : ReprC]
-        c_slice::Box<T> => |it| it.ptr.is_null(),
+        c_slice::Box<T> => |it| it.ptr.is_null(); none: NullPtrMeansNone,
         @for[T : ReprC]
-        repr_c::Vec<T> => |it| it.ptr.is_null(),
+        repr_c::Vec<T> => |it| it.ptr.is_null(); none: NullPtrMeansNone,
 
         // str::Box => |it| it.ptr.is_null(),
-        // repr_c::String => |it| it.ptr.is_null(),
-
-        // char_p::Box => |it| it.is_null(),
+        // (`repr_c::String` and `char_p::Box`, being `#[repr(transparent)]`
+        // wrappers, get theirs from the `derive`.)
     }
 }
 
@@ -0,0 +1,172 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Leak-tracking diagnostics (`leak-tracking` Cargo feature), for integrators
+//! to find which of the objects handed over to them they forgot to free.
+//!
+//! While the [`DebugChecks::LEAK_CHECK`] layer is enabled (see
+//! [`debug_checks`][`crate::debug_checks`]), the allocations owned by
+//! [`repr_c::Box`], [`repr_c::Vec`] (and thus [`repr_c::String`]), and
+//! [`char_p::Box`] are then registered, along with the backtrace of their
+//! creation, in a global table, until they are freed (or converted back to
+//! their Rust counterpart).
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_leak_tracking!(mylib);
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! /** \brief
+//!  *  The number of the objects handed over by the library which have not
+//!  *  been freed yet.
+//!  */
+//! size_t mylib_ffi_live_allocations (void);
+//!
+//! /** \brief
+//!  *  Logs (to `stderr`, unless the library uses `::log`) the objects handed
+//!  *  over by the library which have not been freed yet, along with the
+//!  *  backtraces of their creation.
+//!  */
+//! void mylib_ffi_dump_live_allocations (void);
+//! ```
+//!
+//! Since capturing a backtrace is slow, it only happens as per the
+//! `RUST_BACKTRACE` / `RUST_LIB_BACKTRACE` environment variables (see
+//! [`Backtrace::capture()`]), and this feature is only meant for debugging
+//! builds.
+//!
+//! [`repr_c::Box`]: `crate::prelude::repr_c::Box`
+//! [`repr_c::Vec`]: `crate::prelude::repr_c::Vec`
+//! [`repr_c::String`]: `crate::prelude::repr_c::String`
+//! [`char_p::Box`]: `crate::prelude::char_p::Box`
+
+use_prelude!();
+use crate::debug_checks::{self, DebugChecks};
+use ::std::{
+    backtrace::Backtrace,
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{
+        atomic::{self, AtomicU64},
+        Mutex,
+    },
+};
+
+struct Allocation {
+    /// In creation order.
+    id: u64,
+    type_name: &'static str,
+    backtrace: Backtrace,
+}
+
+/// The live allocations, keyed by address.
+static LIVE: Mutex<BTreeMap<usize, Allocation>> = Mutex::new(BTreeMap::new());
+
+/// Registers the allocation at `ptr`, owned by a `T`, if the
+/// [`DebugChecks::LEAK_CHECK`] layer is enabled.
+pub(in crate)
+fn track<T> (ptr: *mut ())
+{
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    if debug_checks::enabled(DebugChecks::LEAK_CHECK).not() {
+        return;
+    }
+    let allocation = Allocation {
+        id: NEXT_ID.fetch_add(1, atomic::Ordering::Relaxed),
+        type_name: ::core::any::type_name::<T>(),
+        backtrace: Backtrace::capture(),
+    };
+    LIVE.lock()
+        .unwrap_or_else(|it| it.into_inner())
+        .insert(ptr as usize, allocation)
+    ;
+}
+
+/// Unregisters the allocation at `ptr` (if any), which is being freed, or
+/// handed back to Rust.
+pub(in crate)
+fn untrack (ptr: *mut ())
+{
+    LIVE.lock()
+        .unwrap_or_else(|it| it.into_inner())
+        .remove(&(ptr as usize))
+    ;
+}
+
+/// The number of the live allocations.
+pub
+fn live_allocations ()
+  -> usize
+{
+    LIVE.lock().unwrap_or_else(|it| it.into_inner()).len()
+}
+
+/// A report of the live allocations, in creation order, along with the
+/// backtraces of their creation.
+pub
+fn dump_live_allocations ()
+  -> rust::String
+{
+    let live = LIVE.lock().unwrap_or_else(|it| it.into_inner());
+    let mut allocations = live.iter().collect::<rust::Vec<_>>();
+    allocations.sort_unstable_by_key(|&(_, allocation)| allocation.id);
+    let mut report = format!("{} live allocation(s)", allocations.len());
+    for (&address, allocation) in allocations {
+        let Allocation { id, type_name, ref backtrace } = *allocation;
+        write!(report,
+            "\n\n#{id}: `{type_name}` at {address:#x}, created at:\n{backtrace}",
+        ).unwrap();
+    }
+    report
+}
+
+/// Defines and exports `<prefix>_ffi_live_allocations()` and
+/// `<prefix>_ffi_dump_live_allocations()` (see the
+/// [`leak_tracking` module][`crate::leak_tracking`]).
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_leak_tracking!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The number of the objects handed over by the library which have not
+///  *  been freed yet.
+///  */
+/// size_t mylib_ffi_live_allocations (void);
+///
+/// /** \brief
+///  *  Logs (to `stderr`, unless the library uses `::log`) the objects handed
+///  *  over by the library which have not been freed yet, along with the
+///  *  backtraces of their creation.
+///  */
+/// void mylib_ffi_dump_live_allocations (void);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_leak_tracking {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// The number of the objects handed over by the library which have not
+    /// been freed yet.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _ffi_live_allocations>] ()
+      -> usize
+    {
+        $crate::leak_tracking::live_allocations()
+    }
+
+    #[$crate::ffi_export]
+    /// Logs (to `stderr`, unless the library uses `::log`) the objects handed
+    /// over by the library which have not been freed yet, along with the
+    /// backtraces of their creation.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _ffi_dump_live_allocations>] ()
+    {
+        $crate::ඞ::__error__!("{}", $crate::leak_tracking::dump_live_allocations());
+    }
+})}
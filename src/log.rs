@@ -0,0 +1,425 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Forwarding of the [`::log`] records of the library to a host-provided
+//! callback (`log-callback` Cargo feature).
+//!
+//! [`ffi_export_log_callback!`] exports the function with which the host
+//! registers it:
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_log_callback!(mylib);
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! enum LogLevel {
+//!     LOG_LEVEL_OFF = 0,
+//!     LOG_LEVEL_ERROR = 1,
+//!     LOG_LEVEL_WARN = 2,
+//!     LOG_LEVEL_INFO = 3,
+//!     LOG_LEVEL_DEBUG = 4,
+//!     LOG_LEVEL_TRACE = 5,
+//! };
+//! typedef uint8_t LogLevel_t;
+//!
+//! /** \brief
+//!  *  The callback to which the log records are forwarded, along with the
+//!  *  `user_data` it was registered with.
+//!  */
+//! typedef void (*LogCallback_t)(LogLevel_t level, char const * message, void * user_data);
+//!
+//! /** \brief
+//!  *  Forwards the log records up to `max_level` to `callback` (none if it
+//!  *  is `NULL`), from then on.
+//!  * ...
+//!  */
+//! bool mylib_set_log_callback (
+//!     LogLevel_t max_level,
+//!     LogCallback_t callback,
+//!     void * user_data);
+//! ```
+//!
+//! The callback may be called from any thread, concurrently, and
+//! `message` is only valid for the duration of the call. Hence the function
+//! being `unsafe`: the callback, and `user_data`, have to be thread-safe, and
+//! `user_data` has to remain valid until another callback is registered.
+
+use_prelude!();
+use ::std::sync::RwLock;
+
+/// The severity of a log record (same as [`::log::Level`]), or, as a
+/// `max_level`, [`Off`][`LogLevel::Off`] to forward none.
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub
+enum LogLevel {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl From<::log::Level>
+    for LogLevel
+{
+    fn from (level: ::log::Level)
+      -> LogLevel
+    {
+        match level {
+            | ::log::Level::Error => LogLevel::Error,
+            | ::log::Level::Warn => LogLevel::Warn,
+            | ::log::Level::Info => LogLevel::Info,
+            | ::log::Level::Debug => LogLevel::Debug,
+            | ::log::Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl From<LogLevel>
+    for ::log::LevelFilter
+{
+    fn from (level: LogLevel)
+      -> ::log::LevelFilter
+    {
+        match level {
+            | LogLevel::Off => ::log::LevelFilter::Off,
+            | LogLevel::Error => ::log::LevelFilter::Error,
+            | LogLevel::Warn => ::log::LevelFilter::Warn,
+            | LogLevel::Info => ::log::LevelFilter::Info,
+            | LogLevel::Debug => ::log::LevelFilter::Debug,
+            | LogLevel::Trace => ::log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// The host callback to which the log records are forwarded, with its own
+/// (`LogCallback_t`) typedef in the headers.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub
+struct LogCallback(
+    pub
+    unsafe extern "C" fn (
+        level: LogLevel,
+        message: char_p::Ref<'_>,
+        user_data: *mut c_void,
+    ),
+);
+
+/// The C layout of a [`LogCallback`].
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub
+struct LogCallback_Layout(
+    Option<unsafe extern "C" fn (
+        CLayoutOf<LogLevel>,
+        CLayoutOf<char_p::Ref<'static>>,
+        *mut c_void,
+    )>,
+);
+
+unsafe
+impl LegacyCType
+    for LogCallback_Layout
+{ __cfg_headers__! {
+    fn c_short_name_fmt (fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt.write_str("LogCallback")
+    }
+
+    fn c_var_fmt (
+        fmt: &'_ mut fmt::Formatter<'_>,
+        var_name: &'_ str,
+    ) -> fmt::Result
+    {
+        write!(fmt,
+            "LogCallback_t{sep}{}",
+            var_name,
+            sep = if var_name.is_empty() { "" } else { " " },
+        )
+    }
+
+    fn c_layout_on (target: &'_ crate::headers::Target)
+      -> Option<crate::headers::TypeLayout>
+    {
+        Some(target.pointer_layout())
+    }
+
+    fn c_define_self (definer: &'_ mut dyn crate::headers::Definer)
+      -> io::Result<()>
+    {
+        let ref lang = crate::headers::languages::C;
+        <CLayoutOf<LogLevel> as CType>::define_self(lang, definer)?;
+        definer.define_once("LogCallback_t", &mut |definer| writeln!(definer.out(),
+            concat!(
+                "/** \\brief\n",
+                " *  The callback to which the log records are forwarded, along with the\n",
+                " *  `user_data` it was registered with.\n",
+                " */\n",
+                "typedef void (*LogCallback_t)({}, {}, {});\n",
+            ),
+            <CLayoutOf<LogLevel> as CType>::name_wrapping_var(lang, "level"),
+            <CLayoutOf<char_p::Ref<'_>> as CType>::name_wrapping_var(lang, "message"),
+            <*mut c_void as CType>::name_wrapping_var(lang, "user_data"),
+        ))
+    }
+
+    fn cython_define_self (definer: &'_ mut dyn crate::headers::Definer)
+      -> io::Result<()>
+    {
+        let ref lang = crate::headers::languages::Cython;
+        <CLayoutOf<LogLevel> as CType>::define_self(lang, definer)?;
+        definer.define_once("LogCallback_t", &mut |definer| writeln!(definer.out(),
+            "    ctypedef void (*LogCallback_t)({}, {}, {})",
+            <CLayoutOf<LogLevel> as CType>::name_wrapping_var(lang, "level"),
+            <CLayoutOf<char_p::Ref<'_>> as CType>::name_wrapping_var(lang, "message"),
+            <*mut c_void as CType>::name_wrapping_var(lang, "user_data"),
+        ))
+    }
+
+    __cfg_csharp__! {
+        fn csharp_define_self (definer: &'_ mut dyn crate::headers::Definer)
+          -> io::Result<()>
+        {
+            let ref lang = crate::headers::languages::CSharp;
+            <CLayoutOf<LogLevel> as CType>::define_self(lang, definer)?;
+            if definer.settings().csharp_function_pointers {
+                // (no marshaling happens through function pointers)
+                return definer.define_once("LogCallback_t", &mut |definer| writeln!(definer.out(),
+                    concat!(
+                        "public unsafe struct LogCallback_t {{\n",
+                        "    public delegate* unmanaged[Cdecl]<{}, {}, {}, void> Ptr;\n",
+                        "}}\n",
+                    ),
+                    <CLayoutOf<LogLevel> as CType>::name(lang),
+                    <CLayoutOf<char_p::Ref<'_>> as CType>::name(lang),
+                    <*mut c_void as CType>::name(lang),
+                ));
+            }
+            let marshaled = |marshaler: Option<rust::String>, ty: rust::String| {
+                match marshaler {
+                    | Some(marshaler) => format!("[MarshalAs({marshaler})]\n        {ty}"),
+                    | None => ty,
+                }
+            };
+            definer.define_once("LogCallback_t", &mut |definer| writeln!(definer.out(),
+                concat!(
+                    "[UnmanagedFunctionPointer(CallingConvention.Winapi)]\n",
+                    "public unsafe /* static */ delegate\n",
+                    "    void\n",
+                    "    LogCallback_t (\n",
+                    "        {},\n",
+                    "        {},\n",
+                    "        {});\n",
+                ),
+                marshaled(
+                    <CLayoutOf<LogLevel> as CType>::csharp_marshaler(),
+                    <CLayoutOf<LogLevel> as CType>::name_wrapping_var(lang, "level"),
+                ),
+                marshaled(
+                    <CLayoutOf<char_p::Ref<'_>> as CType>::csharp_marshaler(),
+                    <CLayoutOf<char_p::Ref<'_>> as CType>::name_wrapping_var(lang, "message"),
+                ),
+                marshaled(
+                    <*mut c_void as CType>::csharp_marshaler(),
+                    <*mut c_void as CType>::name_wrapping_var(lang, "user_data"),
+                ),
+            ))
+        }
+
+        fn csharp_ty ()
+          -> rust::String
+        {
+            "LogCallback_t".into()
+        }
+    }
+} type OPAQUE_KIND = crate::layout::OpaqueKind::Concrete; }
+
+from_CType_impl_ReprC! {
+    LogCallback_Layout
+}
+
+unsafe // Safety: same ABI (each parameter type having its `CLayout`'s).
+impl ReprC
+    for LogCallback
+{
+    type CLayout = LogCallback_Layout;
+
+    #[inline]
+    fn is_valid (it: &'_ LogCallback_Layout)
+      -> bool
+    {
+        it.0.is_some()
+    }
+
+    __cfg_headers__! {
+        fn niche ()
+          -> Niche
+        {
+            Niche::NonNull
+        }
+    }
+}
+
+unsafe
+impl crate::layout::__HasNiche__
+    for LogCallback
+{
+    #[inline]
+    fn is_niche (it: &'_ LogCallback_Layout)
+      -> bool
+    {
+        it.0.is_none()
+    }
+}
+
+struct Registered {
+    max_level: LogLevel,
+    callback: LogCallback,
+    user_data: *mut c_void,
+}
+
+// Safety: it is up to the host to provide a thread-safe callback (and
+// `user_data`), as documented.
+unsafe impl Send for Registered {}
+unsafe impl Sync for Registered {}
+
+struct State {
+    /// Whether [`CallbackLogger`] is the [`::log`] logger.
+    installed: bool,
+    registered: Option<Registered>,
+}
+
+static STATE: RwLock<State> = RwLock::new(State {
+    installed: false,
+    registered: None,
+});
+
+struct CallbackLogger;
+
+impl ::log::Log
+    for CallbackLogger
+{
+    fn enabled (self: &'_ Self, metadata: &'_ ::log::Metadata<'_>)
+      -> bool
+    {
+        let state = STATE.read().unwrap_or_else(|it| it.into_inner());
+        match &state.registered {
+            | Some(it) => LogLevel::from(metadata.level()) <= it.max_level,
+            | None => false,
+        }
+    }
+
+    fn log (self: &'_ Self, record: &'_ ::log::Record<'_>)
+    {
+        let state = STATE.read().unwrap_or_else(|it| it.into_inner());
+        let Registered { max_level, callback, user_data } = match &state.registered {
+            | Some(it) => it,
+            | None => return,
+        };
+        let level = LogLevel::from(record.level());
+        if level > *max_level {
+            return;
+        }
+        // (interior nul bytes are replaced with `U+FFFD`)
+        let message = char_p::new(record.args().to_string().replace('\0', "\u{fffd}"));
+        unsafe {
+            (callback.0)(level, message.as_ref(), *user_data);
+        }
+    }
+
+    fn flush (self: &'_ Self)
+    {}
+}
+
+/// Forwards, from then on, the log records up to `max_level` to `callback`
+/// (none if it is `None`), replacing any previously registered one.
+///
+/// Returns `false`, doing nothing, if another [`::log`] logger has already
+/// been installed.
+///
+/// # Safety
+///
+///   - `callback` may be called from any thread, concurrently, with
+///     `user_data`: both have to be thread-safe (_i.e._, `Send + Sync`);
+///
+///   - `user_data` has to remain valid until `set_log_callback()` is called
+///     again (registering another callback, or none), and not released
+///     before then.
+pub
+unsafe
+fn set_log_callback (
+    max_level: LogLevel,
+    callback: Option<LogCallback>,
+    user_data: *mut c_void,
+) -> bool
+{
+    static LOGGER: CallbackLogger = CallbackLogger;
+    let mut state = STATE.write().unwrap_or_else(|it| it.into_inner());
+    if state.installed.not() {
+        if ::log::set_logger(&LOGGER).is_err() {
+            return false;
+        }
+        state.installed = true;
+    }
+    let max_level = match callback {
+        | Some(_) => max_level,
+        | None => LogLevel::Off,
+    };
+    state.registered =
+        callback.map(|callback| Registered { max_level, callback, user_data })
+    ;
+    ::log::set_max_level(max_level.into());
+    true
+}
+
+/// Defines and exports `<prefix>_set_log_callback()` (see the
+/// [`log` module][`crate::log`]).
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_log_callback!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  Forwards the log records up to `max_level` to `callback` (none if it
+///  *  is `NULL`), from then on.
+///  * ...
+///  */
+/// bool mylib_set_log_callback (
+///     LogLevel_t max_level,
+///     LogCallback_t callback,
+///     void * user_data);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_log_callback {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Forwards the log records up to `max_level` to `callback` (none if it
+    /// is `NULL`), from then on.
+    ///
+    /// `callback` may be called from any thread, concurrently, along with
+    /// `user_data`, and `message` is only valid for the duration of the call.
+    /// `callback` and `user_data` thus have to be thread-safe, and
+    /// `user_data` has to remain valid until this function is called again.
+    ///
+    /// Returns `false`, doing nothing, if the library already logs elsewhere.
+    #[allow(nonstandard_style)]
+    pub
+    unsafe
+    fn [<$prefix _set_log_callback>] (
+        max_level: $crate::log::LogLevel,
+        callback: $crate::ඞ::Option<$crate::log::LogCallback>,
+        user_data: *mut $crate::ඞ::ffi::c_void,
+    ) -> bool
+    {
+        $crate::log::set_log_callback(max_level, callback, user_data)
+    }
+})}
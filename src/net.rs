@@ -0,0 +1,230 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Network addresses, for FFI (`net` Cargo feature).
+//!
+//! The [`::std::net`] address types are not `ReprC`, their layout being
+//! unspecified. Instead, use the structs of this module, which convert to and
+//! from them. [`IpAddr`] is a tagged struct (rather than a `union`), its
+//! `octets` being those of an IPv6 address, or, for an IPv4 one, starting with
+//! its 4 octets (the remaining ones being zero):
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export]
+//! fn mylib_config_set_listen_addr (
+//!     config: &'_ mut MyConfig,
+//!     addr: repr_c::SocketAddr,
+//! )
+//! {
+//!     config.listen_addr = addr.into();
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! enum IpAddrKind {
+//!     IP_ADDR_KIND_V4 = 4,
+//!     IP_ADDR_KIND_V6 = 6,
+//! };
+//! typedef uint8_t IpAddrKind_t;
+//!
+//! typedef struct IpAddr {
+//!     IpAddrKind_t kind;
+//!     uint8_16_array_t octets;
+//! } IpAddr_t;
+//!
+//! typedef struct SocketAddr {
+//!     IpAddr_t ip;
+//!     uint16_t port;
+//!     uint32_t flowinfo;
+//!     uint32_t scope_id;
+//! } SocketAddr_t;
+//!
+//! void mylib_config_set_listen_addr (
+//!     MyConfig_t * config,
+//!     SocketAddr_t addr);
+//! ```
+
+use_prelude!();
+use ::std::net;
+
+/// An IPv4 address, as its 4 octets (in network order).
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+struct Ipv4Addr {
+    pub
+    octets: [u8; 4],
+}
+
+/// An IPv6 address, as its 16 octets (in network order).
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+struct Ipv6Addr {
+    pub
+    octets: [u8; 16],
+}
+
+/// The version of an [`IpAddr`].
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+enum IpAddrKind {
+    V4 = 4,
+    V6 = 6,
+}
+
+/// An IPv4 or IPv6 address.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+struct IpAddr {
+    pub
+    kind: IpAddrKind,
+
+    /// The 16 octets of an IPv6 address, or the 4 octets of an IPv4 one,
+    /// followed by zeros.
+    pub
+    octets: [u8; 16],
+}
+
+/// An IP address, and a port.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+struct SocketAddr {
+    pub
+    ip: IpAddr,
+
+    pub
+    port: u16,
+
+    /// The IPv6 flow information (ignored for an IPv4 address).
+    pub
+    flowinfo: u32,
+
+    /// The IPv6 scope identifier (ignored for an IPv4 address).
+    pub
+    scope_id: u32,
+}
+
+impl From<net::Ipv4Addr>
+    for Ipv4Addr
+{
+    #[inline]
+    fn from (it: net::Ipv4Addr)
+      -> Ipv4Addr
+    {
+        Self { octets: it.octets() }
+    }
+}
+
+impl From<Ipv4Addr>
+    for net::Ipv4Addr
+{
+    #[inline]
+    fn from (Ipv4Addr { octets }: Ipv4Addr)
+      -> net::Ipv4Addr
+    {
+        octets.into()
+    }
+}
+
+impl From<net::Ipv6Addr>
+    for Ipv6Addr
+{
+    #[inline]
+    fn from (it: net::Ipv6Addr)
+      -> Ipv6Addr
+    {
+        Self { octets: it.octets() }
+    }
+}
+
+impl From<Ipv6Addr>
+    for net::Ipv6Addr
+{
+    #[inline]
+    fn from (Ipv6Addr { octets }: Ipv6Addr)
+      -> net::Ipv6Addr
+    {
+        octets.into()
+    }
+}
+
+impl From<net::IpAddr>
+    for IpAddr
+{
+    fn from (it: net::IpAddr)
+      -> IpAddr
+    {
+        match it {
+            | net::IpAddr::V4(v4) => {
+                let mut octets = [0; 16];
+                octets[.. 4].copy_from_slice(&v4.octets());
+                Self { kind: IpAddrKind::V4, octets }
+            },
+            | net::IpAddr::V6(v6) => Self {
+                kind: IpAddrKind::V6,
+                octets: v6.octets(),
+            },
+        }
+    }
+}
+
+/// For an IPv4 address, the trailing 12 octets are ignored.
+impl From<IpAddr>
+    for net::IpAddr
+{
+    fn from (IpAddr { kind, octets }: IpAddr)
+      -> net::IpAddr
+    {
+        match kind {
+            | IpAddrKind::V4 => net::IpAddr::V4(net::Ipv4Addr::new(
+                octets[0], octets[1], octets[2], octets[3],
+            )),
+            | IpAddrKind::V6 => net::IpAddr::V6(octets.into()),
+        }
+    }
+}
+
+impl From<net::SocketAddr>
+    for SocketAddr
+{
+    fn from (it: net::SocketAddr)
+      -> SocketAddr
+    {
+        let (flowinfo, scope_id) = match it {
+            | net::SocketAddr::V4(_) => (0, 0),
+            | net::SocketAddr::V6(v6) => (v6.flowinfo(), v6.scope_id()),
+        };
+        Self {
+            ip: it.ip().into(),
+            port: it.port(),
+            flowinfo,
+            scope_id,
+        }
+    }
+}
+
+impl From<SocketAddr>
+    for net::SocketAddr
+{
+    fn from (SocketAddr { ip, port, flowinfo, scope_id }: SocketAddr)
+      -> net::SocketAddr
+    {
+        match net::IpAddr::from(ip) {
+            | net::IpAddr::V4(v4) => net::SocketAddrV4::new(v4, port).into(),
+            | net::IpAddr::V6(v6) => {
+                net::SocketAddrV6::new(v6, port, flowinfo, scope_id).into()
+            },
+        }
+    }
+}
@@ -0,0 +1,293 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! What to do with the panics reaching the FFI boundary, as configured by the
+//! host (_e.g._, to hand them over to its crash reporter).
+//!
+//! Such a panic either aborts the process (the default for the exported
+//! functions), or, with `#[ffi_export(catch_panics = <sentinel>)]`, is caught
+//! and makes the function return `<sentinel>` (see [the crate docs][crate]).
+//! [`set_ffi_panic_policy()`] refines this behavior:
+//!
+//!   - [`PanicPolicy::LogAndReturnDefault`], the default, logs the panic
+//!     message (through `::log` if its Cargo feature is enabled, else to
+//!     `stderr`);
+//!
+//!   - [`PanicPolicy::Abort`] also makes the `catch_panics = <sentinel>`
+//!     functions abort the process;
+//!
+//!   - [`PanicPolicy::Callback`] rather hands the message and the backtrace of
+//!     the panic over to a [`PanicCallback`], before returning the sentinel (or
+//!     aborting).
+//!
+//! [`ffi_export_panic_callback!`] exports the function with which the host
+//! registers its callback:
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_panic_callback!(mylib);
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct PanicCallback {
+//!     void * user_data;
+//!     void (*on_panic)(void *, char const *, char const *);
+//! } PanicCallback_t;
+//!
+//! /** \brief
+//!  *  Hands the message and the backtrace of the panics reaching the FFI
+//!  *  boundary over to `callback` (or, if `NULL`, logs them), before the
+//!  *  function returns its error value, or aborts.
+//!  */
+//! void mylib_set_panic_callback (
+//!     PanicCallback_t const * callback);
+//! ```
+//!
+//! [`ffi_export_panic_callback!`]: `crate::ffi_export_panic_callback`
+
+use_prelude!();
+use ::std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    sync::{Once, RwLock},
+};
+
+ReprC! {
+    #[repr(C)]
+    /// A host-provided crash-reporting callback, called (from the panicking
+    /// thread) with the message and the backtrace of a panic, which are only
+    /// valid for the duration of the call.
+    ///
+    /// # C layout
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     void * user_data;
+    ///     void (*on_panic)(void * user_data, char const * message, char const * backtrace);
+    /// } PanicCallback_t;
+    /// ```
+    #[derive(Clone, Copy)]
+    pub
+    struct PanicCallback {
+        user_data: *mut c_void,
+
+        on_panic: unsafe extern "C" fn (
+            user_data: *mut c_void,
+            message: char_p::Raw,
+            backtrace: char_p::Raw,
+        ),
+    }
+}
+
+/// The host, by handing the callback over, vouches for it being callable from
+/// any thread (see [`PanicCallback::new()`]).
+unsafe
+impl Send
+    for PanicCallback
+{}
+
+unsafe
+impl Sync
+    for PanicCallback
+{}
+
+impl fmt::Debug
+    for PanicCallback
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("PanicCallback")
+            .field("user_data", &self.user_data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PanicCallback {
+    /// # Safety
+    ///
+    ///   - `on_panic` may be called, with `user_data`, from any thread, and
+    ///     concurrently: both have to be thread-safe (_i.e._, `Send + Sync`);
+    ///
+    ///   - `user_data` has to remain valid for as long as the callback is
+    ///     registered (_i.e._, until another [`PanicPolicy`] is set);
+    ///
+    ///   - `on_panic` must not unwind.
+    pub
+    unsafe
+    fn new (
+        on_panic: unsafe extern "C" fn (
+            user_data: *mut c_void,
+            message: char_p::Raw,
+            backtrace: char_p::Raw,
+        ),
+        user_data: *mut c_void,
+    ) -> PanicCallback
+    {
+        PanicCallback { user_data, on_panic }
+    }
+
+    fn call (self: &'_ Self, message: &'_ str, backtrace: &'_ str)
+    {
+        // (interior nul bytes are replaced with `U+FFFD`)
+        let message = char_p::new(message.replace('\0', "\u{fffd}"));
+        let backtrace = char_p::new(backtrace.replace('\0', "\u{fffd}"));
+        unsafe {
+            (self.on_panic)(
+                self.user_data,
+                message.as_ref().into(),
+                backtrace.as_ref().into(),
+            )
+        }
+    }
+}
+
+/// What to do with the panics reaching the FFI boundary (see the
+/// [module docs][self]).
+#[derive(Debug, Clone, Copy, Default)]
+pub
+enum PanicPolicy {
+    /// Log the panic message, and abort the process, even when the function
+    /// has a `catch_panics` sentinel.
+    Abort,
+
+    /// Log the panic message, and return the `catch_panics` sentinel of the
+    /// function, if any, else abort the process.
+    #[default]
+    LogAndReturnDefault,
+
+    /// Hand the message and the backtrace of the panic over to the callback,
+    /// and return the `catch_panics` sentinel of the function, if any, else
+    /// abort the process.
+    Callback(PanicCallback),
+}
+
+static POLICY: RwLock<PanicPolicy> = RwLock::new(PanicPolicy::LogAndReturnDefault);
+
+::std::thread_local! {
+    /// The message and the backtrace of the last panic, as captured by the
+    /// panic hook (for the `Callback` policy).
+    static LAST_PANIC: RefCell<Option<(rust::String, rust::String)>> =
+        RefCell::new(None)
+    ;
+}
+
+fn policy ()
+  -> PanicPolicy
+{
+    *POLICY.read().unwrap_or_else(|it| it.into_inner())
+}
+
+/// Sets up what to do with the panics reaching the FFI boundary, from then
+/// on.
+///
+/// The [`PanicPolicy::Callback`] policy installs a panic hook (capturing the
+/// backtraces), which forwards to the previously installed one.
+pub
+fn set_ffi_panic_policy (policy: PanicPolicy)
+{
+    *POLICY.write().unwrap_or_else(|it| it.into_inner()) = policy;
+    if let PanicPolicy::Callback(_) = policy {
+        static HOOK: Once = Once::new();
+        HOOK.call_once(|| {
+            let prev_hook = ::std::panic::take_hook();
+            ::std::panic::set_hook(rust::Box::new(move |info| {
+                if let PanicPolicy::Callback(_) = self::policy() {
+                    let payload = info.payload();
+                    let message =
+                        payload.downcast_ref::<&'static str>().copied()
+                            .or_else(|| payload.downcast_ref::<rust::String>().map(|s| &**s))
+                            .unwrap_or("Box<dyn Any>")
+                    ;
+                    let message = match info.location() {
+                        | Some(location) => format!("{message} (at {location})"),
+                        | None => message.into(),
+                    };
+                    let backtrace = Backtrace::force_capture().to_string();
+                    LAST_PANIC.with(|it| *it.borrow_mut() = Some((message, backtrace)));
+                }
+                prev_hook(info)
+            }));
+        });
+    }
+}
+
+/// Reports a panic which reached the FFI boundary of `fname()`, as per the
+/// [`PanicPolicy`], aborting the process for [`PanicPolicy::Abort`].
+pub(in crate)
+fn report (fname: &'_ str, msg: &'_ str)
+{
+    let last_panic = LAST_PANIC.with(|it| it.borrow_mut().take());
+    let policy = policy();
+    match policy {
+        | PanicPolicy::Callback(callback) => match last_panic {
+            | Some((message, backtrace)) => callback.call(&message, &backtrace),
+            | None => callback.call(msg, ""),
+        },
+        | PanicPolicy::Abort | PanicPolicy::LogAndReturnDefault => {
+            crate::ඞ::__error__!("Error, `{fname}()` panicked: {msg}");
+        },
+    }
+    if let PanicPolicy::Abort = policy {
+        ::std::process::abort();
+    }
+}
+
+/// Hands a panic which is about to abort the process (for having reached the
+/// FFI boundary uncaught) over to the [`PanicPolicy::Callback`], if any.
+pub(in crate)
+fn report_uncaught ()
+{
+    let last_panic = LAST_PANIC.with(|it| it.borrow_mut().take());
+    if let (PanicPolicy::Callback(callback), Some((message, backtrace))) = (policy(), last_panic) {
+        callback.call(&message, &backtrace);
+    }
+}
+
+/// Defines and exports `<prefix>_set_panic_callback()` (see the
+/// [`panic` module][`crate::panic`]).
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_panic_callback!(mylib);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  Hands the message and the backtrace of the panics reaching the FFI
+///  *  boundary over to `callback` (or, if `NULL`, logs them), before the
+///  *  function returns its error value, or aborts.
+///  */
+/// void mylib_set_panic_callback (
+///     PanicCallback_t const * callback);
+/// ```
+///
+/// The generated function is `unsafe`, since the host vouches, by calling it,
+/// for the contract of [`PanicCallback::new()`][`crate::panic::PanicCallback::new`].
+#[macro_export]
+macro_rules! ffi_export_panic_callback {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Hands the message and the backtrace of the panics reaching the FFI
+    /// boundary over to `callback` (or, if `NULL`, logs them), before the
+    /// function returns its error value, or aborts.
+    ///
+    /// `on_panic` may be called from any thread, concurrently, along with
+    /// `user_data`: both thus have to be thread-safe, and `user_data` has to
+    /// remain valid until this function is called again.
+    #[allow(nonstandard_style)]
+    pub
+    unsafe
+    fn [<$prefix _set_panic_callback>] (
+        callback: $crate::ඞ::Option<&'_ $crate::panic::PanicCallback>,
+    )
+    {
+        $crate::panic::set_ffi_panic_policy(match callback {
+            | $crate::ඞ::Some(&callback) => {
+                $crate::panic::PanicPolicy::Callback(callback)
+            },
+            | $crate::ඞ::None => $crate::panic::PanicPolicy::LogAndReturnDefault,
+        })
+    }
+})}
@@ -96,6 +96,26 @@ fn ffi_export (attrs: TokenStream, input: TokenStream)
     )
 }
 
+#[proc_macro_attribute] pub
+fn ffi_export_accessors (attrs: TokenStream, input: TokenStream)
+  -> TokenStream
+{
+    unwrap!(
+        ffi_export::ffi_export_accessors(attrs.into(), input.into())
+            .map(utils::mb_file_expanded)
+    )
+}
+
+#[proc_macro_attribute] pub
+fn ffi_export_json (attrs: TokenStream, input: TokenStream)
+  -> TokenStream
+{
+    unwrap!(
+        ffi_export::ffi_export_json(attrs.into(), input.into())
+            .map(utils::mb_file_expanded)
+    )
+}
+
 #[proc_macro_attribute] pub
 fn derive_ReprC (
     attrs: TokenStream,
@@ -12,6 +12,9 @@ mod js;
 pub(in crate)
 mod struct_;
 
+pub(in crate)
+mod union_;
+
 pub(in crate)
 fn derive (
     args: TokenStream2,
@@ -43,10 +46,20 @@ fn derive (
                 did you mean to implement `ReprC` instead?\
             " => enum_token
         },
-        | Data::Union(DataUnion { union_token, .. }) => bail! {
-            "`union`s are not supported yet" => union_token
-        },
+        | Data::Union(DataUnion { fields, .. }) => union_::derive(
+            args,
+            attrs,
+            ident,
+            generics,
+            fields,
+        ),
     }?;
+    // The generated items may refer to the type itself.
+    let ret = if utils::deprecation_note(attrs)?.is_some() {
+        utils::allow_deprecated(ret)?
+    } else {
+        ret
+    };
     Ok(quote!(
         #input
 
@@ -59,6 +59,9 @@ fn derive (
         let ref EachFieldTy =
             fields.iter().vmap(|Field { ty, .. }| ty)
         ;
+        let EachFieldName =
+            fields.iter().map(|Field { ident, .. }| ident)
+        ;
         let ref StructName_str =
             args.rename.map_or_else(
                 || StructName.to_string().into_token_stream(),
@@ -88,6 +91,37 @@ fn derive (
 
         let ref struct_docs = utils::extract_docs(attrs)?;
 
+        if let Some(note) = utils::deprecation_note(attrs)? {
+            impl_body.extend(quote!(
+                fn deprecated ()
+                  -> #ඞ::Option<&'static #ඞ::str>
+                {
+                    #ඞ::Some(#note)
+                }
+            ));
+        }
+
+        let layout = {
+            let (packed, align) = packing_of(attrs)?;
+            let each_field_layout = quote!([#(
+                < #EachFieldTy as #CType >::layout_on(target),
+            )*]);
+            let layout = match packed {
+                | Some(n) => quote!(
+                    #headers::TypeLayout::of_packed_struct(#each_field_layout, #n)
+                ),
+                | None => quote!(
+                    #headers::TypeLayout::of_struct(#each_field_layout)
+                ),
+            };
+            match align {
+                | Some(n) => quote!(
+                    #ඞ::Option::map(#layout, |it| it.aligned_to(#n))
+                ),
+                | None => layout,
+            }
+        };
+
         let ref each_field: Vec<Quote![ StructField ]> =
             (0..).zip(fields).try_vmap(|(i, f)| Result::Ok({
                 let ref field_docs = utils::extract_docs(&f.attrs)?;
@@ -116,13 +150,43 @@ fn derive (
             #(
                 < #EachFieldTy as #CType >::define_self(language, definer)?;
             )*
-                language.emit_struct(
+                #ඞ::__with_doxygen_group__(
                     definer,
+                    language,
                     &[#(#struct_docs),*],
-                    &#ඞ::marker::PhantomData::<Self>,
-                    &[#(#each_field),*],
+                    #ඞ::module_path!(),
+                    #ඞ::file!(),
+                    #ඞ::env!("CARGO_MANIFEST_DIR"),
+                    &mut |definer: &'_ mut dyn #headers::Definer, docs: &'_ [&'_ #ඞ::str]| {
+                        language.emit_struct(
+                            definer,
+                            docs,
+                            &#ඞ::marker::PhantomData::<Self>,
+                            &[#(#each_field),*],
+                        )
+                    },
                 )
             }
+
+            fn layout_on (
+                target: &'_ #headers::Target,
+            ) -> #ඞ::Option<#headers::TypeLayout>
+            {
+                #layout
+            }
+
+            fn field_offsets ()
+              -> #ඞ::Option<#ඞ::Vec<#ඞ::usize>>
+            {
+                let it = #ඞ::mem::MaybeUninit::<Self>::uninit();
+                let base = it.as_ptr();
+                #ඞ::Some(#ඞ::Vec::from([#(
+                    // Safety: in-bounds projection, with no reads nor references
+                    // (so that `packed` fields are fine).
+                    unsafe { #ඞ::core::ptr::addr_of!((*base).#EachFieldName) }
+                        as #ඞ::usize - base as #ඞ::usize
+                ),*]))
+            }
         ));
     }
 
@@ -190,3 +254,31 @@ fn derive (
 
     Ok(ret)
 }
+
+/// The `N`s of the `#[repr(packed(N))]` and `#[repr(align(N))]` of a struct.
+fn packing_of (attrs: &'_ [Attribute])
+  -> Result<(Option<LitInt>, Option<LitInt>)>
+{
+    let mut packed = None;
+    let mut align = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        let metas = attr.parse_args_with(
+            Punctuated::<Meta, Token![,]>::parse_terminated,
+        )?;
+        for meta in metas {
+            let n = match &meta {
+                | Meta::List(MetaList { nested, .. }) => match nested.first() {
+                    | Some(NestedMeta::Lit(Lit::Int(n))) => Some(n.clone()),
+                    | _ => bail!("expected an integer" => meta),
+                },
+                | _ => None,
+            };
+            if meta.path().is_ident("packed") {
+                packed = Some(n.unwrap_or_else(|| parse_quote!(1)));
+            } else if meta.path().is_ident("align") {
+                align = n;
+            }
+        }
+    }
+    Ok((packed, align))
+}
@@ -0,0 +1,192 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+pub(in crate)
+fn derive (
+    args: Args,
+    attrs: &'_ [Attribute],
+    UnionName @ _: &'_ Ident,
+    generics: &'_ Generics,
+    fields: &'_ FieldsNamed,
+) -> Result<TokenStream2>
+{
+    if let Some(js) = &args.js {
+        bail! {
+            "`js` is not supported for `union`s" => js,
+        }
+    }
+
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        headers,
+        layout::{
+            CLayoutOf,
+            CType as CType,
+            OpaqueKind,
+            ReprC,
+        },
+    };
+
+    let mut impl_body = quote!(
+        type OPAQUE_KIND = #OpaqueKind::Concrete;
+    );
+
+    if cfg!(feature = "headers") {
+        let EachGenericTy =
+            generics.type_params().map(|it| &it.ident)
+        ;
+        let ref EachFieldTy =
+            fields.named.iter().vmap(|Field { ty, .. }| ty)
+        ;
+        let ref UnionName_str =
+            args.rename.map_or_else(
+                || UnionName.to_string().into_token_stream(),
+                ToTokens::into_token_stream,
+            )
+        ;
+
+        impl_body.extend(quote!(
+            fn short_name ()
+              -> #ඞ::String
+            {
+                let mut _ret =
+                    <#ඞ::String as #ඞ::From<_>>::from(#UnionName_str)
+                ;
+                #(
+                    #ඞ::fmt::Write::write_fmt(
+                        &mut _ret,
+                        #ඞ::format_args!(
+                            "_{}",
+                            <#CLayoutOf<#EachGenericTy> as #CType>::short_name(),
+                        ),
+                    ).unwrap();
+                )*
+                _ret
+            }
+        ));
+
+        let ref union_docs = utils::extract_docs(attrs)?;
+
+        if let Some(note) = utils::deprecation_note(attrs)? {
+            impl_body.extend(quote!(
+                fn deprecated ()
+                  -> #ඞ::Option<&'static #ඞ::str>
+                {
+                    #ඞ::Some(#note)
+                }
+            ));
+        }
+
+        let ref each_field: Vec<Quote![ StructField ]> =
+            fields.named.iter().try_vmap(|f| Result::Ok({
+                let ref field_docs = utils::extract_docs(&f.attrs)?;
+                let ref field_name_str = f.ident.as_ref().unwrap().to_string();
+                let FieldTy = &f.ty;
+                quote!(
+                    #ඞ::StructField {
+                        docs: &[#(#field_docs),*],
+                        name: #field_name_str,
+                        ty: &#ඞ::marker::PhantomData::<#FieldTy>,
+                    }
+                )
+            }))?
+        ;
+
+        impl_body.extend(quote_spanned!(Span::mixed_site()=>
+            #[allow(nonstandard_style)]
+            fn define_self__impl (
+                language: &'_ dyn #headers::languages::HeaderLanguage,
+                definer: &'_ mut dyn #headers::Definer,
+            ) -> #ඞ::io::Result<()>
+            {
+            #(
+                < #EachFieldTy as #CType >::define_self(language, definer)?;
+            )*
+                #ඞ::__with_doxygen_group__(
+                    definer,
+                    language,
+                    &[#(#union_docs),*],
+                    #ඞ::module_path!(),
+                    #ඞ::file!(),
+                    #ඞ::env!("CARGO_MANIFEST_DIR"),
+                    &mut |definer: &'_ mut dyn #headers::Definer, docs: &'_ [&'_ #ඞ::str]| {
+                        language.emit_union(
+                            definer,
+                            docs,
+                            &#ඞ::marker::PhantomData::<Self>,
+                            &[#(#each_field),*],
+                        )
+                    },
+                )
+            }
+
+            fn layout_on (
+                target: &'_ #headers::Target,
+            ) -> #ඞ::Option<#headers::TypeLayout>
+            {
+                #headers::TypeLayout::of_union([#(
+                    < #EachFieldTy as #CType >::layout_on(target),
+                )*])
+            }
+        ));
+    }
+
+    let (intro_generics, fwd_generics, where_clauses) =
+        generics.split_for_impl()
+    ;
+
+    Ok(quote!(
+        impl #intro_generics
+            #ඞ::Clone
+        for
+            #UnionName #fwd_generics
+        #where_clauses
+        {
+            #[inline]
+            fn clone (self: &'_ Self)
+              -> Self
+            {
+                *self
+            }
+        }
+
+        impl #intro_generics
+            #ඞ::Copy
+        for
+            #UnionName #fwd_generics
+        #where_clauses
+        {}
+
+        unsafe
+        impl #intro_generics
+            #CType
+        for
+            #UnionName #fwd_generics
+        #where_clauses
+        {
+            #impl_body
+        }
+
+        // If it is CType, it trivially is ReprC.
+        // (Which member is active is up to the user to know.)
+        unsafe
+        impl #intro_generics
+            #ReprC
+        for
+            #UnionName #fwd_generics
+        #where_clauses
+        {
+            type CLayout = Self;
+
+            #[inline]
+            fn is_valid (
+                _: &'_ Self::CLayout,
+            ) -> #ඞ::bool
+            {
+                true
+            }
+        }
+    ))
+}
@@ -87,7 +87,7 @@ fn try_handle_trait (
         ident: ref TraitName @ _,
         ref generics,
         colon_token: _,
-        supertraits: _,
+        ref supertraits,
         brace_token: _,
         ref mut items,
     } = *trait_;
@@ -154,10 +154,32 @@ fn try_handle_trait (
 
     let if_retain = args.clone.is_some().kleenable();
 
+    // `Send` / `Sync` supertraits: documented as guarantees in the headers.
+    let mut vtable_docs = vec![];
+    for supertrait in supertraits {
+        let Super @ _ = match supertrait {
+            | TypeParamBound::Lifetime(_) => continue,
+            | TypeParamBound::Trait(TraitBound { path, .. }) => {
+                path.segments.last().unwrap().ident.to_string()
+            },
+        };
+        match &Super[..] {
+            | "Send" => vtable_docs.push(
+                " `Send`: the object behind the vtable may be used, and released, from any thread."
+            ),
+            | "Sync" => vtable_docs.push(
+                " `Sync`: the `&self` methods of the object behind the vtable may be called concurrently, from several threads."
+            ),
+            // Other supertraits are not reflected in the vtable (nor rejected).
+            | _ => {},
+        }
+    }
+
     // Emit the vtable type definition
     let vtable_def = squote!(
         #[#ඞ::derive_ReprC]
         #[repr(C)]
+        #(#[doc = #vtable_docs])*
         #pub_
         struct #VTableName #intro_trait_generics_and_lt
         #trait_where_clause
@@ -428,11 +450,52 @@ fn try_handle_trait (
         ));
     }
     drop(each_vtable_entry_value_f);
+    // For the C# backend to emit a base class implementing the trait.
+    let mut dyn_trait_def = TokenStream2::new();
+    if cfg!(feature = "headers")
+    && cfg!(feature = "explicit-registration").not()
+    && generics.params.is_empty()
+    {
+        let trait_name_str = TraitName.to_string();
+        let each_dyn_trait_method = each_vtable_entry.vmap(VTableEntry::dyn_trait_method);
+        let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
+            quote!( #![crate = #ඞ] )
+        });
+        // (`quote!`, for the `lang` closure parameters to be visible to the
+        // `each_dyn_trait_method`.)
+        dyn_trait_def = quote!(
+            #[cfg(not(target_arch = "wasm32"))]
+            #ඞ::inventory::submit! {
+                #inventory_krate
+
+                #ඞ::DynTrait {
+                    name: #trait_name_str,
+                    vtable: |lang| <
+                        #ඞ::CLayoutOf<#VTableName<'static>> as #ඞ::CType
+                    >::name(lang),
+                    virtual_ptr: |lang| <
+                        #ඞ::CLayoutOf<#VirtualPtr<dyn 'static + #TraitName>> as #ඞ::CType
+                    >::name(lang),
+                    owned_ptr: |lang| <
+                        #ඞ::CLayoutOf<#ඞ::ptr::NonNullOwned<#ErasedTy>> as #ඞ::CType
+                    >::name(lang),
+                    ref_ptr: |lang| <
+                        #ඞ::CLayoutOf<#ඞ::ptr::NonNullRef<#ErasedTy>> as #ඞ::CType
+                    >::name(lang),
+                    methods: |lang| #ඞ::Vec::from([#(
+                        #each_dyn_trait_method,
+                    )*]),
+                }
+            }
+        );
+    }
     ret = squote!(
         #trait_
 
         #vtable_def
 
+        #dyn_trait_def
+
         #[allow(warnings, clippy::all)]
         const _: () = {
             #ret
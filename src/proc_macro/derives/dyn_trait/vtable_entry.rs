@@ -13,6 +13,9 @@ enum VTableEntry<'trait_> {
         ErasedSelf: Type,
         EachArgTy: Vec<&'trait_ Type>,
         OutputTy: &'trait_ [Type],
+        /// Whether the method returns an `impl Future<Output = OutputTy>` (or a
+        /// `Pin<Box<dyn Future<Output = OutputTy>>>`), which crosses the FFI boundary as a `repr_c::Box<repr_c::Future<_>>`.
+        is_async: bool,
     },
 }
 
@@ -41,7 +44,8 @@ impl<'trait_> VTableEntry<'trait_> {
                 ref each_arg_name,
                 ErasedSelf: _,
                 EachArgTy: _,
-                OutputTy: _,
+                OutputTy,
+                is_async,
                 src: TraitItemMethod {
                     sig: ref full_signature,
                     ref attrs,
@@ -63,20 +67,81 @@ impl<'trait_> VTableEntry<'trait_> {
                         | _ => unreachable!(),
                     })
                 ;
+                let mut body = quote!(
+                    unsafe {
+                        ::core::mem::transmute(
+                            (self.__vtable().#name)(
+                                // FIXME: use traits to feature .into_raw() / .from_raw()
+                                ::core::mem::transmute(self.__ptr()), #(
+                                ::core::mem::transmute(#each_arg_name), )*
+                            )
+                        )
+                    }
+                );
+                if is_async {
+                    let T @ _ = &OutputTy[0];
+                    body = quote!(
+                        ::safer_ffi::future::Future::<#T>::__into_std(#body)
+                    );
+                    if matches!(
+                        full_signature.output,
+                        ReturnType::Type(_, ref ty) if matches!(**ty, Type::ImplTrait(_))
+                    ).not()
+                    {
+                        body = quote!(
+                            ::safer_ffi::ඞ::boxed::Box::pin(#body)
+                        );
+                    }
+                }
                 quote!(
                     #(#attrs)*
                     #[inline]
                     #signature
                     {
-                        unsafe {
-                            ::core::mem::transmute(
-                                (self.__vtable().#name)(
-                                    // FIXME: use traits to feature .into_raw() / .from_raw()
-                                    ::core::mem::transmute(self.__ptr()), #(
-                                    ::core::mem::transmute(#each_arg_name), )*
-                                )
-                            )
-                        }
+                        #body
+                    }
+                )
+            },
+        }
+    }
+
+    /// The `DynTraitMethod` description of the entry, for the C# backend.
+    pub(in super)
+    fn dyn_trait_method (
+        self: &'_ VTableEntry<'trait_>,
+    ) -> TokenStream2
+    {
+        match *self {
+            | Self::VirtualMethod {
+                name,
+                ref ErasedSelf,
+                ref EachArgTy,
+                OutputTy,
+                is_async,
+                ref src,
+                ..
+            } => {
+                let name_str = name.to_string();
+                let SelfTy @ _ = CType(ErasedSelf);
+                let each_arg_str = src.sig.inputs.iter().skip(1).enumerate().vmap(|(i, fn_arg)| {
+                    match fn_arg {
+                        | FnArg::Typed(PatType { pat, .. }) => match &**pat {
+                            | Pat::Ident(PatIdent { ident, .. }) => ident.to_string(),
+                            | _ => format!("arg{}", i + 1),
+                        },
+                        | FnArg::Receiver(_) => unreachable!(),
+                    }
+                });
+                let EachArgTy @ _ = EachArgTy.iter().copied().vmap(CType);
+                let OutputTy @ _ = c_output_ty(OutputTy, is_async);
+                quote!(
+                    ::safer_ffi::ඞ::DynTraitMethod {
+                        name: #name_str,
+                        self_ty: <#SelfTy as ::safer_ffi::ඞ::CType>::name(lang),
+                        args: ::safer_ffi::ඞ::Vec::from([#(
+                            (#each_arg_str, <#EachArgTy as ::safer_ffi::ඞ::CType>::name(lang)),
+                        )*]),
+                        ret: <#OutputTy as ::safer_ffi::ඞ::CType>::name(lang),
                     }
                 )
             },
@@ -118,12 +183,14 @@ impl<'trait_> VTableEntry<'trait_> {
                 ErasedSelf,
                 EachArgTy,
                 OutputTy,
+                is_async,
                 src: _,
                 receiver: _,
             } => {
                 let span = Span::mixed_site().located_at(name.span());
+                let is_async = *is_async;
                 let EachArgTy @ _ = EachArgTy.iter().copied().vmap(CType);
-                let OutputTy @ _ = CType(OutputTy.get(0).unwrap_or(&parse_quote!( () )));
+                let OutputTy @ _ = c_output_ty(OutputTy, is_async);
                 let type_ = quote_spanned!(span=>
                     for<#(#each_for_lifetime),*>
                     unsafe
@@ -192,6 +259,21 @@ impl<'trait_> VTableEntry<'trait_> {
                             vfn_generics.type_params().map(|it| &it.ident),
                             vfn_generics.const_params().map(|it| &it.ident),
                         );
+                        let mut call = quote_spanned!(span=>
+                            #QSelf::#name(
+                                ::core::mem::transmute(__this) #(,
+                                ::safer_ffi::layout::from_raw_unchecked(
+                                    #each_arg_name
+                                ) )*
+                            )
+                        );
+                        if is_async {
+                            // The borrows of the future are erased: it is up
+                            // to the caller not to outlive them.
+                            call = quote_spanned!(span=>
+                                ::safer_ffi::future::Future::__new_unchecked(#call)
+                            );
+                        }
                         quote_spanned!(span=> {
                             unsafe
                             extern "C"
@@ -202,12 +284,7 @@ impl<'trait_> VTableEntry<'trait_> {
                             #where_clause
                             {
                                 // ::safer_ffi::layout::into_raw(
-                                ::core::mem::transmute(#QSelf::#name(
-                                    ::core::mem::transmute(__this) #(,
-                                    ::safer_ffi::layout::from_raw_unchecked(
-                                        #each_arg_name
-                                    ) )*
-                                ))
+                                ::core::mem::transmute(#call)
                             }
 
                             #name ::< #(#fwd_generics),* > // as #type_
@@ -227,6 +304,33 @@ fn vtable_entries<'trait_> (
 ) -> Result<Vec<VTableEntry<'trait_>>>
 {
     use ::quote::format_ident as ident;
+    // `async fn`s and `-> impl Future` methods are not `dyn`-safe: desugar
+    // the former into the latter, and opt them out of `dyn Trait` (the
+    // `VirtualPtr<dyn Trait>`, being `Sized`, still gets to implement them).
+    // (Both require Rust 1.75.0; `-> Pin<Box<dyn Future…>>` methods, on the
+    // other hand, are `dyn`-safe as-is.)
+    for it in trait_items.iter_mut() {
+        if let TraitItem::Method(TraitItemMethod { sig, .. }) = it {
+            if let Some(async_) = sig.asyncness.take() {
+                let OutputTy @ _ = match &sig.output {
+                    | ReturnType::Type(_, ty) => ty.to_token_stream(),
+                    | ReturnType::Default => quote!( () ),
+                };
+                sig.output = parse_quote_spanned!(async_.span=>
+                    -> impl ::core::future::Future<Output = #OutputTy>
+                        + ::core::marker::Send
+                );
+            }
+            if  matches!(sig.output, ReturnType::Type(_, ref ty) if matches!(**ty, Type::ImplTrait(_)))
+            &&  future_output(&sig.output)?.is_some()
+            {
+                sig.generics.make_where_clause().predicates.push(parse_quote!(
+                    Self : ::core::marker::Sized
+                ));
+            }
+        }
+    }
+    let trait_items: &'trait_ [TraitItem] = trait_items;
     // let mut Sized @ _ = None;
     // let mut skip_attrs_found = vec![];
     macro_rules! failwith {( $err_msg:expr => $at:expr $(,)? ) => (
@@ -235,7 +339,7 @@ fn vtable_entries<'trait_> (
     // macro_rules! continue_ {() => (
     //     return None
     // )}
-    trait_items.iter_mut().filter_map(|it| Some(Result::Ok(match *it {
+    trait_items.iter().filter_map(|it| Some(Result::Ok(match *it {
         | TraitItem::Method(ref trait_item_method @ TraitItemMethod {
             attrs: _,
             sig: ref sig @ Signature {
@@ -385,10 +489,13 @@ fn vtable_entries<'trait_> (
                         })
                         .collect()
                 ,
-                OutputTy: match RetTy {
-                    | ReturnType::Type(_, it) => ::core::slice::from_ref(it),
-                    | ReturnType::Default => &[],
+                OutputTy: match (future_output(RetTy), RetTy) {
+                    | (Err(err), _) => return Some(Err(err)),
+                    | (Ok(Some(T @ _)), _) => ::core::slice::from_ref(T),
+                    | (Ok(None), ReturnType::Type(_, it)) => ::core::slice::from_ref(it),
+                    | (Ok(None), ReturnType::Default) => &[],
                 },
+                is_async: matches!(future_output(RetTy), Ok(Some(_))),
                 receiver,
                 src: trait_item_method,
             }
@@ -435,3 +542,88 @@ fn vtable_entries<'trait_> (
     })))
     .collect()
 }
+
+/// The C layout of the return type of the vtable entry.
+fn c_output_ty (
+    OutputTy @ _: &'_ [Type],
+    is_async: bool,
+) -> TokenStream2
+{
+    match OutputTy.get(0) {
+        | Some(T @ _) if is_async => CType(&parse_quote!(
+            ::safer_ffi::prelude::repr_c::Box<
+                ::safer_ffi::prelude::repr_c::Future<#T>
+            >
+        )),
+        | mb_T => CType(mb_T.unwrap_or(&parse_quote!( () ))),
+    }
+}
+
+/// The `T` in `-> impl Future<Output = T> + Send` (or in
+/// `-> Pin<Box<dyn Future<Output = T> + Send>>`), if any.
+fn future_output (
+    RetTy @ _: &'_ ReturnType,
+) -> Result<Option<&'_ Type>>
+{
+    /// The `T` in `<Name><T>`.
+    fn generic_arg<'r> (
+        path: &'r Path,
+        name: &'_ str,
+    ) -> Option<&'r Type>
+    {
+        match path.segments.last()? {
+            | PathSegment {
+                ident,
+                arguments: PathArguments::AngleBracketed(args),
+            } if ident == name && args.args.len() == 1 => match &args.args[0] {
+                | GenericArgument::Type(ty) => Some(ty),
+                | _ => None,
+            },
+            | _ => None,
+        }
+    }
+
+    let bounds = match RetTy {
+        | ReturnType::Type(_, ty) => match &**ty {
+            | Type::ImplTrait(TypeImplTrait { bounds, .. }) => bounds,
+            | Type::Path(TypePath { qself: None, path }) => {
+                match generic_arg(path, "Pin") {
+                    | Some(Type::Path(TypePath { qself: None, path })) => {
+                        match generic_arg(path, "Box") {
+                            | Some(Type::TraitObject(TypeTraitObject { bounds, .. })) => bounds,
+                            | _ => return Ok(None),
+                        }
+                    },
+                    | _ => return Ok(None),
+                }
+            },
+            | _ => return Ok(None),
+        },
+        | ReturnType::Default => return Ok(None),
+    };
+    let find_bound = |name: &str| bounds.iter().find_map(|bound| match bound {
+        | TypeParamBound::Trait(TraitBound { path, .. }) => {
+            path.segments.last().filter(|it| it.ident == name)
+        },
+        | _ => None,
+    });
+    let Future @ _ = match find_bound("Future") {
+        | Some(it) => it,
+        | None => return Ok(None),
+    };
+    if find_bound("Send").is_none() {
+        bail! {
+            "`dyn` methods returning a `Future` must make it `+ Send`"
+                => RetTy
+        }
+    }
+    match &Future.arguments {
+        | PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            | GenericArgument::Binding(Binding { ident, ty, .. }) if ident == "Output" => Some(ty),
+            | _ => None,
+        }),
+        | _ => None,
+    }
+    .map(Some)
+    .ok_or_else(|| Error::new_spanned(Future, "expected `Future<Output = …>`"))
+}
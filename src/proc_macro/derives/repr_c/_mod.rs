@@ -8,6 +8,8 @@ mod args;
 pub(in crate)
 mod enum_;
 
+mod frozen;
+
 #[cfg(feature = "js")]
 pub(in crate)
 mod js;
@@ -15,6 +17,9 @@ mod js;
 pub(in crate)
 mod struct_;
 
+pub(in crate)
+mod union_;
+
 pub(in crate)
 fn derive (
     attrs: TokenStream2,
@@ -34,7 +39,45 @@ fn derive (
         ref generics,
         ref data,
     } = input;
+    let is_opaque = args.opaque.is_some() || attrs.iter().any(|attr| {
+        attr.path.is_ident("repr")
+        && matches!(attr.parse_args::<Ident>(), Ok(repr) if repr == "opaque")
+    });
+    if is_opaque.not() {
+        let extraneous: Option<&dyn ToTokens> = match (&args.auto_drop, &args.auto_clone) {
+            | (Some(it), _) => Some(it),
+            | (_, Some(it)) => Some(it),
+            | _ => None,
+        };
+        if let Some(extraneous) = extraneous {
+            bail! {
+                "`auto_drop` and `auto_clone` are only supported for `#[repr(opaque)]` types"
+                    => extraneous
+            }
+        }
+    }
+    if let Some(is_valid) = &args.is_valid {
+        if args.opaque.is_some() || matches!(data, Data::Struct(_)).not() {
+            bail! {
+                "`is_valid` is only supported for `#[repr(C)]` structs"
+                    => is_valid
+            }
+        }
+    }
     let ret = match *data {
+        | _ if args.opaque.is_some() && args.frozen.is_some() => bail! {
+            "`frozen` is only supported for `#[repr(C)]` structs"
+                => args.frozen
+        },
+        // The Rust layout is left as is (and private): only a forward
+        // declaration of the type is ever emitted.
+        | _ if args.opaque.is_some() => struct_::derive_opaque(
+            args,
+            attrs,
+            vis,
+            ident,
+            generics,
+        ),
         | Data::Struct(DataStruct { ref fields, .. }) => struct_::derive(
             args,
             attrs,
@@ -43,6 +86,10 @@ fn derive (
             generics,
             fields,
         ),
+        | Data::Enum(_) if args.frozen.is_some() => bail! {
+            "`frozen` is only supported for `#[repr(C)]` structs"
+                => args.frozen
+        },
         | Data::Enum(DataEnum { ref variants, .. }) => enum_::derive(
             args,
             attrs,
@@ -51,10 +98,21 @@ fn derive (
             generics,
             variants,
         ),
-        | Data::Union(DataUnion { ref union_token, .. }) => bail! {
-            "`union`s are not supported yet" => union_token
-        },
+        | Data::Union(DataUnion { ref fields, .. }) => union_::derive(
+            args,
+            attrs,
+            vis,
+            ident,
+            generics,
+            fields,
+        ),
     }?;
+    // The generated items may refer to the type itself.
+    let ret = if utils::deprecation_note(&input.attrs)?.is_some() {
+        utils::allow_deprecated(ret)?
+    } else {
+        ret
+    };
     Ok(quote!(
         #input
 
@@ -3,7 +3,12 @@
 use super::*;
 
 mod kw {
+    ::syn::custom_keyword!(auto_clone);
+    ::syn::custom_keyword!(auto_drop);
+    ::syn::custom_keyword!(frozen);
+    ::syn::custom_keyword!(is_valid);
     ::syn::custom_keyword!(js);
+    ::syn::custom_keyword!(opaque);
     ::syn::custom_keyword!(rename);
 }
 
@@ -14,6 +19,23 @@ struct Args {
 
     pub(in crate)
     js: Option<kw::js>,
+
+    pub(in crate)
+    frozen: Option<kw::frozen>,
+
+    pub(in crate)
+    auto_drop: Option<kw::auto_drop>,
+
+    pub(in crate)
+    auto_clone: Option<kw::auto_clone>,
+
+    pub(in crate)
+    opaque: Option<kw::opaque>,
+
+    /// An extra validity check of the whole C layout (_e.g._, of invariants
+    /// across fields), on top of the fields' own ones.
+    pub(in crate)
+    is_valid: Option<Path>,
 }
 
 impl Parse for Args {
@@ -23,6 +45,11 @@ impl Parse for Args {
         let mut ret = Args {
             js: None,
             rename: None,
+            frozen: None,
+            auto_drop: None,
+            auto_clone: None,
+            opaque: None,
+            is_valid: None,
         };
 
         while input.is_empty().not() {
@@ -40,6 +67,33 @@ impl Parse for Args {
                         return Err(input.error("duplicate attribute"));
                     }
                 },
+                | _case if snoopy.peek(kw::frozen) => {
+                    if ret.frozen.replace(input.parse().unwrap()).is_some() {
+                        return Err(input.error("duplicate attribute"));
+                    }
+                },
+                | _case if snoopy.peek(kw::auto_drop) => {
+                    if ret.auto_drop.replace(input.parse().unwrap()).is_some() {
+                        return Err(input.error("duplicate attribute"));
+                    }
+                },
+                | _case if snoopy.peek(kw::auto_clone) => {
+                    if ret.auto_clone.replace(input.parse().unwrap()).is_some() {
+                        return Err(input.error("duplicate attribute"));
+                    }
+                },
+                | _case if snoopy.peek(kw::opaque) => {
+                    if ret.opaque.replace(input.parse().unwrap()).is_some() {
+                        return Err(input.error("duplicate attribute"));
+                    }
+                },
+                | _case if snoopy.peek(kw::is_valid) => {
+                    let _: kw::is_valid = input.parse().unwrap();
+                    let _: Token![=] = input.parse()?;
+                    if ret.is_valid.replace(input.parse()?).is_some() {
+                        return Err(input.error("duplicate attribute"));
+                    }
+                },
                 | _default => return Err(snoopy.error()),
             }
             let _: Option<Token![,]> = input.parse()?;
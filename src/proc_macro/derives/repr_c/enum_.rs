@@ -33,19 +33,6 @@ fn derive (
 
     let mut ret = quote!();
 
-    if let Some(payload) =
-        variants
-            .iter()
-            .find(|Variant { fields, .. }| matches!(
-                fields,
-                Fields::Unit,
-            ).not())
-    {
-        bail! {
-            "Non field-less `enum`s are not supported yet." => payload.fields,
-        }
-    }
-
     if let Some(param) = generics.params.first() {
         bail! {
             "generic `enum`s are not supported yet." => param,
@@ -57,6 +44,20 @@ fn derive (
         }
     }
 
+    if variants.iter().any(|Variant { fields, .. }| matches!(
+        fields,
+        Fields::Unit,
+    ).not())
+    {
+        return derive_tagged_union(
+            args,
+            attrs,
+            pub_,
+            EnumName,
+            variants,
+        );
+    }
+
     if variants.is_empty() {
         bail! {
             "C does not support empty enums!"
@@ -122,6 +123,8 @@ fn derive (
             ඞ::fmt,
             headers::{
                 Definer,
+                Target,
+                TypeLayout,
                 languages::{
                     HeaderLanguage,
                     EnumVariant,
@@ -135,6 +138,16 @@ fn derive (
                 ToTokens::into_token_stream,
             )
         ;
+        if let Some(note) = utils::deprecation_note(attrs)? {
+            impl_body.extend(quote!(
+                fn deprecated ()
+                  -> #ඞ::Option<&'static #ඞ::str>
+                {
+                    #ඞ::Some(#note)
+                }
+            ));
+        }
+
         let ref each_enum_variant =
             variants.try_vmap(|v| Result::Ok({
                 let ref VariantName_str = v.ident.to_string();
@@ -172,14 +185,31 @@ fn derive (
             ) -> #ඞ::io::Result<()>
             {
                 <#Int as #CType>::define_self(language, definer)?;
-                language.emit_simple_enum(
+                #ඞ::__with_doxygen_group__(
                     definer,
+                    language,
                     &[#(#each_doc),*],
-                    &#ඞ::marker::PhantomData::<Self>,
-                    #mb_phantom_int,
-                    &[#(#each_enum_variant),*],
+                    #ඞ::module_path!(),
+                    #ඞ::file!(),
+                    #ඞ::env!("CARGO_MANIFEST_DIR"),
+                    &mut |definer: &'_ mut dyn #Definer, docs: &'_ [&'_ #ඞ::str]| {
+                        language.emit_simple_enum(
+                            definer,
+                            docs,
+                            &#ඞ::marker::PhantomData::<Self>,
+                            #mb_phantom_int,
+                            &[#(#each_enum_variant),*],
+                        )
+                    },
                 )
             }
+
+            fn layout_on (
+                target: &'_ #Target,
+            ) -> #ඞ::Option<#TypeLayout>
+            {
+                <#Int as #CType>::layout_on(target)
+            }
         ));
     }
 
@@ -276,6 +306,248 @@ fn derive (
     Ok(ret)
 }
 
+/// `enum`s with payloads are lowered to their `#[repr(C, Int)]` layout:
+///
+/// ```rust ,ignore
+/// #[repr(C)]
+/// struct Enum_Layout {
+///     tag: CLayoutOf<Enum_Tag>, // field-less `#[repr(Int)] enum`
+///     payload: Enum_Payload,
+/// }
+///
+/// #[repr(C)]
+/// union Enum_Payload {
+///     Variant: CLayoutOf<Enum_Variant>, // `#[repr(C)] struct` of its fields
+///     …
+/// }
+/// ```
+fn derive_tagged_union (
+    args: Args,
+    attrs: &'_ mut Vec<Attribute>,
+    pub_: &'_ Visibility,
+    EnumName @ _: &'_ Ident,
+    variants: &'_ Punctuated<Variant, Token![,]>,
+) -> Result<TokenStream2>
+{
+    if let Some(js) = &args.js {
+        bail! {
+            "`js` is not supported for `enum`s with payloads" => js,
+        }
+    }
+
+    let repr_attr =
+        attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("repr"))
+            .ok_or(())
+            .or_else(|()| bail!("missing `#[repr(C, …)]` annotation"))?
+    ;
+    let ref reprs = repr_attr.parse_args_with(
+        Punctuated::<Ident, Token![,]>::parse_terminated,
+    )?;
+    // Without `C`, the tag would be laid out within each variant's payload.
+    if reprs.iter().any(|repr| repr == "C").not() {
+        bail! {
+            "`enum`s with payloads require a `#[repr(C, <integer type>)]` annotation"
+                => repr_attr
+        }
+    }
+    // The integer type of the tag, if any.
+    let tag_repr =
+        reprs
+            .iter()
+            .find(|&repr| repr != "C")
+            .map_or_else(|| quote!(C), ToTokens::into_token_stream)
+    ;
+
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        layout::{
+            CLayoutOf,
+            ReprC,
+        },
+    };
+
+    let ref EnumName_str: Expr![String] = args.rename.unwrap_or_else(|| {
+        let s = EnumName.to_string();
+        parse_quote!(#s)
+    });
+    let c_name = |suffix: &dyn ::core::fmt::Display| -> Expr![String] {
+        let suffix = suffix.to_string();
+        parse_quote!(
+            #ඞ::format!("{}_{}", #EnumName_str, #suffix)
+        )
+    };
+    let no_generics = &Generics::default();
+
+    let mut ret = quote!();
+
+    // The tag.
+    let EnumName_Tag @ _ = format_ident!("{}_Tag", EnumName);
+    let tag_def: ItemEnum = {
+        let ref doc = format!(" The tag of a `{EnumName}`.");
+        let EachVariant = variants.iter().map(|v| &v.ident);
+        let each_discriminant = variants.iter().map(|v| {
+            v.discriminant.as_ref().map(|(eq, disc)| quote!(#eq #disc))
+        });
+        parse_quote!(
+            #[doc = #doc]
+            #[allow(dead_code, nonstandard_style)]
+            #[repr(#tag_repr)]
+            #pub_
+            enum #EnumName_Tag {
+                #( #EachVariant #each_discriminant ),*
+            }
+        )
+    };
+    ret.extend(tag_def.to_token_stream());
+    ret.extend(derive(
+        Args { rename: Some(c_name(&"Tag")), js: None, frozen: None },
+        &mut tag_def.attrs.clone(),
+        pub_,
+        &EnumName_Tag,
+        no_generics,
+        &tag_def.variants,
+    )?);
+
+    // A struct for the fields of each (non-unit) variant.
+    let mut EachPayloadVariant @ _ = vec![];
+    let mut EachPayloadTy @ _ = vec![];
+    let mut each_payload_docs = vec![];
+    for Variant { attrs: variant_attrs, ident: VariantName, fields, .. } in variants {
+        if fields.is_empty() {
+            continue;
+        }
+        let EnumName_Variant @ _ = format_ident!("{}_{}", EnumName, VariantName);
+        let mut struct_attrs: Vec<Attribute> =
+            variant_attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("doc"))
+                .cloned()
+                .chain([
+                    parse_quote!(
+                        #[allow(dead_code, nonstandard_style)]
+                    ),
+                    parse_quote!(
+                        #[repr(C)]
+                    ),
+                ])
+                .collect()
+        ;
+        // Tuple variants get `_0`, `_1`, … fields, as in the C definitions.
+        let ref fields = Fields::Named({
+            let each_field_name = (0_u8..).zip(fields).map(|(i, f)| {
+                match f.ident {
+                    | Some(ref ident) => ident.clone(),
+                    | None => format_ident!("_{}", i),
+                }
+            });
+            let each_field_attrs = fields.iter().map(|f| &f.attrs);
+            let EachFieldTy = fields.iter().map(|f| &f.ty);
+            parse_quote!({
+                #(
+                    #(#each_field_attrs)*
+                    #each_field_name: #EachFieldTy
+                ),*
+            })
+        });
+        ret.extend(quote!(
+            #(#struct_attrs)*
+            #pub_
+            struct #EnumName_Variant #fields
+        ));
+        ret.extend(super::struct_::derive(
+            Args { rename: Some(c_name(VariantName)), js: None, frozen: None },
+            &mut struct_attrs,
+            pub_,
+            &EnumName_Variant,
+            no_generics,
+            fields,
+        )?);
+        EachPayloadVariant.push(VariantName);
+        EachPayloadTy.push(EnumName_Variant);
+        each_payload_docs.push(
+            variant_attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("doc"))
+                .vec()
+        );
+    }
+
+    // The union of these.
+    let EnumName_Payload @ _ = format_ident!("{}_Payload", EnumName);
+    let payload_name = c_name(&"Payload");
+    ret.extend(crate::derives::c_type::derive(
+        quote!(rename = #payload_name),
+        quote!(
+            #[allow(nonstandard_style)]
+            #[repr(C)]
+            #pub_
+            union #EnumName_Payload {
+                #(
+                    #(#each_payload_docs)*
+                    pub
+                    #EachPayloadVariant: #CLayoutOf<#EachPayloadTy>,
+                )*
+            }
+        ),
+    )?);
+
+    // The tag and the payload.
+    let EnumName_Layout @ _ = format_ident!("{}_Layout", EnumName);
+    let ref each_doc = attrs.iter().filter(|attr| attr.path.is_ident("doc")).vec();
+    ret.extend(crate::derives::c_type::derive(
+        quote!(rename = #EnumName_str),
+        quote!(
+            #(#each_doc)*
+            #[allow(nonstandard_style)]
+            #[repr(C)]
+            #pub_
+            struct #EnumName_Layout {
+                /// Which variant this is, and thus which `payload` member
+                /// (if any) is active.
+                pub
+                tag: #CLayoutOf<#EnumName_Tag>,
+
+                /// The fields of the variant.
+                pub
+                payload: #EnumName_Payload,
+            }
+        ),
+    )?);
+
+    ret.extend(quote!(
+        unsafe
+        impl #ReprC for #EnumName {
+            type CLayout = #EnumName_Layout;
+
+            #[inline]
+            fn is_valid (
+                it: &'_ Self::CLayout,
+            ) -> #ඞ::bool
+            {
+                if <#EnumName_Tag as #ReprC>::is_valid(&it.tag) == false {
+                    return false;
+                }
+            #(
+                if it.tag.discriminant == #EnumName_Tag::#EachPayloadVariant as _ {
+                    return <#EachPayloadTy as #ReprC>::is_valid(unsafe {
+                        &it.payload.#EachPayloadVariant
+                    });
+                }
+            )*
+                // unit variant.
+                true
+            }
+        }
+    ));
+
+    Ok(quote!(
+        const _: () = { #ret };
+    ))
+}
+
 fn parse_discriminant_type (
     attrs: &'_ [Attribute],
     out_warnings: &mut TokenStream2,
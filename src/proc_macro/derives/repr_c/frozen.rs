@@ -0,0 +1,204 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! `#[derive_ReprC(frozen)]`: checking the layout of a `#[repr(C)]` struct
+//! against the one recorded in the lockfile of the crate.
+
+use super::*;
+
+/// Name of the lockfile, at the root of the crate (next to its `Cargo.toml`).
+const LOCKFILE: &str = "safer-ffi-abi.lock";
+
+pub(in crate)
+fn derive (
+    frozen_span: Span,
+    StructName @ _: &'_ Ident,
+    generics: &'_ Generics,
+    fields: &'_ Fields,
+) -> Result<TokenStream2>
+{
+    if let Some(extraneous) = generics.params.first() {
+        bail! {
+            "`frozen` structs cannot be generic" => extraneous,
+        }
+    }
+
+    #[apply(let_quote!)]
+    use ::safer_ffi::ඞ;
+
+    let ref StructName_str = StructName.to_string();
+    let EachFieldTy @ _ = fields.iter().map(|f| &f.ty).vec();
+    let each_field_name =
+        (0..).zip(fields).map(|(i, f)| match f.ident {
+            | Some(ref ident) => ident.to_string(),
+            | None => i.to_string(),
+        })
+        .vec()
+    ;
+    // Since there is no implicit padding (checked below), the offset of each
+    // field is the sum of the sizes of the previous ones.
+    let each_offset =
+        (0..EachFieldTy.len()).map(|i| {
+            let EachPrevFieldTy @ _ = &EachFieldTy[.. i];
+            quote!(
+                0 #(+ #ඞ::mem::size_of::<#EachPrevFieldTy>())*
+            )
+        })
+        .vec()
+    ;
+    let ref size = quote!(#ඞ::mem::size_of::<#StructName>());
+    let ref align = quote!(#ඞ::mem::align_of::<#StructName>());
+
+    let lockfile =
+        ::std::path::Path::new(
+            &::std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()
+        )
+        .join(LOCKFILE)
+    ;
+    let ref lockfile_str = lockfile.display().to_string();
+
+    let padding_msg = format!(
+        "`{StructName}` is `frozen`, and yet it has implicit padding: \
+        make it explicit with extra (padding) fields",
+    );
+    let mut ret = quote_spanned!(frozen_span=>
+        const _: () = {
+            if #size != 0 #(+ #ඞ::mem::size_of::<#EachFieldTy>())* {
+                #ඞ::panic!(#padding_msg);
+            }
+        };
+    );
+
+    // (`explicit-registration` does without the `::inventory`-based
+    // registries: the lockfile is then only checked, never written.)
+    if cfg!(feature = "explicit-registration").not() {
+        let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
+            quote!( #![crate = ::safer_ffi] )
+        });
+        ret.extend(quote!(
+            #[cfg(not(target_arch = "wasm32"))]
+            ::safer_ffi::__cfg_headers__! {
+                ::safer_ffi::inventory::submit! {
+                    #inventory_krate
+
+                    ::safer_ffi::FrozenLayout {
+                        lockfile: #lockfile_str,
+                        name: #ඞ::concat!(#ඞ::module_path!(), "::", #StructName_str),
+                        size: #size,
+                        align: #align,
+                        fields: &[
+                            #( (#each_field_name, #each_offset), )*
+                        ],
+                    }
+                }
+            }
+        ));
+    }
+
+    // Not recorded yet: this happens when generating the headers with
+    // `Builder::with_update_frozen_layouts(true)`.
+    let ref contents = match ::std::fs::read_to_string(&lockfile) {
+        | Ok(it) => it,
+        | Err(_) => return Ok(ret),
+    };
+    // Have the crate be recompiled whenever the lockfile changes.
+    ret.extend(quote!(
+        const _: &[#ඞ::u8] = #ඞ::include_bytes!(#lockfile_str);
+    ));
+    // The entries are keyed by `module::path::StructName`, and the module
+    // path is only known to the expanded code: each entry recorded under that
+    // `StructName` is checked iff its path is the one of the struct.
+    for (key, recorded) in parse_entries(contents, StructName_str) {
+        let recorded = recorded.map_err(|err| Error::new(
+            StructName.span(),
+            format!("malformed `{LOCKFILE}`: {err}"),
+        ))?;
+        let ref is_this_struct = quote!(
+            #ඞ::str_eq(
+                #ඞ::concat!(#ඞ::module_path!(), "::", #StructName_str),
+                #key,
+            )
+        );
+
+        let keys_match =
+            recorded.len() == 2 + each_field_name.len()
+            && recorded[0].0 == "size"
+            && recorded[1].0 == "align"
+            && recorded[2 ..].iter().zip(&each_field_name).all(|(&(key, _), name)| {
+                key.strip_prefix('.') == Some(&name[..])
+            })
+        ;
+        if keys_match.not() {
+            let msg = format!(
+                "the fields of the `frozen` `{StructName}` no longer match \
+                the ones recorded in `{LOCKFILE}`",
+            );
+            ret.extend(quote_spanned!(StructName.span()=>
+                const _: () = {
+                    if #is_this_struct {
+                        #ඞ::panic!(#msg);
+                    }
+                };
+            ));
+            continue;
+        }
+        let expected =
+            [("size".to_owned(), size), ("alignment".to_owned(), align)]
+                .into_iter()
+                .chain(each_field_name.iter().zip(&each_offset).map(|(name, offset)| {
+                    (format!("offset of `.{name}`"), offset)
+                }))
+        ;
+        for ((what, actual), &(_, recorded)) in expected.zip(&recorded) {
+            let recorded = ::proc_macro2::Literal::usize_unsuffixed(recorded);
+            let msg = format!(
+                "ABI break: the {what} of the `frozen` `{StructName}` no longer \
+                matches the one recorded in `{LOCKFILE}`",
+            );
+            ret.extend(quote_spanned!(StructName.span()=>
+                const _: () = {
+                    if #is_this_struct && #actual != #recorded {
+                        #ඞ::panic!(#msg);
+                    }
+                };
+            ));
+        }
+    }
+    Ok(ret)
+}
+
+/// Looks for the `[module::path::StructName]` sections of the lockfile, made of
+/// `key = value` lines (the offsets of the fields being keyed by `.field`).
+fn parse_entries<'contents> (
+    contents: &'contents str,
+    StructName @ _: &'_ str,
+) -> Vec<(
+    &'contents str,
+    ::core::result::Result<Vec<(&'contents str, usize)>, String>,
+)>
+{
+    let suffix = format!("::{StructName}]");
+    let mut lines = contents.lines().map(str::trim).peekable();
+    let mut ret = vec![];
+    while let Some(line) = lines.next() {
+        if line.starts_with('[').not() || line.ends_with(&suffix).not() {
+            continue;
+        }
+        let key = &line[1 .. line.len() - 1];
+        let mut entry_lines = vec![];
+        while let Some(line) = lines.next_if(|line| {
+            line.is_empty().not() && line.starts_with('[').not()
+        })
+        {
+            entry_lines.push(line);
+        }
+        ret.push((key, entry_lines.into_iter().map(|line| -> ::core::result::Result<_, String> {
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                format!("expected `key = value`, got `{line}`")
+            })?;
+            let value: usize = value.trim().parse().map_err(|_| {
+                format!("expected an integer, got `{}`", value.trim())
+            })?;
+            Ok((key.trim(), value))
+        }).collect()));
+    }
+    ret
+}
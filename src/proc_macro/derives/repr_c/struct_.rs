@@ -20,14 +20,56 @@ fn derive (
     fields: &'_ Fields,
 ) -> Result<TokenStream2>
 {
-    if let Some(repr) = attrs.iter().find_map(|attr| {
-        bool::then(
-            attr.path.is_ident("repr"),
-            || attr.parse_args::<Ident>().ok()
-        ).flatten()
-    })
-    {
+    // `packed`, `packed(N)` and `align(N)`, to be mirrored by the C layout.
+    let mut layout_modifiers = vec![];
+    let mut repr = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        let metas = attr.parse_args_with(
+            Punctuated::<Meta, Token![,]>::parse_terminated,
+        )?;
+        for meta in metas {
+            match meta {
+                | _ if meta.path().is_ident("packed") || meta.path().is_ident("align") => {
+                    layout_modifiers.push(meta);
+                },
+                | Meta::Path(ref path) if path.get_ident().is_some() && repr.is_none() => {
+                    repr = path.get_ident().cloned();
+                },
+                | _ => bail! {
+                    "unsupported `repr`" => meta,
+                },
+            }
+        }
+    }
+    if let (Some(modifier), Some(repr)) = (layout_modifiers.first(), &repr) {
+        if repr != "C" {
+            bail! {
+                "`packed` and `align` are only supported for `#[repr(C)]` structs"
+                    => modifier
+            }
+        }
+        if let Some(frozen) = &args.frozen {
+            bail! {
+                "`frozen` is not supported for `packed` or `align`ed structs"
+                    => frozen
+            }
+        }
+    }
+    // Fields of packed structs cannot be borrowed in place.
+    let packed = layout_modifiers.iter().any(|meta| meta.path().is_ident("packed"));
+
+    if let Some(repr) = repr {
         match &repr.to_string()[..] {
+            | "transparent" | "opaque" if args.frozen.is_some() => bail! {
+                "`frozen` is only supported for `#[repr(C)]` structs"
+                    => args.frozen
+            },
+
+            | "transparent" | "opaque" if args.is_valid.is_some() => bail! {
+                "`is_valid` is only supported for `#[repr(C)]` structs"
+                    => args.is_valid
+            },
+
             | "transparent" => return derive_transparent(
                 args,
                 attrs,
@@ -63,6 +105,10 @@ fn derive (
 
     let mut ret = quote!();
 
+    if let Some(frozen) = &args.frozen {
+        ret.extend(super::frozen::derive(frozen.span, StructName, generics, fields)?);
+    }
+
     #[apply(let_quote!)]
     use ::safer_ffi::{
         ඞ,
@@ -88,12 +134,14 @@ fn derive (
     ret.extend({
         let c_type_def = ItemStruct {
             attrs: docs_of(attrs).cloned()
+                    // (for the headers to flag the type)
+                    .chain(attrs.iter().filter(|a| a.path.is_ident("deprecated")).cloned())
                     .chain([
                         parse_quote!(
                             #[allow(nonstandard_style)]
                         ),
                         parse_quote!(
-                            #[repr(C)]
+                            #[repr(C #(, #layout_modifiers)*)]
                         ),
                     ])
                     .collect()
@@ -153,7 +201,16 @@ fn derive (
     // Impl ReprC to point to the just defined type
     ret.extend({
         let EachFieldTy @ _ = EachFieldTy();
-        let each_field_name = each_field_name();
+        let each_field_name = each_field_name().vec();
+        let each_field_ref = each_field_name.iter().map(|field_name| {
+            if packed {
+                quote!(&{ _it.#field_name })
+            } else {
+                quote!(&_it.#field_name)
+            }
+        });
+        // allow using `#()*` as `#()?`.
+        let is_valid = args.is_valid.as_ref().map_or(&[][..], ::core::slice::from_ref);
         let (intro_generics, fwd_generics, where_clauses) =
             ctype_generics.split_for_impl()
         ;
@@ -174,10 +231,9 @@ fn derive (
                 {
                     let mut _ret = true;
                     #(
+                        let field: &'_ #CLayoutOf<#EachFieldTy> = #each_field_ref;
                         if #ඞ::mem::size_of::<#EachFieldTy>() != 0
-                        && <#EachFieldTy as #ReprC>::is_valid(
-                            &_it.#each_field_name
-                        ) == false
+                        && <#EachFieldTy as #ReprC>::is_valid(field) == false
                         {
                             #ඞ::__error__!(
                                 "\
@@ -190,14 +246,23 @@ fn derive (
                                 #ඞ::any::type_name::<#EachFieldTy>(),
                                 unsafe {
                                     #ඞ::slice::from_raw_parts(
-                                        <*const _>::cast::<#ඞ::u8>(&_it.#each_field_name),
-                                        #ඞ::mem::size_of_val(&_it.#each_field_name),
+                                        <*const _>::cast::<#ඞ::u8>(field),
+                                        #ඞ::mem::size_of_val(field),
                                     )
                                 },
                             );
                             _ret = false;
                         }
                     )*
+                    #(
+                        if _ret && #is_valid(_it) == false {
+                            #ඞ::__error__!(
+                                "Encountered invalid bit-pattern for `{}`",
+                                #ඞ::any::type_name::<Self>(),
+                            );
+                            _ret = false;
+                        }
+                    )*
                     _ret
                 }
             }
@@ -266,6 +331,13 @@ fn derive_transparent (
         let (intro_generics, fwd_generics, where_clauses) =
             impl_generics.split_for_impl()
         ;
+        let niche = cfg!(feature = "headers").then(|| quote!(
+            fn niche ()
+              -> #ඞ::Niche
+            {
+                <#FieldTy as #ඞ::ReprC>::niche()
+            }
+        ));
         quote!(
             unsafe
             impl #intro_generics
@@ -282,6 +354,8 @@ fn derive_transparent (
                 {
                     <#FieldTy as #ඞ::ReprC>::is_valid(it)
                 }
+
+                #niche
             }
         )
     });
@@ -298,6 +372,13 @@ fn derive_transparent (
         let (intro_generics, fwd_generics, where_clauses) =
             niche_generics.split_for_impl()
         ;
+        let niche_of_none = cfg!(feature = "headers").then(|| quote!(
+            fn niche_of_none ()
+              -> #ඞ::Niche
+            {
+                <#FieldTy as #ඞ::__HasNiche__>::niche_of_none()
+            }
+        ));
         quote!(
             unsafe
             impl #intro_generics
@@ -317,6 +398,8 @@ fn derive_transparent (
                         #ඞ::__HasNiche__
                     >::is_niche(it)
                 }
+
+                #niche_of_none
             }
         )
     });
@@ -341,6 +424,64 @@ fn derive_transparent (
     Ok(ret)
 }
 
+/// `auto_drop` and `auto_clone`: export a `<ty>_free()` (resp. a
+/// `<ty>_clone()`) function, for the `repr_c::Box<Ty>`s handed to C.
+fn owned_helpers (
+    args: &'_ Args,
+    StructName @ _: &'_ Ident,
+    generics: &'_ Generics,
+) -> Result<TokenStream2>
+{
+    #[apply(let_quote)]
+    use ::safer_ffi::{
+        ඞ,
+        prelude::repr_c,
+    };
+
+    let mut ret = quote!();
+    if let Some(param) = generics.params.first() {
+        if args.auto_drop.is_some() || args.auto_clone.is_some() {
+            bail! {
+                "`auto_drop` and `auto_clone` are not supported for generic types"
+                    => param
+            }
+        }
+    }
+    let ty = utils::snake_case(&StructName.to_string());
+    let ty_arg = format_ident!("{}", ty, span = StructName.span());
+    if args.auto_drop.is_some() {
+        let ty_free = format_ident!("{}_free", ty, span = StructName.span());
+        let free_doc = format!(" Frees a `{StructName}`.");
+        ret.extend(quote!(
+            #[::safer_ffi::ffi_export(free_of = #StructName)]
+            #[doc = #free_doc]
+            #[allow(dead_code)]
+            fn #ty_free (
+                #ty_arg: #repr_c::Box<#StructName>,
+            )
+            {
+                #ඞ::mem::drop(#ty_arg)
+            }
+        ));
+    }
+    if args.auto_clone.is_some() {
+        let ty_clone = format_ident!("{}_clone", ty, span = StructName.span());
+        let clone_doc = format!(" Returns a (newly allocated) copy of a `{StructName}`.");
+        ret.extend(quote!(
+            #[::safer_ffi::ffi_export]
+            #[doc = #clone_doc]
+            #[allow(dead_code)]
+            fn #ty_clone (
+                #ty_arg: &'_ #StructName,
+            ) -> #repr_c::Box<#StructName>
+            {
+                #repr_c::Box::new(#ඞ::clone::Clone::clone(#ty_arg))
+            }
+        ));
+    }
+    Ok(ret)
+}
+
 pub(in crate)
 fn derive_opaque (
     args: Args,
@@ -373,6 +514,8 @@ fn derive_opaque (
         "__opaque_{}", StructName,
     );
 
+    ret.extend(owned_helpers(&args, StructName, generics)?);
+
     let (intro_generics, fwd_generics, where_clauses) =
         generics.split_for_impl()
     ;
@@ -424,6 +567,13 @@ fn derive_opaque (
                 },
             };
             let docs = utils::extract_docs(attrs)?;
+            let deprecated = utils::deprecation_note(attrs)?.map(|note| quote!(
+                fn deprecated ()
+                  -> #ඞ::Option<&'static #ඞ::str>
+                {
+                    #ඞ::Some(#note)
+                }
+            ));
             quote!(
                 fn short_name ()
                   -> #ඞ::String
@@ -431,16 +581,28 @@ fn derive_opaque (
                     #short_name
                 }
 
+                #deprecated
+
                 #[allow(nonstandard_style)]
                 fn define_self__impl (
                     language: &'_ dyn #ඞ::HeaderLanguage,
                     definer: &'_ mut dyn #ඞ::Definer,
                 ) -> #ඞ::io::Result<()>
                 {
-                    language.emit_opaque_type(
+                    #ඞ::__with_doxygen_group__(
                         definer,
+                        language,
                         &[#(#docs),*],
-                        &#ඞ::PhantomData::<Self>,
+                        #ඞ::module_path!(),
+                        #ඞ::file!(),
+                        #ඞ::env!("CARGO_MANIFEST_DIR"),
+                        &mut |definer: &'_ mut dyn #ඞ::Definer, docs: &'_ [&'_ #ඞ::str]| {
+                            language.emit_opaque_type(
+                                definer,
+                                docs,
+                                &#ඞ::PhantomData::<Self>,
+                            )
+                        },
                     )
                 }
             )
@@ -0,0 +1,140 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use super::*;
+
+pub(in crate)
+fn derive (
+    args: Args,
+    attrs: &'_ mut Vec<Attribute>,
+    pub_: &'_ Visibility,
+    UnionName @ _: &'_ Ident,
+    generics: &'_ Generics,
+    fields: &'_ FieldsNamed,
+) -> Result<TokenStream2>
+{
+    if let Some(js) = &args.js {
+        bail! {
+            "`js` is not supported for `union`s" => js,
+        }
+    }
+    if let Some(frozen) = &args.frozen {
+        bail! {
+            "`frozen` is only supported for `#[repr(C)]` structs" => frozen
+        }
+    }
+    match attrs.iter().find_map(|attr| {
+        bool::then(
+            attr.path.is_ident("repr"),
+            || attr.parse_args::<Ident>().ok()
+        ).flatten()
+    })
+    {
+        | Some(repr) if repr == "C" => {},
+        | Some(repr) => bail! {
+            "unsupported `repr`" => repr,
+        },
+        | None => bail! {
+            "missing explicit `#[repr(C)]` annotation"
+        },
+    }
+    if let Some(param) = generics.params.first() {
+        bail! {
+            "generic `union`s are not supported yet." => param,
+        }
+    }
+    if let Some(where_clause) = &generics.where_clause {
+        bail! {
+            "not supported" => where_clause.where_token,
+        }
+    }
+
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        layout::{
+            CLayoutOf,
+            ReprC,
+        },
+    };
+
+    let mut ret = quote!();
+
+    let ref UnionName_Layout @ _ = format_ident!("{}_Layout", UnionName);
+
+    // define the CType
+    ret.extend({
+        // (`#[deprecated]` too, for the headers to flag the type)
+        let each_doc = attrs.iter().filter(|attr| {
+            attr.path.is_ident("doc") || attr.path.is_ident("deprecated")
+        });
+        let each_field_docs = fields.named.iter().map(|f| {
+            f   .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("doc"))
+                .vec()
+        });
+        let each_field_name = fields.named.iter().map(|f| &f.ident);
+        let EachFieldTy @ _ = fields.named.iter().map(|f| &f.ty);
+        let rename = args.rename.unwrap_or_else(|| {
+            let s = UnionName.to_string();
+            parse_quote!(#s)
+        });
+        crate::derives::c_type::derive(
+            quote!(
+                rename = #rename,
+            ),
+            quote!(
+                #(#each_doc)*
+                #[allow(nonstandard_style)]
+                #[repr(C)]
+                #pub_
+                union #UnionName_Layout {
+                    #(
+                        #(#each_field_docs)*
+                        pub
+                        #each_field_name: #CLayoutOf<#EachFieldTy>,
+                    )*
+                }
+            ),
+        )?
+    });
+
+    // Impl ReprC to point to the just defined type
+    ret.extend(quote!(
+        unsafe
+        impl
+            #ReprC
+        for
+            #UnionName
+        {
+            type CLayout = #UnionName_Layout;
+
+            /// Reading any field of a `union` is `unsafe` (and it is up to the
+            /// user to know which one is active), so there is nothing to check.
+            #[inline]
+            fn is_valid (_: &'_ Self::CLayout)
+              -> #ඞ::bool
+            {
+                true
+            }
+        }
+    ));
+
+    // Add docs about C layout.
+    attrs.extend_::<Attribute, _>([
+        parse_quote!(
+            /// # C Layout
+        ),
+        parse_quote!(
+            ///
+        ),
+        {
+            let line = format!(
+                "{}  - [`{UnionName}_Layout`](#impl-ReprC)", " ",
+            );
+            parse_quote!(#[doc = #line])
+        },
+    ]);
+
+    Ok(ret)
+}
@@ -2,8 +2,11 @@
 
 use super::*;
 
+mod accessors;
 mod const_;
 mod fn_;
+mod impl_;
+mod json;
 mod static_;
 mod type_;
 
@@ -23,17 +26,77 @@ fn ffi_export (
             parse2(args)?,
             &struct_.ident,
             &struct_.generics,
+            Some(&struct_.fields),
             &struct_,
         ),
         | Item::Enum(enum_) => type_::handle(
             parse2(args)?,
             &enum_.ident,
             &enum_.generics,
+            None,
             &enum_,
         ),
         | Item::Fn(fn_) => fn_::handle(parse2(args)?, fn_),
+        | Item::Impl(impl_) => impl_::handle(parse2(args)?, impl_),
         | Item::Const(const_) => const_::handle(parse2(args)?, const_),
         | Item::Static(static_) => static_::handle(parse2(args)?, static_),
         | _otherwise => bail!("unsupported item type"),
     }
 }
+
+pub(in super)
+fn ffi_export_accessors (
+    args: TokenStream2,
+    input: TokenStream2,
+) -> Result<TokenStream2>
+{
+    accessors::handle(parse2(args)?, parse2(input)?)
+}
+
+pub(in super)
+fn ffi_export_json (
+    args: TokenStream2,
+    input: TokenStream2,
+) -> Result<TokenStream2>
+{
+    json::handle(args, parse2(input)?)
+}
+
+/// Registers the `FfiExport`s of the `#[ffi_export]`ed `item` for the header
+/// generation: through `::inventory`, or, with the `explicit-registration`
+/// Cargo feature, as a hidden `__ffi_export__<item>` `static`, for
+/// `register_headers!(item)` to pick up.
+fn register_ffi_exports (
+    item: &'_ Ident,
+    each_ffi_export: &'_ [TokenStream2],
+) -> TokenStream2
+{
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+    };
+
+    if cfg!(feature = "explicit-registration") {
+        let registered = format_ident!("__ffi_export__{}", item);
+        quote!(
+            #[doc(hidden)]
+            #[allow(dead_code, nonstandard_style)]
+            pub(crate)
+            static #registered: &[#ඞ::FfiExport] = &[
+                #(#each_ffi_export),*
+            ];
+        )
+    } else {
+        let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
+            quote!( #![crate = #ඞ] )
+        });
+        quote!(#(
+            #[cfg(not(target_arch = "wasm32"))]
+            #ඞ::inventory::submit! {
+                #inventory_krate
+
+                #each_ffi_export
+            }
+        )*)
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+
+#[derive(Default)]
+pub(in super)
+struct Args {
+    readonly: Option<kw::readonly>,
+}
+
+/// The `#[ffi_export_accessors(…)]` of a field.
+#[derive(Default)]
+struct FieldArgs {
+    readonly: Option<kw::readonly>,
+    skip: Option<kw::skip>,
+}
+
+mod kw {
+    ::syn::custom_keyword!(readonly);
+    ::syn::custom_keyword!(skip);
+}
+
+impl Parse for Args {
+    fn parse (
+        input: ParseStream<'_>,
+    ) -> Result<Args>
+    {
+        let mut ret = Args::default();
+        while input.is_empty().not() {
+            let snoopy = input.lookahead1();
+            match () {
+                | _case if snoopy.peek(kw::readonly) => {
+                    if ret.readonly.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.readonly = Some(input.parse().unwrap());
+                },
+
+                | _default => return Err(snoopy.error()),
+            }
+            let _: Option<Token![,]> = input.parse()?;
+        }
+        Ok(ret)
+    }
+}
+
+impl Parse for FieldArgs {
+    fn parse (
+        input: ParseStream<'_>,
+    ) -> Result<FieldArgs>
+    {
+        let mut ret = FieldArgs::default();
+        while input.is_empty().not() {
+            let snoopy = input.lookahead1();
+            match () {
+                | _case if snoopy.peek(kw::readonly) => {
+                    if ret.readonly.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.readonly = Some(input.parse().unwrap());
+                },
+
+                | _case if snoopy.peek(kw::skip) => {
+                    if ret.skip.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.skip = Some(input.parse().unwrap());
+                },
+
+                | _default => return Err(snoopy.error()),
+            }
+            let _: Option<Token![,]> = input.parse()?;
+        }
+        Ok(ret)
+    }
+}
+
+/// `#[ffi_export_accessors]` on a `struct Ty`: export a `<ty>_get_<field>()`
+/// getter (returning a `.clone()` of the field) and a `<ty>_set_<field>()`
+/// setter for each of its fields, so that C can access them through a
+/// stable ABI even when `Ty` is `#[repr(opaque)]`.
+pub(in super)
+fn handle (
+    args: Args,
+    mut input: ItemStruct,
+) -> Result<TokenStream2>
+{
+    if let Some(param) = input.generics.params.first() {
+        bail! {
+            "generic `struct`s are not supported" => param,
+        }
+    }
+    let fields = match &mut input.fields {
+        | Fields::Named(FieldsNamed { named, .. }) => named,
+        | _ => bail! {
+            "expected a `struct` with named fields" => input.ident,
+        },
+    };
+    let Ty @ _ = &input.ident;
+    let ty = utils::snake_case(&Ty.to_string());
+    let ty_arg = format_ident!("{}", ty, span = Ty.span());
+
+    let mut accessors = quote!();
+    for field in fields.iter_mut() {
+        // Strip (and parse) the `#[ffi_export_accessors(…)]` of the field.
+        let mut field_args = FieldArgs::default();
+        let mut error = None;
+        field.attrs.retain(|attr| {
+            if attr.path.is_ident("ffi_export_accessors").not() {
+                return true;
+            }
+            match attr.parse_args() {
+                | Ok(it) => field_args = it,
+                | Err(err) => error = Some(err),
+            }
+            false
+        });
+        if let Some(err) = error {
+            return Err(err);
+        }
+        if field_args.skip.is_some() {
+            continue;
+        }
+        let field_name = field.ident.as_ref().unwrap();
+        let FieldTy @ _ = &field.ty;
+        let get_doc = format!(" Returns (a copy of) the `{field_name}` of a `{Ty}`.");
+        let field_docs: Vec<_> = field.attrs.iter().filter(|attr| attr.path.is_ident("doc")).collect();
+        let separator = if field_docs.is_empty() { None } else { Some(quote!( #[doc = ""] )) };
+        let ty_get_field = format_ident!("{}_get_{}", ty, field_name, span = field_name.span());
+        accessors.extend(super::fn_::handle(Default::default(), parse_quote!(
+            #[doc = #get_doc]
+            #separator
+            #(#field_docs)*
+            #[allow(dead_code)]
+            fn #ty_get_field (
+                #ty_arg: &'_ #Ty,
+            ) -> #FieldTy
+            {
+                ::core::clone::Clone::clone(&#ty_arg.#field_name)
+            }
+        ))?);
+        if args.readonly.is_some() || field_args.readonly.is_some() {
+            continue;
+        }
+        let set_doc = format!(" Sets the `{field_name}` of a `{Ty}`.");
+        let ty_set_field = format_ident!("{}_set_{}", ty, field_name, span = field_name.span());
+        accessors.extend(super::fn_::handle(Default::default(), parse_quote!(
+            #[doc = #set_doc]
+            #[allow(dead_code)]
+            fn #ty_set_field (
+                #ty_arg: &'_ mut #Ty,
+                value: #FieldTy,
+            )
+            {
+                #ty_arg.#field_name = value;
+            }
+        ))?);
+    }
+    Ok(quote!(
+        #input
+
+        #accessors
+    ))
+}
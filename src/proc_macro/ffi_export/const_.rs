@@ -24,51 +24,65 @@ fn handle (
         let Ty @ _ = &input.ty;
         let ref each_doc = utils::extract_docs(&input.attrs)?;
 
-        let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
-            quote!( #![crate = #ඞ] )
-        });
-        Ok(quote!(
-            #input
-
-            #[cfg(not(target_arch = "wasm32"))]
-            #ඞ::inventory::submit! {
-                #inventory_krate
-
-                #ඞ::FfiExport {
-                    name: #VAR_str,
-                    gen_def: |
-                        definer: &'_ mut dyn #ඞ::Definer,
-                        lang: #ඞ::Language,
-                    | {
-                        #krate::__with_cfg_python__!(|$if_cfg_python| {
-                            use #krate::headers::{
-                                Language,
-                                languages::{self, HeaderLanguage},
-                            };
+        let registered = super::register_ffi_exports(VAR, &[quote!(
+            #ඞ::FfiExport {
+                name: #VAR_str,
+                kind: #krate::headers::ItemKind::Constant,
+                gen_def: |
+                    definer: &'_ mut dyn #ඞ::Definer,
+                    lang: #ඞ::Language,
+                | {
+                    let header_builder = #krate::__with_cfg_python__!(|$if_cfg_python| {
+                        use #krate::headers::{
+                            Language,
+                            languages::{self, HeaderLanguage},
+                        };
 
-                            let header_builder: &'static dyn HeaderLanguage = {
-                                match lang {
-                                    | Language::C => &languages::C,
-                                    | Language::CSharp => &languages::CSharp,
-                                $($($if_cfg_python)?
-                                    | Language::Python => &languages::Python,
-                                )?
-                                }
-                            };
+                        let header_builder: &'static dyn HeaderLanguage = {
+                            match lang {
+                                | Language::C => &languages::C,
+                                | Language::CSharp => &languages::CSharp,
+                            $($($if_cfg_python)?
+                                | Language::Python => &languages::Python,
+                            )?
+                                | Language::MsvcDef => &languages::SymbolList::MsvcDef,
+                                | Language::LdVersionScript => &languages::SymbolList::LdVersionScript,
+                                | Language::MacosExportedSymbols => &languages::SymbolList::MacosExportedSymbols,
+                                | Language::Markdown => &languages::Markdown,
+                                | Language::Cython => &languages::Cython,
+                                | Language::CSmokeTest => &languages::CSmokeTest,
+                                | Language::LayoutSnapshot => &languages::LayoutSnapshot,
+                            }
+                        };
 
-                            header_builder
-                        }).emit_constant(
-                            definer,
-                            &[ #(#each_doc),* ],
-                            #VAR_str,
-                            &#ඞ::PhantomData::<
-                                #ඞ::CLayoutOf< #Ty >,
-                            >,
-                            &#VAR,
-                        )
-                    },
-                }
+                        header_builder
+                    });
+                    #krate::headers::__with_doxygen_group__(
+                        definer,
+                        header_builder,
+                        &[ #(#each_doc),* ],
+                        #ඞ::module_path!(),
+                        #ඞ::file!(),
+                        #ඞ::env!("CARGO_MANIFEST_DIR"),
+                        &mut |definer: &'_ mut dyn #ඞ::Definer, docs: &'_ [&'_ #ඞ::str]| {
+                            header_builder.emit_constant(
+                                definer,
+                                docs,
+                                #VAR_str,
+                                &#ඞ::PhantomData::<
+                                    #ඞ::CLayoutOf< #Ty >,
+                                >,
+                                &#VAR,
+                            )
+                        },
+                    )
+                },
             }
+        )]);
+        Ok(quote!(
+            #input
+
+            #registered
         ))
     }
 }
@@ -5,7 +5,19 @@ pub(in crate)
 struct Args {
     pub(in crate) js: Option<Js>,
     pub(in crate) executor: Option<Executor>,
+    pub(in crate) async_via: Option<AsyncVia>,
     pub(in crate) rename: Option<Rename>,
+    pub(in crate) export_name: Option<ExportName>,
+    pub(in crate) convention: Option<Convention>,
+    pub(in crate) weak_linkage: Option<kw::weak_linkage>,
+    pub(in crate) since: Option<Since>,
+    pub(in crate) group: Option<Group>,
+    pub(in crate) free_of: Option<FreeOf>,
+    pub(in crate) error_code: Option<kw::error_code>,
+    pub(in crate) catch_panics: Option<CatchPanics>,
+    pub(in crate) last_error: Option<kw::last_error>,
+    pub(in crate) validate_utf8: Option<kw::validate_utf8>,
+    pub(in crate) cfg_header: Option<CfgHeader>,
 }
 
 #[cfg_attr(not(feature = "js"),
@@ -27,6 +39,21 @@ struct Executor {
     pub(in crate) block_on: Expr,
 }
 
+/// `async_via = callback`
+pub(in crate)
+struct AsyncVia {
+    pub(in crate) kw: kw::async_via,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) _callback: kw::callback,
+}
+
+/// `catch_panics` or `catch_panics = <sentinel>`
+pub(in crate)
+struct CatchPanics {
+    pub(in crate) kw: kw::catch_panics,
+    pub(in crate) sentinel: Option<(Token![=], Expr)>,
+}
+
 pub(in crate)
 struct Rename {
     pub(in crate) _kw: kw::rename,
@@ -34,11 +61,80 @@ struct Rename {
     pub(in crate) new_name: LitStr,
 }
 
+/// `export_name = "…"`
+pub(in crate)
+struct ExportName {
+    pub(in crate) _kw: kw::export_name,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) name: LitStr,
+}
+
+/// `convention = "stdcall"`
+pub(in crate)
+struct Convention {
+    pub(in crate) kw: kw::convention,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) abi: LitStr,
+}
+
+/// The ABIs which `convention = "…"` accepts.
+pub(in crate)
+const CONVENTIONS: &[&str] = &[
+    "C",
+    #[cfg(feature = "c-unwind")]
+    "C-unwind",
+    "system",
+    "stdcall",
+    "fastcall",
+];
+
+pub(in crate)
+struct Since {
+    pub(in crate) _kw: kw::since,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) version: LitStr,
+}
+
+pub(in crate)
+struct Group {
+    pub(in crate) _kw: kw::group,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) name: LitStr,
+}
+
+/// `cfg_header = "defined(MYLIB_GPU)"`
+pub(in crate)
+struct CfgHeader {
+    pub(in crate) _kw: kw::cfg_header,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) condition: LitStr,
+}
+
+pub(in crate)
+struct FreeOf {
+    pub(in crate) kw: kw::free_of,
+    pub(in crate) _eq: Token![=],
+    pub(in crate) ty: TypePath,
+}
+
 mod kw {
+    ::syn::custom_keyword!(error_code);
+    ::syn::custom_keyword!(async_via);
     ::syn::custom_keyword!(async_worker);
+    ::syn::custom_keyword!(callback);
+    ::syn::custom_keyword!(catch_panics);
+    ::syn::custom_keyword!(cfg_header);
+    ::syn::custom_keyword!(convention);
     ::syn::custom_keyword!(executor);
+    ::syn::custom_keyword!(export_name);
+    ::syn::custom_keyword!(free_of);
+    ::syn::custom_keyword!(group);
     ::syn::custom_keyword!(js);
+    ::syn::custom_keyword!(last_error);
     ::syn::custom_keyword!(rename);
+    ::syn::custom_keyword!(since);
+    ::syn::custom_keyword!(validate_utf8);
+    ::syn::custom_keyword!(weak_linkage);
 }
 
 impl Parse for Args {
@@ -61,6 +157,120 @@ impl Parse for Args {
                     });
                 },
 
+                | _case if snoopy.peek(kw::async_via) => {
+                    if ret.async_via.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.async_via = Some(AsyncVia {
+                        kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        _callback: input.parse()?,
+                    });
+                },
+
+                | _case if snoopy.peek(kw::catch_panics) => {
+                    if ret.catch_panics.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.catch_panics = Some(CatchPanics {
+                        kw: input.parse().unwrap(),
+                        sentinel: if input.peek(Token![=]) {
+                            Some((input.parse().unwrap(), input.parse()?))
+                        } else {
+                            None
+                        },
+                    });
+                },
+
+                | _case if snoopy.peek(kw::cfg_header) => {
+                    if ret.cfg_header.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.cfg_header = Some(CfgHeader {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        condition: input.parse()?,
+                    });
+                },
+
+                | _case if snoopy.peek(kw::convention) => {
+                    if ret.convention.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.convention = Some(Convention {
+                        kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        abi: {
+                            let it = input.parse::<LitStr>()?;
+                            if CONVENTIONS.contains(&it.value().as_str()).not() {
+                                bail! {
+                                    format!(
+                                        "unsupported calling convention, expected one of {:?}",
+                                        CONVENTIONS,
+                                    ) => it,
+                                }
+                            }
+                            it
+                        },
+                    });
+                },
+
+                | _case if snoopy.peek(kw::error_code) => {
+                    if ret.error_code.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.error_code = Some(input.parse().unwrap());
+                },
+
+                | _case if snoopy.peek(kw::export_name) => {
+                    if ret.export_name.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.export_name = Some(ExportName {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        name: {
+                            let it = input.parse::<LitStr>()?;
+                            if it.parse::<Ident>().is_err() {
+                                bail! {
+                                    "expected a symbol name (identifier)" => it,
+                                }
+                            }
+                            it
+                        },
+                    });
+                },
+
+                | _case if snoopy.peek(kw::free_of) => {
+                    if ret.free_of.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.free_of = Some(FreeOf {
+                        kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        ty: input.parse()?,
+                    });
+                },
+
+                | _case if snoopy.peek(kw::group) => {
+                    if ret.group.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.group = Some(Group {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        name: {
+                            let it = input.parse::<LitStr>()?;
+                            if it.parse::<Ident>().is_err() {
+                                bail! {
+                                    "expected a group name (identifier)" => it,
+                                }
+                            }
+                            it
+                        },
+                    });
+                },
+
                 | _case if snoopy.peek(kw::js) => {
                     if ret.js.is_some() {
                         return Err(input.error("duplicate parameter"));
@@ -81,6 +291,13 @@ impl Parse for Args {
                     });
                 },
 
+                | _case if snoopy.peek(kw::last_error) => {
+                    if ret.last_error.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.last_error = Some(input.parse().unwrap());
+                },
+
                 | _case if snoopy.peek(kw::rename) => {
                     if ret.rename.is_some() {
                         return Err(input.error("duplicate parameter"));
@@ -100,6 +317,43 @@ impl Parse for Args {
                     });
                 },
 
+                | _case if snoopy.peek(kw::validate_utf8) => {
+                    if ret.validate_utf8.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.validate_utf8 = Some(input.parse().unwrap());
+                },
+
+                | _case if snoopy.peek(kw::weak_linkage) => {
+                    if ret.weak_linkage.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.weak_linkage = Some(input.parse().unwrap());
+                },
+
+                | _case if snoopy.peek(kw::since) => {
+                    if ret.since.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.since = Some(Since {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        version: {
+                            let it = input.parse::<LitStr>()?;
+                            let value = it.value();
+                            let parts = value.split('.').collect::<Vec<_>>();
+                            if matches!(parts.len(), 2 ..= 3).not()
+                            || parts.iter().any(|part| part.parse::<u32>().is_err())
+                            {
+                                bail! {
+                                    "expected a `MAJOR.MINOR` or `MAJOR.MINOR.PATCH` version" => it,
+                                }
+                            }
+                            it
+                        },
+                    });
+                },
+
                 | _default => return Err(snoopy.error()),
             }
             let _: Option<Token![,]> = input.parse()?;
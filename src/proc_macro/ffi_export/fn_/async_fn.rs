@@ -11,7 +11,7 @@ use {
 
 pub(in super)
 fn export (
-    Args { executor, js, rename }: Args,
+    Args { executor, async_via, js, rename, export_name, convention, weak_linkage: _, since, group, free_of: _, error_code, catch_panics, last_error: _, validate_utf8: _, cfg_header }: Args,
     fun: &'_ ItemFn,
 ) -> Result<TokenStream2>
 {
@@ -130,6 +130,93 @@ fn export (
 
     let block_on = respan(fun.block.span(), block_on.into_token_stream());
 
+    // `async_via = callback`: the future is rather `spawn`ed onto the
+    // `executor`, and its output handed to a trailing `completion` parameter.
+    if let Some(AsyncVia { kw: async_via, .. }) = async_via {
+        if let Some(Js { kw, .. }) = js {
+            bail! {
+                "`js` is not supported with `async_via = callback`" => kw,
+            }
+        }
+        if let Some((_, ErrTy)) = result_ret(&fun.sig.output) {
+            bail! {
+                "`Result` returns are not supported with `async_via = callback`"
+                => ErrTy,
+            }
+        }
+        if let Some(CatchPanics { kw, .. }) = catch_panics {
+            bail! {
+                "`catch_panics` is not supported with `async_via = callback`"
+                => kw,
+            }
+        }
+        if let Some(error_code) = error_code {
+            bail! {
+                "`error_code` is not supported with `async_via = callback`"
+                => error_code,
+            }
+        }
+        let spawn = block_on;
+        let completion = Ident::new("completion", Span::mixed_site());
+        let mut fun_signature = fun.sig.clone();
+        fun_signature.asyncness = None;
+        let (Completion @ _, complete) =
+            match ::core::mem::replace(
+                &mut fun_signature.output,
+                ReturnType::Default,
+            )
+            {
+                | ReturnType::Default => (
+                    quote!( ::safer_ffi::completion::VoidCompletion ),
+                    quote!(
+                        #async_body.await;
+                        #completion.complete()
+                    ),
+                ),
+                | ReturnType::Type(_, RetTy @ _) => (
+                    quote!( ::safer_ffi::completion::Completion<#RetTy> ),
+                    quote!(
+                        #completion.complete(#async_body.await)
+                    ),
+                ),
+            }
+        ;
+        fun_signature.inputs.push(parse_quote_spanned!(async_via.span=>
+            #completion: #Completion
+        ));
+        let pub_ = &fun.vis;
+        let each_attr = &fun.attrs;
+        let rename = rename.map(|Rename { new_name, .. }| quote!(
+            rename = #new_name,
+        ));
+        let export_name = export_name.map(|ExportName { name, .. }| quote!(
+            export_name = #name,
+        ));
+        let convention = convention.map(|Convention { abi, .. }| quote!(
+            convention = #abi,
+        ));
+        let since = since.map(|Since { version, .. }| quote!(
+            since = #version,
+        ));
+        let group = group.map(|Group { name, .. }| quote!(
+            group = #name,
+        ));
+        let cfg_header = cfg_header.map(|CfgHeader { condition, .. }| quote!(
+            cfg_header = #condition,
+        ));
+        return Ok(quote!(
+            #[::safer_ffi::ffi_export(#rename #export_name #convention #since #group #cfg_header)]
+            #(#each_attr)*
+            #pub_ #fun_signature
+            {
+                #(#prelude)*
+                let _ = (#spawn)(async move {
+                    #complete
+                });
+            }
+        ));
+    }
+
     let ret = if cfg!(feature = "js") {
         if js.is_none() {
             // Nothing to do in this branch:
@@ -318,8 +405,30 @@ fn export (
         let pub_ = &fun.vis;
         let each_attr = &fun.attrs;
         fun_signature.asyncness = None;
+        let export_name = export_name.map(|ExportName { name, .. }| quote!(
+            export_name = #name,
+        ));
+        let convention = convention.map(|Convention { abi, .. }| quote!(
+            convention = #abi,
+        ));
+        let since = since.map(|Since { version, .. }| quote!(
+            since = #version,
+        ));
+        let group = group.map(|Group { name, .. }| quote!(
+            group = #name,
+        ));
+        let error_code = error_code.map(|kw| quote!(
+            #kw,
+        ));
+        let catch_panics = catch_panics.map(|CatchPanics { kw, sentinel }| {
+            let sentinel = sentinel.map(|(eq, sentinel)| quote!( #eq #sentinel ));
+            quote!( #kw #sentinel, )
+        });
+        let cfg_header = cfg_header.map(|CfgHeader { condition, .. }| quote!(
+            cfg_header = #condition,
+        ));
         quote!(
-            #[::safer_ffi::ffi_export]
+            #[::safer_ffi::ffi_export(#export_name #convention #since #group #error_code #catch_panics #cfg_header)]
             #(#each_attr)*
             #pub_ #fun_signature
             {
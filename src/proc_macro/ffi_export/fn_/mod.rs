@@ -8,6 +8,8 @@ mod async_fn;
 
 const SUPPORTED_ABIS: &[&str] = &[
     "C",
+    #[cfg(feature = "c-unwind")]
+    "C-unwind",
 ];
 
 fn concrete_c_type (T @ _: &'_ Type)
@@ -18,14 +20,367 @@ fn concrete_c_type (T @ _: &'_ Type)
     )
 }
 
+/// `-> Result<T, E>`: the `(T, E)` pair, to lower it to `-> E` (with a
+/// trailing `out: *mut T` parameter, unless `T = ()`).
+fn result_ret (output: &'_ ReturnType)
+  -> Option<(&'_ Type, &'_ Type)>
+{
+    let path = match output {
+        | ReturnType::Type(_, ty) => match &**ty {
+            | Type::Path(TypePath { qself: None, path }) => path,
+            | _ => return None,
+        },
+        | ReturnType::Default => return None,
+    };
+    let last = path.segments.last()?;
+    match (&last.arguments, last.ident == "Result") {
+        | (PathArguments::AngleBracketed(generics), true) => {
+            match generics.args.iter().collect::<Vec<_>>()[..] {
+                | [GenericArgument::Type(OkTy), GenericArgument::Type(ErrTy)] => {
+                    Some((OkTy, ErrTy))
+                },
+                | _ => None,
+            }
+        },
+        | _ => None,
+    }
+}
+
+/// `Out<'_, T>` or `&mut MaybeUninit<T>`.
+fn is_out_param (ty: &'_ Type)
+  -> bool
+{
+    let path = match *ty {
+        | Type::Path(TypePath { ref path, .. }) => path,
+        | Type::Reference(TypeReference { mutability: Some(_), ref elem, .. }) => match **elem {
+            | Type::Path(TypePath { ref path, .. }) => {
+                return path.segments.last().map_or(false, |it| it.ident == "MaybeUninit");
+            },
+            | _ => return false,
+        },
+        | _ => return false,
+    };
+    path.segments.last().map_or(false, |it| it.ident == "Out")
+}
+
+fn is_unit (ty: &'_ Type)
+  -> bool
+{
+    matches!(*ty, Type::Tuple(TypeTuple { ref elems, .. }) if elems.is_empty())
+}
+
+/// `(A, B)`: the `Tuple2<A, B>` it is lowered to (from `Tuple1` up to
+/// `Tuple6`).
+fn tuple_repr (ty: &'_ Type)
+  -> Option<Type>
+{
+    let elems = match *ty {
+        | Type::Tuple(TypeTuple { ref elems, .. }) => elems,
+        | Type::Paren(TypeParen { ref elem, .. }) => return tuple_repr(elem),
+        | _ => return None,
+    };
+    if (1 ..= 6).contains(&elems.len()).not() {
+        return None;
+    }
+    let TupleN = format_ident!("Tuple{}", elems.len(), span = ty.span());
+    let elems = elems.iter();
+    Some(parse_quote_spanned!(ty.span()=>
+        ::safer_ffi::#TupleN<#(#elems),*>
+    ))
+}
+
+/// The type the given one is lowered to, in the exported signature.
+fn ffi_ty (ty: &'_ Type)
+  -> Type
+{
+    tuple_repr(ty).unwrap_or_else(|| ty.clone())
+}
+
+/// `from_raw_unchecked(c_layout)`, as a `ty` (which it converts back to, for
+/// a tuple).
+fn from_raw_unchecked (ty: &'_ Type, c_layout: &'_ dyn ToTokens)
+  -> TokenStream2
+{
+    match tuple_repr(ty) {
+        | Some(Tuple) => quote_spanned!(Span::mixed_site()=>
+            ::safer_ffi::ඞ::From::from(
+                ::safer_ffi::layout::from_raw_unchecked::<#Tuple>(#c_layout)
+            )
+        ),
+        | None => quote_spanned!(Span::mixed_site()=>
+            ::safer_ffi::layout::from_raw_unchecked(#c_layout)
+        ),
+    }
+}
+
+/// `from_raw_unchecked(arg)`, for the `arg` of the `export_name` function.
+///
+/// With the `debug-ffi-checks` Cargo feature, the `arg` is validated instead,
+/// the violations being reported to the `::safer_ffi::ffi_checks` handler.
+fn from_raw_arg (ty: &'_ Type, arg: &'_ Ident, export_name: &'_ LitStr)
+  -> TokenStream2
+{
+    if cfg!(feature = "debug-ffi-checks").not() {
+        return from_raw_unchecked(ty, arg);
+    }
+    let arg_str = &LitStr::new(&arg.to_string(), arg.span());
+    let checked_ty = ffi_ty(ty);
+    let checked = quote_spanned!(Span::mixed_site()=> {
+        #[allow(unused_imports)]
+        use ::safer_ffi::ffi_checks::{Check as _, CheckFallback as _};
+        let arg = ::safer_ffi::ffi_checks::from_raw::<#checked_ty>(
+            #arg,
+            #export_name,
+            #arg_str,
+        );
+        if let ::safer_ffi::ඞ::Err(reason) = (&::safer_ffi::ffi_checks::Arg(&arg)).check() {
+            ::safer_ffi::ffi_checks::violation::<#checked_ty>(
+                #export_name,
+                #arg_str,
+                reason,
+            );
+        }
+        arg
+    });
+    match tuple_repr(ty) {
+        | Some(_) => quote_spanned!(Span::mixed_site()=>
+            ::safer_ffi::ඞ::From::from(#checked)
+        ),
+        | None => checked,
+    }
+}
+
+/// `into_raw(value)`, for a `ty` (converted to its `TupleN` beforehand, for a
+/// tuple).
+fn into_raw (ty: &'_ Type, value: &'_ dyn ToTokens)
+  -> TokenStream2
+{
+    match tuple_repr(ty) {
+        | Some(Tuple) => quote_spanned!(Span::mixed_site()=>
+            ::safer_ffi::layout::into_raw::<#Tuple>(
+                ::safer_ffi::ඞ::From::from(#value)
+            )
+        ),
+        | None => quote_spanned!(Span::mixed_site()=>
+            ::safer_ffi::layout::into_raw(#value)
+        ),
+    }
+}
+
+/// The `args: ...` trailing parameter of a C-variadic function.
+fn is_c_variadic (ty: &'_ Type)
+  -> bool
+{
+    matches!(*ty, Type::Verbatim(ref dots) if dots.to_string() == "...")
+}
+
+/// `validate_utf8`: a string parameter, exported as a (possibly `NULL`, or
+/// invalid) raw C layout, which the checked constructor of its type validates.
+#[derive(Clone, Copy)]
+enum Utf8Param {
+    /// `&str`
+    Str,
+    /// `str::Ref<'_>`
+    StrRef,
+    /// `char_p::Ref<'_>`
+    CharP,
+}
+
+impl Utf8Param {
+    fn of (ty: &'_ Type)
+      -> Option<Utf8Param>
+    {
+        match *ty {
+            | Type::Reference(TypeReference { mutability: None, ref elem, .. }) => {
+                match **elem {
+                    | Type::Path(TypePath { qself: None, ref path })
+                        if path.is_ident("str")
+                    => Some(Utf8Param::Str),
+                    | _ => None,
+                }
+            },
+            | Type::Path(TypePath { qself: None, ref path }) => {
+                let mut segments = path.segments.iter().rev();
+                let (last, module) = (segments.next()?, segments.next()?);
+                if last.ident != "Ref" {
+                    return None;
+                }
+                match &*module.ident.to_string() {
+                    | "str" => Some(Utf8Param::StrRef),
+                    | "char_p" => Some(Utf8Param::CharP),
+                    | _ => None,
+                }
+            },
+            | _ => None,
+        }
+    }
+
+    /// The type of the parameter, in the exported signature.
+    fn raw_ty (self: Self)
+      -> Type
+    {
+        match self {
+            | Utf8Param::Str | Utf8Param::StrRef => parse_quote!(
+                ::safer_ffi::ඞ::Option<::safer_ffi::prelude::c_slice::Ref<'_, u8>>
+            ),
+            | Utf8Param::CharP => parse_quote!(
+                *const ::safer_ffi::c_char
+            ),
+        }
+    }
+
+    /// The `Result` of the validation of `raw` (a `raw_ty()`).
+    fn validate (self: Self, raw: &'_ TokenStream2)
+      -> TokenStream2
+    {
+        match self {
+            | Utf8Param::Str => quote!(
+                ::safer_ffi::prelude::str::Ref::try_from_c_slice(#raw)
+                    .map(::safer_ffi::prelude::str::Ref::as_str)
+            ),
+            | Utf8Param::StrRef => quote!(
+                ::safer_ffi::prelude::str::Ref::try_from_c_slice(#raw)
+            ),
+            | Utf8Param::CharP => quote!(
+                ::safer_ffi::prelude::char_p::Ref::from_ptr_checked(#raw)
+            ),
+        }
+    }
+}
+
 pub(in super)
 fn handle (
     args: Args,
     mut fun: ItemFn,
 ) -> Result<TokenStream2>
 {
+    // `#[ffi_pure]` / `#[ffi_const]` (only meaningful to the C header
+    // generation, hence stripped from the Rust function).
+    let mut purity = None::<(&str, Path)>;
+    let mut attrs_err = None;
+    fun.attrs.retain(|attr| {
+        let kind = match () {
+            | _ if attr.path.is_ident("ffi_pure") => "pure",
+            | _ if attr.path.is_ident("ffi_const") => "const",
+            | _ => return true,
+        };
+        if attr.tokens.is_empty().not() {
+            attrs_err.get_or_insert(Error::new_spanned(
+                &attr.tokens,
+                format!("`#[ffi_{kind}]` takes no arguments"),
+            ));
+        } else if purity.is_some() {
+            attrs_err.get_or_insert(Error::new_spanned(
+                attr,
+                "`#[ffi_pure]` and `#[ffi_const]` are mutually exclusive",
+            ));
+        }
+        purity.get_or_insert((kind, attr.path.clone()));
+        false
+    });
+    if let Some(err) = attrs_err {
+        return Err(err);
+    }
+    if let Some((kind, path)) = &purity {
+        if fun.sig.asyncness.is_some() || args.executor.is_some() {
+            bail! {
+                format!("`#[ffi_{kind}]` is not supported for `async fn`s") => path,
+            }
+        }
+        let returns_unit = match &fun.sig.output {
+            | ReturnType::Default => true,
+            | ReturnType::Type(_, ty) => is_unit(ty),
+        };
+        if returns_unit {
+            bail! {
+                format!("`#[ffi_{kind}]` functions must return a value") => path,
+            }
+        }
+    }
+
+    // C-variadic fn case (`#![feature(c_variadic)]`).
+    if let Some(variadic) = &fun.sig.variadic {
+        if let Some(executor) = &args.executor {
+            bail! {
+                "`executor` is not supported for C-variadic functions" => executor.kw,
+            }
+        }
+        if let Some(asyncness) = &fun.sig.asyncness {
+            bail! {
+                "C-variadic functions cannot be `async`" => asyncness,
+            }
+        }
+        if let Some(js) = &args.js {
+            bail! {
+                "`js` is not supported for C-variadic functions" => js.kw,
+            }
+        }
+        if let Some(FreeOf { kw, .. }) = &args.free_of {
+            bail! {
+                "`free_of` is not supported for C-variadic functions" => kw,
+            }
+        }
+        if fun.sig.unsafety.is_none() {
+            bail! {
+                "C-variadic functions must be `unsafe`" => variadic,
+            }
+        }
+        if let Some(CatchPanics { kw, .. }) = &args.catch_panics {
+            bail! {
+                "`catch_panics` is not supported for C-variadic functions" => kw,
+            }
+        }
+        if let Some(Convention { abi, .. }) = &args.convention {
+            if abi.value() != "C" {
+                bail! {
+                    "C-variadic functions must use the C calling convention" => abi,
+                }
+            }
+        }
+        match fun.sig.inputs.iter().rev().next() {
+            | Some(FnArg::Typed(PatType { ty, .. })) if is_c_variadic(ty) => {},
+            | _ => bail! {
+                "expected a named `args: ...` parameter" => variadic,
+            },
+        }
+        if fun.sig.inputs.len() < 2 {
+            bail! {
+                "C-variadic functions need at least one parameter before the `...`"
+                => variadic,
+            }
+        }
+    }
+
+    if let Some(AsyncVia { kw, .. }) = &args.async_via {
+        if fun.sig.asyncness.is_none() {
+            bail! {
+                "`async_via` can only be applied to an `async fn`" => kw,
+            }
+        }
+    }
+
     // async fn case.
     if args.executor.is_some() || fun.sig.asyncness.is_some() {
+        if let Some(weak_linkage) = &args.weak_linkage {
+            bail! {
+                "`weak_linkage` is not supported for `async fn`s" => weak_linkage,
+            }
+        }
+        if let Some(FreeOf { kw, .. }) = &args.free_of {
+            bail! {
+                "`free_of` is not supported for `async fn`s" => kw,
+            }
+        }
+        if let Some(last_error) = &args.last_error {
+            bail! {
+                "`last_error` is not supported for `async fn`s" => last_error,
+            }
+        }
+        if let Some(validate_utf8) = &args.validate_utf8 {
+            bail! {
+                "`validate_utf8` is not supported for `async fn`s" => validate_utf8,
+            }
+        }
         if true {
             #[cfg(feature = "async-fn")]
             return async_fn::export(args, &fun);
@@ -64,17 +419,58 @@ fn handle (
     fun.attrs.push(parse_quote!(
         #[forbid(elided_lifetimes_in_paths)]
     ));
+    // `convention = "…"` picks the ABI (of both the Rust function and the
+    // exported one).
+    if let Some(Convention { abi, .. }) = &args.convention {
+        if let Some(Abi { name: Some(name), .. }) = &fun.sig.abi {
+            if name.value() != abi.value() {
+                bail! {
+                    "conflicting ABIs: remove it or the `convention = …`" => name,
+                }
+            }
+        }
+        fun.sig.abi = Some(parse_quote!(
+            extern #abi
+        ));
+    }
     // Ergonomics: lack-of-`extern` defaults to `extern "C"`.
     fun.sig.abi.get_or_insert_with(|| parse_quote!(
         extern "C"
     ));
+    // Ergonomics: `-> impl Trait` (for a `#[derive_ReprC(dyn)]` trait) is
+    // boxed into a `VirtualPtr<dyn Trait>`.
+    if let ReturnType::Type(_, ref mut ret_ty) = fun.sig.output {
+        if let Type::ImplTrait(TypeImplTrait { impl_token, bounds }) = &**ret_ty {
+            if cfg!(feature = "dyn-traits").not() {
+                bail! {
+                    "\
+                        Support for `-> impl Trait` exports requires that the \
+                        `dyn-traits` Cargo feature of `safer-ffi` be enabled.\
+                    " => impl_token,
+                }
+            }
+            let block = &fun.block;
+            let boxed: Block = parse_quote_spanned!(ret_ty.span()=> {
+                ::safer_ffi::ඞ::From::from(
+                    ::safer_ffi::ඞ::boxed::Box::new((move || #block)())
+                )
+            });
+            **ret_ty = parse_quote_spanned!(ret_ty.span()=>
+                ::safer_ffi::prelude::VirtualPtr<dyn #bounds>
+            );
+            *fun.block = boxed;
+        }
+    }
     // No more changes to the original function:
     let fun = fun;
 
     let extern_ = fun.sig.abi.as_ref().unwrap();
+    let unwinds = matches!(&extern_.name, Some(abi) if abi.value() == "C-unwind");
     if matches!(
         &extern_.name, Some(abi)
         if SUPPORTED_ABIS.contains(&abi.value().as_str()).not()
+        // (already checked when parsing args)
+        && args.convention.is_none()
     )
     {
         return Err(Error::new_spanned(
@@ -91,14 +487,79 @@ fn handle (
         }
     }
 
+    if let Some(FreeOf { kw, .. }) = &args.free_of {
+        if fun.sig.inputs.len() != 1 {
+            bail! {
+                "a `free_of` function must take exactly one argument: \
+                the pointer to free" => kw,
+            }
+        }
+    }
+
+    // `-> Result<T, E>` is lowered to `-> E`, with `T` written to an `out`
+    // parameter.
+    let result_ret = result_ret(&fun.sig.output);
+    let out_arg = Ident::new("out", Span::mixed_site());
+    if let Some(last_error) = &args.last_error {
+        if result_ret.is_none() {
+            bail! {
+                "`last_error` requires a `Result` return type" => last_error,
+            }
+        }
+    }
+    if let Some(validate_utf8) = &args.validate_utf8 {
+        if result_ret.is_none() {
+            bail! {
+                "`validate_utf8` requires a `Result` return type, \
+                whose error type implements `From<char_p::InvalidStr>`" => validate_utf8,
+            }
+        }
+        if let Some(js) = &args.js {
+            bail! {
+                "`js` is not supported along `validate_utf8`" => js.kw,
+            }
+        }
+    }
+    // `validate_utf8`: the string parameters (if any), lowered to their raw C
+    // layout.
+    let utf8_param = |ty: &'_ Type| {
+        args.validate_utf8.as_ref().and_then(|_| Utf8Param::of(ty))
+    };
+    let param_ty = |ty: &'_ Type| match utf8_param(ty) {
+        | Some(param) => param.raw_ty(),
+        | None => ffi_ty(ty),
+    };
+    if let Some((OkTy, _)) = result_ret {
+        if let Some(variadic) = &fun.sig.variadic {
+            bail! {
+                "`Result` returns are not supported for C-variadic functions" => variadic,
+            }
+        }
+        let has_out_arg = fun.sig.inputs.iter().any(|fn_arg| matches!(
+            *fn_arg,
+            FnArg::Typed(PatType { ref pat, .. })
+            if matches!(**pat, Pat::Ident(PatIdent { ref ident, .. }) if ident == "out")
+        ));
+        if has_out_arg && is_unit(OkTy).not() {
+            bail! {
+                "a parameter is already named `out`, which the `Ok` value \
+                of the returned `Result` is written to" => OkTy,
+            }
+        }
+    }
+
     // The actually ffi-exported function: a shim around the given input.
     let mut ffi_fun = fun.clone();
+    // (the `args: ...` are set aside, and put back afterwards.)
+    let va_arg = ffi_fun.sig.variadic.is_some().then(|| {
+        ffi_fun.sig.inputs.pop().unwrap().into_value()
+    });
     let each_arg = &ffi_fun.sig.inputs.iter_mut().enumerate().vmap(|(i, arg)| {
         match *arg {
             | FnArg::Receiver(_) => unreachable!(),
             | FnArg::Typed(PatType { ref mut pat, ref mut ty, .. }) => {
                 // C-ize each arg type.
-                **ty = concrete_c_type(ty);
+                **ty = concrete_c_type(&param_ty(ty));
 
                 // Normalize the arg name: strip `ref`s and `mut` if ident, else
                 // fall back to a `ARG_PREFIX{i}` override.
@@ -141,7 +602,7 @@ fn handle (
         fun.sig.inputs.iter().map(|fn_arg| match *fn_arg {
             | FnArg::Typed(PatType { ref ty, .. }) => &**ty,
             | FnArg::Receiver(_) => unreachable!(),
-        })
+        }).filter(|&ty| is_c_variadic(ty).not())
     }
     // C-ize the return type.
     match ffi_fun.sig.output {
@@ -149,7 +610,15 @@ fn handle (
             ->
             ::safer_ffi::ඞ::CLayoutOf<()>
         ),
-        ReturnType::Type(_, ref mut ty) => **ty = concrete_c_type(ty),
+        ReturnType::Type(_, ref mut ty) => **ty = concrete_c_type(&ffi_ty(ty)),
+    }
+    if let Some((OkTy, ErrTy)) = result_ret {
+        let CErrTy = concrete_c_type(ErrTy);
+        ffi_fun.sig.output = parse_quote!( -> #CErrTy );
+        if is_unit(OkTy).not() {
+            let OutTy = concrete_c_type(&parse_quote!( *mut #OkTy ));
+            ffi_fun.sig.inputs.push(parse_quote!( #out_arg: #OutTy ));
+        }
     }
 
     let ItemFn {
@@ -163,28 +632,174 @@ fn handle (
         "{}__ffi_export__", fname,
         span = fname.span().resolved_at(Span::mixed_site()),
     );
+    // The symbol, which may differ from the name in the headers.
+    let symbol_str = match &args.export_name {
+        | Some(ExportName { name, .. }) => name,
+        | None => export_name_str,
+    };
     ffi_fun.attrs.push(parse_quote!(
         #[cfg_attr(not(target_arch = "wasm32"),
-            export_name = #export_name_str,
+            export_name = #symbol_str,
         )]
     ));
+    if let Some(weak_linkage) = &args.weak_linkage {
+        // Requires `#![feature(linkage)]` in the calling crate.
+        ffi_fun.attrs.push(parse_quote_spanned!(weak_linkage.span=>
+            #[cfg_attr(not(target_arch = "wasm32"),
+                linkage = "weak",
+            )]
+        ));
+    }
     #[apply(let_quote!)]
     use ::safer_ffi::{
         ඞ,
         layout,
     };
-    *ffi_fun.block = parse_quote_spanned!(Span::mixed_site()=> {
-        let abort_on_unwind_guard;
-        (
-            abort_on_unwind_guard = #ඞ::UnwindGuard(#export_name_str),
+    let unit: Type = parse_quote!( () );
+    let ret_ty = match &fun.sig.output {
+        | ReturnType::Type(_, ty) => &**ty,
+        | ReturnType::Default => &unit,
+    };
+    let body = if let Some(va_arg) = va_arg {
+        // The `...` cannot be forwarded to `#fname`: the shim thus inlines its
+        // body instead, with the (Rust-typed) parameters bound beforehand.
+        let each_pat = fun.sig.inputs.iter().filter_map(|fn_arg| match *fn_arg {
+            | FnArg::Typed(PatType { ref pat, ref ty, .. }) if is_c_variadic(ty).not() => Some(pat),
+            | _ => None,
+        });
+        let EachArgTy @ _ = arg_tys(&fun).vec();
+        let each_from_raw = EachArgTy.iter().zip(each_arg).map(|(ty, arg)| {
+            from_raw_arg(ty, arg, export_name_str)
+        });
+        let block = &fun.block;
+        let into_raw = into_raw(ret_ty, &quote!( (move || #block)() ));
+        ffi_fun.sig.inputs.push(va_arg);
+        quote_spanned!(Span::mixed_site()=>
             unsafe {
-                #layout::into_raw(
-                    #fname( #(#layout::from_raw_unchecked(#each_arg)),* )
+                #(
+                    let #each_pat: #EachArgTy = #each_from_raw;
+                )*
+                #into_raw
+            }
+        )
+    } else {
+        let each_from_raw = arg_tys(&fun).zip(each_arg).map(|(ty, arg)| {
+            match utf8_param(ty) {
+                // (validated beforehand)
+                | Some(_) => arg.to_token_stream(),
+                | None => from_raw_arg(ty, arg, export_name_str),
+            }
+        });
+        // `validate_utf8`: an invalid string is recorded as the last error, and
+        // returned as the `Err`or.
+        let each_validation = arg_tys(&fun).zip(each_arg).filter_map(|(ty, arg)| {
+            let param = utf8_param(ty)?;
+            let raw = from_raw_arg(&param.raw_ty(), arg, export_name_str);
+            let validated = param.validate(&raw);
+            let arg_str = arg.to_string();
+            Some(quote_spanned!(Span::mixed_site()=>
+                let #arg = match #validated {
+                    | ::safer_ffi::ඞ::Ok(it) => it,
+                    | ::safer_ffi::ඞ::Err(err) => {
+                        ::safer_ffi::error::set_last_error(::safer_ffi::ඞ::core::format_args!(
+                            "invalid `{}` argument: {}", #arg_str, err,
+                        ));
+                        return ::safer_ffi::ඞ::Err(::safer_ffi::ඞ::From::from(err));
+                    },
+                };
+            ))
+        }).vec();
+        let mut ret = if each_validation.is_empty() {
+            quote_spanned!(Span::mixed_site()=>
+                unsafe {
+                    #fname( #(#each_from_raw),* )
+                }
+            )
+        } else {
+            quote_spanned!(Span::mixed_site()=>
+                (move || unsafe {
+                    #(#each_validation)*
+                    #fname( #(#each_from_raw),* )
+                })()
+            )
+        };
+        if let Some(CatchPanics { sentinel, .. }) = &args.catch_panics {
+            // Should the panic itself be caught, the guard still aborts on any
+            // unwinding out of its payload's `drop`.
+            let on_panic = match sentinel {
+                | Some((_eq, sentinel)) => sentinel.to_token_stream(),
+                | None => quote!( #ඞ::process::abort() ),
+            };
+            ret = quote_spanned!(Span::mixed_site()=>
+                match #ඞ::catch_panic(#export_name_str, || #ret) {
+                    | #ඞ::Some(ret) => ret,
+                    | #ඞ::None => #on_panic,
+                }
+            );
+        }
+        let c_ret = match result_ret {
+            | Some((OkTy, ErrTy)) => {
+                // `last_error`: the `Display` of the error is recorded.
+                let set_last_error = args.last_error.as_ref().map(|_| quote_spanned!(Span::mixed_site()=>
+                    ::safer_ffi::error::set_last_error(&err);
+                ));
+                // (else, the `Ok` value is dropped).
+                let (ok_pat, write_out) = if is_unit(OkTy) {
+                    (quote!( _ ), quote!())
+                } else {
+                    (quote_spanned!(Span::mixed_site()=> ok), quote_spanned!(Span::mixed_site()=>
+                        let #out_arg: *mut #OkTy = unsafe {
+                            #layout::from_raw_unchecked(#out_arg)
+                        };
+                        if !#out_arg.is_null() {
+                            unsafe { #out_arg.write(ok) }
+                        }
+                    ))
+                };
+                quote_spanned!(Span::mixed_site()=>
+                    match ret {
+                        | #ඞ::result::Result::Ok(#ok_pat) => {
+                            #write_out
+                            unsafe {
+                                #layout::into_raw::<#ErrTy>(
+                                    <#ErrTy as ::safer_ffi::error_code::ErrorCode>::SUCCESS
+                                )
+                            }
+                        },
+                        | #ඞ::result::Result::Err(err) => unsafe {
+                            #set_last_error
+                            #layout::into_raw::<#ErrTy>(err)
+                        },
+                    }
                 )
             },
-            #ඞ::mem::forget(abort_on_unwind_guard),
-        ).1
-    });
+            | None => {
+                let into_raw = into_raw(ret_ty, &quote_spanned!(Span::mixed_site()=> ret));
+                quote_spanned!(Span::mixed_site()=>
+                    unsafe { #into_raw }
+                )
+            },
+        };
+        quote_spanned!(Span::mixed_site()=> {
+            let ret = #ret;
+            #c_ret
+        })
+    };
+    // `extern "C-unwind"`: panics are allowed to unwind into the caller.
+    *ffi_fun.block = if unwinds {
+        parse_quote_spanned!(Span::mixed_site()=> {
+            #body
+        })
+    } else {
+        parse_quote_spanned!(Span::mixed_site()=> {
+            let abort_on_unwind_guard;
+            (
+                abort_on_unwind_guard = #ඞ::UnwindGuard(#export_name_str),
+                #body,
+                #ඞ::mem::forget(abort_on_unwind_guard),
+            ).1
+        })
+    };
 
     #[cfg_attr(not(feature = "js"), allow(unused))]
     let mut js_body = quote!();
@@ -353,71 +968,259 @@ fn handle (
             | ReturnType::Default => &*storage.get_or_insert(
                 Type::Verbatim(quote!( () ))
             ),
-            | ReturnType::Type(_, ref ty) => &**ty,
+            | ReturnType::Type(_, ref ty) => &*storage.get_or_insert(ffi_ty(ty)),
+        };
+        let mut EachArgTy @ _ = arg_tys(&fun).map(param_ty).vec();
+        let mut each_arg = each_arg.clone();
+        let RetTy @ _ = match result_ret {
+            | Some((OkTy, ErrTy)) => {
+                if is_unit(OkTy).not() {
+                    EachArgTy.push(parse_quote!( *mut #OkTy ));
+                    each_arg.push(out_arg.clone());
+                }
+                ErrTy
+            },
+            | None => RetTy,
         };
-        let ref EachArgTy @ _ = arg_tys(&fun).vec();
-        let each_doc = utils::extract_docs(&fun.attrs)?;
+        let ref EachArgTy = EachArgTy;
+        let ref each_arg = each_arg;
+        // `&mut` borrows cannot alias any other argument.
+        let each_restrict = EachArgTy.iter().map(|ty| matches!(
+            ty, Type::Reference(TypeReference { mutability: Some(_), .. }),
+        ));
+        let mut each_doc = utils::extract_docs(&fun.attrs)?;
+        for (arg, ty) in each_arg.iter().zip(EachArgTy) {
+            if is_out_param(ty) {
+                if each_doc.is_empty().not() {
+                    each_doc.push(parse_quote!(""));
+                }
+                let out_doc = format!(
+                    " `{arg}` is write-only: it may point to uninitialized memory, \
+                    which this function initializes.",
+                );
+                each_doc.push(parse_quote!(#out_doc));
+            }
+        }
+        if args.weak_linkage.is_some() {
+            if each_doc.is_empty().not() {
+                each_doc.push(parse_quote!(""));
+            }
+            each_doc.push(parse_quote!(
+                " This function is a weak symbol: the application may override \
+                it with its own (strong) definition, at link time."
+            ));
+        }
+        if let Some(CatchPanics { sentinel, .. }) = &args.catch_panics {
+            if each_doc.is_empty().not() {
+                each_doc.push(parse_quote!(""));
+            }
+            let panic_doc = match sentinel {
+                | Some((_eq, sentinel)) => {
+                    // (`- 1` => `-1`, `i32 :: MIN` => `i32::MIN`)
+                    let sentinel = sentinel.to_token_stream().to_string().replace(" :: ", "::");
+                    let sentinel = match sentinel.strip_prefix("- ") {
+                        | Some(abs) => format!("-{abs}"),
+                        | None => sentinel,
+                    };
+                    format!(" Should this function panic, it returns `{sentinel}` instead.")
+                },
+                | None => " Should this function panic, it aborts the process \
+                    (after logging the panic message).".into(),
+            };
+            each_doc.push(parse_quote!(#panic_doc));
+        } else if unwinds {
+            if each_doc.is_empty().not() {
+                each_doc.push(parse_quote!(""));
+            }
+            each_doc.push(parse_quote!(
+                " Should this function panic, the panic unwinds into the caller."
+            ));
+        }
+        if matches!(result_ret, Some((OkTy, _)) if is_unit(OkTy).not()) {
+            if each_doc.is_empty().not() {
+                each_doc.push(parse_quote!(""));
+            }
+            each_doc.push(parse_quote!(
+                " On success, the result is written to `out` (unless it is `NULL`)."
+            ));
+        }
+        let since = match &args.since {
+            | Some(Since { version, .. }) => {
+                if each_doc.is_empty().not() {
+                    each_doc.push(parse_quote!(""));
+                }
+                let since_doc = format!(" Available since version {}.", version.value());
+                each_doc.push(parse_quote!(#since_doc));
+                quote!( #ඞ::Some(#version) )
+            },
+            | None => quote!( #ඞ::None ),
+        };
+        let group = match &args.group {
+            | Some(Group { name, .. }) => quote!( #ඞ::Some(#name) ),
+            | None => quote!( #ඞ::None ),
+        };
+        let error_code = args.error_code.is_some();
+        let variadic = fun.sig.variadic.is_some();
+        let export_name = match &args.export_name {
+            | Some(ExportName { name, .. }) => quote!( #ඞ::Some(#name) ),
+            | None => quote!( #ඞ::None ),
+        };
+        let convention = match &args.convention {
+            | Some(Convention { abi, .. }) => quote!( #ඞ::Some(#abi) ),
+            | None => quote!( #ඞ::None ),
+        };
+        let (deprecated, allow_deprecated) = match utils::deprecation_note(&fun.attrs)? {
+            | Some(note) => (quote!( #ඞ::Some(#note) ), quote!( #[allow(deprecated)] )),
+            | None => (quote!( #ඞ::None ), quote!()),
+        };
+        let must_use = fun.attrs.iter().any(|attr| attr.path.is_ident("must_use"));
+        // `#[cfg(…)]`: the declaration is guarded by the equivalent C
+        // preprocessor condition (unless overridden by `cfg_header`), and
+        // skipped altogether by the other languages when the predicate does
+        // not hold.
+        let each_cfg =
+            fun.attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("cfg"))
+                .map(|attr| attr.parse_meta().map(|meta| match meta {
+                    | Meta::List(MetaList { nested, .. }) => nested.into_token_stream(),
+                    | _ => quote!(),
+                }))
+                .collect::<Result<Vec<_>>>()?
+        ;
+        let cfg = match (&args.cfg_header, utils::cfg_predicate(&fun.attrs)?) {
+            | (Some(CfgHeader { condition, .. }), _) => quote!(
+                #ඞ::Some(&#headers::CfgPredicate::Header(#condition))
+            ),
+            | (None, Some(predicate)) => quote!( #ඞ::Some(&#predicate) ),
+            | (None, None) => quote!( #ඞ::None ),
+        };
+        let purity = match purity {
+            | Some((kind, _)) => {
+                if each_doc.is_empty().not() {
+                    each_doc.push(parse_quote!(""));
+                }
+                let purity_doc = match kind {
+                    | "pure" => " This function is pure: it has no side effects, \
+                        and its result only depends on its arguments and on the \
+                        memory they point to.",
+                    | _ => " This function is `const`: it has no side effects, \
+                        and its result only depends on the values of its arguments.",
+                };
+                each_doc.push(parse_quote!(#purity_doc));
+                quote!( #ඞ::Some(#kind) )
+            },
+            | None => quote!( #ඞ::None ),
+        };
+        // (`explicit-registration` does without the `::inventory`-based
+        // registries)
+        if let (Some(FreeOf { ty, .. }), false) = (
+            &args.free_of,
+            cfg!(feature = "explicit-registration"),
+        )
+        {
+            let handle = format!(
+                "{}Handle",
+                ty.path.segments.last().expect("non-empty path").ident,
+            );
+            let mut ArgTyStatic @ _ = EachArgTy[0].clone();
+            visit_mut::VisitMut::visit_type_mut(
+                &mut utils::RemapNonStaticLifetimesTo { new_lt_name: "static" },
+                &mut ArgTyStatic,
+            );
+            let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
+                quote!( #![crate = #ඞ] )
+            });
+            ret.extend(quote!(
+                #[cfg(all(not(target_arch = "wasm32"), #(#each_cfg),*))]
+                #ඞ::inventory::submit! {
+                    #inventory_krate
+
+                    #ඞ::FreeFn {
+                        handle: #handle,
+                        group: #group,
+                        free_fn: #export_name_str,
+                        ty: |lang| <
+                            #ඞ::CLayoutOf<#ArgTyStatic> as #ඞ::CType
+                        >::name(lang),
+                    }
+                }
+            ));
+        }
         let (generics, _, where_clause) = fun.sig.generics.split_for_impl();
-        let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
-            quote!( #![crate = #ඞ] )
-        });
-        ret.extend(quote!(
-            #[cfg(not(target_arch = "wasm32"))]
-            #ඞ::inventory::submit! {
-                #inventory_krate
-
-                #ඞ::FfiExport {
-                    name: #export_name_str,
-                    gen_def: {
-                        fn gen_def #generics (
-                            definer: &'_ mut dyn #ඞ::Definer,
-                            lang: #headers::Language,
-                        ) -> #ඞ::io::Result<()>
-                        #where_clause
-                        {#ඞ::io::Result::<()>::Ok({
-                            // FIXME: this merges the value namespace with the type
-                            // namespace...
-                            if ! definer.insert(#export_name_str) {
-                                return #ඞ::result::Result::Err(
-                                    #ඞ::io::Error::new(
-                                        #ඞ::io::ErrorKind::AlreadyExists,
-                                        #ඞ::concat!(
-                                            "Error, attempted to declare `",
-                                            #export_name_str,
-                                            "` while another declaration already exists",
-                                        ),
+        ret.extend(super::register_ffi_exports(&fun.sig.ident, &[quote!(
+            #ඞ::FfiExport {
+                name: #export_name_str,
+                kind: #headers::ItemKind::Function,
+                gen_def: {
+                    #allow_deprecated
+                    fn gen_def #generics (
+                        definer: &'_ mut dyn #ඞ::Definer,
+                        lang: #headers::Language,
+                    ) -> #ඞ::io::Result<()>
+                    #where_clause
+                    {#ඞ::io::Result::<()>::Ok({
+                        // FIXME: this merges the value namespace with the type
+                        // namespace...
+                        if ! definer.insert(#export_name_str) {
+                            return #ඞ::result::Result::Err(
+                                #ඞ::io::Error::new(
+                                    #ඞ::io::ErrorKind::AlreadyExists,
+                                    #ඞ::concat!(
+                                        "Error, attempted to declare `",
+                                        #export_name_str,
+                                        "` while another declaration already exists",
+                                    ),
+                                )
+                            );
+                        }
+                    #(
+                        #headers::__define_self__::<#EachArgTy>(definer, lang)?;
+                    )*
+                        #headers::__define_self__::<#RetTy>(definer, lang)?;
+                        #headers::__define_fn__(
+                            definer,
+                            lang,
+                            &[ #(#each_doc),* ],
+                            #export_name_str,
+                            &[
+                                #(
+                                    #ඞ::FunctionArg::new(
+                                        #ඞ::stringify!(#each_arg),
+                                        &#ඞ::PhantomData::<
+                                            #ඞ::CLayoutOf<#EachArgTy>,
+                                        >,
                                     )
-                                );
-                            }
-                        #(
-                            #headers::__define_self__::<#EachArgTy>(definer, lang)?;
-                        )*
-                            #headers::__define_self__::<#RetTy>(definer, lang)?;
-                            #headers::__define_fn__(
-                                definer,
-                                lang,
-                                &[ #(#each_doc),* ],
-                                #export_name_str,
-                                &[
-                                    #(
-                                        #ඞ::FunctionArg {
-                                            name: #ඞ::stringify!(#each_arg),
-                                            ty: &#ඞ::PhantomData::<
-                                                #ඞ::CLayoutOf<#EachArgTy>,
-                                            >,
-                                        }
-                                    ),*
-                                ],
-                                &#ඞ::PhantomData::<
-                                    #ඞ::CLayoutOf< #RetTy >,
-                                >,
-                            )?;
-                        })}
-                        gen_def
-                    },
-                }
+                                    .with_restrict(#each_restrict)
+                                    .with_niche(<#EachArgTy as #ඞ::ReprC>::niche())
+                                ),*
+                            ],
+                            &#ඞ::PhantomData::<
+                                #ඞ::CLayoutOf< #RetTy >,
+                            >,
+                            &#headers::FnMeta {
+                                module_path: #ඞ::module_path!(),
+                                file: #ඞ::file!(),
+                                manifest_dir: #ඞ::env!("CARGO_MANIFEST_DIR"),
+                                since: #since,
+                                group: #group,
+                                error_code: #error_code,
+                                variadic: #variadic,
+                                export_name: #export_name,
+                                convention: #convention,
+                                deprecated: #deprecated,
+                                must_use: #must_use,
+                                purity: #purity,
+                                ret_niche: <#RetTy as #ඞ::ReprC>::niche(),
+                                cfg: #cfg,
+                                cfg_active: #ඞ::cfg!(all(#(#each_cfg),*)),
+                            },
+                        )?;
+                    })}
+                    gen_def
+                },
             }
-        ));
+        )]));
     }
 
     Ok(ret)
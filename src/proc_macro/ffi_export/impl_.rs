@@ -0,0 +1,238 @@
+use super::*;
+
+#[derive(Default)]
+pub(in super)
+struct Args {
+    prefix: Option<Prefix>,
+}
+
+/// `prefix = "counter"`
+struct Prefix {
+    _kw: kw::prefix,
+    _eq: Token![=],
+    name: LitStr,
+}
+
+mod kw {
+    ::syn::custom_keyword!(prefix);
+}
+
+impl Parse for Args {
+    fn parse (
+        input: ParseStream<'_>,
+    ) -> Result<Args>
+    {
+        let mut ret = Args::default();
+        while input.is_empty().not() {
+            let snoopy = input.lookahead1();
+            match () {
+                | _case if snoopy.peek(kw::prefix) => {
+                    if ret.prefix.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.prefix = Some(Prefix {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        name: {
+                            let it = input.parse::<LitStr>()?;
+                            if it.parse::<Ident>().is_err() {
+                                bail! {
+                                    "expected a function name prefix (identifier)" => it,
+                                }
+                            }
+                            it
+                        },
+                    });
+                },
+
+                | _default => return Err(snoopy.error()),
+            }
+            let _: Option<Token![,]> = input.parse()?;
+        }
+        Ok(ret)
+    }
+}
+
+/// `#[ffi_export]` on an `impl Ty { … }` block: export each of its `pub fn`s
+/// as a `<ty>_<method>()` free function (taking the receiver, if any, as its
+/// first parameter), along with a `<ty>_free()` one.
+///
+/// The associated functions returning `Self` (the constructors) return a
+/// `repr_c::Box<Ty>` instead.
+pub(in super)
+fn handle (
+    args: Args,
+    input: ItemImpl,
+) -> Result<TokenStream2>
+{
+    if let Some((_, trait_, _)) = &input.trait_ {
+        bail! {
+            "trait `impl`s are not supported" => trait_,
+        }
+    }
+    if input.generics.params.is_empty().not() {
+        bail! {
+            "generic `impl`s are not supported" => input.generics.params,
+        }
+    }
+    let Ty @ _ = &*input.self_ty;
+    let Ty_ident = match Ty {
+        | Type::Path(TypePath { qself: None, path }) => {
+            &path.segments.last().unwrap().ident
+        },
+        | _ => bail! {
+            "expected a type name" => Ty,
+        },
+    };
+    let ty = utils::snake_case(&Ty_ident.to_string());
+    let prefix = match &args.prefix {
+        | Some(Prefix { name, .. }) => name.value(),
+        | None => ty.clone(),
+    };
+    let self_arg = format_ident!("{}", ty, span = Ty_ident.span());
+
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        prelude::repr_c,
+    };
+    let mut ret = input.to_token_stream();
+    for item in &input.items {
+        let method = match item {
+            | ImplItem::Method(method) if matches!(method.vis, Visibility::Public(_)) => method,
+            | _ => continue,
+        };
+        let sig = &method.sig;
+        let method_name = &sig.ident;
+        if let Some(asyncness) = &sig.asyncness {
+            bail! {
+                "`async` methods are not supported" => asyncness,
+            }
+        }
+        if let Some(param) = sig.generics.params.iter().find(|param| {
+            matches!(param, GenericParam::Lifetime(_)).not()
+        })
+        {
+            bail! {
+                "only lifetime parameters are supported" => param,
+            }
+        }
+        let mut params = vec![];
+        let mut call_args = vec![];
+        for (i, fn_arg) in sig.inputs.iter().enumerate() {
+            match fn_arg {
+                | FnArg::Receiver(Receiver { reference: Some((_, lt)), mutability, .. }) => {
+                    let lt = lt.clone().unwrap_or_else(|| parse_quote!( '_ ));
+                    params.push(quote!( #self_arg: &#lt #mutability #Ty ));
+                    call_args.push(quote!( #self_arg ));
+                },
+                | FnArg::Receiver(Receiver { reference: None, .. }) => {
+                    params.push(quote!( #self_arg: #repr_c::Box<#Ty> ));
+                    // (annotated, lest `.into()` resolve to anything but the
+                    // inherent `repr_c::Box -> rust::Box` conversion)
+                    call_args.push(quote!({
+                        let boxed: #ඞ::boxed::Box<#Ty> =
+                            #repr_c::Box::<#Ty>::into(#self_arg)
+                        ;
+                        *boxed
+                    }));
+                },
+                | FnArg::Typed(PatType { pat, ty: arg_ty, .. }) => {
+                    let arg_name = match &**pat {
+                        | Pat::Ident(PatIdent { ident, .. }) if ident != "self" => ident.clone(),
+                        | Pat::Ident(PatIdent { ident, .. }) => bail! {
+                            "unsupported receiver type" => ident,
+                        },
+                        | _ => format_ident!("arg_{}", i, span = pat.span()),
+                    };
+                    let mut arg_ty = (**arg_ty).clone();
+                    visit_mut::VisitMut::visit_type_mut(&mut ReplaceSelf(Ty), &mut arg_ty);
+                    params.push(quote!( #arg_name: #arg_ty ));
+                    call_args.push(quote!( #arg_name ));
+                },
+            }
+        }
+        let call = quote!( <#Ty>::#method_name(#(#call_args),*) );
+        let (RetTy, body) = match &sig.output {
+            | ReturnType::Type(_, ret_ty) if is_self(ret_ty, Ty_ident) => (
+                quote!( #repr_c::Box<#Ty> ),
+                quote!( #repr_c::Box::new(#call) ),
+            ),
+            | ReturnType::Type(_, ret_ty) => {
+                let mut ret_ty = (**ret_ty).clone();
+                visit_mut::VisitMut::visit_type_mut(&mut ReplaceSelf(Ty), &mut ret_ty);
+                (ret_ty.into_token_stream(), call)
+            },
+            | ReturnType::Default => (quote!( () ), call),
+        };
+        let body = match &sig.unsafety {
+            | Some(unsafety) => quote!( #unsafety { #body } ),
+            | None => body,
+        };
+        let fname = format_ident!("{}_{}", prefix, method_name, span = method_name.span());
+        let each_doc = method.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+        let unsafety = &sig.unsafety;
+        let (generics, _, where_clause) = sig.generics.split_for_impl();
+        ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+            #(#each_doc)*
+            #[allow(dead_code)]
+            #unsafety
+            fn #fname #generics (
+                #(#params),*
+            ) -> #RetTy
+            #where_clause
+            {
+                #body
+            }
+        ))?);
+    }
+    let ty_free = format_ident!("{}_free", prefix, span = Ty_ident.span());
+    let free_doc = format!(" Frees a `{Ty_ident}`.");
+    ret.extend(super::fn_::handle(parse2(quote!( free_of = #Ty ))?, parse_quote!(
+        #[doc = #free_doc]
+        #[allow(dead_code)]
+        fn #ty_free (
+            #self_arg: #repr_c::Box<#Ty>,
+        )
+        {
+            #ඞ::mem::drop(#self_arg)
+        }
+    ))?);
+    Ok(ret)
+}
+
+/// `Self`, or the type name itself.
+fn is_self (
+    ty: &'_ Type,
+    Ty_ident @ _: &'_ Ident,
+) -> bool
+{
+    matches!(
+        ty,
+        Type::Path(TypePath { qself: None, path })
+        if path.is_ident("Self") || path.is_ident(Ty_ident)
+    )
+}
+
+/// Replaces the `Self` types with the `impl`ementor, since the free functions
+/// are outside the `impl` block.
+struct ReplaceSelf<'__> (
+    &'__ Type,
+);
+
+impl visit_mut::VisitMut
+    for ReplaceSelf<'_>
+{
+    fn visit_type_mut (
+        self: &'_ mut Self,
+        ty: &'_ mut Type,
+    )
+    {
+        match ty {
+            | Type::Path(TypePath { qself: None, path }) if path.is_ident("Self") => {
+                *ty = self.0.clone();
+            },
+            | _ => visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+/// `#[ffi_export_json]` on a `Ty`: exports `ty_to_json()`, `ty_from_json()`
+/// and `ty_json_free()` functions, (de)serializing it through `serde_json`.
+pub(in super)
+fn handle (
+    args: TokenStream2,
+    input: Item,
+) -> Result<TokenStream2>
+{
+    if let Some(tt) = args.into_iter().next() {
+        bail! {
+            "unexpected parameter" => tt,
+        }
+    }
+    let (Ty @ _, generics) = match &input {
+        | Item::Struct(ItemStruct { ident, generics, .. })
+        | Item::Enum(ItemEnum { ident, generics, .. })
+        => (ident, generics),
+        | _ => bail! {
+            "expected a `struct` or an `enum`" => input,
+        },
+    };
+    if let Some(param) = generics.params.first() {
+        bail! {
+            "generic types are not supported" => param,
+        }
+    }
+    let ty = utils::snake_case(&Ty.to_string());
+    let ty_arg = format_ident!("{}", ty, span = Ty.span());
+    let ty_to_json = format_ident!("{}_to_json", ty, span = Ty.span());
+    let ty_from_json = format_ident!("{}_from_json", ty, span = Ty.span());
+    let ty_json_free = format_ident!("{}_json_free", ty, span = Ty.span());
+
+    let to_json_doc = format!(
+        " Serializes a `{Ty}` to JSON, to be freed with `{ty_json_free}()`, \
+        returning `NULL` if it cannot be.",
+    );
+    let from_json_doc = format!(
+        " Deserializes a `{Ty}` from JSON, returning `NULL` if it is invalid.",
+    );
+    let json_free_doc = format!(" Frees a string obtained from `{ty_to_json}()`.");
+    let mut ret = quote!(
+        #input
+    );
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        prelude::char_p,
+        prelude::repr_c,
+    };
+    ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+        #[doc = #to_json_doc]
+        #[allow(dead_code)]
+        fn #ty_to_json (
+            #ty_arg: &'_ #Ty,
+        ) -> #ඞ::Option<#char_p::Box>
+        {
+            #ඞ::serde_json::to_string(#ty_arg)
+                .ok()
+                // (JSON escapes its `\0`s)
+                .map(|json| #ඞ::convert::TryFrom::try_from(json).unwrap())
+        }
+    ))?);
+    ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+        #[doc = #from_json_doc]
+        #[allow(dead_code)]
+        fn #ty_from_json (
+            json: #char_p::Ref<'_>,
+        ) -> #ඞ::Option<#repr_c::Box<#Ty>>
+        {
+            #ඞ::serde_json::from_str::<#Ty>(json.to_str())
+                .ok()
+                .map(|it| #ඞ::Box::new(it).into())
+        }
+    ))?);
+    ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+        #[doc = #json_free_doc]
+        #[allow(dead_code)]
+        fn #ty_json_free (
+            json: #char_p::Box,
+        )
+        {
+            #ඞ::mem::drop(json)
+        }
+    ))?);
+    Ok(ret)
+}
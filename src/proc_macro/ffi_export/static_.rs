@@ -3,9 +3,63 @@ use super::*;
 pub(in super)
 fn handle (
     _args: parse::Nothing,
-    _input: ItemStatic,
+    mut input: ItemStatic,
 ) -> Result<TokenStream2>
 {
-    #[cfg(not(TODO))]
-    todo!("`#[ffi_export]`ing a `static`");
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        headers,
+    };
+
+    let VAR @ _ = &input.ident;
+    let VAR_str @ _ = &VAR.to_string();
+    let Ty @ _ = &*input.ty.clone();
+    let mutable = input.mutability.is_some();
+    let ref each_doc = utils::extract_docs(&input.attrs)?;
+
+    input.attrs.push(parse_quote!(
+        #[cfg_attr(not(target_arch = "wasm32"),
+            export_name = #VAR_str,
+        )]
+    ));
+    let mut ret = quote!(
+        #input
+
+        // The C code accesses the `static` through its `CLayout`.
+        const _: () = {
+            fn __assert_repr_c<T : #ඞ::ReprC> () {}
+            let _ = __assert_repr_c::<#Ty>;
+        };
+    );
+
+    if cfg!(feature = "headers") {
+        ret.extend(super::register_ffi_exports(&input.ident, &[quote!(
+            #ඞ::FfiExport {
+                name: #VAR_str,
+                kind: #headers::ItemKind::Static,
+                gen_def: |
+                    definer: &'_ mut dyn #ඞ::Definer,
+                    lang: #headers::Language,
+                | {
+                    #headers::__define_self__::<#Ty>(definer, lang)?;
+                    #headers::__define_static__(
+                        definer,
+                        lang,
+                        &[ #(#each_doc),* ],
+                        #VAR_str,
+                        &#ඞ::PhantomData::<
+                            #ඞ::CLayoutOf< #Ty >,
+                        >,
+                        #mutable,
+                        #ඞ::module_path!(),
+                        #ඞ::file!(),
+                        #ඞ::env!("CARGO_MANIFEST_DIR"),
+                    )
+                },
+            }
+        )]));
+    }
+
+    Ok(ret)
 }
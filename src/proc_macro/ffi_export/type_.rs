@@ -1,16 +1,96 @@
 use super::*;
 
+#[derive(Default)]
+pub(in super)
+struct Args {
+    vec: Option<kw::vec>,
+    extends: Option<Extends>,
+    instantiate: Option<Instantiate>,
+}
+
+/// `instantiate = (i32, f64)`, or, for several generic parameters,
+/// `instantiate = ((i32, u8), (f64, u8))`.
+struct Instantiate {
+    kw: kw::instantiate,
+    _eq: Token![=],
+    _parens: token::Paren,
+    EachTy: Punctuated<Type, Token![,]>,
+}
+
+struct Extends {
+    _kw: kw::extends,
+    _eq: Token![=],
+    Base: Path,
+}
+
+mod kw {
+    ::syn::custom_keyword!(extends);
+    ::syn::custom_keyword!(instantiate);
+    ::syn::custom_keyword!(vec);
+}
+
+impl Parse for Args {
+    fn parse (
+        input: ParseStream<'_>,
+    ) -> Result<Args>
+    {
+        let mut ret = Args::default();
+        while input.is_empty().not() {
+            let snoopy = input.lookahead1();
+            match () {
+                | _case if snoopy.peek(kw::vec) => {
+                    if ret.vec.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.vec = Some(input.parse().unwrap());
+                },
+
+                | _case if snoopy.peek(kw::extends) => {
+                    if ret.extends.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    ret.extends = Some(Extends {
+                        _kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        Base: input.parse()?,
+                    });
+                },
+
+                | _case if snoopy.peek(kw::instantiate) => {
+                    if ret.instantiate.is_some() {
+                        return Err(input.error("duplicate parameter"));
+                    }
+                    let contents;
+                    ret.instantiate = Some(Instantiate {
+                        kw: input.parse().unwrap(),
+                        _eq: input.parse()?,
+                        _parens: parenthesized!(contents in input),
+                        EachTy: Punctuated::parse_terminated(&contents)?,
+                    });
+                },
+
+                | _default => return Err(snoopy.error()),
+            }
+            let _: Option<Token![,]> = input.parse()?;
+        }
+        Ok(ret)
+    }
+}
+
 pub(in super)
 fn handle (
-    _args: parse::Nothing,
+    args: Args,
     Ty @ _: &'_ Ident,
     generics: &'_ Generics,
+    fields: Option<&'_ Fields>,
     input: &dyn ToTokens,
 ) -> Result<TokenStream2>
 {
-    if let Some(extraneous) = generics.params.first() {
+    if let Some(extraneous) =
+        generics.params.iter().find(|p| matches!(p, GenericParam::Type(_)).not())
+    {
         bail! {
-            "generic parameters not allowed here" => extraneous,
+            "only generic type parameters are allowed here" => extraneous,
         }
     }
     if let Some(clause) = &generics.where_clause {
@@ -18,23 +98,249 @@ fn handle (
             "`where` clauses not allowed here" => clause.where_token,
         }
     }
-    let ref Ty_str @ _ = Ty.to_string();
-    let inventory_krate = cfg!(not(feature = "inventory-0-3-1")).then(|| {
-        quote!( #![crate = ::safer_ffi] )
-    });
-    Ok(quote!(
+    // The (monomorphized) types to export.
+    let instances = match (&args.instantiate, generics.params.len()) {
+        | (None, 0) => vec![quote!(#Ty)],
+        | (None, _) => bail! {
+            "generic types require an `instantiate = (…)` list \
+            of the instantiations to export" => generics.params,
+        },
+        | (Some(Instantiate { kw, .. }), 0) => bail! {
+            "`instantiate` is only for generic types" => kw,
+        },
+        | (Some(Instantiate { EachTy, .. }), 1) => {
+            EachTy.iter().vmap(|T| quote!(#Ty<#T>))
+        },
+        | (Some(Instantiate { EachTy, .. }), n) => EachTy.iter().try_vmap(|T| match T {
+            | Type::Tuple(TypeTuple { elems, .. }) if elems.len() == n => {
+                let elems = elems.iter();
+                Ok(quote!(#Ty<#(#elems),*>))
+            },
+            | _ => bail! {
+                format!("expected a tuple of {n} types") => T,
+            },
+        })?,
+    };
+    if generics.params.is_empty().not() {
+        if let Some(kw) = &args.vec {
+            bail!("`vec` is not supported for generic types" => kw);
+        }
+        if let Some(Extends { _kw, .. }) = &args.extends {
+            bail!("`extends` is not supported for generic types" => _kw);
+        }
+    }
+    let mut ret = quote!(
         #input
-
-        #[cfg(not(target_arch = "wasm32"))]
+    );
+    let mut each_ffi_export = instances.iter().vmap(|Ty_instance| {
+        let ref Ty_str @ _ = Ty_instance.to_string().replace(' ', "");
+        quote!(
+            ::safer_ffi::FfiExport {
+                name: #Ty_str,
+                kind: ::safer_ffi::headers::ItemKind::Type,
+                gen_def: ::safer_ffi::headers::__define_self__::<#Ty_instance>,
+            }
+        )
+    });
+    if let Some(kw) = &args.vec {
+        each_ffi_export.push(vec_ffi_export(Ty, kw.span));
+    }
+    let registered = super::register_ffi_exports(Ty, &each_ffi_export);
+    ret.extend(quote!(
         ::safer_ffi::__cfg_headers__! {
-            ::safer_ffi::inventory::submit! {
-                #inventory_krate
+            #registered
+        }
+    ));
+    if let Some(kw) = &args.vec {
+        ret.extend(vec_helpers(Ty, kw.span)?);
+    }
+    if let Some(Extends { Base, .. }) = &args.extends {
+        ret.extend(cast_helpers(Ty, fields, Base)?);
+    }
+    Ok(ret)
+}
 
-                ::safer_ffi::FfiExport {
-                    name: #Ty_str,
-                    gen_def: ::safer_ffi::headers::__define_self__::<#Ty>,
+/// `#[ffi_export(extends = Base)]`: export the `Ty_as_Base()` upcast, and the
+/// runtime-checked `Base_try_as_Ty()` downcast (_c.f._
+/// `::safer_ffi::layout::Tagged`).
+fn cast_helpers (
+    Ty @ _: &'_ Ident,
+    fields: Option<&'_ Fields>,
+    Base @ _: &'_ Path,
+) -> Result<TokenStream2>
+{
+    let Base_ident = &Base.segments.last().unwrap().ident;
+    let base_field =
+        fields
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .find(|(_, f)| matches!(
+                &f.ty,
+                Type::Path(TypePath { qself: None, path })
+                if path.segments.last().unwrap().ident == *Base_ident
+            ))
+    ;
+    let base_field: Member = match base_field {
+        | Some((_, Field { ident: Some(ident), .. })) => Member::Named(ident.clone()),
+        | Some((i, _)) => Member::Unnamed(i.into()),
+        | None => bail! {
+            "`extends = …` requires a field of the given type" => Base,
+        },
+    };
+    let Ty_as_Base = format_ident!("{}_as_{}", Ty, Base_ident);
+    let Base_try_as_Ty = format_ident!("{}_try_as_{}", Base_ident, Ty);
+    let ref upcast_docs = [
+        format!(" Upcasts a `{Ty}` to its `{Base_ident}`."),
+        format!(""),
+        format!(" See also `{Base_try_as_Ty}` for the converse operation."),
+    ];
+    let ref downcast_docs = [
+        format!(" Downcasts a `{Base_ident}` to the `{Ty}` it is part of,"),
+        format!(" or returns `NULL` if it is not part of a `{Ty}`."),
+        format!(""),
+        format!(" `it` must point to a valid `{Base_ident}`."),
+        format!(""),
+        format!(" See also `{Ty_as_Base}` for the converse operation."),
+    ];
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        layout,
+    };
+    let mut ret = quote!();
+    ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+        #(#[doc = #upcast_docs])*
+        #[allow(dead_code, nonstandard_style)]
+        fn #Ty_as_Base (
+            it: &'_ #Ty,
+        ) -> &'_ #Base
+        {
+            &it.#base_field
+        }
+    ))?);
+    ret.extend(super::fn_::handle(Default::default(), parse_quote!(
+        #(#[doc = #downcast_docs])*
+        #[allow(dead_code, nonstandard_style)]
+        unsafe
+        fn #Base_try_as_Ty (
+            it: *const #Base,
+        ) -> *const #Ty
+        {
+            let uninit = #ඞ::mem::MaybeUninit::<#Ty>::uninit();
+            let at_ty = uninit.as_ptr();
+            // Safety: the offset of the `Base` field within `Ty`.
+            let base_offset =
+                (#ඞ::core::ptr::addr_of!((*at_ty).#base_field) as #ඞ::usize)
+                -
+                (at_ty as #ඞ::usize)
+            ;
+            #layout::__downcast_ref__::<#Base, #Ty>(it, base_offset)
+                .unwrap_or(#ඞ::core::ptr::null())
+        }
+    ))?);
+    Ok(ret)
+}
+
+/// `#[ffi_export(vec)]`: export the functions to index and free a
+/// `repr_c::Vec<Ty>`, so that returning a list of `Ty`s works out of the box.
+///
+/// Their names share a `vec_<ty>_` prefix, so that they end up next to each
+/// other in the (stable) headers, and the `Vec_<Ty>_t` definition they
+/// require (see [`vec_ffi_export()`]) points back to them.
+fn vec_helpers (
+    Ty @ _: &'_ Ident,
+    span: Span,
+) -> Result<TokenStream2>
+{
+    let ref VecTy_t = format!("`Vec_{Ty}_t`");
+    let (vec_ty_get, vec_ty_free) = vec_helper_names(Ty, span);
+    let ref get_docs = [
+        format!(" Returns a pointer to the element at `index` of the given {VecTy_t},"),
+        format!(" or `NULL` if `index` is out of bounds."),
+        format!(""),
+        format!(" The pointer remains valid until the {VecTy_t} is freed"),
+        format!(" with `{vec_ty_free}`."),
+    ];
+    let ref free_docs = [
+        format!(" Frees a {VecTy_t} (and its elements)."),
+        format!(""),
+        format!(" The elements can be accessed beforehand with `{vec_ty_get}`."),
+    ];
+    #[apply(let_quote!)]
+    use ::safer_ffi::{
+        ඞ,
+        prelude::repr_c,
+    };
+    let mut ret = quote!();
+    ret.extend(super::fn_::handle(Default::default(), parse_quote_spanned!(span=>
+        #(#[doc = #get_docs])*
+        #[allow(dead_code)]
+        fn #vec_ty_get (
+            vec: &'_ #repr_c::Vec<#Ty>,
+            index: #ඞ::usize,
+        ) -> #ඞ::Option<&'_ #Ty>
+        {
+            vec.get(index)
+        }
+    ))?);
+    ret.extend(super::fn_::handle(Default::default(), parse_quote_spanned!(span=>
+        #(#[doc = #free_docs])*
+        #[allow(dead_code)]
+        fn #vec_ty_free (
+            vec: #repr_c::Vec<#Ty>,
+        )
+        {
+            #ඞ::mem::drop(vec)
+        }
+    ))?);
+    Ok(ret)
+}
+
+/// The `vec_<ty>_get` and `vec_<ty>_free` helpers of `#[ffi_export(vec)]`.
+fn vec_helper_names (
+    Ty @ _: &'_ Ident,
+    span: Span,
+) -> (Ident, Ident)
+{
+    let ty = utils::snake_case(&Ty.to_string());
+    (
+        format_ident!("vec_{ty}_get", span = span),
+        format_ident!("vec_{ty}_free", span = span),
+    )
+}
+
+/// The `FfiExport` of the `Vec_<Ty>_t` definition, for its docs to point to
+/// the `#[ffi_export(vec)]` helpers.
+fn vec_ffi_export (
+    Ty @ _: &'_ Ident,
+    span: Span,
+) -> TokenStream2
+{
+    let (vec_ty_get, vec_ty_free) = vec_helper_names(Ty, span);
+    let ref vec_docs = [
+        format!(" The elements can be accessed with `{vec_ty_get}`, and the"),
+        format!(" `Vec` freed with `{vec_ty_free}`."),
+    ];
+    let ref VecTy_str = format!("Vec_{Ty}");
+    quote!(
+        ::safer_ffi::FfiExport {
+            name: #VecTy_str,
+            kind: ::safer_ffi::headers::ItemKind::Type,
+            gen_def: {
+                fn gen_def (
+                    definer: &'_ mut dyn ::safer_ffi::headers::Definer,
+                    lang: ::safer_ffi::headers::Language,
+                ) -> ::std::io::Result<()>
+                {
+                    ::safer_ffi::headers::__define_vec__::<#Ty>(
+                        definer,
+                        lang,
+                        &[#(#vec_docs),*],
+                    )
                 }
-            }
+                gen_def
+            },
         }
-    ))
+    )
 }
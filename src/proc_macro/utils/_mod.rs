@@ -237,6 +237,125 @@ fn extract_docs (
         .collect()
 }
 
+/// The `note` of the `#[deprecated]` attribute, if any, among `attrs` (`""`
+/// for a bare `#[deprecated]`).
+pub(in crate)
+fn deprecation_note (
+    attrs: &'_ [Attribute]
+) -> Result<Option<String>>
+{
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("deprecated")) {
+        | Some(attr) => attr,
+        | None => return Ok(None),
+    };
+    let note = match attr.parse_meta()? {
+        | Meta::Path(_) => None,
+        | Meta::NameValue(MetaNameValue { lit: Lit::Str(note), .. }) => Some(note),
+        | Meta::List(MetaList { nested, .. }) => nested.into_iter().find_map(|it| match it {
+            | NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(note),
+                ..
+            })) if path.is_ident("note") => Some(note),
+            | _ => None,
+        }),
+        | meta => bail!("expected `#[deprecated(note = \"…\")]`" => meta),
+    };
+    Ok(Some(note.map_or_else(String::new, |it| it.value())))
+}
+
+/// The `#[cfg(…)]` predicates of the `attrs` (all of which have to hold), as
+/// a `CfgPredicate` expression, for the C header to guard the declaration
+/// accordingly.
+pub(in crate)
+fn cfg_predicate (
+    attrs: &'_ [Attribute]
+) -> Result<Option<TokenStream2>>
+{
+    let_quote!(use ::safer_ffi::headers);
+
+    fn predicate (
+        headers: &'_ LazyQuote,
+        meta: &'_ NestedMeta,
+    ) -> Result<TokenStream2>
+    {
+        Ok(match meta {
+            | NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                let name = path.get_ident().unwrap().to_string();
+                quote!( #headers::CfgPredicate::Name(#name) )
+            },
+            | NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            })) if path.get_ident().is_some() => {
+                let key = path.get_ident().unwrap().to_string();
+                quote!( #headers::CfgPredicate::KeyValue(#key, #value) )
+            },
+            | NestedMeta::Meta(Meta::List(MetaList { path, nested, .. })) => {
+                let each_predicate =
+                    nested.iter().map(|it| predicate(headers, it)).collect::<Result<Vec<_>>>()?
+                ;
+                match () {
+                    | _ if path.is_ident("all") => quote!(
+                        #headers::CfgPredicate::All(&[ #(#each_predicate),* ])
+                    ),
+                    | _ if path.is_ident("any") => quote!(
+                        #headers::CfgPredicate::Any(&[ #(#each_predicate),* ])
+                    ),
+                    | _ if path.is_ident("not") && each_predicate.len() == 1 => {
+                        let predicate = &each_predicate[0];
+                        quote!( #headers::CfgPredicate::Not(&#predicate) )
+                    },
+                    | _ => bail!("unsupported `cfg` predicate" => path),
+                }
+            },
+            | _ => bail!("unsupported `cfg` predicate" => meta),
+        })
+    }
+
+    let mut each_predicate = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("cfg")) {
+        match attr.parse_meta()? {
+            | Meta::List(MetaList { nested, .. }) if nested.len() == 1 => {
+                each_predicate.push(predicate(headers, &nested[0])?);
+            },
+            | meta => bail!("expected `#[cfg(<predicate>)]`" => meta),
+        }
+    }
+    Ok(match each_predicate.len() {
+        | 0 => None,
+        | 1 => each_predicate.pop(),
+        | _ => Some(quote!( #headers::CfgPredicate::All(&[ #(#each_predicate),* ]) )),
+    })
+}
+
+/// Marks each of the generated `items` with `#[allow(deprecated)]`, for them
+/// to be able to refer to a `#[deprecated]` type without warnings.
+pub(in crate)
+fn allow_deprecated (
+    items: TokenStream2
+) -> Result<TokenStream2>
+{
+    let mut file: File = parse2(items)?;
+    for item in &mut file.items {
+        let attrs = match *item {
+            | Item::Const(ItemConst { ref mut attrs, .. })
+            | Item::Fn(ItemFn { ref mut attrs, .. })
+            | Item::Impl(ItemImpl { ref mut attrs, .. })
+            | Item::Macro(ItemMacro { ref mut attrs, .. })
+            | Item::Struct(ItemStruct { ref mut attrs, .. })
+            | Item::Union(ItemUnion { ref mut attrs, .. })
+            => attrs,
+            | _ => continue,
+        };
+        attrs.push(parse_quote!(
+            #[allow(deprecated)]
+        ));
+    }
+    Ok(file.into_token_stream())
+}
+
 pub(crate)
 struct LazyQuote(
     pub(crate) fn() -> TokenStream2,
@@ -262,3 +381,20 @@ fn parenthesized<T> (
     let contents;
     scope(parenthesized!(contents in input), &contents)
 }
+
+/// `FooBar` => `foo_bar`
+pub(in crate)
+fn snake_case (s: &'_ str)
+  -> String
+{
+    let mut ret = String::with_capacity(s.len() + 4);
+    let mut prev_is_lower = false;
+    for c in s.chars() {
+        if c.is_ascii_uppercase() && prev_is_lower {
+            ret.push('_');
+        }
+        prev_is_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        ret.push(c.to_ascii_lowercase());
+    }
+    ret
+}
@@ -0,0 +1,147 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A standard progress-reporting (and cancellation) callback, for
+//! long-running exports to share a single idiom.
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::{prelude::*, progress::{Cancelled, ProgressCallback}};
+//!
+//! #[ffi_export]
+//! /// Returns `false` if cancelled.
+//! fn mylib_process (
+//!     items: c_slice::Ref<'_, Item>,
+//!     mut progress: ProgressCallback<'_>,
+//! ) -> bool
+//! {
+//!     let total = items.len() as u64;
+//!     items.iter().enumerate().try_for_each(|(i, item)| {
+//!         process(item);
+//!         progress.report(i as u64 + 1, total)
+//!     }).is_ok()
+//! }
+//! ```
+//!
+//! Since the C type is defined once in the header, all the functions taking
+//! a `ProgressCallback` share it.
+
+use_prelude!();
+
+ReprC! {
+    #[repr(C)]
+    /// A host-provided progress callback, which may ask for the operation to
+    /// be cancelled.
+    ///
+    /// # C layout
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     void * user_data;
+    ///     // Returns `false` to cancel the operation.
+    ///     // May be `NULL`, in which case no progress is reported.
+    ///     bool (*report)(void * user_data, uint64_t current, uint64_t total);
+    /// } ProgressCallback_t;
+    /// ```
+    pub
+    struct ProgressCallback['lt] {
+        user_data: *mut c_void,
+        report: Option<
+            unsafe extern "C" fn (user_data: *mut c_void, current: u64, total: u64) -> bool
+        >,
+        _lt: PhantomData<&'lt mut ()>,
+    }
+}
+
+/// The host has asked for the operation to be cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub
+struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt.write_str("the operation has been cancelled")
+    }
+}
+
+cfg_std! {
+    impl ::std::error::Error for Cancelled {}
+}
+
+impl<'lt> ProgressCallback<'lt> {
+    /// Wraps a C callback and its `user_data` into a [`ProgressCallback`].
+    ///
+    /// # Safety
+    ///
+    /// For the duration of `'lt`, it must be sound to call `report` (if any)
+    /// with `user_data`.
+    pub
+    unsafe
+    fn from_raw (
+        user_data: *mut c_void,
+        report: Option<
+            unsafe extern "C" fn (user_data: *mut c_void, current: u64, total: u64) -> bool
+        >,
+    ) -> ProgressCallback<'lt>
+    {
+        Self { user_data, report, _lt: PhantomData }
+    }
+
+    /// A callback borrowing a Rust closure (_e.g._, to call a
+    /// `ProgressCallback`-taking export from Rust).
+    pub
+    fn from_fn_mut<F> (f: &'lt mut F)
+      -> ProgressCallback<'lt>
+    where
+        F : FnMut(u64, u64) -> bool,
+    {
+        unsafe extern "C"
+        fn report<F> (user_data: *mut c_void, current: u64, total: u64)
+          -> bool
+        where
+            F : FnMut(u64, u64) -> bool,
+        {
+            let f = &mut *user_data.cast::<F>();
+            f(current, total)
+        }
+        unsafe {
+            Self::from_raw(<*mut F>::cast(f), Some(report::<F>))
+        }
+    }
+
+    /// Reports that `current` out of `total` units of work have been done.
+    ///
+    /// Errors if the host asks for the operation to be cancelled.
+    pub
+    fn report (self: &'_ mut Self, current: u64, total: u64)
+      -> Result<(), Cancelled>
+    {
+        match self.report {
+            | Some(report) if unsafe { report(self.user_data, current, total) }.not() => {
+                Err(Cancelled)
+            },
+            | _ => Ok(()),
+        }
+    }
+
+    /// Adapts the callback into a Rust closure, for APIs expecting an
+    /// `impl FnMut(current, total) -> Result<(), Cancelled>`.
+    pub
+    fn as_fn_mut (self: &'_ mut Self)
+      -> impl '_ + FnMut(u64, u64) -> Result<(), Cancelled>
+    {
+        move |current, total| self.report(current, total)
+    }
+}
+
+impl fmt::Debug
+    for ProgressCallback<'_>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_struct("ProgressCallback")
+            .field("user_data", &self.user_data)
+            .field("report", &self.report)
+            .finish()
+    }
+}
@@ -7,6 +7,9 @@ use_prelude!();
 pub use slice::*;
 mod slice;
 
+pub use utf16::*;
+mod utf16;
+
 cfg_alloc! {
     use repr_c::Vec;
 
@@ -124,3 +127,110 @@ cfg_alloc! {
         }
     }
 }
+
+/// Exports helpers converting text between UTF-8 and UTF-16 (_e.g._, for
+/// Windows or .NET consumers), so that these do not need to write their own
+/// conversion glue around every string parameter.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_encoding_helpers!(mylib);
+/// ```
+///
+/// exports:
+///
+///   - `mylib_utf8_is_valid()`;
+///   - `mylib_utf8_sanitize()`, replacing invalid UTF-8 with `U+FFFD`;
+///   - `mylib_utf8_to_utf16()` and `mylib_utf16_to_utf8()`, which are lossy
+///     in the same fashion;
+///   - `mylib_utf8_free()` and `mylib_utf16_free()`, to free the vectors
+///     returned by the above.
+///
+/// Requires the `"alloc"` feature.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! ffi_export_encoding_helpers {(
+    $prefix:ident $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Returns whether the given bytes are valid UTF-8.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf8_is_valid>] (
+        utf8: $crate::prelude::c_slice::Ref<'_, u8>,
+    ) -> bool
+    {
+        $crate::ඞ::core::str::from_utf8(utf8.as_slice()).is_ok()
+    }
+
+    #[$crate::ffi_export]
+    /// Returns a copy of the given bytes, with the invalid UTF-8 sequences
+    /// replaced by `U+FFFD`.
+    ///
+    #[doc = " The returned vector must be freed with `" $prefix "_utf8_free()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf8_sanitize>] (
+        utf8: $crate::prelude::c_slice::Ref<'_, u8>,
+    ) -> $crate::prelude::repr_c::Vec<u8>
+    {
+        $crate::ඞ::alloc::string::String::from_utf8_lossy(utf8.as_slice())
+            .into_owned()
+            .into_bytes()
+            .into()
+    }
+
+    #[$crate::ffi_export]
+    /// Converts the given UTF-8 text to UTF-16 (invalid UTF-8 sequences
+    /// being replaced by `U+FFFD`).
+    ///
+    #[doc = " The returned vector must be freed with `" $prefix "_utf16_free()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf8_to_utf16>] (
+        utf8: $crate::prelude::c_slice::Ref<'_, u8>,
+    ) -> $crate::prelude::repr_c::Vec<u16>
+    {
+        $crate::ඞ::alloc::string::String::from_utf8_lossy(utf8.as_slice())
+            .encode_utf16()
+            .collect::<$crate::ඞ::alloc::vec::Vec<u16>>()
+            .into()
+    }
+
+    #[$crate::ffi_export]
+    /// Converts the given UTF-16 text to UTF-8 (unpaired surrogates being
+    /// replaced by `U+FFFD`).
+    ///
+    #[doc = " The returned vector must be freed with `" $prefix "_utf8_free()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf16_to_utf8>] (
+        utf16: $crate::prelude::c_slice::Ref<'_, u16>,
+    ) -> $crate::prelude::repr_c::Vec<u8>
+    {
+        $crate::ඞ::alloc::string::String::from_utf16_lossy(utf16.as_slice())
+            .into_bytes()
+            .into()
+    }
+
+    #[$crate::ffi_export]
+    #[doc = " Frees a vector obtained from `" $prefix "_utf8_sanitize()` or `" $prefix "_utf16_to_utf8()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf8_free>] (
+        utf8: $crate::prelude::repr_c::Vec<u8>,
+    )
+    {
+        drop(utf8)
+    }
+
+    #[$crate::ffi_export]
+    #[doc = " Frees a vector obtained from `" $prefix "_utf8_to_utf16()`."]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _utf16_free>] (
+        utf16: $crate::prelude::repr_c::Vec<u16>,
+    )
+    {
+        drop(utf16)
+    }
+})}
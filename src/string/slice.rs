@@ -1,7 +1,10 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 use_prelude!();
 use ::core::slice;
-use crate::slice::*;
+use crate::{
+    char_p::InvalidStr,
+    slice::*,
+};
 
 cfg_alloc! {
     ReprC! {
@@ -122,6 +125,7 @@ ReprC! {
     /// `&'lt str`, but with a guaranteed `#[repr(C)]` layout.
     pub
     struct str_ref['lt,] (
+        pub(in crate)
         slice_ref<'lt, u8>,
     );
 }
@@ -141,6 +145,41 @@ impl<'lt> From<&'lt str>
 }
 
 impl<'lt> str_ref<'lt> {
+    /// Checked constructor, for the strings handed over by C, rather than
+    /// trusting them to be valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    ///   - Unless `NULL`, `ptr` must point, for the duration of `'lt`, to the
+    ///     beginning of `len` valid and immutable bytes (provided `len` does
+    ///     not overflow `isize::MAX`).
+    pub
+    unsafe
+    fn from_raw_parts_checked (ptr: *const u8, len: usize)
+      -> Result<str_ref<'lt>, InvalidStr>
+    {
+        if ptr.is_null() {
+            return Err(InvalidStr::Null);
+        }
+        if len > isize::MAX as usize {
+            return Err(InvalidStr::TooLong);
+        }
+        let bytes = unsafe { slice::from_raw_parts(ptr, len) };
+        Ok(::core::str::from_utf8(bytes)?.into())
+    }
+
+    /// Checked constructor out of the (possibly `NULL`) C layout of a
+    /// `str::Ref`, as lowered by `#[ffi_export(validate_utf8)]`.
+    pub
+    fn try_from_c_slice (bytes: Option<slice_ref<'lt, u8>>)
+      -> Result<str_ref<'lt>, InvalidStr>
+    {
+        let bytes = bytes.ok_or(InvalidStr::Null)?;
+        unsafe {
+            Self::from_raw_parts_checked(bytes.ptr.as_ptr(), bytes.len)
+        }
+    }
+
     #[inline]
     pub
     fn as_str (self: str_ref<'lt>)
@@ -0,0 +1,260 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! UTF-16 counterparts of [`str_ref`][`super::str_ref`] and
+//! [`String`][`super::String`] (_fat_ pointers: `uint16_t const *` + length),
+//! for platforms and languages (Windows, C#, Java…) whose strings are UTF-16.
+use_prelude!();
+use crate::slice::*;
+
+ReprC! {
+    #[repr(transparent)]
+    /// A borrowed UTF-16 encoded string, _i.e._, a `&'lt [u16]` with a
+    /// guaranteed `#[repr(C)]` layout.
+    ///
+    /// Contrary to a `&str`, it may hold ill-formed UTF-16 (such as unpaired
+    /// surrogates), as Windows file names can.
+    ///
+    /// # C layout
+    ///
+    /// ```c
+    /// typedef struct {
+    ///     uint16_t const * ptr;
+    ///     size_t len;
+    /// } slice_ref_uint16_t;
+    /// ```
+    pub
+    struct str16_ref['lt,] (
+        slice_ref<'lt, u16>,
+    );
+}
+
+impl<'lt> From<&'lt [u16]>
+    for str16_ref<'lt>
+{
+    #[inline]
+    fn from (units: &'lt [u16])
+      -> str16_ref<'lt>
+    {
+        Self(units.into())
+    }
+}
+
+impl<'lt> str16_ref<'lt> {
+    #[inline]
+    pub
+    fn as_units (self: str16_ref<'lt>)
+      -> &'lt [u16]
+    {
+        self.0.as_slice()
+    }
+
+    cfg_alloc! {
+        /// Decodes the string, replacing ill-formed UTF-16 with `U+FFFD`.
+        #[inline]
+        pub
+        fn to_string_lossy (self: str16_ref<'lt>)
+          -> rust::String
+        {
+            rust::String::from_utf16_lossy(self.as_units())
+        }
+    }
+}
+
+impl<'lt> Copy
+    for str16_ref<'lt>
+{}
+
+impl<'lt> Clone
+    for str16_ref<'lt>
+{
+    #[inline]
+    fn clone (self: &'_ str16_ref<'lt>)
+      -> str16_ref<'lt>
+    {
+        *self
+    }
+}
+
+impl<'lt> Deref
+    for str16_ref<'lt>
+{
+    type Target = [u16];
+
+    #[inline]
+    fn deref (self: &'_ str16_ref<'lt>)
+      -> &'_ [u16]
+    {
+        self.as_units()
+    }
+}
+
+impl fmt::Debug
+    for str16_ref<'_>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt.write_str("\"")?;
+        for c in ::core::char::decode_utf16(self.iter().copied()) {
+            match c {
+                | Ok(c) => fmt::Display::fmt(&c.escape_debug(), fmt)?,
+                | Err(err) => write!(fmt, "\\u{{{:x}}}", err.unpaired_surrogate())?,
+            }
+        }
+        fmt.write_str("\"")
+    }
+}
+
+cfg_alloc! {
+    ReprC! {
+        #[repr(transparent)]
+        #[cfg_attr(all(docs, feature = "nightly"), doc(cfg(feature = "alloc")))]
+        /// An owned UTF-16 encoded string, _i.e._, a `Box<[u16]>` with a
+        /// guaranteed `#[repr(C)]` layout.
+        ///
+        /// # C layout
+        ///
+        /// ```c
+        /// typedef struct {
+        ///     uint16_t * ptr;
+        ///     size_t len;
+        /// } slice_boxed_uint16_t;
+        /// ```
+        pub
+        struct String16 (
+            slice_boxed<u16>,
+        );
+    }
+
+    impl String16 {
+        #[inline]
+        pub
+        fn as_ref (self: &'_ String16)
+          -> str16_ref<'_>
+        {
+            str16_ref(self.0.as_ref())
+        }
+
+        #[inline]
+        pub
+        fn to_string_lossy (self: &'_ String16)
+          -> rust::String
+        {
+            self.as_ref().to_string_lossy()
+        }
+    }
+
+    /// Encodes the `str` as UTF-16 (_c.f._ [`str::encode_utf16()`]).
+    impl<'lt> From<&'lt str>
+        for String16
+    {
+        #[inline]
+        fn from (s: &'lt str)
+          -> String16
+        {
+            s.encode_utf16().collect::<rust::Vec<u16>>().into()
+        }
+    }
+
+    /// Encodes the `String` as UTF-16 (_c.f._ [`str::encode_utf16()`]).
+    impl From<rust::String>
+        for String16
+    {
+        #[inline]
+        fn from (s: rust::String)
+          -> String16
+        {
+            Self::from(&*s)
+        }
+    }
+
+    impl From<rust::Vec<u16>>
+        for String16
+    {
+        #[inline]
+        fn from (units: rust::Vec<u16>)
+          -> String16
+        {
+            Self(units.into_boxed_slice().into())
+        }
+    }
+
+    /// Fails if the UTF-16 is ill-formed.
+    impl TryFrom<String16>
+        for rust::String
+    {
+        type Error = ::alloc::string::FromUtf16Error;
+
+        #[inline]
+        fn try_from (s: String16)
+          -> Result<rust::String, ::alloc::string::FromUtf16Error>
+        {
+            rust::String::from_utf16(&s)
+        }
+    }
+
+    impl Deref
+        for String16
+    {
+        type Target = [u16];
+
+        #[inline]
+        fn deref (self: &'_ String16)
+          -> &'_ [u16]
+        {
+            &self.0
+        }
+    }
+
+    impl Clone
+        for String16
+    {
+        #[inline]
+        fn clone (self: &'_ String16)
+          -> String16
+        {
+            self.to_vec().into()
+        }
+    }
+
+    impl fmt::Debug
+        for String16
+    {
+        fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+          -> fmt::Result
+        {
+            fmt::Debug::fmt(&self.as_ref(), fmt)
+        }
+    }
+}
+
+cfg_std! {
+    /// On Windows, the (potentially ill-formed) UTF-16 is kept as is.
+    /// Elsewhere, the `OsStr` is lossily converted to a `str` first.
+    impl<'lt> From<&'lt ::std::ffi::OsStr>
+        for String16
+    {
+        fn from (s: &'lt ::std::ffi::OsStr)
+          -> String16
+        {
+            #[cfg(windows)] {
+                ::std::os::windows::ffi::OsStrExt::encode_wide(s)
+                    .collect::<rust::Vec<u16>>()
+                    .into()
+            }
+            #[cfg(not(windows))] {
+                Self::from(&*s.to_string_lossy())
+            }
+        }
+    }
+
+    impl From<::std::ffi::OsString>
+        for String16
+    {
+        #[inline]
+        fn from (s: ::std::ffi::OsString)
+          -> String16
+        {
+            Self::from(&*s)
+        }
+    }
+}
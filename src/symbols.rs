@@ -0,0 +1,367 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Runtime introspection of the exported API, for dynamic hosts (scripting
+//! engines, plugin loaders, …) to discover it.
+//!
+//! The registry the header generation uses being only available in the builds
+//! featuring the `headers` Cargo feature (and never on `wasm32`), the symbols
+//! are listed at header generation time, committed to a file (kept up to date
+//! by [`check_symbols()`]), and then baked into the library by
+//! [`ffi_export_symbols!`]:
+//!
+//! ```rust,ignore
+//! ::safer_ffi::ffi_export_symbols!(mylib, "mylib.symbols");
+//!
+//! #[::safer_ffi::cfg_headers]
+//! #[test]
+//! fn generate_headers ()
+//!   -> ::std::io::Result<()>
+//! {
+//!     ::safer_ffi::symbols::check_symbols("mylib.symbols")?;
+//!     ::safer_ffi::headers::builder()
+//!         .to_file("mylib.h")?
+//!         .generate()
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! enum ItemKind {
+//!     ITEM_KIND_FUNCTION,
+//!     ITEM_KIND_CONSTANT,
+//!     ITEM_KIND_STATIC,
+//!     ITEM_KIND_TYPE,
+//! };
+//! typedef uint8_t ItemKind_t;
+//!
+//! typedef struct SymbolInfo {
+//!     Vec_uint8_t name;
+//!     ItemKind_t kind;
+//!     Vec_uint8_t declaration;
+//! } SymbolInfo_t;
+//!
+//! /** \brief
+//!  *  The names of the exported items, sorted, to be freed with
+//!  *  `mylib_list_symbols_free()`.
+//!  */
+//! Vec_Vec_uint8_t mylib_list_symbols (void);
+//!
+//! void mylib_list_symbols_free (
+//!     Vec_Vec_uint8_t symbols);
+//!
+//! /** \brief
+//!  *  The exported items, sorted by name, with their C declaration, to be
+//!  *  freed with `mylib_symbol_infos_free()`.
+//!  */
+//! Vec_SymbolInfo_t mylib_symbol_infos (void);
+//!
+//! void mylib_symbol_infos_free (
+//!     Vec_SymbolInfo_t symbols);
+//! ```
+//!
+//! The file being `include!`d, it has to exist before the first build: start
+//! from an empty list, and let the test write the actual one.
+//!
+//! ```sh
+//! echo '&[]' > mylib.symbols
+//! SAFER_FFI_UPDATE_SYMBOLS=1 cargo test --features headers
+//! ```
+//!
+//! [`ffi_export_symbols!`]: `crate::ffi_export_symbols`
+
+use_prelude!();
+#[cfg(feature = "headers")]
+use crate::headers::{Language, Settings};
+
+/// Set to (re)write the symbols file rather than checking it.
+#[cfg(feature = "headers")]
+const UPDATE: &str = "SAFER_FFI_UPDATE_SYMBOLS";
+
+/// The kind of an exported item.
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(
+    Debug,
+    Copy, Clone,
+    PartialEq, Eq,
+    Hash,
+)]
+pub
+enum ItemKind {
+    /// An `#[ffi_export]`ed function.
+    Function,
+
+    /// An `#[ffi_export]`ed `const`.
+    Constant,
+
+    /// An `#[ffi_export]`ed `static`.
+    Static,
+
+    /// An `#[ffi_export]`ed type.
+    Type,
+}
+
+/// An exported item, as baked into the library by
+/// [`ffi_export_symbols!`][`crate::ffi_export_symbols`]: its name, its kind,
+/// and its C declaration.
+pub
+type Symbol = (&'static str, ItemKind, &'static str);
+
+/// An exported item, as listed by [`symbol_infos()`].
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub
+struct SymbolInfo {
+    /// The name of the item (for a type, that of its Rust definition).
+    pub
+    name: repr_c::String,
+
+    pub
+    kind: ItemKind,
+
+    /// The declaration of the item, as featured in the C header (for a type,
+    /// its definition, preceded by those of the types it depends on).
+    pub
+    declaration: repr_c::String,
+}
+
+/// The names of the exported items, sorted.
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn list_symbols ()
+  -> rust::Vec<&'static str>
+{
+    // (skipping the functions `#[cfg]`-gated away from the current target)
+    crate::headers::declarations(Language::C, Settings::default())
+        .expect("writing to a `Vec` does not fail")
+        .into_iter()
+        .map(|(export, _)| export.name)
+        .collect()
+}
+
+/// The exported items, sorted by name, with their C declaration.
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn symbol_infos ()
+  -> rust::Vec<SymbolInfo>
+{
+    crate::headers::declarations(Language::C, Settings::default())
+        .expect("writing to a `Vec` does not fail")
+        .into_iter()
+        .map(|(export, declaration)| SymbolInfo {
+            name: rust::String::from(export.name).into(),
+            kind: export.kind,
+            declaration: declaration.into(),
+        })
+        .collect()
+}
+
+/// Checks the [`symbol_infos()`] against the ones committed to the file at
+/// `path` (relative to the current directory, _i.e._, to the root of the crate
+/// in a `cargo test`), which is written instead (see [`write_symbols()`]) if
+/// the `SAFER_FFI_UPDATE_SYMBOLS` environment variable is set.
+///
+/// A missing file is otherwise an error (lest a CI run without it silently
+/// pass).
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn check_symbols (path: impl AsRef<::std::path::Path>)
+  -> ::std::io::Result<()>
+{
+    use ::std::io;
+
+    let path = path.as_ref();
+    if ::std::env::var_os(UPDATE).is_some() {
+        return write_symbols(path);
+    }
+    let recorded = match ::std::fs::read_to_string(path) {
+        | Ok(it) => it,
+        | Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(err.kind(), format!(
+                "the symbols file `{}` does not exist \
+                (set `{UPDATE}=1` to write it)",
+                path.display(),
+            )));
+        },
+        | Err(err) => return Err(err),
+    };
+    let generated = symbols_file();
+    if recorded.lines().eq(generated.lines()) {
+        return Ok(());
+    }
+    let recorded = recorded.lines().collect::<::std::collections::BTreeSet<_>>();
+    let generated = generated.lines().collect::<::std::collections::BTreeSet<_>>();
+    let mut msg = format!(
+        "the exported items no longer match `{}`:",
+        path.display(),
+    );
+    for line in recorded.difference(&generated) {
+        msg += &format!("\n  - {line}");
+    }
+    for line in generated.difference(&recorded) {
+        msg += &format!("\n  + {line}");
+    }
+    msg += &format!("\n(set `{UPDATE}=1` to update it, if intended)");
+    Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+/// Writes the [`symbol_infos()`] to the file at `path` (relative to the
+/// current directory, _i.e._, to the root of the crate in a `cargo test`), as
+/// a Rust `&[Symbol]` expression, for
+/// [`ffi_export_symbols!`][`crate::ffi_export_symbols`] to bake it into the
+/// library.
+///
+/// The file is left untouched when already up to date, not to trigger a
+/// rebuild.
+#[cfg(feature = "headers")]
+#[cfg_attr(feature = "nightly",
+    doc(cfg(feature = "headers")),
+)]
+pub
+fn write_symbols (path: impl AsRef<::std::path::Path>)
+  -> ::std::io::Result<()>
+{
+    let path = path.as_ref();
+    let contents = symbols_file();
+    match ::std::fs::read_to_string(path) {
+        | Ok(it) if it == contents => Ok(()),
+        | _ => ::std::fs::write(path, contents),
+    }
+}
+
+/// The [`symbol_infos()`] as a Rust `&[Symbol]` expression.
+#[cfg(feature = "headers")]
+fn symbols_file ()
+  -> rust::String
+{
+    let mut contents = rust::String::from(
+        "// Generated by `::safer_ffi::symbols::write_symbols()`.\n&[\n",
+    );
+    for info in symbol_infos() {
+        contents += &format!(
+            "    ({:?}, ::safer_ffi::symbols::ItemKind::{:?}, {:?}),\n",
+            &*info.name, info.kind, &*info.declaration,
+        );
+    }
+    contents += "]\n";
+    contents
+}
+
+/// The `SymbolInfo`s of the baked `symbols`.
+#[doc(hidden)] /** Not part of the public API! */ pub
+fn __symbol_infos (symbols: &'_ [Symbol])
+  -> rust::Vec<SymbolInfo>
+{
+    symbols
+        .iter()
+        .map(|&(name, kind, declaration)| SymbolInfo {
+            name: rust::String::from(name).into(),
+            kind,
+            declaration: rust::String::from(declaration).into(),
+        })
+        .collect()
+}
+
+/// Defines and exports `<prefix>_list_symbols()` and `<prefix>_symbol_infos()`
+/// (see the [`symbols` module][`crate::symbols`]), along with the matching
+/// `_free()` functions, listing the symbols written to the given file
+/// (relative to the root of the crate) by
+/// [`check_symbols()`][`crate::symbols::check_symbols`].
+///
+/// The file has to exist before the first build (see the
+/// [`symbols` module][`crate::symbols`] for how to bootstrap it).
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_symbols!(mylib, "mylib.symbols");
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// /** \brief
+///  *  The names of the exported items, sorted, to be freed with
+///  *  `mylib_list_symbols_free()`.
+///  */
+/// Vec_Vec_uint8_t mylib_list_symbols (void);
+///
+/// void mylib_list_symbols_free (
+///     Vec_Vec_uint8_t symbols);
+///
+/// /** \brief
+///  *  The exported items, sorted by name, with their C declaration, to be
+///  *  freed with `mylib_symbol_infos_free()`.
+///  */
+/// Vec_SymbolInfo_t mylib_symbol_infos (void);
+///
+/// void mylib_symbol_infos_free (
+///     Vec_SymbolInfo_t symbols);
+/// ```
+#[macro_export]
+macro_rules! ffi_export_symbols {(
+    $prefix:ident, $path:literal $(,)?
+) => ($crate::paste::item! {
+    #[allow(nonstandard_style)]
+    const [<__ $prefix _symbols>]: &[$crate::symbols::Symbol] =
+        include!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path))
+    ;
+
+    #[$crate::ffi_export]
+    #[doc = $crate::ඞ::concat!(
+        " The names of the exported items, sorted, to be freed with\n",
+        " `", $crate::ඞ::stringify!($prefix), "_list_symbols_free()`.",
+    )]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _list_symbols>] ()
+      -> $crate::prelude::repr_c::Vec<$crate::prelude::repr_c::String>
+    {
+        [<__ $prefix _symbols>]
+            .iter()
+            .map(|&(name, _, _)| $crate::ඞ::String::from(name).into())
+            .collect::<$crate::ඞ::Vec<$crate::prelude::repr_c::String>>()
+            .into()
+    }
+
+    #[$crate::ffi_export]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _list_symbols_free>] (
+        symbols: $crate::prelude::repr_c::Vec<$crate::prelude::repr_c::String>,
+    )
+    {
+        $crate::ඞ::mem::drop(symbols)
+    }
+
+    #[$crate::ffi_export]
+    #[doc = $crate::ඞ::concat!(
+        " The exported items, sorted by name, with their C declaration, to be\n",
+        " freed with `", $crate::ඞ::stringify!($prefix), "_symbol_infos_free()`.",
+    )]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _symbol_infos>] ()
+      -> $crate::prelude::repr_c::Vec<$crate::symbols::SymbolInfo>
+    {
+        $crate::symbols::__symbol_infos([<__ $prefix _symbols>]).into()
+    }
+
+    #[$crate::ffi_export]
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _symbol_infos_free>] (
+        symbols: $crate::prelude::repr_c::Vec<$crate::symbols::SymbolInfo>,
+    )
+    {
+        $crate::ඞ::mem::drop(symbols)
+    }
+})}
@@ -0,0 +1,77 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Durations, for FFI.
+//!
+//! [`core::time::Duration`] is not `ReprC`, its layout being unspecified.
+//! Instead, use the [`Duration`] struct, a portable "seconds + nanoseconds"
+//! pair (with matching C and C# definitions), which converts to and from it:
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//!
+//! #[ffi_export]
+//! fn mylib_set_timeout (timeout: repr_c::Duration)
+//! {
+//!     let timeout: ::std::time::Duration = timeout.into();
+//!     // …
+//! }
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct Duration {
+//!     uint64_t secs;
+//!     uint32_t nanos;
+//! } Duration_t;
+//!
+//! void mylib_set_timeout (
+//!     Duration_t timeout);
+//! ```
+
+use_prelude!();
+
+/// A [`core::time::Duration`] with a portable `#[repr(C)]` layout: its whole
+/// seconds, and the remaining nanoseconds.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub
+struct Duration {
+    /// The whole seconds.
+    pub
+    secs: u64,
+
+    /// The remaining nanoseconds (expected to be less than `1_000_000_000`).
+    pub
+    nanos: u32,
+}
+
+impl From<::core::time::Duration>
+    for Duration
+{
+    #[inline]
+    fn from (it: ::core::time::Duration)
+      -> Duration
+    {
+        Self {
+            secs: it.as_secs(),
+            nanos: it.subsec_nanos(),
+        }
+    }
+}
+
+/// Excess `nanos` (from C code) carry over into the seconds.
+///
+/// # Panics
+///
+/// If that carry overflows the seconds.
+impl From<Duration>
+    for ::core::time::Duration
+{
+    #[inline]
+    fn from (Duration { secs, nanos }: Duration)
+      -> ::core::time::Duration
+    {
+        ::core::time::Duration::new(secs, nanos)
+    }
+}
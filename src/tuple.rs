@@ -151,3 +151,47 @@ ReprC! {
         pub _5: T5,
     }
 }
+
+macro_rules! impl_tuple_conversions {(
+    $(
+        $TupleN:ident ( $($T:ident => $_i:ident),+ $(,)? )
+    )*
+) => (
+    $(
+        /// `(T0, T1, …)` in exported signatures is lowered to this type.
+        impl<$($T : ReprC),+> From<($($T ,)+)>
+            for $TupleN<$($T),+>
+        {
+            #[inline]
+            fn from (($($_i ,)+): ($($T ,)+))
+              -> Self
+            {
+                Self { $($_i),+ }
+            }
+        }
+
+        impl<$($T : ReprC),+> From<$TupleN<$($T),+>>
+            for ($($T ,)+)
+        {
+            #[inline]
+            fn from ($TupleN { $($_i),+ }: $TupleN<$($T),+>)
+              -> Self
+            {
+                ($($_i ,)+)
+            }
+        }
+    )*
+)}
+
+impl_tuple_conversions! {
+    Tuple1(T0 => _0)
+    Tuple2(T0 => _0, T1 => _1)
+}
+
+#[cfg(not(docs))]
+impl_tuple_conversions! {
+    Tuple3(T0 => _0, T1 => _1, T2 => _2)
+    Tuple4(T0 => _0, T1 => _1, T2 => _2, T3 => _3)
+    Tuple5(T0 => _0, T1 => _1, T2 => _2, T3 => _3, T4 => _4)
+    Tuple6(T0 => _0, T1 => _1, T2 => _2, T3 => _3, T4 => _4, T5 => _5)
+}
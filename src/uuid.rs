@@ -0,0 +1,188 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! [`::uuid::Uuid`] support (`uuid` Cargo feature).
+//!
+//! A `Uuid` is [`ReprC`], as its 16 bytes (in the big-endian order of
+//! [`Uuid::as_bytes()`][`::uuid::Uuid::as_bytes`]):
+//!
+//! ```rust,ignore
+//! use ::safer_ffi::prelude::*;
+//! use ::uuid::Uuid;
+//!
+//! #[ffi_export]
+//! fn mylib_user_exists (id: Uuid)
+//!   -> bool
+//! ```
+//!
+//! generates:
+//!
+//! ```C
+//! typedef struct Uuid {
+//!     uint8_t bytes[16];
+//! } Uuid_t;
+//!
+//! bool mylib_user_exists (
+//!     Uuid_t id);
+//! ```
+//!
+//! The C# bindings define a blittable `Uuid_t` struct, which converts
+//! implicitly to and from `System.Guid` (taking care of the latter's
+//! mixed-endian layout).
+
+use_prelude!();
+
+/// The C layout of a [`::uuid::Uuid`]: its 16 bytes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub
+struct Uuid_Layout {
+    pub
+    bytes: [u8; 16],
+}
+
+// `Uuid` is a `#[repr(transparent)]` wrapper around its `[u8; 16]` bytes.
+const _: () = {
+    assert!(::core::mem::size_of::<::uuid::Uuid>() == 16);
+    assert!(::core::mem::align_of::<::uuid::Uuid>() == 1);
+};
+
+unsafe
+impl LegacyCType
+    for Uuid_Layout
+{ __cfg_headers__! {
+    fn c_short_name_fmt (fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt.write_str("Uuid")
+    }
+
+    fn c_var_fmt (
+        fmt: &'_ mut fmt::Formatter<'_>,
+        var_name: &'_ str,
+    ) -> fmt::Result
+    {
+        write!(fmt,
+            "Uuid_t{sep}{}",
+            var_name,
+            sep = if var_name.is_empty() { "" } else { " " },
+        )
+    }
+
+    fn c_layout_on (_: &'_ crate::headers::Target)
+      -> Option<crate::headers::TypeLayout>
+    {
+        Some(crate::headers::TypeLayout { size: 16, align: 1 })
+    }
+
+    fn c_define_self (definer: &'_ mut dyn crate::headers::Definer)
+      -> io::Result<()>
+    {
+        <u8 as CType>::define_self(&crate::headers::languages::C, definer)?;
+        definer.define_once("Uuid_t", &mut |definer| writeln!(definer.out(),
+            concat!(
+                "/** \\brief\n",
+                " *  A UUID, as its 16 bytes (in big-endian order).\n",
+                " */\n",
+                "typedef struct Uuid {{\n",
+                "    uint8_t bytes[16];\n",
+                "}} Uuid_t;\n",
+            ),
+        ))
+    }
+
+    fn cython_define_self (definer: &'_ mut dyn crate::headers::Definer)
+      -> io::Result<()>
+    {
+        <u8 as CType>::define_self(&crate::headers::languages::Cython, definer)?;
+        definer.define_once("Uuid_t", &mut |definer| writeln!(definer.out(),
+            concat!(
+                "    ctypedef struct Uuid_t:\n",
+                "        uint8_t bytes[16]\n",
+            ),
+        ))
+    }
+
+    __cfg_csharp__! {
+        fn csharp_define_self (definer: &'_ mut dyn crate::headers::Definer)
+          -> io::Result<()>
+        {
+            // `Guid`'s first three fields are native-endian (so, in practice,
+            // little-endian) integers, hence the conversions, byte-wise.
+            definer.define_once("Uuid_t", &mut |definer| writeln!(definer.out(),
+                concat!(
+                    "[StructLayout(LayoutKind.Sequential, Size = 16)]\n",
+                    "public unsafe struct Uuid_t {{\n",
+                    "    public fixed byte bytes[16];\n",
+                    "\n",
+                    "    public static implicit operator Guid (Uuid_t it)\n",
+                    "    {{\n",
+                    "        return new Guid(\n",
+                    "            (it.bytes[0] << 24) | (it.bytes[1] << 16) | (it.bytes[2] << 8) | it.bytes[3],\n",
+                    "            (short) ((it.bytes[4] << 8) | it.bytes[5]),\n",
+                    "            (short) ((it.bytes[6] << 8) | it.bytes[7]),\n",
+                    "            it.bytes[8], it.bytes[9], it.bytes[10], it.bytes[11],\n",
+                    "            it.bytes[12], it.bytes[13], it.bytes[14], it.bytes[15]);\n",
+                    "    }}\n",
+                    "\n",
+                    "    public static implicit operator Uuid_t (Guid guid)\n",
+                    "    {{\n",
+                    "        byte[] le = guid.ToByteArray();\n",
+                    "        Uuid_t it = default;\n",
+                    "        int[] order = {{ 3, 2, 1, 0, 5, 4, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15 }};\n",
+                    "        for (int i = 0; i < 16; i++) {{\n",
+                    "            it.bytes[i] = le[order[i]];\n",
+                    "        }}\n",
+                    "        return it;\n",
+                    "    }}\n",
+                    "}}\n",
+                ),
+            ))
+        }
+
+        fn csharp_ty ()
+          -> rust::String
+        {
+            "Uuid_t".into()
+        }
+    }
+} type OPAQUE_KIND = crate::layout::OpaqueKind::Concrete; }
+
+from_CType_impl_ReprC! {
+    Uuid_Layout
+}
+
+unsafe // Safety: same layout (see the assertions above), and any bytes are
+       // a valid `Uuid`.
+impl ReprC
+    for ::uuid::Uuid
+{
+    type CLayout = Uuid_Layout;
+
+    #[inline]
+    fn is_valid (_: &'_ Uuid_Layout)
+      -> bool
+    {
+        true
+    }
+}
+
+impl From<::uuid::Uuid>
+    for Uuid_Layout
+{
+    #[inline]
+    fn from (it: ::uuid::Uuid)
+      -> Uuid_Layout
+    {
+        Self { bytes: it.into_bytes() }
+    }
+}
+
+impl From<Uuid_Layout>
+    for ::uuid::Uuid
+{
+    #[inline]
+    fn from (Uuid_Layout { bytes }: Uuid_Layout)
+      -> ::uuid::Uuid
+    {
+        ::uuid::Uuid::from_bytes(bytes)
+    }
+}
@@ -56,6 +56,10 @@ impl<T> From<rust::Vec<T>>
         let len = vec.len();
         let cap = vec.capacity();
         let ptr = mem::ManuallyDrop::new(vec).as_mut_ptr();
+        #[cfg(feature = "leak-tracking")]
+        if cap != 0 && mem::size_of::<T>() != 0 {
+            crate::leak_tracking::track::<Self>(ptr.cast());
+        }
         Self {
             ptr: unsafe {
                 // Safety: `Vec` guarantees its pointer is nonnull.
@@ -75,6 +79,8 @@ impl<T> From<Vec<T>> for rust::Vec<T>
       -> rust::Vec<T>
     {
         let mut this = mem::ManuallyDrop::new(value);
+        #[cfg(feature = "leak-tracking")]
+        crate::leak_tracking::untrack(this.ptr.as_mut_ptr().cast());
         unsafe {
             // Safety: pointers originate from `Vec`.
             rust::Vec::from_raw_parts(
@@ -197,3 +203,111 @@ impl<T : fmt::Debug + ReprC> fmt::Debug
 macro_rules! c_vec { [$($input:tt)*] => (
     $crate::prelude::repr_c::Vec::from($crate::ඞ::vec![ $($input)* ])
 )}
+
+/// Defines and exports the functions through which C can build a
+/// [`repr_c::Vec<T>`][`Vec`] to pass in: create it with some capacity, push
+/// to it, reserve room in it, and free it.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_vec!(mylib_vec_u8: Vec<u8>);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// Vec_uint8_t mylib_vec_u8_with_capacity (
+///     size_t capacity);
+///
+/// void mylib_vec_u8_push (
+///     Vec_uint8_t * vec,
+///     uint8_t value);
+///
+/// void mylib_vec_u8_reserve (
+///     Vec_uint8_t * vec,
+///     size_t additional);
+///
+/// void mylib_vec_u8_free (
+///     Vec_uint8_t vec);
+/// ```
+///
+/// The `ptr`, `len`, and `cap` fields are then kept consistent by these
+/// functions, which is why C code is not to set them itself.
+///
+/// The element types are to be listed explicitly, one `prefix: Vec<T>` per
+/// `repr_c::Vec<T>` of the API which C is to build (several of them may be
+/// given to a single invocation): a macro only gets to see the items it is
+/// applied to, and the same `Vec<T>` appearing in several functions would
+/// otherwise have its helpers defined (and exported) several times over.
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_vec! {
+///     mylib_vec_u8: Vec<u8>,
+///     mylib_vec_point: Vec<Point>,
+/// }
+/// ```
+#[macro_export]
+macro_rules! ffi_export_vec {
+    (
+        $(
+            $prefix:ident : Vec<$T:ty $(,)?>
+        ),+ $(,)?
+    ) => (
+        $(
+            $crate::__ffi_export_vec!($prefix: $T);
+        )+
+    );
+}
+
+#[doc(hidden)] /** Not part of the public API! */ #[macro_export]
+macro_rules! __ffi_export_vec {(
+    $prefix:ident : $T:ty
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Creates a new empty vector, able to hold `capacity` elements before
+    /// growing.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _with_capacity>] (
+        capacity: usize,
+    ) -> $crate::prelude::repr_c::Vec<$T>
+    {
+        $crate::ඞ::Vec::<$T>::with_capacity(capacity).into()
+    }
+
+    #[$crate::ffi_export]
+    /// Takes ownership of `value`, appending it to the vector (which may thus
+    /// have to grow, moving its elements to a new `ptr`).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _push>] (
+        vec: &'_ mut $crate::prelude::repr_c::Vec<$T>,
+        value: $T,
+    )
+    {
+        vec.with_rust_mut(|vec| vec.push(value))
+    }
+
+    #[$crate::ffi_export]
+    /// Makes room for at least `additional` more elements (which may move the
+    /// elements to a new `ptr`).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _reserve>] (
+        vec: &'_ mut $crate::prelude::repr_c::Vec<$T>,
+        additional: usize,
+    )
+    {
+        vec.with_rust_mut(|vec| vec.reserve(additional))
+    }
+
+    #[$crate::ffi_export]
+    /// Frees the vector, along with its elements.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _free>] (
+        vec: $crate::prelude::repr_c::Vec<$T>,
+    )
+    {
+        $crate::ඞ::drop(vec)
+    }
+})}
@@ -0,0 +1,333 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! A `#[repr(C)]` ring buffer, for streaming elements (_e.g._, audio frames)
+//! across the FFI boundary without copying them into a `repr_c::Vec` first.
+//!
+//! See [`ffi_export_vec_deque!`][`crate::ffi_export_vec_deque`] for the
+//! functions through which C can push and pop elements.
+
+use_prelude!();
+
+#[derive_ReprC(is_valid = VecDeque::<T>::is_valid_layout)]
+#[repr(C)]
+#[cfg_attr(all(docs, feature = "nightly"), doc(cfg(feature = "alloc")))]
+/// Same as [`VecDeque<T>`][`::alloc::collections::VecDeque`], but with a
+/// guaranteed `#[repr(C)]` layout.
+///
+/// # C layout (for some given type T)
+///
+/// ```c
+/// typedef struct {
+///     // Cannot be NULL
+///     T * ptr;
+///     size_t cap;
+///     // The `i`-th element is at `ptr[(head + i) % cap]`.
+///     size_t head;
+///     size_t len;
+/// } VecDeque_T;
+/// ```
+///
+/// `head < cap` (but for an unallocated `cap == 0` buffer, whose `head` is
+/// `0`), and `len <= cap`.
+pub
+struct VecDeque<T> {
+    ptr: ptr::NonNullOwned<T>,
+    cap: usize,
+    head: usize,
+    len: usize,
+}
+
+impl<T : ReprC> VecDeque<T>
+where
+    // (same bounds as the derived `ReprC` impl)
+    ptr::NonNullOwned<T> : ConcreteReprC,
+{
+    /// The invariants tying the header fields together, for a `VecDeque`
+    /// handed over by C.
+    fn is_valid_layout (it: &'_ CLayoutOf<Self>)
+      -> bool
+    {
+        let &VecDeque_Layout { cap, head, len, .. } = it;
+        (head < cap || head == 0) && len <= cap
+    }
+}
+
+impl<T> VecDeque<T> {
+    pub
+    fn new ()
+      -> VecDeque<T>
+    {
+        rust::Vec::new().into()
+    }
+
+    pub
+    fn with_capacity (capacity: usize)
+      -> VecDeque<T>
+    {
+        rust::Vec::with_capacity(capacity).into()
+    }
+
+    #[inline]
+    pub
+    fn len (self: &'_ Self)
+      -> usize
+    {
+        self.len
+    }
+
+    #[inline]
+    pub
+    fn is_empty (self: &'_ Self)
+      -> bool
+    {
+        self.len == 0
+    }
+
+    #[inline]
+    pub
+    fn capacity (self: &'_ Self)
+      -> usize
+    {
+        self.cap
+    }
+
+    /// Pointer to the slot of the `offset`-th element (from the `head`).
+    fn slot (self: &'_ Self, offset: usize)
+      -> *mut T
+    {
+        debug_assert!(offset < self.cap);
+        let idx =
+            if offset < self.cap - self.head {
+                self.head + offset
+            } else {
+                offset - (self.cap - self.head)
+            }
+        ;
+        unsafe {
+            self.ptr.0.as_ptr().add(idx)
+        }
+    }
+
+    pub
+    fn get (self: &'_ Self, index: usize)
+      -> Option<&'_ T>
+    {
+        if index >= self.len {
+            return None;
+        }
+        unsafe {
+            Some(&*self.slot(index))
+        }
+    }
+
+    /// Appends `value`, growing the buffer if it is full.
+    pub
+    fn push_back (self: &'_ mut Self, value: T)
+    {
+        if self.len == self.cap {
+            let mut grown = rust::Vec::with_capacity(
+                usize::max(4, self.cap.saturating_mul(2))
+            );
+            grown.extend(::core::iter::from_fn(|| self.pop_front()));
+            // `self` is now empty: only its buffer is freed.
+            *self = grown.into();
+        }
+        unsafe {
+            self.slot(self.len).write(value);
+        }
+        self.len += 1;
+    }
+
+    pub
+    fn pop_front (self: &'_ mut Self)
+      -> Option<T>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let value = unsafe { self.slot(0).read() };
+        self.head = if self.head + 1 == self.cap { 0 } else { self.head + 1 };
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+/// Convert a [`std::vec::Vec`] to a [`safer_ffi::VecDeque`] (in place).
+impl<T> From<rust::Vec<T>>
+    for VecDeque<T>
+{
+    #[inline]
+    fn from (vec: rust::Vec<T>)
+      -> VecDeque<T>
+    {
+        let len = vec.len();
+        let cap = vec.capacity();
+        let ptr = mem::ManuallyDrop::new(vec).as_mut_ptr();
+        Self {
+            ptr: unsafe {
+                // Safety: `Vec` guarantees its pointer is nonnull.
+                ptr::NonNull::new_unchecked(ptr)
+            }.into(),
+            cap,
+            head: 0,
+            len,
+        }
+    }
+}
+
+/// Convert a [`std::collections::VecDeque`] to a [`safer_ffi::VecDeque`]
+/// (reusing its buffer).
+impl<T> From<::alloc::collections::VecDeque<T>>
+    for VecDeque<T>
+{
+    #[inline]
+    fn from (deque: ::alloc::collections::VecDeque<T>)
+      -> VecDeque<T>
+    {
+        rust::Vec::from(deque).into()
+    }
+}
+
+/// Convert a [`safer_ffi::VecDeque`] to a [`std::collections::VecDeque`].
+///
+/// The elements are moved to a new buffer.
+impl<T> From<VecDeque<T>>
+    for ::alloc::collections::VecDeque<T>
+{
+    fn from (mut value: VecDeque<T>)
+      -> ::alloc::collections::VecDeque<T>
+    {
+        let mut ret = Self::with_capacity(value.len);
+        ret.extend(::core::iter::from_fn(|| value.pop_front()));
+        ret
+    }
+}
+
+impl<T> Drop
+    for VecDeque<T>
+{
+    fn drop (self: &'_ mut VecDeque<T>)
+    {
+        while let Some(value) = self.pop_front() {
+            drop(value);
+        }
+        unsafe {
+            drop::<rust::Vec<T>>(
+                rust::Vec::from_raw_parts(self.ptr.as_mut_ptr(), 0, self.cap)
+            );
+        }
+    }
+}
+
+impl<T : fmt::Debug> fmt::Debug
+    for VecDeque<T>
+{
+    fn fmt (self: &'_ Self, fmt: &'_ mut fmt::Formatter<'_>)
+      -> fmt::Result
+    {
+        fmt .debug_list()
+            .entries((0 .. self.len).filter_map(|i| self.get(i)))
+            .finish()
+    }
+}
+
+unsafe // Safety: from delegation
+    impl<T> Send
+        for VecDeque<T>
+    where
+        rust::Vec<T> : Send,
+    {}
+
+unsafe // Safety: from delegation
+    impl<T> Sync
+        for VecDeque<T>
+    where
+        rust::Vec<T> : Sync,
+    {}
+
+/// Defines and exports the functions through which C can create, push to, pop
+/// from, and free a [`repr_c::VecDeque<T>`][`VecDeque`].
+///
+/// ```rust,ignore
+/// ::safer_ffi::ffi_export_vec_deque!(mylib_frames: VecDeque<Frame>);
+/// ```
+///
+/// generates:
+///
+/// ```C
+/// VecDeque_Frame_t mylib_frames_with_capacity (
+///     size_t capacity);
+///
+/// void mylib_frames_free (
+///     VecDeque_Frame_t deque);
+///
+/// void mylib_frames_push_back (
+///     VecDeque_Frame_t * deque,
+///     Frame_t value);
+///
+/// bool mylib_frames_pop_front (
+///     VecDeque_Frame_t * deque,
+///     Frame_t * out);
+/// ```
+///
+/// Since the layout of the ring buffer is visible from C, the elements may
+/// also be read in place, as long as the deque is not mutated meanwhile.
+#[macro_export]
+macro_rules! ffi_export_vec_deque {(
+    $prefix:ident : VecDeque<$T:ty $(,)?> $(,)?
+) => ($crate::paste::item! {
+    #[$crate::ffi_export]
+    /// Creates a new empty ring buffer, able to hold `capacity` elements
+    /// before growing.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _with_capacity>] (
+        capacity: usize,
+    ) -> $crate::prelude::repr_c::VecDeque<$T>
+    {
+        $crate::prelude::repr_c::VecDeque::with_capacity(capacity)
+    }
+
+    #[$crate::ffi_export]
+    /// Frees the ring buffer, along with the elements it still holds.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _free>] (
+        deque: $crate::prelude::repr_c::VecDeque<$T>,
+    )
+    {
+        $crate::ඞ::drop(deque)
+    }
+
+    #[$crate::ffi_export]
+    /// Takes ownership of `value`, appending it to the ring buffer (which may
+    /// thus have to grow, moving its elements to a new `ptr`).
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _push_back>] (
+        deque: &'_ mut $crate::prelude::repr_c::VecDeque<$T>,
+        value: $T,
+    )
+    {
+        deque.push_back(value)
+    }
+
+    #[$crate::ffi_export]
+    /// Moves the first element out of the ring buffer, into `out`.
+    ///
+    /// Returns `false`, leaving `out` untouched, if the ring buffer is empty.
+    #[allow(nonstandard_style)]
+    pub
+    fn [<$prefix _pop_front>] (
+        deque: &'_ mut $crate::prelude::repr_c::VecDeque<$T>,
+        out: $crate::prelude::Out<'_, $T>,
+    ) -> bool
+    {
+        match deque.pop_front() {
+            | Some(value) => {
+                out.write(value);
+                true
+            },
+            | None => false,
+        }
+    }
+})}
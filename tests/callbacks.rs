@@ -0,0 +1,261 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+#[ffi_export]
+fn test_sum_12 (
+    cb: RefDynFnMut12<'_, u64,
+        u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8,
+    >,
+    fptr: extern "C" fn (
+        u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8,
+    ) -> u64,
+) -> u64
+{
+    {cb}.call(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)
+        + fptr(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)
+}
+
+#[test]
+fn test_arity_12 ()
+{
+    extern "C"
+    fn sum (
+        a1: u8, a2: u8, a3: u8, a4: u8, a5: u8, a6: u8,
+        a7: u8, a8: u8, a9: u8, a10: u8, a11: u8, a12: u8,
+    ) -> u64
+    {
+        [a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12]
+            .iter()
+            .map(|&a| a as u64)
+            .sum()
+    }
+    let mut closure = |
+        a1: u8, a2: u8, a3: u8, a4: u8, a5: u8, a6: u8,
+        a7: u8, a8: u8, a9: u8, a10: u8, a11: u8, a12: u8,
+    | sum(a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12);
+    assert_eq!(test_sum_12(RefDynFnMut12::new(&mut closure), sum), 2 * 78);
+}
+
+#[test]
+fn test_ffi_iter ()
+{
+    unsafe extern "C"
+    fn next (user_data: *mut ::core::ffi::c_void, out: *mut i32)
+      -> bool
+    {
+        let remaining = &mut *user_data.cast::<i32>();
+        if *remaining == 0 {
+            return false;
+        }
+        out.write(*remaining);
+        *remaining -= 1;
+        true
+    }
+    let mut remaining = 3;
+    let iter = unsafe {
+        ::safer_ffi::ffi_iter::from_callback(<*mut i32>::cast(&mut remaining), next)
+    };
+    assert_eq!(iter.collect::<Vec<i32>>(), [3, 2, 1]);
+}
+
+#[test]
+fn test_callback_scope ()
+{
+    static SCOPE: ::safer_ffi::callback_scope::CallbackScope =
+        ::safer_ffi::callback_scope::CallbackScope::new()
+    ;
+    let tracked = SCOPE.track(|| 42).unwrap();
+    let thread = ::std::thread::spawn(move || tracked());
+    SCOPE.shutdown();
+    assert_eq!(SCOPE.in_flight(), 0);
+    assert_eq!(thread.join().unwrap(), 42);
+    assert!(SCOPE.track(|| 27).is_none());
+}
+
+#[ffi_export]
+fn test_count_up_to (
+    n: u64,
+    mut progress: ::safer_ffi::progress::ProgressCallback<'_>,
+) -> u64
+{
+    (1 ..= n)
+        .take_while(|&i| progress.report(i, n).is_ok())
+        .count() as u64
+}
+
+#[test]
+fn test_progress_callback ()
+{
+    let mut reports = vec![];
+    let mut report = |current, total| {
+        reports.push((current, total));
+        current < 2
+    };
+    let progress = ::safer_ffi::progress::ProgressCallback::from_fn_mut(&mut report);
+    assert_eq!(test_count_up_to(3, progress), 1);
+    assert_eq!(reports, [(1, 3), (2, 3)]);
+}
+
+::safer_ffi::ffi_export_cancellation_token!(test);
+
+#[ffi_export]
+fn test_count_until_cancelled (
+    n: u64,
+    token: &'_ repr_c::CancellationToken,
+) -> u64
+{
+    (1 ..= n)
+        .take_while(|_| token.check().is_ok())
+        .count() as u64
+}
+
+#[test]
+fn cancellation_token ()
+{
+    use ::std::{
+        future::Future,
+        sync::{Arc, atomic::{self, AtomicBool}},
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    struct Flag(AtomicBool);
+    impl Wake for Flag {
+        fn wake (self: Arc<Self>)
+        {
+            self.0.store(true, atomic::Ordering::SeqCst);
+        }
+    }
+
+    let token = test_cancellation_token_new();
+    assert_eq!(test_count_until_cancelled(3, &token), 3);
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(flag.clone());
+    let mut cancelled = Box::pin(token.cancelled());
+    // A stale waker: replaced by the next poll's, it is not to be woken.
+    let stale = Arc::new(Flag(AtomicBool::new(false)));
+    assert!(
+        cancelled.as_mut()
+            .poll(&mut Context::from_waker(&Waker::from(stale.clone())))
+            .is_pending()
+    );
+    let mut cx = Context::from_waker(&waker);
+    assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+    assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+    // Nor is the waker of a dropped future.
+    let dropped = Arc::new(Flag(AtomicBool::new(false)));
+    assert!(
+        Box::pin(token.cancelled()).as_mut()
+            .poll(&mut Context::from_waker(&Waker::from(dropped.clone())))
+            .is_pending()
+    );
+    test_cancellation_token_cancel(&token);
+    assert!(test_cancellation_token_is_cancelled(&token));
+    assert!(flag.0.load(atomic::Ordering::SeqCst));
+    assert!(stale.0.load(atomic::Ordering::SeqCst).not());
+    assert!(dropped.0.load(atomic::Ordering::SeqCst).not());
+    assert_eq!(cancelled.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert_eq!(test_count_until_cancelled(3, &token), 0);
+    test_cancellation_token_free(token);
+}
+
+#[ffi_export]
+fn test_fold_with (
+    xs: c_slice::Ref<'_, i32>,
+    mut cb: ::safer_ffi::closure::BorrowedCallback<'_, (i32, i32), i32>,
+) -> i32
+{
+    xs.iter().fold(0, |acc, &x| cb.call(acc, x))
+}
+
+#[test]
+fn borrowed_callback ()
+{
+    let mut calls = 0;
+    let mut add = |acc: i32, x: i32| {
+        calls += 1;
+        acc + x
+    };
+    let cb = ::safer_ffi::closure::BorrowedCallback2::from_fn_mut(&mut add);
+    assert_eq!(test_fold_with([1, 2, 3][..].into(), cb), 6);
+    assert_eq!(calls, 3);
+
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef struct BorrowedCallback2_int32_int32_int32 {\n"));
+        assert!(out.contains("    int32_t (*fn_ptr)(void *, int32_t, int32_t);\n"));
+        assert!(out.contains("    void * ctx;\n} BorrowedCallback2_int32_int32_int32_t;"));
+        assert!(out.contains("test_fold_with (\n    slice_ref_int32_t xs,\n    BorrowedCallback2_int32_int32_int32_t cb);"));
+    }
+}
+
+#[ffi_export(async_via = callback, executor = ::futures::executor::block_on)]
+async fn test_async_double (x: i32)
+  -> i32
+{
+    ffi_await!(async move {
+        x * 2
+    })
+}
+
+#[ffi_export(async_via = callback, executor = ::futures::executor::block_on)]
+async fn test_async_noop ()
+{
+    ffi_await!(async {})
+}
+
+#[test]
+fn async_via_callback ()
+{
+    use ::safer_ffi::completion::{Completion, VoidCompletion};
+
+    let (tx, rx) = ::std::sync::mpsc::channel();
+    test_async_double(21, Completion::new(move |ret| tx.send(ret).unwrap()));
+    assert_eq!(rx.recv().unwrap(), 42);
+
+    let (tx, rx) = ::std::sync::mpsc::channel();
+    test_async_noop(VoidCompletion::new(move || tx.send(()).unwrap()));
+    assert_eq!(rx.recv().unwrap(), ());
+
+    // A C callback.
+    unsafe extern "C"
+    fn on_done (user_data: *mut ::core::ffi::c_void, ret: i32)
+    {
+        *user_data.cast::<i32>() = ret;
+    }
+    let mut out = 0;
+    test_async_double(4, unsafe { Completion::from_raw(<*mut i32>::cast(&mut out), on_done) });
+    assert_eq!(out, 8);
+
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("} Completion_int32_t;"));
+        assert!(out.contains("test_async_double (\n    int32_t x,\n    Completion_int32_t completion);"));
+        assert!(out.contains("test_async_noop (\n    VoidCompletion_t completion);"));
+    }
+}
@@ -0,0 +1,32 @@
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! Helpers shared by the integration tests.
+#![allow(unused_macros)]
+
+/// The headers generated (as a `String`) by `::safer_ffi::headers::builder()`
+/// configured with the given `.with_…(…)` calls, or by the given `Builder`
+/// (not yet given an output).
+///
+/// ```rust,ignore
+/// let out = generate!(
+///     .with_language(Language::CSharp)
+/// )?;
+/// ```
+macro_rules! generate {
+    (
+        $( . $method:ident $args:tt )*
+    ) => (
+        generate!(
+            ::safer_ffi::headers::builder() $( .$method $args )*
+        )
+    );
+
+    (
+        $builder:expr $(,)?
+    ) => ({
+        let mut out = ::std::vec::Vec::new();
+        $builder
+            .to_writer(&mut out)
+            .generate()
+            .map(|()| ::std::string::String::from_utf8(out).unwrap())
+    });
+}
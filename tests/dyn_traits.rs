@@ -1,5 +1,9 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 
+#[macro_use]
+mod common;
+
+use ::core::{future::Future, ops::Not as _, pin::Pin};
 use ::safer_ffi::prelude::*;
 
 #[derive_ReprC(dyn)]
@@ -59,3 +63,138 @@ fn _cloneable<'r, T : 'r + Cloneable + Clone> (owned: T, r: &'r T)
     r.method();
     r2.method();
 }
+
+static CALLS: ::std::sync::atomic::AtomicU32 = ::std::sync::atomic::AtomicU32::new(0);
+
+#[ffi_export]
+fn make_fn_mut ()
+  -> impl FfiFnMut
+{
+    || {
+        CALLS.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn impl_trait_export ()
+{
+    let mut f: VirtualPtr<dyn FfiFnMut> = make_fn_mut();
+    f.call();
+    f.call();
+    assert_eq!(CALLS.load(::std::sync::atomic::Ordering::Relaxed), 2);
+}
+
+#[derive_ReprC(dyn)]
+trait AsyncPlugin {
+    fn fetch (&self, id: u32)
+      -> Pin<Box<dyn '_ + Send + Future<Output = i32>>>
+    ;
+}
+
+struct Doubler;
+
+impl AsyncPlugin for Doubler {
+    fn fetch (&self, id: u32)
+      -> Pin<Box<dyn '_ + Send + Future<Output = i32>>>
+    {
+        Box::pin(async move {
+            2 * id as i32
+        })
+    }
+}
+
+#[test]
+fn async_methods ()
+{
+    let plugin: VirtualPtr<dyn Send + Sync + AsyncPlugin> = Box::new(Doubler).into();
+    let mut fut = repr_c::Future::new(async move {
+        plugin.fetch(21).await
+    });
+    assert_eq!(fut.wait(), Some(42));
+}
+
+#[derive_ReprC(dyn, Clone)]
+trait Greeter {
+    fn greet (&self, times: u32)
+      -> i32
+    ;
+}
+
+#[ffi_export]
+fn greet_twice (greeter: VirtualPtr<dyn Greeter>)
+  -> i32
+{
+    greeter.greet(2)
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_csharp_dyn_trait_classes ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+        .with_csharp_dyn_trait_classes(true)
+    )?;
+    assert!(out.contains("public abstract unsafe class Greeter {\n    public abstract Int32 Greet (UInt32 times);\n"));
+    assert!(out.contains("    public VirtualPtr__Erased_ptr_GreeterVTable_t IntoVirtualPtr ()\n"));
+    assert!(out.contains("        retain_vptr = RetainVPtr,\n        greet = GreetTrampoline,\n"));
+    assert!(out.contains("    private static Int32 GreetTrampoline (Erased_t /*const*/ * handle, UInt32 times)\n"));
+    // Opt-in.
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("class Greeter").not());
+    Ok(())
+}
+
+#[derive_ReprC(dyn)]
+trait Handler : Send + Sync {
+    fn on_event (&self, ev: i32)
+      -> i32
+    ;
+
+    fn on_error (&self, code: i32)
+      -> i32
+    {
+        -code
+    }
+}
+
+struct Doubling;
+
+impl Handler for Doubling {
+    fn on_event (&self, ev: i32)
+      -> i32
+    {
+        2 * ev
+    }
+}
+
+#[ffi_export]
+fn handle_event (handler: VirtualPtr<dyn Handler>, ev: i32)
+  -> i32
+{
+    handler.on_event(ev)
+}
+
+#[test]
+fn supertraits_and_default_methods ()
+{
+    fn assert_thread_safe<T : Send + Sync> (_: &T)
+    {}
+    let handler: VirtualPtr<dyn Handler> = Box::new(Doubling).into();
+    assert_thread_safe(&handler);
+    assert_eq!(handler.on_error(1), -1);
+    assert_eq!(handle_event(handler, 21), 42);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains(concat!(
+            "/** \\brief\n",
+            " *  `Send`: the object behind the vtable may be used, and released, from any thread.\n",
+            " *  `Sync`: the `&self` methods of the object behind the vtable may be called concurrently, from several threads.\n",
+            " */\n",
+            "typedef struct HandlerVTable {",
+        )));
+    }
+}
@@ -0,0 +1,1267 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+#![cfg_attr(feature = "nightly", feature(c_variadic, linkage))]
+
+//! Tests of the `#[ffi_export(…)]` options, and of the header generation
+//! settings.
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+#[ffi_export]
+#[cfg(windows)]
+fn test_windows_only ()
+  -> i32
+{
+    42
+}
+
+#[ffi_export(cfg_header = "defined(TEST_FANCY_UNIX)")]
+#[cfg(all(unix, target_pointer_width = "64"))]
+fn test_fancy_unix_only ()
+  -> i32
+{
+    42
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_cfg_guards ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#if defined(_WIN32)\nint32_t\ntest_windows_only (void);\n"));
+    assert!(out.contains("#endif /* defined(_WIN32) */\n"));
+    assert!(out.contains("#if defined(TEST_FANCY_UNIX)\nint32_t\ntest_fancy_unix_only (void);\n"));
+
+    let out = generate!(
+        .with_cfg_conditions(&[("windows", "defined(TEST_WINDOWS)")])
+    )?;
+    assert!(out.contains("#if defined(TEST_WINDOWS)\nint32_t\ntest_windows_only (void);\n"));
+
+    // The other languages only get to see the functions of the current target.
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert_eq!(out.contains("test_windows_only"), cfg!(windows));
+    Ok(())
+}
+
+/// The answer.
+#[ffi_export]
+static TEST_ANSWER: i32 = 42;
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_static ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("extern int32_t const TEST_ANSWER;"));
+    assert_eq!(TEST_ANSWER, 42);
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_guard_style ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::GuardStyle;
+
+    let generate = |guard_style| -> ::std::io::Result<String> {
+        generate!(
+            .with_guard("TEST_GUARD_H")
+            .with_guard_style(guard_style)
+        )
+    };
+
+    let out = generate(GuardStyle::IfnDef)?;
+    assert!(out.contains("\n#ifndef TEST_GUARD_H\n#define TEST_GUARD_H\n#ifdef __cplusplus\n"));
+    assert!(out.ends_with("#endif\n\n#endif /* TEST_GUARD_H */\n"));
+    assert!(out.contains("#pragma once").not());
+
+    let out = generate(GuardStyle::PragmaOnce)?;
+    assert!(out.contains("\n#pragma once\n#ifdef __cplusplus\n"));
+    assert!(out.contains("TEST_GUARD_H").not());
+
+    let out = generate(GuardStyle::Both)?;
+    assert!(out.contains("\n#pragma once\n#ifndef TEST_GUARD_H\n#define TEST_GUARD_H\n#ifdef __cplusplus\n"));
+    assert!(out.ends_with("\n#endif /* TEST_GUARD_H */\n"));
+    Ok(())
+}
+
+/// Doubles `x`.
+///
+/// # Arguments
+///
+///   * `x` - the value to double, in place.
+#[ffi_export]
+fn test_double_in_place (x: &mut i32)
+{
+    *x *= 2;
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_profiles ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Profile;
+
+    let generate = |profile, restrict_pointers: Option<bool>| -> ::std::io::Result<String> {
+        let mut builder = ::safer_ffi::headers::builder().with_profile(profile);
+        if let Some(restrict_pointers) = restrict_pointers {
+            builder = builder.with_restrict_pointers(restrict_pointers);
+        }
+        generate!(builder)
+    };
+    let doc_tag = "\\param x the value to double, in place.";
+
+    let out = generate(Profile::StrictC89, None)?;
+    assert!(out.contains("#include <limits.h>\n"));
+    assert!(out.contains("#else\ntypedef signed char int8_t;\n"));
+    assert!(out.contains(doc_tag).not());
+    assert!(out.contains("#pragma once").not());
+    assert!(out.contains("    int32_t * x);"));
+
+    let out = generate(Profile::ModernC, None)?;
+    assert!(out.contains("#include <limits.h>").not());
+    assert!(out.contains(doc_tag));
+    assert!(out.contains("#pragma once\n#ifndef "));
+    assert!(out.contains("    int32_t * SAFER_FFI_RESTRICT SAFER_FFI_NONNULL x);"));
+    // Explicit options take precedence.
+    let out = generate(Profile::ModernC, Some(false))?;
+    assert!(out.contains("    int32_t * SAFER_FFI_NONNULL x);"));
+
+    let out = generate(Profile::EmbeddedNoStdlib, None)?;
+    assert!(out.contains("#include <stdint.h>\n"));
+    assert!(out.contains(doc_tag).not());
+    assert!(out.contains("    int32_t * SAFER_FFI_RESTRICT x);"));
+
+    let out = generate(Profile::WindowsSdkStyle, None)?;
+    assert!(out.contains(doc_tag));
+    assert!(out.contains("#pragma once\n"));
+    assert!(out.contains("#ifndef __RUST_").not());
+    assert!(out.contains("    int32_t * x);"));
+
+    let mut x = 21;
+    test_double_in_place(&mut x);
+    assert_eq!(x, 42);
+    Ok(())
+}
+
+/// Divides `x` by `y`.
+///
+/// # Arguments
+///
+///   * `x` - the dividend.
+///   * `y` - the divisor,
+///     which must not be zero.
+///
+/// # Returns
+///
+/// The quotient.
+///
+/// # Safety
+///
+/// `y` must not be zero.
+#[ffi_export]
+fn test_exact_div (x: i32, y: i32)
+  -> i32
+{
+    x / y
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_doc_tags ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Language;
+
+    let generate = |language, doc_tags| -> ::std::io::Result<String> {
+        generate!(
+            .with_language(language)
+            .with_doc_tags(doc_tags)
+        )
+    };
+
+    let out = generate(Language::C, true)?;
+    assert!(out.contains(concat!(
+        "/** \\brief\n",
+        " *  Divides `x` by `y`.\n",
+        " *\n",
+        " *  \\param x the dividend.\n",
+        " *  \\param y the divisor, which must not be zero.\n",
+        " *  \\return The quotient.\n",
+        " *  \\warning <b>Safety:</b> `y` must not be zero.\n",
+        " */\n",
+        "int32_t\n",
+        "test_exact_div (\n",
+    )));
+    assert!(out.contains(" *  # Arguments\n").not());
+
+    let out = generate(Language::CSharp, true)?;
+    assert!(out.contains("/// <param name=\"x\">the dividend.</param>\n"));
+    assert!(out.contains("/// <param name=\"y\">the divisor, which must not be zero.</param>\n"));
+    assert!(out.contains("/// <returns>The quotient.</returns>\n"));
+    assert!(out.contains("/// <remarks><b>Safety:</b> `y` must not be zero.</remarks>\n"));
+
+    // Verbatim docs otherwise.
+    let out = generate(Language::C, false)?;
+    assert!(out.contains(" *  # Arguments\n *\n *  * `x` - the dividend.\n"));
+    assert!(out.contains("\\param").not());
+    assert_eq!(test_exact_div(84, 2), 42);
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_symbol_lists ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Language;
+
+    let generate = |language| -> ::std::io::Result<String> {
+        generate!(
+            .with_language(language)
+        )
+    };
+
+    let out = generate(Language::MsvcDef)?;
+    assert!(out.contains("\nEXPORTS\n"));
+    assert!(out.contains("\n    test_header_name_v2\n"));
+    assert!(out.contains("\n    TEST_ANSWER DATA\n"));
+    assert!(out.contains("typedef").not());
+
+    let out = generate(Language::LdVersionScript)?;
+    assert!(out.contains("{\n  global:\n"));
+    assert!(out.contains("\n    test_header_name_v2;\n"));
+    assert!(out.contains("\n    TEST_ANSWER;\n"));
+    assert!(out.contains("  local:\n    *;\n};\n"));
+
+    let out = generate(Language::MacosExportedSymbols)?;
+    assert!(out.contains("\n_test_header_name_v2\n"));
+    assert!(out.contains("\n_TEST_ANSWER\n"));
+    assert!(out.contains("global:").not());
+    Ok(())
+}
+
+#[ffi_export(export_name = "test_header_name_v2")]
+fn test_header_name ()
+  -> i32
+{
+    42
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_export_name ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("int32_t\ntest_header_name_v2 (void);"));
+    assert!(out.contains("#define test_header_name test_header_name_v2\n"));
+    assert_eq!(test_header_name(), 42);
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_api_macro ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_api_macro("TEST_API")
+    )?;
+    assert_eq!(out.matches("#ifndef TEST_API\n").count(), 1);
+    assert!(out.contains(concat!(
+        "#ifndef TEST_API\n",
+        "#if defined(TEST_STATIC)\n",
+        "#define TEST_API\n",
+        "#elif defined(_WIN32) || defined(__CYGWIN__)\n",
+        "#ifdef TEST_EXPORTS\n",
+        "#define TEST_API __declspec(dllexport)\n",
+        "#else\n",
+        "#define TEST_API __declspec(dllimport)\n",
+        "#endif\n",
+        "#elif defined(__GNUC__)\n",
+        "#define TEST_API __attribute__((visibility(\"default\")))\n",
+        "#else\n",
+        "#define TEST_API\n",
+        "#endif\n",
+        "#endif\n",
+    )));
+    assert!(out.contains("TEST_API int32_t\ntest_header_name_v2 (void);"));
+    assert!(out.contains("TEST_API extern int32_t const TEST_ANSWER;"));
+
+    let out = generate!()?;
+    assert!(out.contains("TEST_API").not());
+    Ok(())
+}
+
+/// Introduced later on.
+#[ffi_export(since = "1.2")]
+fn test_introduced_later ()
+  -> i32
+{
+    42
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_since ()
+  -> ::std::io::Result<()>
+{
+    let generate = |version_macro: Option<&str>| -> ::std::io::Result<String> {
+        let mut builder = ::safer_ffi::headers::builder();
+        if let Some(version_macro) = version_macro {
+            builder = builder.with_version_macro(version_macro);
+        }
+        generate!(builder)
+    };
+    let declaration = concat!(
+        "/** \\brief\n",
+        " *  Introduced later on.\n",
+        " *\n",
+        " *  Available since version 1.2.\n",
+        " */\n",
+        "int32_t\n",
+        "test_introduced_later (void);\n",
+    );
+
+    let out = generate(Some("TEST_VERSION"))?;
+    assert!(out.contains(&format!(
+        "#if !defined(TEST_VERSION) || TEST_VERSION >= 1002000 /* 1.2 */\n{declaration}\n#endif\n",
+    )));
+    // The other functions are not guarded.
+    assert_eq!(out.matches("#if !defined(TEST_VERSION)").count(), 1);
+
+    let out = generate(None)?;
+    assert!(out.contains(declaration));
+    assert!(out.contains("TEST_VERSION").not());
+    assert_eq!(test_introduced_later(), 42);
+    Ok(())
+}
+
+/// Counts the bytes of `name` and `data`.
+#[ffi_export]
+fn test_wrapped_len (name: char_p::Ref<'_>, data: c_slice::Ref<'_, u8>)
+  -> usize
+{
+    name.to_bytes().len() + data.len()
+}
+
+/// Fails with `code`, unless it is `0`.
+#[ffi_export(error_code)]
+fn test_wrapped_check (code: i32)
+  -> i32
+{
+    code
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_csharp_wrapper_class ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Language;
+
+    let out = generate!(
+        .with_language(Language::CSharp)
+        .with_csharp_wrapper_class("Test")
+    )?;
+    assert!(out.contains("public static unsafe partial class Test {\n"));
+    assert!(out.contains(concat!(
+        "    public static UIntPtr TestWrappedLen (\n",
+        "        string name,\n",
+        "        ReadOnlySpan<byte> data)\n",
+        "    {\n",
+        "        byte[] name_utf8 = name is null ? null : System.Text.Encoding.UTF8.GetBytes(name + \"\\0\");\n",
+        "        fixed (byte * name_ptr = name_utf8)\n",
+        "        fixed (byte * data_ptr = data)\n",
+        "        {\n",
+        "            return Ffi.test_wrapped_len(name_ptr, new slice_ref_uint8_t { ptr = data_ptr, len = (UIntPtr) data.Length });\n",
+        "        }\n",
+        "    }\n",
+        "}\n",
+    )));
+    assert_eq!(out.matches("public class FfiException : Exception {").count(), 1);
+    assert!(out.contains(concat!(
+        "    public static void TestWrappedCheck (\n",
+        "        Int32 code)\n",
+        "    {\n",
+        "        {\n",
+        "            var __ret = Ffi.test_wrapped_check(code);\n",
+        "            if ((long) __ret != 0) throw new FfiException(\"test_wrapped_check\", (long) __ret);\n",
+        "        }\n",
+        "    }\n",
+    )));
+
+    let out = generate!(
+        .with_language(Language::CSharp)
+    )?;
+    assert!(out.contains("TestWrappedLen").not());
+    assert_eq!(test_wrapped_len(c!("ab"), b"cde"[..].into()), 5);
+    assert_eq!(test_wrapped_check(0), 0);
+    Ok(())
+}
+
+#[ffi_export(group = "ImageIo")]
+fn test_image_width ()
+  -> u32
+{
+    640
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_group ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#ifdef __cplusplus\nnamespace image_io {\n#endif\n\n"));
+    assert!(out.contains(
+        "uint32_t\ntest_image_width (void);\n\n#ifdef __cplusplus\n} /* namespace image_io */\n#endif\n"
+    ));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("public static unsafe partial class ImageIo {\n"));
+    assert!(out.contains("public static unsafe extern\n    UInt32 test_image_width ();"));
+    assert_eq!(test_image_width(), 640);
+    Ok(())
+}
+
+#[ffi_export(convention = "system")]
+fn test_system_convention (x: i32)
+  -> i32
+{
+    x + 1
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_convention ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#define SAFER_FFI_SYSTEM __stdcall\n"));
+    assert!(out.contains("int32_t\nSAFER_FFI_SYSTEM test_system_convention (\n"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("CallingConvention = CallingConvention.Winapi)] public static unsafe extern"));
+    assert_eq!(test_system_convention(41), 42);
+    Ok(())
+}
+
+/// Held by the tests panicking through `catch_panics` functions, since the
+/// panic policy they report to is global.
+static PANIC_POLICY: ::std::sync::Mutex<()> = ::std::sync::Mutex::new(());
+
+#[ffi_export(catch_panics = -1)]
+fn test_catch_panics (x: i32)
+  -> i32
+{
+    assert!(x >= 0, "negative input");
+    x
+}
+
+#[test]
+fn catch_panics ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("Should this function panic, it returns `-1` instead."));
+    }
+    extern "C" {
+        fn test_catch_panics (x: i32) -> i32;
+    }
+    let _guard = PANIC_POLICY.lock().unwrap_or_else(|it| it.into_inner());
+    unsafe {
+        assert_eq!(test_catch_panics(42), 42);
+        assert_eq!(test_catch_panics(-42), -1);
+    }
+}
+
+#[ffi_export(catch_panics = -1)]
+fn test_panic_policy (x: i32)
+  -> i32
+{
+    assert!(x >= 0, "negative input");
+    x
+}
+
+#[test]
+fn panic_policy ()
+{
+    use ::safer_ffi::panic::{PanicCallback, PanicPolicy};
+    use ::std::sync::Mutex;
+
+    /// The message, and whether there was a backtrace.
+    static REPORTED: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+    // (no panicking across the `extern "C"` boundary: merely record)
+    unsafe extern "C"
+    fn on_panic (
+        _: *mut ::core::ffi::c_void,
+        message: char_p::Raw,
+        backtrace: char_p::Raw,
+    )
+    {
+        let (message, backtrace) = (message.as_ref(), backtrace.as_ref());
+        if let Ok(mut reported) = REPORTED.lock() {
+            *reported = Some((message.to_string(), backtrace.to_str().is_empty().not()));
+        }
+    }
+
+    let _guard = PANIC_POLICY.lock().unwrap_or_else(|it| it.into_inner());
+    ::safer_ffi::panic::set_ffi_panic_policy(PanicPolicy::Callback(unsafe {
+        // Safety: `on_panic` is thread-safe and does not unwind.
+        PanicCallback::new(on_panic, ::core::ptr::null_mut())
+    }));
+    extern "C" {
+        fn test_panic_policy (x: i32) -> i32;
+    }
+    unsafe {
+        assert_eq!(test_panic_policy(42), 42);
+        assert_eq!(REPORTED.lock().unwrap().take(), None);
+        assert_eq!(test_panic_policy(-42), -1);
+    }
+    ::safer_ffi::panic::set_ffi_panic_policy(PanicPolicy::LogAndReturnDefault);
+    let (reported, has_backtrace) = REPORTED.lock().unwrap().take().unwrap();
+    assert!(reported.starts_with("negative input (at "), "{reported}");
+    assert!(has_backtrace);
+}
+
+#[ffi_export]
+fn test_checked_div (x: u32, y: u32)
+  -> Result<u32, i32>
+{
+    x.checked_div(y).ok_or(-1)
+}
+
+#[test]
+fn result_returns ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("int32_t\ntest_checked_div (\n    uint32_t x,\n    uint32_t y,\n    uint32_t * out);"));
+    }
+    assert_eq!(test_checked_div(42, 2), Ok(21));
+    extern "C" {
+        #[link_name = "test_checked_div"]
+        fn c_test_checked_div (x: u32, y: u32, out: *mut u32) -> i32;
+    }
+    let mut out = 0;
+    unsafe {
+        assert_eq!(c_test_checked_div(42, 2, &mut out), 0);
+        assert_eq!(out, 21);
+        assert_eq!(c_test_checked_div(42, 0, &mut out), -1);
+        assert_eq!(out, 21);
+        assert_eq!(c_test_checked_div(42, 6, ::core::ptr::null_mut()), 0);
+    }
+}
+
+::safer_ffi::ffi_export_last_error!(test);
+
+#[ffi_export(last_error)]
+fn test_checked_sub (x: u32, y: u32)
+  -> Result<u32, i32>
+{
+    x.checked_sub(y).ok_or(-1)
+}
+
+#[test]
+fn last_error ()
+{
+    ::safer_ffi::error::clear_last_error();
+    assert!(test_last_error_message().is_none());
+    assert_eq!(test_checked_sub(42, 2), Ok(40));
+    assert!(test_last_error_message().is_none());
+    extern "C" {
+        #[link_name = "test_checked_sub"]
+        fn c_test_checked_sub (x: u32, y: u32, out: *mut u32) -> i32;
+    }
+    unsafe {
+        assert_eq!(c_test_checked_sub(2, 42, ::core::ptr::null_mut()), -1);
+    }
+    let message = test_last_error_message().unwrap();
+    assert_eq!(message.to_str(), "-1");
+    test_last_error_free(Some(message));
+    // (the message is per thread)
+    ::std::thread::spawn(|| {
+        assert!(test_last_error_message().is_none());
+    }).join().unwrap();
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("char *\ntest_last_error_message (void);"));
+    }
+}
+
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub
+enum TestStrError {
+    Ok = 0,
+    InvalidStr = 1,
+}
+
+impl ::safer_ffi::error_code::ErrorCode
+    for TestStrError
+{
+    const SUCCESS: Self = TestStrError::Ok;
+}
+
+impl From<::safer_ffi::char_p::InvalidStr>
+    for TestStrError
+{
+    fn from (_: ::safer_ffi::char_p::InvalidStr)
+      -> Self
+    {
+        TestStrError::InvalidStr
+    }
+}
+
+#[ffi_export(validate_utf8)]
+fn test_validate_utf8 (name: char_p::Ref<'_>, suffix: str::Ref<'_>)
+  -> Result<usize, TestStrError>
+{
+    Ok(name.to_str().len() + suffix.len())
+}
+
+#[test]
+fn validate_utf8 ()
+{
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct RawSlice {
+        ptr: *const u8,
+        len: usize,
+    }
+    extern "C" {
+        #[link_name = "test_validate_utf8"]
+        fn c_test_validate_utf8 (
+            name: *const ::std::os::raw::c_char,
+            suffix: RawSlice,
+            out: *mut usize,
+        ) -> i32;
+    }
+    let mut out = 0;
+    unsafe {
+        let suffix = RawSlice { ptr: b"!".as_ptr(), len: 1 };
+        assert_eq!(
+            c_test_validate_utf8(b"hello\0".as_ptr().cast(), suffix, &mut out),
+            TestStrError::Ok as i32,
+        );
+        assert_eq!(out, 6);
+        ::safer_ffi::error::clear_last_error();
+        assert_eq!(
+            c_test_validate_utf8(b"\xff\0".as_ptr().cast(), suffix, &mut out),
+            TestStrError::InvalidStr as i32,
+        );
+        let message = test_last_error_message().unwrap();
+        assert_eq!(
+            message.to_str(),
+            "invalid `name` argument: invalid UTF-8 (past the first 0 bytes)",
+        );
+        test_last_error_free(Some(message));
+        let null = RawSlice { ptr: ::core::ptr::null(), len: 0 };
+        assert_eq!(
+            c_test_validate_utf8(b"hello\0".as_ptr().cast(), null, &mut out),
+            TestStrError::InvalidStr as i32,
+        );
+        let message = test_last_error_message().unwrap();
+        assert_eq!(message.to_str(), "invalid `suffix` argument: null pointer");
+        test_last_error_free(Some(message));
+        assert_eq!(out, 6);
+    }
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("TestStrError_t\ntest_validate_utf8 (\n    char const * name,\n    slice_ref_uint8_t suffix,\n    size_t * out);"));
+    }
+}
+
+#[ffi_export]
+fn test_out_params (
+    a: ::safer_ffi::Out<'_, i32>,
+    b: &'_ mut ::core::mem::MaybeUninit<u8>,
+)
+{
+    a.write(42);
+    b.write(27);
+}
+
+#[test]
+fn out_params ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains(" *  `a` is write-only: it may point to uninitialized memory, which this function initializes.\n"));
+        assert!(out.contains("test_out_params (\n    int32_t * a,\n    uint8_t * b);"));
+    }
+    let (mut a, mut b) = (::core::mem::MaybeUninit::uninit(), ::core::mem::MaybeUninit::uninit());
+    test_out_params(a.as_out(), &mut b);
+    assert_eq!(unsafe { (a.assume_init(), b.assume_init()) }, (42, 27));
+}
+
+#[derive_ReprC]
+#[repr(opaque)]
+pub
+struct TestCounter {
+    n: u32,
+}
+
+#[ffi_export]
+impl TestCounter {
+    /// Creates a counter, starting at `start`.
+    pub
+    fn new (start: u32)
+      -> Self
+    {
+        Self { n: start }
+    }
+
+    pub
+    fn increment (&mut self)
+      -> u32
+    {
+        self.n += 1;
+        self.n
+    }
+
+    /// Consumes (frees) the counter, returning its final count.
+    pub
+    fn into_count (self)
+      -> u32
+    {
+        self.n
+    }
+}
+
+#[test]
+fn impl_methods ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("TestCounter_t *\ntest_counter_new (\n    uint32_t start);"));
+        assert!(out.contains("uint32_t\ntest_counter_increment (\n    TestCounter_t * test_counter);"));
+        assert!(out.contains("void\ntest_counter_free (\n    TestCounter_t * test_counter);"));
+        assert!(out.contains("uint32_t\ntest_counter_into_count (\n    TestCounter_t * test_counter);"));
+    }
+    extern "C" {
+        fn test_counter_new (start: u32) -> repr_c::Box<TestCounter>;
+        fn test_counter_increment (test_counter: &'_ mut TestCounter) -> u32;
+        fn test_counter_free (test_counter: repr_c::Box<TestCounter>);
+        fn test_counter_into_count (test_counter: repr_c::Box<TestCounter>) -> u32;
+    }
+    unsafe {
+        let mut counter = test_counter_new(41);
+        assert_eq!(test_counter_increment(&mut counter), 42);
+        test_counter_free(counter);
+        let mut counter = test_counter_new(26);
+        assert_eq!(test_counter_increment(&mut counter), 27);
+        assert_eq!(test_counter_into_count(counter), 27);
+    }
+}
+
+#[derive_ReprC(auto_drop, auto_clone)]
+#[repr(opaque)]
+#[derive(Clone)]
+pub
+struct TestConfig {
+    #[allow(dead_code)]
+    name: String,
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_auto_drop ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("void\ntest_config_free (\n    TestConfig_t * test_config);"));
+    assert!(out.contains("TestConfig_t *\ntest_config_clone (\n    TestConfig_t const * test_config);"));
+    Ok(())
+}
+
+#[::safer_ffi::ffi_export_accessors]
+#[derive_ReprC]
+#[repr(opaque)]
+pub
+struct TestSettings {
+    retries: u32,
+    #[ffi_export_accessors(readonly)]
+    verbose: bool,
+    #[ffi_export_accessors(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn field_accessors ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("uint32_t\ntest_settings_get_retries (\n    TestSettings_t const * test_settings);"));
+        assert!(out.contains("void\ntest_settings_set_retries (\n    TestSettings_t * test_settings,\n    uint32_t value);"));
+        assert!(out.contains("test_settings_get_verbose ("));
+        assert!(out.contains("test_settings_set_verbose (").not());
+        assert!(out.contains("test_settings_get_cache (").not());
+    }
+    let mut settings = TestSettings { retries: 3, verbose: true, cache: vec![] };
+    test_settings_set_retries(&mut settings, 5);
+    assert_eq!(test_settings_get_retries(&settings), 5);
+    assert!(test_settings_get_verbose(&settings));
+}
+
+#[derive_ReprC(opaque, auto_drop)]
+pub
+struct TestSession {
+    #[allow(dead_code)]
+    history: Vec<String>,
+}
+
+#[ffi_export]
+fn test_session_new ()
+  -> repr_c::Box<TestSession>
+{
+    Box::new(TestSession { history: vec![] }).into()
+}
+
+#[test]
+fn derive_opaque ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef struct TestSession TestSession_t;"));
+        assert!(out.contains("history").not());
+    }
+    test_session_free(test_session_new());
+}
+
+#[derive_ReprC]
+#[repr(C)]
+#[deprecated(note = "use `TestSettings` instead")]
+pub
+struct TestLegacySettings {
+    pub verbose: bool,
+}
+
+#[ffi_export]
+#[deprecated = "use `test_settings_get_verbose()` instead"]
+fn test_legacy_settings_is_verbose (settings: &'_ TestLegacySettings)
+  -> bool
+{
+    settings.verbose
+}
+
+#[ffi_export]
+#[deprecated = "call \"test_reset()\"\tinstead\u{7}"]
+fn test_legacy_reset ()
+{}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_deprecated ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#define SAFER_FFI_DEPRECATED(note) [[deprecated(note)]]\n"));
+    assert!(out.contains("SAFER_FFI_DEPRECATED(\"use `TestSettings` instead\")\ntypedef struct TestLegacySettings {"));
+    assert!(out.contains("SAFER_FFI_DEPRECATED(\"use `test_settings_get_verbose()` instead\")\nbool\ntest_legacy_settings_is_verbose ("));
+    assert!(out.contains(r#"SAFER_FFI_DEPRECATED("call \"test_reset()\"\tinstead\007")"#));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[Obsolete(\"use `TestSettings` instead\")]\n[StructLayout(LayoutKind.Sequential, Size = 1)]\npublic unsafe struct TestLegacySettings_t {"));
+    assert!(out.contains("    [Obsolete(\"use `test_settings_get_verbose()` instead\")]\n"));
+    assert!(out.contains(r#"[Obsolete("call \"test_reset()\"\tinstead\u0007")]"#));
+    Ok(())
+}
+
+#[ffi_export]
+#[must_use]
+fn test_settings_describe (settings: &'_ TestSettings)
+  -> char_p::Box
+{
+    format!("{} retries", settings.retries).try_into().unwrap()
+}
+
+#[test]
+fn must_use ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("#define SAFER_FFI_MUST_USE __attribute__((warn_unused_result))\n"));
+        assert!(out.contains("SAFER_FFI_MUST_USE\nchar *\ntest_settings_describe ("));
+    }
+    let settings = TestSettings { retries: 3, verbose: false, cache: vec![] };
+    assert_eq!(test_settings_describe(&settings).to_str(), "3 retries");
+}
+
+#[cfg(feature = "c-unwind")]
+#[ffi_export(convention = "C-unwind")]
+fn test_unwinding (
+    cb: extern "C-unwind" fn(i32) -> i32,
+    x: i32,
+) -> i32
+{
+    cb(x) + 1
+}
+
+#[cfg(feature = "c-unwind")]
+#[test]
+fn c_unwind ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("Should this function panic, the panic unwinds into the caller."));
+    }
+    extern "C-unwind" {
+        fn test_unwinding (cb: extern "C-unwind" fn(i32) -> i32, x: i32) -> i32;
+    }
+    extern "C-unwind"
+    fn double (x: i32)
+      -> i32
+    {
+        assert!(x >= 0, "negative input");
+        2 * x
+    }
+    unsafe {
+        assert_eq!(test_unwinding(double, 20), 41);
+        assert!(::std::panic::catch_unwind(|| test_unwinding(double, -1)).is_err());
+    }
+}
+
+/// (Arguments are only ever checked with the `debug-ffi-checks` feature: the
+/// panicking violation handler of the `ffi_checks` test is then caught.)
+#[ffi_export(catch_panics = usize::MAX)]
+fn test_ffi_checks (
+    name: str::Ref<'_>,
+    xs: c_slice::Ref<'_, u32>,
+) -> usize
+{
+    name.len() + xs.len()
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawSlice {
+    ptr: *const u8,
+    len: usize,
+}
+
+extern "C" {
+    #[link_name = "test_ffi_checks"]
+    fn raw_test_ffi_checks (name: RawSlice, xs: RawSlice) -> usize;
+}
+
+#[test]
+fn ffi_checks_valid_args ()
+{
+    let xs = [1_u32, 2, 3];
+    let name = RawSlice { ptr: "hi".as_ptr(), len: 2 };
+    let xs = RawSlice { ptr: xs.as_ptr().cast(), len: xs.len() };
+    let empty = RawSlice { ptr: ::core::ptr::NonNull::<u32>::dangling().as_ptr().cast(), len: 0 };
+    unsafe {
+        assert_eq!(raw_test_ffi_checks(name, xs), 5);
+        assert_eq!(raw_test_ffi_checks(name, empty), 2);
+    }
+}
+
+#[cfg(feature = "debug-ffi-checks")]
+#[test]
+fn ffi_checks ()
+{
+    use ::std::sync::Mutex;
+
+    static REPORTED: Mutex<Option<String>> = Mutex::new(None);
+
+    let _guard = PANIC_POLICY.lock().unwrap_or_else(|it| it.into_inner());
+    ::safer_ffi::ffi_checks::set_violation_handler(Some(|violation| {
+        *REPORTED.lock().unwrap() = Some(violation.to_string());
+        // Rather than aborting: caught by `catch_panics`.
+        panic!("{violation}")
+    }));
+    let xs = [1_u32, 2, 3];
+    let name = RawSlice { ptr: "hi".as_ptr(), len: 2 };
+    let xs = RawSlice { ptr: xs.as_ptr().cast(), len: xs.len() };
+    let violation = |name, xs| {
+        assert_eq!(unsafe { raw_test_ffi_checks(name, xs) }, usize::MAX);
+        REPORTED.lock().unwrap().take().unwrap()
+    };
+    unsafe {
+        assert_eq!(raw_test_ffi_checks(name, xs), 5);
+    }
+    assert!(REPORTED.lock().unwrap().is_none());
+    let reported = violation(RawSlice { ptr: ::core::ptr::null(), len: 0 }, xs);
+    assert!(reported.starts_with("invalid argument `name: "), "{reported}");
+    assert!(reported.ends_with("null or misaligned pointer)"), "{reported}");
+    let reported = violation(name, RawSlice { len: usize::MAX / 2, ..xs });
+    assert!(reported.starts_with("invalid argument `xs: "), "{reported}");
+    assert!(reported.ends_with("overflows `isize::MAX`"), "{reported}");
+    let reported = violation(RawSlice { ptr: b"\xff".as_ptr(), len: 1 }, xs);
+    assert!(reported.ends_with("of `test_ffi_checks()`: not valid UTF-8"), "{reported}");
+    ::safer_ffi::ffi_checks::set_violation_handler(None);
+}
+
+#[ffi_export]
+#[ffi_const]
+fn test_clamp (x: i32, lo: i32, hi: i32)
+  -> i32
+{
+    x.clamp(lo, hi)
+}
+
+#[ffi_export]
+#[ffi_pure]
+fn test_settings_retries (settings: &'_ TestSettings)
+  -> u32
+{
+    settings.retries
+}
+
+#[test]
+fn ffi_pure_and_const ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("#define SAFER_FFI_CONST __attribute__((const))\n"));
+        assert!(out.contains("#define SAFER_FFI_PURE __attribute__((pure))\n"));
+        assert!(out.contains(" */\nSAFER_FFI_CONST\nint32_t\ntest_clamp ("));
+        assert!(out.contains(" */\nSAFER_FFI_PURE\nuint32_t\ntest_settings_retries ("));
+    }
+    assert_eq!(test_clamp(42, 0, 10), 10);
+    let settings = TestSettings { retries: 3, verbose: false, cache: vec![] };
+    assert_eq!(test_settings_retries(&settings), 3);
+}
+
+#[ffi_export_json]
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+pub
+struct TestJsonConfig {
+    servers: Vec<String>,
+    retries: Option<u32>,
+}
+
+#[ffi_export_json]
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+pub
+struct TestJsonRoutes {
+    hops: ::std::collections::HashMap<(u32, u32), u32>,
+}
+
+#[test]
+fn ffi_export_json ()
+{
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("test_json_config_to_json (\n    TestJsonConfig_t const * test_json_config);"));
+        assert!(out.contains("test_json_config_from_json (\n    char const * json);"));
+        assert!(out.contains("returning `NULL` if it is invalid."));
+    }
+    let config = TestJsonConfig { servers: vec!["a".into()], retries: None };
+    let json = test_json_config_to_json(&config).unwrap();
+    assert_eq!(json.to_str(), r#"{"servers":["a"],"retries":null}"#);
+    let roundtrip = test_json_config_from_json(json.as_ref()).unwrap();
+    assert_eq!(*roundtrip, config);
+    test_json_config_json_free(json);
+    assert!(test_json_config_from_json(c!("{")).is_none());
+    // Non-string keys: not serializable to JSON.
+    let routes = TestJsonRoutes { hops: [((1, 2), 3)].into_iter().collect() };
+    assert!(test_json_routes_to_json(&routes).is_none());
+}
+
+#[cfg(feature = "headers")]
+mod doxygen_groups {
+    //! Not part of the brief of the group, which is the one of the file.
+
+    use super::*;
+
+    /// A grouped struct.
+    #[derive_ReprC]
+    #[repr(C)]
+    pub
+    struct TestGroupedPoint {
+        x: i32,
+    }
+
+    /// A grouped constant.
+    #[ffi_export]
+    pub
+    const TEST_GROUPED_ANSWER: i32 = 42;
+
+    /// A grouped function.
+    #[ffi_export]
+    fn test_grouped_point_x (point: TestGroupedPoint)
+      -> i32
+    {
+        point.x
+    }
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_doxygen_groups ()
+  -> ::std::io::Result<()>
+{
+    // `file!()` is not to be resolved against the current directory.
+    let current_dir = ::std::env::current_dir()?;
+    ::std::env::set_current_dir(::std::env::temp_dir())?;
+    let generated = generate!(
+        .with_doxygen_groups(true)
+    );
+    ::std::env::set_current_dir(current_dir)?;
+    let out = generated?;
+    assert_eq!(
+        out.matches("/** \\defgroup ffi_export_options_doxygen_groups `ffi_export_options::doxygen_groups`\n").count(),
+        1,
+    );
+    assert!(out.contains(concat!(
+        "/** \\defgroup ffi_export_options_doxygen_groups `ffi_export_options::doxygen_groups`\n",
+        " * Tests of the `#[ffi_export(…)]` options, and of the header generation\n",
+        " * settings.\n",
+        " */\n",
+    )));
+    assert!(out.contains("Not part of the brief").not());
+    for docs in ["A grouped struct.", "A grouped constant.", "A grouped function."] {
+        assert!(out.contains(&format!(
+            "/** \\brief\n *  {docs}\n *\n *  \\ingroup ffi_export_options_doxygen_groups\n */\n",
+        )));
+    }
+    // The items at the root of the crate get grouped too.
+    assert!(out.contains("/** \\defgroup ffi_export_options `ffi_export_options`\n"));
+    assert!(out.contains(" *  \\ingroup ffi_export_options\n */\nextern int32_t const TEST_ANSWER;"));
+
+    let out = generate!()?;
+    assert!(out.contains("\\defgroup").not());
+    assert!(out.contains("\\ingroup").not());
+    Ok(())
+}
+
+/// Sums the `count` `int32_t`s following it.
+#[cfg(feature = "nightly")]
+#[ffi_export]
+unsafe extern "C"
+fn test_sum_variadic (count: u32, mut args: ...)
+  -> i32
+{
+    (0 .. count).map(|_| args.arg::<i32>()).sum()
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn c_variadic ()
+{
+    assert_eq!(unsafe { test_sum_variadic(3, 1_i32, 2_i32, 39_i32) }, 42);
+    assert_eq!(unsafe { test_sum_variadic(0) }, 0);
+    #[cfg(feature = "headers")] {
+        use ::safer_ffi::headers::Language;
+
+        let generate = |language| -> ::std::io::Result<String> {
+            generate!(
+                .with_language(language)
+            )
+        };
+        let out = generate(Language::C).unwrap();
+        assert!(out.contains("int32_t\ntest_sum_variadic (\n    uint32_t count,\n    ...);\n"));
+        // C# cannot express `...`: the function is skipped.
+        let out = generate(Language::CSharp).unwrap();
+        assert!(out.contains("test_sum_variadic").not());
+        // The symbol is still exported, though.
+        let out = generate(Language::LdVersionScript).unwrap();
+        assert!(out.contains("\n    test_sum_variadic;\n"));
+    }
+}
+
+/// The default verbosity.
+#[cfg(feature = "nightly")]
+#[ffi_export(weak_linkage)]
+fn test_default_verbosity ()
+  -> i32
+{
+    3
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn weak_linkage ()
+{
+    assert_eq!(test_default_verbosity(), 3);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains(concat!(
+            "/** \\brief\n",
+            " *  The default verbosity.\n",
+            " *\n",
+            " *  This function is a weak symbol: the application may override it with its own (strong) definition, at link time.\n",
+            " */\n",
+            "int32_t\n",
+            "test_default_verbosity (void);\n",
+        )));
+    }
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_includes_and_snippets ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Language;
+
+    let generate = |language| -> ::std::io::Result<String> {
+        generate!(
+            .with_language(language)
+            .with_includes(&["<math.h>", "\"test_defs.h\""])
+            .with_header_snippet("#define TEST_SNIPPET 1")
+            .with_footer_snippet("/* test footer */")
+        )
+    };
+
+    let out = generate(Language::C)?;
+    assert!(out.contains("\n#include <math.h>\n#include \"test_defs.h\"\n\n#define TEST_SNIPPET 1\n"));
+    assert!(out.contains("\n/* test footer */\n#ifdef __cplusplus\n}"));
+    // The snippets surround the generated items.
+    let snippet_at = out.find("TEST_SNIPPET").unwrap();
+    let footer_at = out.find("test footer").unwrap();
+    let item_at = out.find("test_header_name_v2 (void);").unwrap();
+    assert!(snippet_at < item_at && item_at < footer_at);
+
+    // Only C headers get the `#include`s.
+    let out = generate(Language::CSharp)?;
+    assert!(out.contains("#include").not());
+    assert!(out.contains("#define TEST_SNIPPET 1\n"));
+    assert!(out.contains("/* test footer */\n"));
+    Ok(())
+}
@@ -0,0 +1,332 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+static BATCH_TOTAL: ::std::sync::atomic::AtomicU32 =
+    ::std::sync::atomic::AtomicU32::new(0)
+;
+
+#[derive_ReprC]
+#[repr(C)]
+pub
+struct BatchAddArgs {
+    amount: u32,
+}
+
+::safer_ffi::ffi_export_batch! {
+    pub
+    fn test_execute_batch;
+
+    pub
+    struct BatchCall;
+
+    pub
+    enum BatchOp {
+        Add(args: BatchAddArgs) => BATCH_TOTAL.fetch_add(args.amount, ::std::sync::atomic::Ordering::SeqCst),
+        Reset => BATCH_TOTAL.store(0, ::std::sync::atomic::Ordering::SeqCst),
+    }
+}
+
+#[test]
+fn test_batch ()
+{
+    let add = BatchAddArgs { amount: 21 };
+    let call = |op: BatchOp, args: *const BatchAddArgs| BatchCall {
+        op: unsafe { ::safer_ffi::layout::into_raw(op) },
+        args: args.cast(),
+    };
+    let calls = [
+        call(BatchOp::Reset, ptr::null()),
+        call(BatchOp::Add, &add),
+        call(BatchOp::Add, &add),
+        call(BatchOp::Add, ptr::null()),
+        call(BatchOp::Add, &add),
+    ];
+    unsafe {
+        assert_eq!(test_execute_batch(calls[..].into()), 3);
+        assert_eq!(BATCH_TOTAL.load(::std::sync::atomic::Ordering::SeqCst), 42);
+        // Unknown `op`.
+        let unknown = BatchCall {
+            op: ::core::mem::transmute::<u32, ::safer_ffi::layout::CLayoutOf<BatchOp>>(42),
+            args: ptr::null(),
+        };
+        let calls = [call(BatchOp::Add, &add), unknown, call(BatchOp::Add, &add)];
+        assert_eq!(test_execute_batch(calls[..].into()), 1);
+        assert_eq!(BATCH_TOTAL.load(::std::sync::atomic::Ordering::SeqCst), 63);
+        // Misaligned `args`.
+        let misaligned = (&add as *const BatchAddArgs).cast::<u8>().add(1).cast();
+        let calls = [call(BatchOp::Add, misaligned)];
+        assert_eq!(test_execute_batch(calls[..].into()), 0);
+        assert_eq!(BATCH_TOTAL.load(::std::sync::atomic::Ordering::SeqCst), 63);
+    }
+}
+
+::safer_ffi::ffi_export_iter!(test_countdown: Iter<i32>);
+
+#[test]
+fn test_iter ()
+{
+    let mut it = repr_c::Iter::new((1..=2).rev());
+    let mut out = 0;
+    assert!(test_countdown_next(&mut it, (&mut out).as_out()));
+    assert_eq!(out, 2);
+    assert!(test_countdown_next(&mut it, (&mut out).as_out()));
+    assert_eq!(out, 1);
+    assert!(test_countdown_next(&mut it, (&mut out).as_out()).not());
+    test_countdown_free(it);
+}
+
+::safer_ffi::ffi_export_flags! {
+    pub
+    struct TestFlags: u32 {
+        /// The first flag.
+        const FOO = 1 << 0;
+        const BAR = 1 << 1;
+        const BAZ = 1 << 2;
+    }
+}
+
+#[test]
+fn test_flags_from_csv ()
+{
+    let mut flags = TestFlags(0);
+    assert!(TestFlags_from_csv(c!("foo, BAZ,"), (&mut flags).as_out()));
+    assert_eq!(flags, TestFlags::FOO | TestFlags::BAZ);
+    assert_eq!(TEST_FLAGS_BAR, 2);
+    assert!(TestFlags_from_csv(c!("foo,qux"), (&mut flags).as_out()).not());
+    assert_eq!(flags, TestFlags::FOO | TestFlags::BAZ);
+    assert_eq!(TestFlags::from_csv(""), Some(TestFlags(0)));
+}
+
+::safer_ffi::ffi_export_vec_deque!(test_frames: VecDeque<u16>);
+
+#[test]
+fn test_vec_deque ()
+{
+    let mut deque = test_frames_with_capacity(2);
+    let mut out = 0;
+    assert!(test_frames_pop_front(&mut deque, (&mut out).as_out()).not());
+    test_frames_push_back(&mut deque, 1);
+    test_frames_push_back(&mut deque, 2);
+    assert!(test_frames_pop_front(&mut deque, (&mut out).as_out()));
+    assert_eq!(out, 1);
+    // Wraps around, and then grows.
+    test_frames_push_back(&mut deque, 3);
+    test_frames_push_back(&mut deque, 4);
+    assert_eq!(deque.len(), 3);
+    let deque: ::std::collections::VecDeque<u16> = deque.into();
+    assert_eq!(deque, [2, 3, 4]);
+    let mut deque = repr_c::VecDeque::from(deque);
+    assert_eq!(deque.pop_front(), Some(2));
+    test_frames_free(deque);
+}
+
+#[test]
+fn vec_deque_corrupt_header ()
+{
+    use ::safer_ffi::layout::{from_raw, into_raw};
+
+    let mut deque = repr_c::VecDeque::<u16>::with_capacity(2);
+    deque.push_back(42);
+    let mut raw = unsafe { into_raw(deque) };
+    assert!(<repr_c::VecDeque<u16> as ReprC>::is_valid(&raw));
+    // `head` out of bounds.
+    raw.head = raw.cap;
+    assert!(unsafe { from_raw::<repr_c::VecDeque<u16>>(raw) }.is_none());
+    raw.head = 0;
+    // More elements than room for them.
+    raw.len = raw.cap + 1;
+    assert!(unsafe { from_raw::<repr_c::VecDeque<u16>>(raw) }.is_none());
+    raw.len = 1;
+    let mut deque = unsafe { from_raw::<repr_c::VecDeque<u16>>(raw) }.unwrap();
+    assert_eq!(deque.pop_front(), Some(42));
+    // An unallocated buffer is fine.
+    let raw = unsafe { into_raw(repr_c::VecDeque::<u16>::new()) };
+    assert!(<repr_c::VecDeque<u16> as ReprC>::is_valid(&raw));
+    drop(unsafe { from_raw::<repr_c::VecDeque<u16>>(raw) });
+}
+
+::safer_ffi::ffi_export_vec! {
+    test_vec_u8: Vec<u8>,
+    test_vec_u16: Vec<u16>,
+}
+
+#[test]
+fn test_vec_exports ()
+{
+    let mut vec = test_vec_u8_with_capacity(1);
+    assert_eq!(vec.len(), 0);
+    test_vec_u8_push(&mut vec, 42);
+    test_vec_u8_push(&mut vec, 27);
+    test_vec_u8_reserve(&mut vec, 10);
+    assert_eq!(vec[..], [42, 27]);
+    let rust_vec: Vec<u8> = vec.into();
+    assert!(rust_vec.capacity() >= 12);
+    test_vec_u8_free(rust_vec.into());
+    let mut vec = test_vec_u16_with_capacity(0);
+    test_vec_u16_push(&mut vec, 0xBEEF);
+    assert_eq!(vec[..], [0xBEEF]);
+    test_vec_u16_free(vec);
+}
+
+::safer_ffi::ffi_export_future!(test_future: Future<u32>);
+
+#[test]
+fn test_future ()
+{
+    use ::safer_ffi::future::FuturePoll;
+    let mut out = 0;
+    let mut fut = repr_c::Future::new(async { 42 });
+    assert_eq!(
+        unsafe {
+            test_future_poll(&mut fut, (&mut out).as_out(), None, ptr::null_mut())
+        },
+        FuturePoll::Ready,
+    );
+    assert_eq!(out, 42);
+    assert!(test_future_wait(&mut fut, (&mut out).as_out()).not());
+    test_future_free(fut);
+    let mut fut = repr_c::Future::new(async { 27 });
+    assert!(test_future_wait(&mut fut, (&mut out).as_out()));
+    assert_eq!(out, 27);
+    test_future_free(fut);
+    let mut fut = repr_c::Future::new(async { 0 });
+    test_future_cancel(&mut fut);
+    assert_eq!(
+        unsafe {
+            test_future_poll(&mut fut, (&mut out).as_out(), None, ptr::null_mut())
+        },
+        FuturePoll::Cancelled,
+    );
+    test_future_free(fut);
+}
+
+::safer_ffi::ffi_export_arc!(test_counter: u64);
+
+#[test]
+fn test_arc ()
+{
+    let std_arc = ::std::sync::Arc::new(42_u64);
+    let arc: repr_c::Arc<u64> = std_arc.clone().into();
+    // What C does: pass the pointer along, without giving up its ownership.
+    let retained = test_counter_retain(unsafe { ptr::read(&arc) });
+    assert_eq!(*retained, 42);
+    assert_eq!(::std::sync::Arc::strong_count(&std_arc), 3);
+    test_counter_release(retained);
+    test_counter_release(arc);
+    assert_eq!(::std::sync::Arc::strong_count(&std_arc), 1);
+}
+
+::safer_ffi::ffi_export_hash_map!(test_scores: HashMap<i32, f64>);
+
+#[test]
+fn test_hash_map ()
+{
+    unsafe extern "C"
+    fn sum_values (user_data: *mut ::std::ffi::c_void, _: *const i32, value: *const f64)
+    {
+        *user_data.cast::<f64>() += *value;
+    }
+
+    let mut map = test_scores_new();
+    assert!(test_scores_insert(&mut map, 1, 0.5).not());
+    assert!(test_scores_insert(&mut map, 2, 1.0).not());
+    assert!(test_scores_insert(&mut map, 2, 2.0));
+    assert_eq!(test_scores_len(&map), 2);
+    assert_eq!(test_scores_get(&map, &2), Some(&2.0));
+    assert!(test_scores_remove(&mut map, &1));
+    assert!(test_scores_remove(&mut map, &1).not());
+    assert_eq!(test_scores_get(&map, &1), None);
+    let mut sum = 0.0_f64;
+    unsafe {
+        test_scores_for_each(&map, <*mut f64>::cast(&mut sum), sum_values);
+    }
+    assert_eq!(sum, 2.0);
+    test_scores_free(map);
+}
+
+#[cfg(feature = "buffer-utils")]
+::safer_ffi::ffi_export_buffer_utils!(test);
+
+#[cfg(feature = "buffer-utils")]
+#[test]
+fn test_buffer_utils ()
+{
+    assert_eq!(test_buffer_crc32(b"123456789"[..].into()), 0xCBF4_3926);
+    assert_eq!(test_buffer_crc32(b""[..].into()), 0);
+    assert!(test_buffer_equal(b"abc"[..].into(), b"abc"[..].into()));
+    assert!(test_buffer_equal(b"abc"[..].into(), b"abd"[..].into()).not());
+    assert!(test_buffer_equal(b"abc"[..].into(), b"ab"[..].into()).not());
+}
+
+::safer_ffi::ffi_export_encoding_helpers!(test);
+
+#[test]
+fn test_encoding_helpers ()
+{
+    assert!(test_utf8_is_valid(b"h\xc3\xa9llo"[..].into()));
+    assert!(test_utf8_is_valid(b"h\xe9llo"[..].into()).not());
+    let sanitized = test_utf8_sanitize(b"h\xe9llo"[..].into());
+    assert_eq!(sanitized[..], *"h\u{fffd}llo".as_bytes());
+    test_utf8_free(sanitized);
+    let utf16 = test_utf8_to_utf16(b"h\xc3\xa9llo \xf0\x9f\xa6\x80"[..].into());
+    assert_eq!(utf16[..], "h\u{e9}llo \u{1f980}".encode_utf16().collect::<Vec<_>>()[..]);
+    let utf8 = test_utf16_to_utf8(utf16[..].into());
+    assert_eq!(utf8[..], *"h\u{e9}llo \u{1f980}".as_bytes());
+    test_utf8_free(utf8);
+    test_utf16_free(utf16);
+    // Unpaired surrogate.
+    let utf8 = test_utf16_to_utf8([0x68_u16, 0xd800][..].into());
+    assert_eq!(utf8[..], *"h\u{fffd}".as_bytes());
+    test_utf8_free(utf8);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("Vec_uint16_t\ntest_utf8_to_utf16 (\n    slice_ref_uint8_t utf8);"));
+        assert!(out.contains(" *  The returned vector must be freed with `test_utf16_free()`.\n"));
+        assert!(out.contains("rust_utf8").not());
+    }
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_flags ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("typedef uint32_t TestFlags_t;\n\n/** \\brief\n *  The first flag.\n */\n#define TEST_FLAGS_FOO ((uint32_t) 1)\n"));
+    assert!(out.contains("#define TEST_FLAGS_BAR ((uint32_t) 2)\n"));
+    assert!(out.contains("    TestFlags_t * out);"));
+    assert!(out.contains("Vec_uint8_t\ntest_vec_u8_with_capacity (\n    size_t capacity);"));
+    assert!(out.contains("void\ntest_vec_u16_push (\n    Vec_uint16_t * vec,\n    uint16_t value);"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[Flags]\npublic enum TestFlags_t : UInt32 {\n"));
+    assert!(out.contains("    BAZ = 4,\n}"));
+    Ok(())
+}
@@ -0,0 +1,472 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+// The items the generated headers of this test binary are made of.
+
+#[ffi_export]
+fn test_checked_div (x: u32, y: u32)
+  -> Result<u32, i32>
+{
+    x.checked_div(y).ok_or(-1)
+}
+
+::safer_ffi::ffi_export_flags! {
+    pub
+    struct TestFlags: u32 {
+        /// The first flag.
+        const FOO = 1 << 0;
+        const BAR = 1 << 1;
+        const BAZ = 1 << 2;
+    }
+}
+
+/// Never compiled (`cfg(any())` being always false), but still declared by
+/// the C headers, under `#if 0`.
+#[ffi_export]
+#[cfg(any())]
+fn test_never_compiled ()
+  -> i32
+{
+    42
+}
+
+#[ffi_export]
+fn test_count_up_to (
+    n: u64,
+    mut progress: ::safer_ffi::progress::ProgressCallback<'_>,
+) -> u64
+{
+    (1 ..= n)
+        .take_while(|&i| progress.report(i, n).is_ok())
+        .count() as u64
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_crlf ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_line_endings(::safer_ffi::headers::LineEndings::CrLf)
+        .with_utf8_bom(true)
+    )?;
+    assert!(out.starts_with('\u{FEFF}'));
+    assert!(out.as_bytes().windows(2).all(|w| w[1] != b'\n' || w[0] == b'\r'));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_c_smoke_test ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSmokeTest)
+        .with_smoke_test_header("test.h")
+    )?;
+    assert!(out.contains("#include \"test.h\"\n"));
+    assert!(out.contains("int32_t (*safer_ffi_smoke_test_test_checked_div) (uint32_t x, uint32_t y, uint32_t * out) = &test_checked_div;\n"));
+    // The host layouts are not asserted, absent layout targets.
+    assert!(out.contains("TestFlags_t safer_ffi_smoke_test_TestFlags_t;\n\n"));
+    assert!(out.contains("_Static_assert").not());
+    assert!(out.ends_with("int main (void)\n{\n    return 0;\n}\n"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSmokeTest)
+        .with_smoke_test_header("test.h")
+        .with_layout_targets(&["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"])
+    )?;
+    assert!(out.contains("TestFlags_t safer_ffi_smoke_test_TestFlags_t;\n_Static_assert(sizeof(TestFlags_t) == 4, "));
+    Ok(())
+}
+
+/// Compiles the smoke-test translation unit against the C header, with the
+/// `cc` of the host (if any).
+#[cfg(feature = "headers")]
+#[test]
+fn compile_c_smoke_test ()
+  -> ::std::io::Result<()>
+{
+    let dir = ::std::env::temp_dir().join(format!(
+        "safer_ffi-c_smoke_test-{}", ::std::process::id(),
+    ));
+    ::std::fs::create_dir_all(&dir)?;
+    ::safer_ffi::headers::builder()
+        .to_file(dir.join("test.h"))?
+        .generate()?;
+    ::safer_ffi::headers::builder()
+        .with_language(::safer_ffi::headers::Language::CSmokeTest)
+        .with_smoke_test_header("test.h")
+        .to_file(dir.join("smoke_test.c"))?
+        .generate()?;
+    let cc = ::std::env::var("CC").unwrap_or_else(|_| "cc".into());
+    let status =
+        ::std::process::Command::new(&cc)
+            .current_dir(&dir)
+            .args(["-std=c11", "-Werror=incompatible-pointer-types", "-c"])
+            .args(["smoke_test.c", "-o", "smoke_test.o"])
+            .status()
+    ;
+    let status = match status {
+        | Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            eprintln!("Skipping: no `{cc}` C compiler");
+            return ::std::fs::remove_dir_all(&dir);
+        },
+        | status => status?,
+    };
+    assert!(status.success(), "`{cc}` failed on {}", dir.display());
+    ::std::fs::remove_dir_all(&dir)
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_dlopen_api ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_dlopen_api("test")
+    )?;
+    assert!(out.contains("struct test_api {\n"));
+    assert!(out.contains("\n    int32_t (*test_checked_div) (uint32_t x, uint32_t y, uint32_t * out);\n"));
+    assert!(out.contains("int test_load_api (void * dlhandle, struct test_api * out)\n"));
+    assert!(out.contains("\n    *(void **) &out->test_checked_div = dlsym(dlhandle, TEST_API_SYMBOL_NAME(test_checked_div));\n"));
+    // The `#[cfg]`-gated functions are guarded just like their declarations.
+    assert!(out.contains("\n#if 0\n    int32_t (*test_never_compiled) (void);\n#endif /* 0 */\n"));
+    assert!(out.contains(concat!(
+        "\n#if 0\n",
+        "    *(void **) &out->test_never_compiled = dlsym(dlhandle, TEST_API_SYMBOL_NAME(test_never_compiled));\n",
+        "    missing += out->test_never_compiled == NULL;\n",
+        "#endif /* 0 */\n",
+    )));
+    // The declarations are still there.
+    assert!(out.contains("int32_t\ntest_checked_div (\n    uint32_t x,"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_c_standards ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::CStandard;
+
+    let generate = |c_standard| -> ::std::io::Result<String> {
+        generate!(
+            .with_c_standard(c_standard)
+            .with_static_asserts(true)
+            .with_dlopen_api("test")
+        )
+    };
+    let size_check = "typedef char ProgressCallback_t__size_check[(sizeof(ProgressCallback_t) == ";
+    let c11_assert = "\n_Static_assert(_Alignof(ProgressCallback_t) == ";
+    let c23_assert = "\nstatic_assert(alignof(ProgressCallback_t) == ";
+
+    let out = generate(CStandard::C89)?;
+    assert!(out.contains("\n#ifndef __cplusplus\ntypedef unsigned char bool;\n"));
+    assert!(out.contains(size_check));
+    assert!(out.contains("static\nint test_load_api ("));
+    assert!(out.contains("_Static_assert").not());
+
+    let out = generate(CStandard::C99)?;
+    assert!(out.contains("\n#include <stdbool.h>\n"));
+    assert!(out.contains(size_check));
+    assert!(out.contains("static inline\nint test_load_api ("));
+    assert!(out.contains("_Static_assert").not());
+
+    let out = generate(CStandard::C11)?;
+    assert!(out.contains("\n#include <stdbool.h>\n"));
+    assert!(out.contains(c11_assert));
+    assert!(out.contains(size_check).not());
+
+    let out = generate(CStandard::C23)?;
+    assert!(out.contains("stdbool.h").not());
+    assert!(out.contains("typedef unsigned char bool;").not());
+    assert!(out.contains(c23_assert));
+    assert!(out.contains("_Static_assert").not());
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn layout_snapshot ()
+  -> ::std::io::Result<()>
+{
+    let dir = ::std::env::temp_dir().join(format!(
+        "safer-ffi-layouts-{}", ::std::process::id(),
+    ));
+    ::std::fs::create_dir_all(&dir)?;
+    let snapshot = dir.join("layouts.snap");
+    // A missing snapshot fails…
+    let err = ::safer_ffi::headers::check_layout_snapshot(&snapshot).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::NotFound);
+    assert!(err.to_string().contains("set `SAFER_FFI_UPDATE_LAYOUTS=1` to write it"));
+    // … (`SAFER_FFI_UPDATE_LAYOUTS=1` writing this very output) …
+    ::safer_ffi::headers::builder()
+        .with_language(::safer_ffi::headers::Language::LayoutSnapshot)
+        .to_file(&snapshot)?
+        .generate()?;
+    let contents = ::std::fs::read_to_string(&snapshot)?;
+    assert!(contents.starts_with("# Layouts of the types exported by the crate.\n"));
+    assert!(contents.contains("\n[TestFlags_t]\nsize = 4\nalign = 4\n\n"));
+    // (the offsets being the ones of the compiled `struct`)
+    let ptr = ::core::mem::size_of::<usize>();
+    assert!(contents.contains(&format!(
+        "\n[ProgressCallback_t]\nsize = {}\nalign = {ptr}\n.user_data = 0\n.report = {ptr}\n",
+        2 * ptr,
+    )));
+    // … then is checked.
+    ::safer_ffi::headers::check_layout_snapshot(&snapshot)?;
+    ::std::fs::write(&snapshot, contents.replace("[TestFlags_t]\nsize = 4", "[TestFlags_t]\nsize = 8"))?;
+    let err = ::safer_ffi::headers::check_layout_snapshot(&snapshot).unwrap_err();
+    assert!(err.to_string().contains("`TestFlags_t` was:\n      size = 8\n"));
+    ::std::fs::remove_dir_all(&dir)
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_csharp_function_pointers ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+        .with_csharp_function_pointers(true)
+    )?;
+    assert!(out.contains("public delegate* unmanaged[Cdecl]<void *, UInt64, UInt64, byte> Ptr;"));
+    assert!(out.contains("UnmanagedType.FunctionPtr").not());
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_csharp_names ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+        .with_csharp_namespace("Acme.Interop")
+        .with_csharp_class_name("Native")
+        .with_library_name("acme")
+    )?;
+    assert!(out.contains("namespace Acme.Interop {"));
+    assert!(out.contains("public unsafe partial class Native {"));
+    assert!(out.contains("private const string RustLib = \"acme\";"));
+    assert!(out.contains("public static void SetDllImportResolver ("));
+    assert!(out.contains("class Ffi ").not());
+    Ok(())
+}
+
+#[ffi_export]
+fn test_assume_nonnull (
+    data: &'static u32,
+    raw: *const u32,
+) -> Option<&'static u32>
+{
+    raw.is_null().not().then(|| data)
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_assume_nonnull ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_assume_nonnull(true)
+    )?;
+    assert!(out.contains("#define SAFER_FFI_ASSUME_NONNULL_BEGIN _Pragma(\"clang assume_nonnull begin\")\n"));
+    assert!(out.contains(concat!(
+        "SAFER_FFI_ASSUME_NONNULL_BEGIN\n",
+        "/** <No documentation available> */\n",
+        "uint32_t const * SAFER_FFI_NULLABLE\n",
+        "test_assume_nonnull (\n",
+        "    uint32_t const * SAFER_FFI_NONNULL data,\n",
+        "    uint32_t const * SAFER_FFI_NULLABLE raw);\n",
+        "SAFER_FFI_ASSUME_NONNULL_END\n",
+    )));
+    Ok(())
+}
+
+::safer_ffi::ffi_export_symbols!(test, "tests/headers.symbols");
+
+#[test]
+fn baked_symbols ()
+{
+    // Whatever `tests/headers.symbols` featured at compile time (checked to be
+    // up to date by the `symbols` test).
+    const SYMBOLS: &[::safer_ffi::symbols::Symbol] = include!("headers.symbols");
+    assert!(SYMBOLS.iter().any(|&(name, _, _)| name == "test_checked_div"));
+    let names = test_list_symbols();
+    assert_eq!(
+        names.iter().map(|name| &**name).collect::<Vec<_>>(),
+        SYMBOLS.iter().map(|&(name, _, _)| name).collect::<Vec<_>>(),
+    );
+    test_list_symbols_free(names);
+    let infos = test_symbol_infos();
+    assert_eq!(infos.len(), SYMBOLS.len());
+    for (info, &(name, kind, declaration)) in infos.iter().zip(SYMBOLS) {
+        assert_eq!((&*info.name, info.kind, &*info.declaration), (name, kind, declaration));
+    }
+    test_symbol_infos_free(infos);
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn symbols ()
+  -> ::std::io::Result<()>
+{
+    let names = ::safer_ffi::symbols::list_symbols();
+    assert!(names.iter().any(|&name| name == "test_checked_div"));
+    assert!(names.windows(2).all(|it| it[0] <= it[1]));
+    // Not part of the current target.
+    assert!(names.iter().all(|&name| name != "test_never_compiled"));
+    let infos = ::safer_ffi::symbols::symbol_infos();
+    assert!(infos.iter().all(|info| &*info.name != "test_never_compiled"));
+    let info = infos.iter().find(|info| &*info.name == "test_checked_div").unwrap();
+    assert_eq!(info.kind, ::safer_ffi::headers::ItemKind::Function);
+    assert!(
+        info.declaration.ends_with("test_checked_div (\n    uint32_t x,\n    uint32_t y,\n    uint32_t * out);"),
+        "{}", &*info.declaration,
+    );
+    let dir = ::std::env::temp_dir().join(format!("safer-ffi-symbols-{}", ::std::process::id()));
+    ::std::fs::create_dir_all(&dir)?;
+    let path = dir.join("test.symbols");
+    ::safer_ffi::symbols::write_symbols(&path)?;
+    let written = ::std::fs::read_to_string(&path)?;
+    assert!(written.starts_with("// Generated by `::safer_ffi::symbols::write_symbols()`.\n&[\n"));
+    assert!(written.contains("    (\"test_checked_div\", ::safer_ffi::symbols::ItemKind::Function, \""));
+    assert!(written.ends_with("]\n"));
+    ::std::fs::remove_dir_all(&dir)?;
+    // The committed symbols are the current ones.
+    ::safer_ffi::symbols::check_symbols("tests/headers.symbols")
+}
+
+::safer_ffi::ffi_export_abi_fingerprint!(test, "tests/headers.abi-fingerprint");
+
+#[cfg(feature = "headers")]
+#[test]
+fn abi_fingerprint ()
+  -> ::std::io::Result<()>
+{
+    // The committed fingerprint is the current one…
+    ::safer_ffi::abi::check_abi_fingerprint("tests/headers.abi-fingerprint")?;
+    // … and is baked at compile time.
+    assert_eq!(
+        format!("{:#018x}\n", test_abi_fingerprint()),
+        include_str!("headers.abi-fingerprint"),
+    );
+    let fingerprint = ::safer_ffi::abi::abi_fingerprint();
+    let dir = ::std::env::temp_dir().join(format!("safer-ffi-abi-fingerprint-{}", ::std::process::id()));
+    ::std::fs::create_dir_all(&dir)?;
+    let path = dir.join("test.abi-fingerprint");
+    ::safer_ffi::abi::write_abi_fingerprint(&path)?;
+    assert_eq!(::std::fs::read_to_string(&path)?, format!("{fingerprint:#018x}\n"));
+    ::std::fs::remove_dir_all(&dir)?;
+    let out = generate!(
+        .with_abi_fingerprint_macro("TEST_ABI_FINGERPRINT")
+    )?;
+    assert!(out.contains(&format!("#define TEST_ABI_FINGERPRINT {fingerprint:#018x}ULL\n")));
+    assert!(out.contains("uint64_t\ntest_abi_fingerprint (void);"));
+    Ok(())
+}
+
+#[cfg(feature = "abi-diff")]
+#[test]
+fn abi_diff ()
+  -> ::std::io::Result<()>
+{
+    let manifest = ::safer_ffi::headers::abi_manifest()?;
+    assert!(manifest.contains(r#""declaration": "int32_t test_checked_div ( uint32_t x, uint32_t y, uint32_t * out);""#));
+    assert!(::safer_ffi::headers::diff(&manifest, &manifest)?.is_empty());
+
+    let renamed = manifest.replace(r#""test_checked_div""#, r#""test_checked_div_v2""#);
+    let diff = ::safer_ffi::headers::diff(&manifest, &renamed)?;
+    assert!(diff.is_breaking());
+    assert_eq!(diff.changes.len(), 2);
+    let report = diff.to_string();
+    assert!(report.contains("  - [breaking] removed function `test_checked_div`\n"));
+    assert!(report.contains("  + [additive] added function `test_checked_div_v2`\n"));
+    // (Only the removal is breaking.)
+    assert_eq!(diff.changes.iter().filter(|change| change.is_breaking()).count(), 1);
+    assert!(::safer_ffi::headers::diff("{}", &manifest).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "python-headers")]
+#[test]
+fn generate_headers_python_cffi_builder ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::Python)
+        .with_python_cffi_header("mylib.h")
+        .with_library_name("mylib")
+    )?;
+    assert!(out.contains("ffibuilder.set_source(\n    \"_mylib\",\n    '#include \"mylib.h\"',"));
+    assert!(out.contains("ffibuilder.cdef(\"\"\"\n"));
+    assert!(out.trim_end().ends_with("ffibuilder.compile(verbose=True)"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn analyze_headers ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::Language;
+    let report = ::safer_ffi::headers::builder().analyze()?;
+    assert!(report.functions > 0 && report.constants > 0);
+    assert!(report.defined.iter().any(|it| it.starts_with("ProgressCallback")));
+    assert!(report.name_collisions.is_empty(), "{:?}", report.name_collisions);
+    assert!(report.skipped.is_empty());
+    let report =
+        ::safer_ffi::headers::builder()
+            .with_language(Language::MsvcDef)
+            .analyze()?
+    ;
+    // (and the `#[cfg(any())]`-gated function, lacking a `#[cfg]` guard there)
+    assert_eq!(report.skipped.len(), report.types + report.constants + 1);
+    assert!(report.skipped.iter().any(|it| it == "test_never_compiled"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_layout_targets ()
+{
+    let generate = |targets: &[&str]| {
+        ::safer_ffi::headers::builder()
+            .with_layout_targets(targets)
+            .to_writer(::std::io::sink())
+            .generate()
+    };
+    // Same data model.
+    generate(&["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"]).unwrap();
+    // Pointer-width mismatch (_e.g._, the `c_slice` structs).
+    generate(&["x86_64-unknown-linux-gnu", "i686-unknown-linux-gnu"]).unwrap_err();
+    // Unknown target.
+    generate(&["definitely-not-a-target"]).unwrap_err();
+}
@@ -5,6 +5,9 @@
 #[macro_use]
 extern crate macro_rules_attribute;
 
+#[macro_use]
+mod common;
+
 use ::std::{
     collections::HashSet as Set,
     convert::TryInto,
@@ -16,6 +19,7 @@ use ::safer_ffi::{
     closure::*,
     prelude::*,
     layout::{
+        CLayoutOf,
         CType,
         ReprC,
         derive_ReprC,
@@ -301,6 +305,64 @@ fn test_niche ()
     ));
 }
 
+
+#[ffi_export]
+fn test_array_reverse (it: [u8; 33])
+  -> [u8; 33]
+{
+    let mut it = it;
+    it.reverse();
+    it
+}
+
+#[test]
+fn test_arrays ()
+{
+    let mut it = [0; 33];
+    it[0] = 42;
+    assert_eq!(test_array_reverse(it)[32], 42);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef struct {\n    uint8_t idx[33];\n} uint8_33_array_t;\n"));
+        assert!(out.contains("test_array_reverse (\n    uint8_33_array_t it);"));
+    }
+}
+
+#[ffi_export]
+fn test_tuple_swap (pair: (i32, f32))
+  -> (f32, i32)
+{
+    (pair.1, pair.0)
+}
+
+#[test]
+fn test_tuples ()
+{
+    assert_eq!(test_tuple_swap((42, 0.5)), (0.5, 42));
+    let Tuple2 { _0, _1 } = {
+        extern "C" {
+            fn test_tuple_swap (pair: Tuple2<i32, f32>) -> Tuple2<f32, i32>;
+        }
+        unsafe { test_tuple_swap(Tuple2 { _0: 42, _1: 0.5 }) }
+    };
+    assert_eq!((_0, _1), (0.5, 42));
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("Tuple2_float_int32_t\ntest_tuple_swap (\n    Tuple2_int32_float_t pair);"));
+    }
+}
+
+#[test]
+fn test_non_zero ()
+{
+    use ::core::num::NonZeroU32;
+    let mut out = 0;
+    assert!(test_niche_args(&42, NonZeroU32::new(1), ::core::num::NonZeroU64::new(1).unwrap(), Some(&mut out)));
+    assert_eq!(out, 42);
+    assert!(<Option<NonZeroU32> as ReprC>::is_valid(&0));
+    assert!(<NonZeroU32 as ReprC>::is_valid(&0).not());
+}
+
 #[test]
 fn test_c_str_macro ()
 {
@@ -310,6 +372,118 @@ fn test_c_str_macro ()
     assert_eq!(it.to_str(), "Hello, World!");
 }
 
+#[ffi_export(instantiate = (i32, f64))]
+#[derive_ReprC]
+#[repr(C)]
+pub
+struct Pair<T : ReprC> {
+    a: T,
+    b: T,
+}
+
+#[derive_ReprC]
+#[repr(C, packed)]
+pub
+struct WireHeader {
+    kind: u8,
+    len: u32,
+}
+
+#[derive_ReprC]
+#[repr(C, align(16))]
+pub
+struct Aligned {
+    x: u8,
+}
+
+#[ffi_export]
+fn test_wire_header_len (header: WireHeader, _aligned: Aligned)
+  -> u32
+{
+    header.len
+}
+
+#[test]
+fn test_packed_and_aligned ()
+{
+    assert_eq!(::core::mem::size_of::<CLayoutOf<WireHeader>>(), 5);
+    assert_eq!(::core::mem::align_of::<CLayoutOf<WireHeader>>(), 1);
+    assert_eq!(::core::mem::align_of::<CLayoutOf<Aligned>>(), 16);
+    assert_eq!(test_wire_header_len(WireHeader { kind: 0, len: 42 }, Aligned { x: 0 }), 42);
+}
+
+#[derive_ReprC]
+#[repr(C, u8)]
+/// Some tagged union
+pub
+enum Shape {
+    /// A circle
+    Circle { radius: f32 },
+    Rectangle(f32, MyBool),
+    Empty,
+}
+
+#[ffi_export]
+fn test_shape_area (shape: &'_ Shape)
+  -> f32
+{
+    match *shape {
+        | Shape::Circle { radius } => 3. * radius * radius,
+        | Shape::Rectangle(width, MyBool::True) => width,
+        | Shape::Rectangle(_, MyBool::False) | Shape::Empty => 0.,
+    }
+}
+
+#[test]
+fn test_tagged_union ()
+{
+    assert_eq!(test_shape_area(&Shape::Circle { radius: 2. }), 12.);
+    assert_eq!(::core::mem::size_of::<Shape>(), 12);
+    assert_eq!(::core::mem::size_of::<CLayoutOf<Shape>>(), 12);
+    #[repr(C, align(4))]
+    struct Bytes([u8; 12]);
+    let [a, b, c, d] = 2_f32.to_ne_bytes();
+    // `Shape::Rectangle(2., MyBool::True)`
+    let mut bytes = Bytes([1, 0, 0, 0, a, b, c, d, 43, 0, 0, 0]);
+    let is_valid = |bytes: &Bytes| unsafe {
+        Shape::is_valid(&*<*const _>::cast::<CLayoutOf<Shape>>(bytes))
+    };
+    assert!(is_valid(&bytes));
+    // invalid `MyBool` in the active `payload`.
+    bytes.0[8] = 0;
+    assert!(is_valid(&bytes).not());
+    // `Empty`: the `payload` is not looked at.
+    bytes.0[0] = 2;
+    assert!(is_valid(&bytes));
+    // invalid tag.
+    bytes.0[0] = 3;
+    assert!(is_valid(&bytes).not());
+}
+
+#[derive_ReprC]
+#[repr(C)]
+/// Some union
+pub
+union EventData {
+    key_code: u32,
+    /// Where the click happened
+    position: [f32; 2],
+}
+
+#[ffi_export]
+fn test_event_key_code (data: EventData)
+  -> u32
+{
+    unsafe { data.key_code }
+}
+
+#[test]
+fn test_union ()
+{
+    assert_eq!(::core::mem::size_of::<CLayoutOf<EventData>>(), 8);
+    assert_eq!(test_event_key_code(EventData { key_code: 42 }), 42);
+}
+
 #[cfg(feature = "headers")]
 #[test]
 fn generate_headers ()
@@ -320,6 +494,8 @@ fn generate_headers ()
         in  &[
                 C,
                 CSharp,
+                Markdown,
+                Cython,
             ]
     {
         ::safer_ffi::headers::builder()
@@ -328,3 +504,218 @@ fn generate_headers ()
             .generate()?
     }
 })}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_cython ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::Cython)
+        .with_cython_header("layout_macros.h")
+    )?;
+    assert!(out.contains("from libc.stdint cimport *\n"));
+    assert!(out.contains("cdef extern from \"layout_macros.h\":\n"));
+    // Everything is declared within the `cdef extern from` block.
+    assert!(out.contains(concat!(
+        "    # Some union\n",
+        "    ctypedef union EventData_t:\n",
+        "        uint32_t key_code\n",
+    )));
+    assert!(out.contains("        # Where the click happened\n"));
+    assert!(out.contains("    # Some docstring\n    ctypedef uint8_t MyBool_t\n    enum:\n"));
+    assert!(out.contains("    uint32_t test_event_key_code (EventData_t data)\n"));
+    // `const` in leading position.
+    assert!(out.contains("    bool test_niche_args (const uint32_t * data, uint32_t timeout, uint64_t count, uint32_t * out)\n"));
+    assert!(out.contains(" const *").not());
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_tagged_union ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("typedef union Shape_Payload {\n    /** \\brief\n     *  A circle\n     */\n    Shape_Circle_t Circle;\n"));
+    assert!(out.contains("    Shape_Tag_t tag;\n"));
+    assert!(out.contains("SHAPE_TAG_RECTANGLE,"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[StructLayout(LayoutKind.Explicit, Size = 8)]\npublic unsafe struct Shape_Payload_t {"));
+    assert!(out.contains("[FieldOffset(0)]\n    public Shape_Rectangle_t Rectangle;"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_generic_instances ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("    int32_t b;\n} Pair_int32_t;"));
+    assert!(out.contains("    double b;\n} Pair_double_t;"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_packed_and_aligned ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#pragma pack(push, 1)\n/** <No documentation available> */\ntypedef struct WireHeader {"));
+    assert!(out.contains("} WireHeader_t;\n#pragma pack(pop)\n"));
+    assert!(out.contains("    SAFER_FFI_ALIGNAS(16) uint8_t x;\n} Aligned_t;"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[StructLayout(LayoutKind.Sequential, Size = 5, Pack = 1)]"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::Cython)
+    )?;
+    assert!(out.contains("    ctypedef packed struct WireHeader_t:\n"));
+    assert!(out.contains("    # `#[repr(align(16))]`: laid out as per the C header.\n    ctypedef struct Aligned_t:\n"));
+    #[cfg(feature = "python-headers")] {
+        let err =
+            ::safer_ffi::headers::builder()
+                .with_language(::safer_ffi::headers::Language::Python)
+                .to_writer(&mut Vec::new())
+                .generate()
+                .unwrap_err()
+        ;
+        assert!(err.to_string().contains("`cffi` cannot declare the `#[repr("), "{err}");
+    }
+    Ok(())
+}
+
+#[ffi_export]
+fn test_niche_args (
+    data: &'_ u32,
+    timeout: Option<::core::num::NonZeroU32>,
+    count: ::core::num::NonZeroU64,
+    out: Option<&'_ mut u32>,
+) -> bool
+{
+    if let Some(out) = out {
+        *out = *data;
+    }
+    timeout.is_some() && count.get() > 0
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_nullability ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!(
+        .with_nullability_annotations(true)
+    )?;
+    assert!(out.contains("#if defined(__clang__)\n#define SAFER_FFI_NONNULL _Nonnull\n"));
+    assert!(out.contains(concat!(
+        "    uint32_t const * SAFER_FFI_NONNULL data,\n",
+        "    uint32_t timeout /* 0 means None */,\n",
+        "    uint64_t count /* cannot be 0 */,\n",
+        "    uint32_t * SAFER_FFI_NULLABLE out);",
+    )));
+    let out = generate!()?;
+    assert!(out.contains("SAFER_FFI_NONNULL").not());
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_union ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("typedef union EventData {\n    /** <No documentation available> */\n    uint32_t key_code;\n"));
+    assert!(out.contains("    float position[2];\n} EventData_t;"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[StructLayout(LayoutKind.Explicit, Size = 8)]\npublic unsafe struct EventData_t {"));
+    Ok(())
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_static_asserts ()
+  -> ::std::io::Result<()>
+{
+    use ::safer_ffi::headers::CStandard;
+
+    let generate = |static_asserts, c_standard| -> ::std::io::Result<String> {
+        generate!(
+            .with_static_asserts(static_asserts)
+            .with_c_standard(c_standard)
+        )
+    };
+
+    let out = generate(true, CStandard::C11)?;
+    assert!(out.contains(concat!(
+        "} Aligned_t;\n",
+        "\n",
+        "#ifdef __cplusplus\n",
+        "static_assert(sizeof(Aligned_t) == 16, \"ABI mismatch: size of `Aligned_t`\");\n",
+        "static_assert(alignof(Aligned_t) == 16, \"ABI mismatch: alignment of `Aligned_t`\");\n",
+        "#else\n",
+        "_Static_assert(sizeof(Aligned_t) == 16, \"ABI mismatch: size of `Aligned_t`\");\n",
+        "_Static_assert(_Alignof(Aligned_t) == 16, \"ABI mismatch: alignment of `Aligned_t`\");\n",
+        "#endif\n",
+    )));
+    assert!(out.contains("_Static_assert(sizeof(WireHeader_t) == 5, "));
+    assert!(out.contains("_Static_assert(_Alignof(WireHeader_t) == 1, "));
+
+    let out = generate(true, CStandard::C23)?;
+    assert!(out.contains(concat!(
+        "static_assert(sizeof(Aligned_t) == 16, \"ABI mismatch: size of `Aligned_t`\");\n",
+        "static_assert(alignof(Aligned_t) == 16, \"ABI mismatch: alignment of `Aligned_t`\");\n",
+    )));
+    assert!(out.contains("_Static_assert").not());
+
+    // No `_Static_assert` before C11: the size gets checked nonetheless.
+    let out = generate(true, CStandard::C99)?;
+    assert!(out.contains("typedef char WireHeader_t__size_check[(sizeof(WireHeader_t) == 5) ? 1 : -1];\n"));
+    assert!(out.contains("static_assert").not());
+
+    let out = generate(false, CStandard::C11)?;
+    assert!(out.contains("ABI mismatch").not());
+    assert!(out.contains("__size_check").not());
+    Ok(())
+}
+
+/// A record.
+#[ffi_export(vec)]
+#[derive_ReprC]
+#[repr(C)]
+pub
+struct TestRecord {
+    id: u32,
+}
+
+#[test]
+fn test_vec_helpers ()
+{
+    let records = repr_c::Vec::from(vec![TestRecord { id: 27 }, TestRecord { id: 42 }]);
+    assert_eq!(vec_test_record_get(&records, 1).map(|it| it.id), Some(42));
+    assert!(vec_test_record_get(&records, 2).is_none());
+    vec_test_record_free(records);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("} Vec_TestRecord_t;\n"));
+        // The definition points to the helpers.
+        assert!(out.contains(concat!(
+            " *\n",
+            " *  The elements can be accessed with `vec_test_record_get`, and the\n",
+            " *  `Vec` freed with `vec_test_record_free`.\n",
+            " */\n",
+            "typedef struct Vec_TestRecord {\n",
+        )));
+        assert!(out.contains("vec_test_record_get (\n    Vec_TestRecord_t const * vec,\n    size_t index);"));
+        assert!(out.contains("or `NULL` if `index` is out of bounds."));
+        assert!(out.contains("void\nvec_test_record_free (\n    Vec_TestRecord_t vec);"));
+        assert!(out.contains("The elements can be accessed beforehand with `vec_test_record_get`."));
+    }
+}
@@ -0,0 +1,25 @@
+/** \brief
+ *  A point.
+ */
+typedef struct RegistryPoint {
+    /** <No documentation available> */
+    int32_t x;
+
+    /** <No documentation available> */
+    int32_t y;
+} RegistryPoint_t;
+
+/** \brief
+ *  Whether `p` is the origin.
+ */
+bool
+registry_is_origin (
+    RegistryPoint_t p);
+
+/** \brief
+ *  Adds the coordinates of `p`.
+ */
+int32_t
+registry_sum (
+    RegistryPoint_t p);
+
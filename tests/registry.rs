@@ -0,0 +1,92 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+//! The header generation from the `::inventory`-collected items, and from the
+//! `register_headers!`-listed ones (`explicit-registration` Cargo feature),
+//! which are both to yield `tests/registry.h` (between the markers).
+#![cfg(feature = "headers")]
+
+#[macro_use]
+mod common;
+
+use ::safer_ffi::{
+    prelude::*,
+    layout::derive_ReprC,
+};
+
+/// A point.
+#[ffi_export]
+#[derive_ReprC]
+#[repr(C)]
+pub
+struct RegistryPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Adds the coordinates of `p`.
+#[ffi_export]
+fn registry_sum (p: RegistryPoint)
+  -> i32
+{
+    p.x + p.y
+}
+
+/// Whether `p` is the origin.
+#[ffi_export]
+fn registry_is_origin (p: RegistryPoint)
+  -> bool
+{
+    p.x == 0 && p.y == 0
+}
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/registry.h");
+
+/// The generated declarations (between the markers).
+fn generate (stable_header: bool)
+  -> String
+{
+    let out = generate!(
+        .with_stable_header(stable_header)
+        .with_header_snippet("/* <registry> */")
+        .with_footer_snippet("/* </registry> */")
+    ).unwrap();
+    let start = out.find("/* <registry> */\n").unwrap() + "/* <registry> */\n".len();
+    let end = out.find("/* </registry> */\n").unwrap();
+    out[start .. end].to_owned()
+}
+
+#[cfg(not(feature = "explicit-registration"))]
+#[test]
+fn inventory ()
+  -> ::std::io::Result<()>
+{
+    let body = generate(true);
+    assert!(body.contains("typedef struct RegistryPoint {\n"));
+    assert!(body.contains("int32_t\nregistry_sum (\n    RegistryPoint_t p);\n"));
+    assert!(body.contains("bool\nregistry_is_origin (\n    RegistryPoint_t p);\n"));
+    // Keep the snapshot up to date (for the `explicit-registration` build to
+    // check against).
+    ::std::fs::write(SNAPSHOT, body)
+}
+
+#[cfg(feature = "explicit-registration")]
+#[test]
+fn explicit_registration ()
+{
+    // Nothing registered yet.
+    let body = generate(true);
+    assert_eq!(body, "");
+    // In registration order (when not sorted), the types being defined on
+    // demand, and registered once.
+    ::safer_ffi::register_headers!(registry_is_origin, registry_sum, registry_is_origin);
+    let body = generate(false);
+    let is_origin = body.find("registry_is_origin (").unwrap();
+    let sum = body.find("registry_sum (").unwrap();
+    assert!(body.find("typedef struct RegistryPoint {").unwrap() < is_origin);
+    assert!(is_origin < sum);
+    assert_eq!(body.matches("registry_is_origin (").count(), 1);
+    // Once everything is registered, the same output as with `::inventory`.
+    ::safer_ffi::register_headers!(RegistryPoint, registry_sum);
+    let body = generate(true);
+    assert_eq!(body, ::std::fs::read_to_string(SNAPSHOT).unwrap());
+}
@@ -0,0 +1,293 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+#[test]
+fn test_char_p_paths ()
+{
+    use ::std::{convert::TryFrom, path::{Path, PathBuf}};
+    use ::safer_ffi::char_p::InvalidPath;
+
+    let path = char_p::Box::try_from(PathBuf::from("dir/file.txt")).unwrap();
+    assert_eq!(path.to_path(), Path::new("dir/file.txt"));
+    assert_eq!(path.as_ref().to_path().file_name().unwrap(), "file.txt");
+    assert!(matches!(
+        char_p::Box::try_from(Path::new("nul\0byte")),
+        Err(InvalidPath::InnerNul(())),
+    ));
+    #[cfg(unix)] {
+        use ::std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let not_utf8 = OsStr::from_bytes(b"caf\xe9");
+        assert!(matches!(
+            char_p::Box::try_from(not_utf8),
+            Err(InvalidPath::NotUtf8(())),
+        ));
+        assert_eq!(
+            char_p::Box::from_os_str_lossy(not_utf8).unwrap().to_str(),
+            "caf\u{fffd}",
+        );
+    }
+}
+
+#[ffi_export]
+fn test_u128_add (a: repr_c::U128, b: repr_c::U128)
+  -> repr_c::U128
+{
+    (u128::from(a) + u128::from(b)).into()
+}
+
+#[test]
+fn test_int128 ()
+{
+    let big = repr_c::U128::from(u64::MAX as u128);
+    assert_eq!((big.lo, big.hi), (u64::MAX, 0));
+    let sum = test_u128_add(big, 1_u128.into());
+    assert_eq!((sum.lo, sum.hi), (0, 1));
+    assert_eq!(u128::from(sum), 1 << 64);
+    assert!(sum > big);
+    let minus_one = repr_c::I128::from(-1_i128);
+    assert_eq!((minus_one.lo, minus_one.hi), (u64::MAX, -1));
+    assert_eq!(i128::from(minus_one), -1);
+    assert!(minus_one < repr_c::I128::from(0));
+    assert_eq!(format!("{:?}", repr_c::I128::from(i128::MIN)), i128::MIN.to_string());
+}
+
+#[ffi_export]
+fn test_duration_double (it: repr_c::Duration)
+  -> repr_c::Duration
+{
+    (::std::time::Duration::from(it) * 2).into()
+}
+
+#[test]
+fn test_duration ()
+{
+    use ::std::time::Duration;
+    let it = repr_c::Duration::from(Duration::from_millis(1_500));
+    assert_eq!((it.secs, it.nanos), (1, 500_000_000));
+    assert_eq!(Duration::from(test_duration_double(it)), Duration::from_secs(3));
+    let denormalized = repr_c::Duration { secs: 1, nanos: 1_500_000_000 };
+    assert_eq!(Duration::from(denormalized), Duration::from_millis(2_500));
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef struct Duration {\n"));
+        assert!(out.contains("} Duration_t;"));
+    }
+}
+
+#[ffi_export]
+fn test_uuid_version (id: ::uuid::Uuid)
+  -> usize
+{
+    id.get_version_num()
+}
+
+#[test]
+fn test_uuid ()
+{
+    let id = ::uuid::Uuid::from_bytes([
+        0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f,
+        0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8,
+    ]);
+    assert_eq!(test_uuid_version(id), 4);
+    let c_layout = ::safer_ffi::uuid::Uuid_Layout::from(id);
+    assert_eq!(c_layout.bytes[0], 0x67);
+    assert_eq!(::uuid::Uuid::from(c_layout), id);
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef struct Uuid {\n    uint8_t bytes[16];\n} Uuid_t;\n"));
+        assert!(out.contains("test_uuid_version (\n    Uuid_t id);"));
+        let out = generate!(
+            .with_language(::safer_ffi::headers::Language::CSharp)
+        ).unwrap();
+        assert!(out.contains("public static implicit operator Guid (Uuid_t it)"));
+    }
+}
+
+#[ffi_export]
+fn test_socket_addr_port (addr: repr_c::SocketAddr)
+  -> u16
+{
+    ::std::net::SocketAddr::from(addr).port()
+}
+
+#[test]
+fn test_net ()
+{
+    use ::std::net::{IpAddr, Ipv6Addr, SocketAddr};
+    let v4: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+    let c_v4 = repr_c::SocketAddr::from(v4);
+    assert_eq!(c_v4.ip.octets[.. 5], [127, 0, 0, 1, 0]);
+    assert_eq!(test_socket_addr_port(c_v4), 8080);
+    assert_eq!(SocketAddr::from(c_v4), v4);
+    let v6: SocketAddr = "[fe80::1%3]:443".parse().unwrap();
+    let c_v6 = repr_c::SocketAddr::from(v6);
+    assert_eq!((c_v6.port, c_v6.scope_id), (443, 3));
+    assert_eq!(SocketAddr::from(c_v6), v6);
+    assert_eq!(IpAddr::from(c_v6.ip), IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)));
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("} SocketAddr_t;"));
+        assert!(out.contains("IP_ADDR_KIND_V6 = 6,"));
+    }
+}
+
+#[ffi_export]
+fn test_cow_label (n: i32)
+  -> repr_c::Cow<'static, str>
+{
+    match n {
+        | 0 => ::std::borrow::Cow::Borrowed("zero"),
+        | _ => ::std::borrow::Cow::Owned(n.to_string()),
+    }.into()
+}
+
+#[test]
+fn test_cow ()
+{
+    use ::std::borrow::Cow;
+    use ::safer_ffi::cow::CowTag;
+
+    let zero = test_cow_label(0);
+    assert_eq!(zero.tag(), CowTag::Borrowed);
+    assert!(matches!(Cow::from(zero), Cow::Borrowed("zero")));
+    let forty_two = test_cow_label(42);
+    assert_eq!(forty_two.tag(), CowTag::Owned);
+    assert_eq!(&*forty_two, "42");
+    drop(forty_two);
+    let bytes: repr_c::Cow<'_, [u8]> = Cow::Owned(vec![1, 2, 3]).into();
+    assert_eq!(Cow::from(bytes), Cow::Borrowed(&[1_u8, 2, 3][..]));
+}
+
+#[ffi_export]
+fn test_char16_len (name: char16_p::Ref<'_>)
+  -> usize
+{
+    name.to_units().len()
+}
+
+#[test]
+fn test_char16 ()
+{
+    let name = char16_p::new("h\u{e9}llo \u{1f980}");
+    assert_eq!(test_char16_len(name.as_ref()), 8);
+    assert_eq!(name.to_string_lossy(), "h\u{e9}llo \u{1f980}");
+    assert_eq!(format!("{:?}", name), "\"h\u{e9}llo \u{1f980}\"");
+    let s = repr_c::String16::from("h\u{e9}llo");
+    assert_eq!(&s[..], &"h\u{e9}llo".encode_utf16().collect::<Vec<_>>()[..]);
+    assert_eq!(String::try_from(s).unwrap(), "h\u{e9}llo");
+    let ill_formed = repr_c::String16::from(vec![0x68, 0xd800]);
+    assert_eq!(ill_formed.to_string_lossy(), "h\u{fffd}");
+    assert!(String::try_from(ill_formed).is_err());
+}
+
+#[test]
+fn char16_p_validity ()
+{
+    let units = [0x68_u16, 0, 0];
+    let is_valid = |ptr: *const u8| unsafe {
+        <char16_p::Ref<'_> as ReprC>::is_valid(
+            &::core::mem::transmute::<_, CLayoutOf<char16_p::Ref<'_>>>(ptr)
+        )
+    };
+    assert!(is_valid(units.as_ptr().cast()));
+    assert!(is_valid(ptr::null()).not());
+    // Misaligned.
+    assert!(is_valid(units.as_ptr().cast::<u8>().wrapping_add(1)).not());
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn generate_headers_char16 ()
+  -> ::std::io::Result<()>
+{
+    let out = generate!()?;
+    assert!(out.contains("#ifdef _WIN32\ntypedef wchar_t c_char16_t;\n#else\ntypedef uint16_t c_char16_t;\n#endif\n"));
+    assert!(out.contains("    c_char16_t const * name);"));
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+    )?;
+    assert!(out.contains("[MarshalAs(UnmanagedType.LPWStr)]\n        string name);"));
+    // `[LibraryImport]` cannot marshal the `string`: back to `[DllImport]`.
+    let out = generate!(
+        .with_language(::safer_ffi::headers::Language::CSharp)
+        .with_csharp_library_import(true)
+    )?;
+    let import_of = |fname: &str| {
+        let lines = out.lines().collect::<Vec<_>>();
+        lines
+            .windows(2)
+            .find(|it| it[1].ends_with(&format!(" {fname} (")))
+            .map(|it| it[0].trim().to_owned())
+    };
+    assert_eq!(
+        import_of("test_char16_len").as_deref(),
+        Some("[DllImport(RustLib, ExactSpelling = true)] public static unsafe extern"),
+    );
+    assert_eq!(
+        import_of("test_socket_addr_port").as_deref(),
+        Some("[LibraryImport(RustLib)] public static unsafe partial"),
+    );
+    Ok(())
+}
+
+#[ffi_export]
+fn test_optional_containers (
+    bytes: Option<repr_c::Vec<u8>>,
+    name: Option<repr_c::String>,
+    path: Option<char_p::Box>,
+) -> Option<repr_c::String>
+{
+    let _ = (bytes, path);
+    name
+}
+
+#[test]
+fn optional_containers ()
+{
+    let none = unsafe { ::core::mem::zeroed::<CLayoutOf<repr_c::Vec<u8>>>() };
+    assert!(<Option<repr_c::Vec<u8>> as ReprC>::is_valid(&none));
+    assert!(<repr_c::Vec<u8> as ReprC>::is_valid(&none).not());
+    assert!(test_optional_containers(None, None, None).is_none());
+    let name = test_optional_containers(
+        Some(vec![42].into()),
+        Some("foo".into()),
+        Some(char_p::new("bar")),
+    );
+    assert_eq!(name.as_deref(), Some("foo"));
+    #[cfg(feature = "headers")] {
+        let out = generate!(
+            .with_nullability_annotations(true)
+        ).unwrap();
+        assert!(out.contains(concat!(
+            "    Vec_uint8_t bytes /* NULL .ptr means None */,\n",
+            "    Vec_uint8_t name /* NULL .ptr means None */,\n",
+            "    char * SAFER_FFI_NULLABLE path);",
+        )));
+    }
+}
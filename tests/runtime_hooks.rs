@@ -0,0 +1,246 @@
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate macro_rules_attribute;
+
+#[macro_use]
+mod common;
+
+use ::std::{
+    collections::HashSet as Set,
+    convert::TryInto,
+    io,
+    ptr,
+    ops::Not as _,
+};
+use ::safer_ffi::{
+    closure::*,
+    prelude::*,
+    layout::{
+        CLayoutOf,
+        CType,
+        ReprC,
+        derive_ReprC,
+    },
+    tuple::Tuple2,
+};
+
+::safer_ffi::ffi_export_debug_checks!(test);
+
+/// Held by the tests toggling the (global) debug checks.
+static DEBUG_CHECKS: ::std::sync::Mutex<()> = ::std::sync::Mutex::new(());
+
+#[test]
+fn test_debug_checks ()
+{
+    use ::safer_ffi::debug_checks::{self, DebugChecks};
+    let _guard = DEBUG_CHECKS.lock().unwrap_or_else(|it| it.into_inner());
+    let each_check = [
+        (TEST_DEBUG_CHECK_OWNERSHIP_TRACKING, DebugChecks::OWNERSHIP_TRACKING),
+        (TEST_DEBUG_CHECK_THREAD_AFFINITY, DebugChecks::THREAD_AFFINITY),
+        (TEST_DEBUG_CHECK_LEAK_CHECK, DebugChecks::LEAK_CHECK),
+    ];
+    for (flag, check) in each_check {
+        assert_eq!(flag, check.0);
+        assert!(debug_checks::enabled(check).not());
+        assert_eq!(test_set_debug_checks(flag), cfg!(debug_assertions));
+        // Exactly that layer gets enabled.
+        for (_, other) in each_check {
+            assert_eq!(debug_checks::enabled(other), cfg!(debug_assertions) && other == check);
+        }
+        assert!(debug_checks::enabled(DebugChecks::ALL).not());
+        assert!(test_set_debug_checks(0) || cfg!(not(debug_assertions)));
+        assert!(debug_checks::enabled(check).not());
+    }
+    assert_eq!(test_set_debug_checks(DebugChecks::ALL.0), cfg!(debug_assertions));
+    assert_eq!(debug_checks::enabled(DebugChecks::ALL), cfg!(debug_assertions));
+    // Unknown layers are rejected, leaving the enabled ones as they were.
+    assert!(test_set_debug_checks(1 << 31).not());
+    assert_eq!(debug_checks::enabled(DebugChecks::ALL), cfg!(debug_assertions));
+    assert!(test_set_debug_checks(0) || cfg!(not(debug_assertions)));
+    assert!(debug_checks::enabled(DebugChecks::LEAK_CHECK).not());
+
+    // The `LEAK_CHECK` layer gates the tracking of the allocations.
+    #[cfg(feature = "leak-tracking")] {
+        use ::safer_ffi::leak_tracking::dump_live_allocations;
+
+        struct Checked(#[allow(dead_code)] u32);
+
+        let tracked = |enabled| {
+            assert_eq!(test_set_debug_checks(enabled), cfg!(debug_assertions));
+            let boxed = repr_c::Box::new(Checked(42));
+            let tracked = dump_live_allocations().contains("::Checked>` at ");
+            drop(boxed);
+            tracked
+        };
+        assert_eq!(tracked(TEST_DEBUG_CHECK_LEAK_CHECK), cfg!(debug_assertions));
+        assert!(tracked(TEST_DEBUG_CHECK_OWNERSHIP_TRACKING | TEST_DEBUG_CHECK_THREAD_AFFINITY).not());
+        assert!(tracked(0).not());
+    }
+}
+
+#[cfg(feature = "leak-tracking")]
+::safer_ffi::ffi_export_leak_tracking!(test);
+
+#[cfg(feature = "leak-tracking")]
+#[test]
+fn leak_tracking ()
+{
+    use ::safer_ffi::leak_tracking::dump_live_allocations;
+
+    struct Leaked(#[allow(dead_code)] u32);
+
+    let _guard = DEBUG_CHECKS.lock().unwrap_or_else(|it| it.into_inner());
+    // Not tracked unless the `LEAK_CHECK` layer is enabled.
+    let untracked = repr_c::Box::new(Leaked(27));
+    let dump = dump_live_allocations();
+    assert!(dump.contains("::Leaked>` at ").not(), "{dump}");
+    drop(untracked);
+    if test_set_debug_checks(TEST_DEBUG_CHECK_LEAK_CHECK).not() {
+        // (no debug checks without `debug_assertions`)
+        return;
+    }
+    let boxed = repr_c::Box::new(Leaked(42));
+    let dump = dump_live_allocations();
+    assert!(dump.contains("::Leaked>` at "), "{dump}");
+    assert!(test_ffi_live_allocations() >= 1);
+    drop(boxed);
+    let dump = dump_live_allocations();
+    assert!(dump.contains("::Leaked>` at ").not(), "{dump}");
+    assert!(test_set_debug_checks(0));
+}
+
+::safer_ffi::ffi_export_log_callback!(test);
+
+#[test]
+fn log_callback ()
+{
+    use ::safer_ffi::log::{LogCallback, LogLevel};
+    static RECORDS: ::std::sync::Mutex<Vec<(LogLevel, String, usize)>> =
+        ::std::sync::Mutex::new(Vec::new())
+    ;
+    // (no panicking across the `extern "C"` boundary: merely record)
+    unsafe extern "C"
+    fn on_log (
+        level: LogLevel,
+        message: char_p::Ref<'_>,
+        user_data: *mut ::core::ffi::c_void,
+    )
+    {
+        if let Ok(mut records) = RECORDS.lock() {
+            records.push((level, message.to_str().to_owned(), user_data as usize));
+        }
+    }
+    unsafe {
+        // Safety: `on_log` is thread-safe, and `user_data` is no pointer.
+        assert!(test_set_log_callback(LogLevel::Info, Some(LogCallback(on_log)), 42 as *mut _));
+    }
+    ::log::info!("hello, {}", "world");
+    ::log::debug!("not forwarded");
+    unsafe {
+        assert!(test_set_log_callback(LogLevel::Trace, None, ptr::null_mut()));
+    }
+    ::log::error!("not forwarded");
+    // (other tests may be logging concurrently)
+    let records = RECORDS.lock().unwrap();
+    assert!(records.contains(&(LogLevel::Info, "hello, world".to_owned(), 42)));
+    assert!(records.iter().all(|(_, it, _)| it != "not forwarded"));
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("typedef void (*LogCallback_t)(LogLevel_t level, char const * message, void * user_data);\n"));
+        assert!(out.contains("    LogCallback_t callback,\n"));
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: ::safer_ffi::allocator::CAllocator =
+    ::safer_ffi::allocator::CAllocator::new()
+;
+
+::safer_ffi::ffi_export_allocator!(test, ALLOCATOR);
+
+#[test]
+fn c_allocator ()
+{
+    use ::safer_ffi::allocator::{AllocatorVTable, CAllocator};
+    use ::std::{
+        alloc::{GlobalAlloc, Layout},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    extern "C" {
+        fn malloc (size: usize) -> *mut ::core::ffi::c_void;
+        fn free (ptr: *mut ::core::ffi::c_void);
+    }
+    static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+    static FREES: AtomicUsize = AtomicUsize::new(0);
+    unsafe extern "C"
+    fn counting_alloc (size: usize, align: usize) -> *mut ::core::ffi::c_void
+    {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        if align > 8 { ptr::null_mut() } else { malloc(size) }
+    }
+    unsafe extern "C"
+    fn counting_free (ptr: *mut ::core::ffi::c_void)
+    {
+        FREES.fetch_add(1, Ordering::Relaxed);
+        free(ptr)
+    }
+    let vtable = AllocatorVTable { alloc: counting_alloc, free: counting_free };
+
+    // (the test harness has already allocated)
+    assert!(unsafe { test_set_allocator(vtable) }.not());
+
+    // The returned pointers are `malloc()`ed.
+    use ::core::mem::ManuallyDrop;
+    let s = ManuallyDrop::new(char_p::new("hello"));
+    let empty = ManuallyDrop::new(char_p::new(""));
+    let v = ManuallyDrop::new(repr_c::Vec::from(vec![42_u8]));
+    unsafe {
+        free(s.to_bytes_with_null().as_ptr() as *mut _);
+        free(empty.to_bytes_with_null().as_ptr() as *mut _);
+        free(v.as_ptr() as *mut _);
+    }
+
+    // Over-aligned allocations.
+    let layout = Layout::from_size_align(3, 64).unwrap();
+    unsafe {
+        let fresh = CAllocator::new();
+        let ptr = fresh.alloc(layout);
+        assert!(ptr.is_null().not());
+        assert_eq!(ptr as usize % 64, 0);
+        ptr.write_bytes(0xff, 3);
+        let ptr = fresh.realloc(ptr, layout, 200);
+        assert_eq!(ptr as usize % 64, 0);
+        assert_eq!(*ptr.add(2), 0xff);
+        fresh.dealloc(ptr, Layout::from_size_align(200, 64).unwrap());
+    }
+
+    // A registered allocator.
+    let custom = CAllocator::new();
+    unsafe {
+        assert!(custom.set_allocator(vtable));
+        assert!(custom.set_allocator(vtable).not());
+        let layout = Layout::new::<u64>();
+        let ptr = custom.alloc(layout);
+        assert!(ptr.is_null().not());
+        custom.dealloc(ptr, layout);
+        // (the allocator's own errors are forwarded)
+        assert!(custom.alloc(Layout::from_size_align(8, 16).unwrap()).is_null());
+    }
+    assert_eq!((ALLOCS.load(Ordering::Relaxed), FREES.load(Ordering::Relaxed)), (2, 1));
+
+    // Too late, once `malloc()` has been used.
+    let fresh = CAllocator::new();
+    unsafe {
+        let layout = Layout::new::<u64>();
+        fresh.dealloc(fresh.alloc(layout), layout);
+        assert!(fresh.set_allocator(vtable).not());
+    }
+
+    #[cfg(feature = "headers")] {
+        let out = generate!().unwrap();
+        assert!(out.contains("have been allocated with `malloc()`"));
+    }
+}